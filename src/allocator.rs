@@ -0,0 +1,1523 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use ash::vk;
+
+use crate::{
+    DedicatedResource, Device, Error, MemoryInfo, MemoryProperties, MemoryRequirements,
+    PhysicalDeviceLimits, Result,
+};
+
+/// The interface through which an [`Allocator`] allocates and maps device memory.
+///
+/// This is implemented for [`Device`]. Other implementations may be used to exercise the
+/// allocator without a GPU.
+///
+/// # Safety
+///
+/// The handles returned by `allocate_memory` must be unique among the allocations that have not
+/// been freed yet, and the pointers returned by `map_memory` must remain valid for reads and
+/// writes over the whole allocation until it is freed.
+pub unsafe trait AllocatorBackend: Send + Sync {
+    /// Returns the memory types and heaps available to the backend.
+    fn memory_properties(&self) -> &MemoryInfo;
+
+    /// Returns the limits of the backend.
+    fn limits(&self) -> &PhysicalDeviceLimits;
+
+    /// Allocates `size` bytes of memory from the provided memory type.
+    ///
+    /// When `dedicated` is set, the memory is allocated for that resource only.
+    fn allocate_memory(
+        &self,
+        memory_type: u32,
+        size: u64,
+        dedicated: Option<DedicatedResource>,
+    ) -> Result<vk::DeviceMemory>;
+
+    /// Frees memory previously returned by `allocate_memory`.
+    ///
+    /// # Safety
+    ///
+    /// `memory` must have been returned by `allocate_memory`, must not have been freed already,
+    /// and must no longer be in use.
+    unsafe fn free_memory(&self, memory: vk::DeviceMemory);
+
+    /// Maps the whole allocation into the address space of the current process.
+    ///
+    /// # Safety
+    ///
+    /// `memory` must have been returned by `allocate_memory` from a host-visible memory type,
+    /// and must not be mapped already.
+    unsafe fn map_memory(&self, memory: vk::DeviceMemory) -> Result<NonNull<u8>>;
+
+    /// Makes host writes to a range of mapped memory visible to the device.
+    ///
+    /// # Safety
+    ///
+    /// `memory` must be mapped, and the range must respect the `nonCoherentAtomSize` limit.
+    unsafe fn flush_memory(&self, memory: vk::DeviceMemory, offset: u64, size: u64) -> Result<()>;
+
+    /// Makes device writes to a range of mapped memory visible to the host.
+    ///
+    /// # Safety
+    ///
+    /// `memory` must be mapped, and the range must respect the `nonCoherentAtomSize` limit.
+    unsafe fn invalidate_memory(
+        &self,
+        memory: vk::DeviceMemory,
+        offset: u64,
+        size: u64,
+    ) -> Result<()>;
+}
+
+unsafe impl AllocatorBackend for Device {
+    #[inline]
+    fn memory_properties(&self) -> &MemoryInfo {
+        Device::memory_properties(self)
+    }
+
+    #[inline]
+    fn limits(&self) -> &PhysicalDeviceLimits {
+        Device::limits(self)
+    }
+
+    #[inline]
+    fn allocate_memory(
+        &self,
+        memory_type: u32,
+        size: u64,
+        dedicated: Option<DedicatedResource>,
+    ) -> Result<vk::DeviceMemory> {
+        crate::memory::allocate_memory(self, memory_type, size, dedicated)
+    }
+
+    unsafe fn free_memory(&self, memory: vk::DeviceMemory) {
+        (self.fns().free_memory)(self.handle(), memory, std::ptr::null());
+    }
+
+    unsafe fn map_memory(&self, memory: vk::DeviceMemory) -> Result<NonNull<u8>> {
+        let mut ptr = std::ptr::null_mut();
+
+        let ret = (self.fns().map_memory)(
+            self.handle(),
+            memory,
+            0,
+            vk::WHOLE_SIZE,
+            vk::MemoryMapFlags::empty(),
+            &mut ptr,
+        );
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(NonNull::new(ptr as *mut u8).expect("vkMapMemory returned a null pointer"))
+    }
+
+    unsafe fn flush_memory(&self, memory: vk::DeviceMemory, offset: u64, size: u64) -> Result<()> {
+        let range = mapped_range(memory, offset, size);
+        let ret = (self.fns().flush_mapped_memory_ranges)(self.handle(), 1, &range);
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+
+    unsafe fn invalidate_memory(
+        &self,
+        memory: vk::DeviceMemory,
+        offset: u64,
+        size: u64,
+    ) -> Result<()> {
+        let range = mapped_range(memory, offset, size);
+        let ret = (self.fns().invalidate_mapped_memory_ranges)(self.handle(), 1, &range);
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a [`vk::MappedMemoryRange`] for the provided range.
+fn mapped_range(memory: vk::DeviceMemory, offset: u64, size: u64) -> vk::MappedMemoryRange {
+    vk::MappedMemoryRange {
+        memory,
+        offset,
+        size,
+
+        p_next: std::ptr::null(),
+        s_type: vk::StructureType::MAPPED_MEMORY_RANGE,
+    }
+}
+
+/// The strategy used by an [`Allocator`] to sub-allocate its memory blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AllocationStrategy {
+    /// Allocations are placed one after the other, and the space of a block is only reclaimed
+    /// once all of its allocations have been freed.
+    ///
+    /// This is very cheap and suits short-lived allocations, such as staging buffers.
+    Linear,
+    /// Blocks are recursively split in halves until an allocation fits, and freed halves are
+    /// merged back with their buddy.
+    ///
+    /// Allocation sizes are rounded up to the next power of two.
+    Buddy,
+    /// Free ranges are sorted into size classes by a two-level segregated fit allocator, and
+    /// freed ranges are merged with their free neighbours.
+    ///
+    /// Allocations take constant time and are not rounded up, which suits long-lived resources
+    /// of varied sizes.
+    Tlsf,
+}
+
+/// The parameters passed to the [`Allocator::new`] function.
+#[derive(Debug, Clone)]
+pub struct AllocatorDesc {
+    /// The strategy used to sub-allocate memory blocks.
+    pub strategy: AllocationStrategy,
+    /// The size of the memory blocks allocated from the backend, in bytes.
+    ///
+    /// With [`AllocationStrategy::Buddy`], this is rounded up to the next power of two.
+    pub block_size: u64,
+    /// Allocations that are at least this large get their own memory block.
+    pub dedicated_threshold: u64,
+}
+
+impl Default for AllocatorDesc {
+    fn default() -> Self {
+        Self {
+            strategy: AllocationStrategy::Buddy,
+            block_size: 64 << 20,
+            dedicated_threshold: 32 << 20,
+        }
+    }
+}
+
+/// How a resource lays out its data in memory.
+///
+/// Linear and non-linear resources are kept in separate memory blocks, which honours the
+/// `bufferImageGranularity` limit of the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AllocationTiling {
+    /// The resource is a buffer or a linear image.
+    Linear,
+    /// The resource is an optimal image.
+    Optimal,
+}
+
+/// The parameters passed to the [`Allocator::allocate`] function.
+#[derive(Debug, Clone)]
+pub struct AllocationDesc {
+    /// The memory requirements of the resource.
+    pub requirements: MemoryRequirements,
+    /// The properties that the memory must have.
+    pub required: MemoryProperties,
+    /// The properties that the memory should have, if possible.
+    pub preferred: MemoryProperties,
+    /// How the resource lays out its data in memory.
+    pub tiling: AllocationTiling,
+    /// The resource that should get its own memory block, regardless of its size.
+    ///
+    /// The memory block is allocated for that resource only, which must then be bound to the
+    /// returned allocation.
+    pub dedicated: Option<DedicatedResource>,
+}
+
+/// The minimum order (log2 of the size) of a buddy allocation.
+///
+/// This is large enough to respect any `nonCoherentAtomSize` limit, which is at most 256.
+const MIN_BUDDY_ORDER: u32 = 8;
+
+/// A pointer to mapped memory.
+#[derive(Clone, Copy)]
+struct MappedPtr(NonNull<u8>);
+
+// SAFETY: the memory behind the pointer is shared with the device anyway, and access to it
+// is synchronized through the allocations that own its ranges.
+unsafe impl Send for MappedPtr {}
+unsafe impl Sync for MappedPtr {}
+
+/// The free space of a buddy-allocated block.
+struct BuddyAllocator {
+    /// The order of the whole block.
+    max_order: u32,
+    /// The offsets of the free ranges, indexed by their order minus [`MIN_BUDDY_ORDER`].
+    free_lists: Vec<Vec<u64>>,
+}
+
+impl BuddyAllocator {
+    /// Creates a new buddy allocator for a block of `1 << max_order` bytes.
+    fn new(max_order: u32) -> Self {
+        let mut free_lists = vec![Vec::new(); (max_order - MIN_BUDDY_ORDER + 1) as usize];
+        free_lists[(max_order - MIN_BUDDY_ORDER) as usize].push(0);
+        Self {
+            max_order,
+            free_lists,
+        }
+    }
+
+    /// Allocates a range of at least `size` bytes aligned to `alignment`.
+    ///
+    /// Returns the offset and the order of the allocated range.
+    fn allocate(&mut self, size: u64, alignment: u64) -> Option<(u64, u32)> {
+        let order = size
+            .max(alignment)
+            .max(1 << MIN_BUDDY_ORDER)
+            .checked_next_power_of_two()?
+            .trailing_zeros();
+
+        if order > self.max_order {
+            return None;
+        }
+
+        let found = (order..=self.max_order)
+            .find(|&k| !self.free_lists[(k - MIN_BUDDY_ORDER) as usize].is_empty())?;
+        let offset = self.free_lists[(found - MIN_BUDDY_ORDER) as usize].pop()?;
+
+        // Split the range until it has the requested order, releasing the upper halves.
+        for split in (order..found).rev() {
+            self.free_lists[(split - MIN_BUDDY_ORDER) as usize].push(offset + (1 << split));
+        }
+
+        Some((offset, order))
+    }
+
+    /// Frees a range previously returned by `allocate`.
+    fn free(&mut self, mut offset: u64, mut order: u32) {
+        while order < self.max_order {
+            let buddy = offset ^ (1 << order);
+            let list = &mut self.free_lists[(order - MIN_BUDDY_ORDER) as usize];
+
+            match list.iter().position(|&o| o == buddy) {
+                Some(index) => {
+                    list.swap_remove(index);
+                    offset = offset.min(buddy);
+                    order += 1;
+                }
+                None => break,
+            }
+        }
+
+        self.free_lists[(order - MIN_BUDDY_ORDER) as usize].push(offset);
+    }
+}
+
+/// The log2 of the number of second-level size classes of a [`TlsfAllocator`].
+const TLSF_SL_LOG2: u32 = 4;
+
+/// The number of second-level size classes of a [`TlsfAllocator`].
+const TLSF_SL_COUNT: u32 = 1 << TLSF_SL_LOG2;
+
+/// The free space of a block managed by a two-level segregated fit allocator.
+///
+/// Free ranges are sorted into size classes: the first level splits sizes by powers of two,
+/// and the second level splits each power of two linearly. Bitmaps of the non-empty classes
+/// find a free range large enough for an allocation without scanning the ranges themselves.
+struct TlsfAllocator {
+    /// The free ranges, as `offset => size` pairs.
+    ranges: BTreeMap<u64, u64>,
+    /// The offsets of the free ranges, indexed by `first_level * TLSF_SL_COUNT + second_level`.
+    classes: Vec<Vec<u64>>,
+    /// The first levels that have at least one non-empty class.
+    first_level_map: u64,
+    /// The non-empty classes of each first level.
+    second_level_maps: Vec<u32>,
+}
+
+impl TlsfAllocator {
+    /// Creates a new TLSF allocator for a block of `size` bytes.
+    fn new(size: u64) -> Self {
+        let first_levels = Self::class_of(size).0 as usize + 1;
+        let mut this = Self {
+            ranges: BTreeMap::new(),
+            classes: vec![Vec::new(); first_levels * TLSF_SL_COUNT as usize],
+            first_level_map: 0,
+            second_level_maps: vec![0; first_levels],
+        };
+        this.insert(0, size);
+        this
+    }
+
+    /// Returns the first and second level of the class containing ranges of `size` bytes.
+    fn class_of(size: u64) -> (u32, u32) {
+        if size < TLSF_SL_COUNT as u64 {
+            return (0, size as u32);
+        }
+
+        let log2 = size.ilog2();
+        let second_level = (size >> (log2 - TLSF_SL_LOG2)) as u32 - TLSF_SL_COUNT;
+        (log2 - TLSF_SL_LOG2 + 1, second_level)
+    }
+
+    /// Allocates a range of `size` bytes aligned to `alignment`.
+    ///
+    /// Returns the offset of the allocated range.
+    fn allocate(&mut self, size: u64, alignment: u64) -> Option<u64> {
+        // Any range of the class found is large enough for the allocation and its alignment
+        // padding, so the search rounds the size up to the next class.
+        let mut search = size.checked_add(alignment - 1)?;
+        if search >= TLSF_SL_COUNT as u64 {
+            search = search.checked_add((1 << (search.ilog2() - TLSF_SL_LOG2)) - 1)?;
+        }
+
+        let (first_level, second_level) = self.find(Self::class_of(search))?;
+        let class = (first_level * TLSF_SL_COUNT + second_level) as usize;
+        let offset = *self.classes[class].last()?;
+        let range_size = self.ranges[&offset];
+        self.remove(offset, range_size);
+
+        // The padding before and after the allocation returns to the free ranges.
+        let start = offset.next_multiple_of(alignment);
+        if start > offset {
+            self.insert(offset, start - offset);
+        }
+        let end = start + size;
+        if end < offset + range_size {
+            self.insert(end, offset + range_size - end);
+        }
+
+        Some(start)
+    }
+
+    /// Frees a range previously returned by `allocate`.
+    fn free(&mut self, mut offset: u64, mut size: u64) {
+        if let Some((&prev, &prev_size)) = self.ranges.range(..offset).next_back() {
+            if prev + prev_size == offset {
+                self.remove(prev, prev_size);
+                offset = prev;
+                size += prev_size;
+            }
+        }
+
+        if let Some(&next_size) = self.ranges.get(&(offset + size)) {
+            self.remove(offset + size, next_size);
+            size += next_size;
+        }
+
+        self.insert(offset, size);
+    }
+
+    /// Returns the first non-empty class at least as large as the provided one.
+    fn find(&self, (first_level, second_level): (u32, u32)) -> Option<(u32, u32)> {
+        let second_level_map =
+            self.second_level_maps.get(first_level as usize)? & (u32::MAX << second_level);
+        if second_level_map != 0 {
+            return Some((first_level, second_level_map.trailing_zeros()));
+        }
+
+        let first_level_map = self.first_level_map & u64::MAX.checked_shl(first_level + 1)?;
+        if first_level_map == 0 {
+            return None;
+        }
+
+        let first_level = first_level_map.trailing_zeros();
+        let second_level = self.second_level_maps[first_level as usize].trailing_zeros();
+        Some((first_level, second_level))
+    }
+
+    /// Adds a free range.
+    fn insert(&mut self, offset: u64, size: u64) {
+        let (first_level, second_level) = Self::class_of(size);
+        self.ranges.insert(offset, size);
+        self.classes[(first_level * TLSF_SL_COUNT + second_level) as usize].push(offset);
+        self.first_level_map |= 1 << first_level;
+        self.second_level_maps[first_level as usize] |= 1 << second_level;
+    }
+
+    /// Removes a free range.
+    fn remove(&mut self, offset: u64, size: u64) {
+        let (first_level, second_level) = Self::class_of(size);
+        self.ranges.remove(&offset);
+
+        let class = &mut self.classes[(first_level * TLSF_SL_COUNT + second_level) as usize];
+        let index = class.iter().position(|&o| o == offset).unwrap();
+        class.swap_remove(index);
+
+        if class.is_empty() {
+            self.second_level_maps[first_level as usize] &= !(1 << second_level);
+            if self.second_level_maps[first_level as usize] == 0 {
+                self.first_level_map &= !(1 << first_level);
+            }
+        }
+    }
+}
+
+/// The free space of a block.
+enum BlockAllocator {
+    /// Allocations are placed after `offset`.
+    Linear { offset: u64 },
+    /// Allocations are placed by a buddy allocator.
+    Buddy(BuddyAllocator),
+    /// Allocations are placed by a TLSF allocator.
+    Tlsf(TlsfAllocator),
+}
+
+/// A memory block from which allocations are sub-allocated.
+struct Block {
+    /// The memory of the block.
+    memory: vk::DeviceMemory,
+    /// The size of the block, in bytes.
+    size: u64,
+    /// The persistent mapping of the block, if its memory is host-visible.
+    mapped: Option<MappedPtr>,
+    /// The free space of the block.
+    allocator: BlockAllocator,
+    /// The live allocations of the block, as `offset => size` pairs.
+    allocations: BTreeMap<u64, u64>,
+    /// The number of bytes reserved by the live allocations.
+    used: u64,
+}
+
+impl Block {
+    /// Allocates a range of `size` bytes aligned to `alignment` in this block.
+    ///
+    /// Returns the offset of the range and its buddy order (zero for linear and TLSF blocks).
+    fn allocate(&mut self, size: u64, alignment: u64) -> Option<(u64, u32)> {
+        let (offset, order, reserved) = match &mut self.allocator {
+            BlockAllocator::Linear { offset } => {
+                let start = offset.checked_next_multiple_of(alignment)?;
+                let end = start.checked_add(size)?;
+                if end > self.size {
+                    return None;
+                }
+                let reserved = end - *offset;
+                *offset = end;
+                (start, 0, reserved)
+            }
+            BlockAllocator::Buddy(buddy) => {
+                let (offset, order) = buddy.allocate(size, alignment)?;
+                (offset, order, 1 << order)
+            }
+            BlockAllocator::Tlsf(tlsf) => (tlsf.allocate(size, alignment)?, 0, size),
+        };
+
+        self.allocations.insert(offset, size);
+        self.used += reserved;
+        Some((offset, order))
+    }
+
+    /// Frees a range previously returned by `allocate`.
+    fn free(&mut self, offset: u64, order: u32) {
+        let size = self.allocations.remove(&offset).unwrap_or(0);
+
+        match &mut self.allocator {
+            BlockAllocator::Linear { offset: next } => {
+                if self.allocations.is_empty() {
+                    *next = 0;
+                    self.used = 0;
+                }
+            }
+            BlockAllocator::Buddy(buddy) => {
+                buddy.free(offset, order);
+                self.used -= 1 << order;
+            }
+            BlockAllocator::Tlsf(tlsf) => {
+                tlsf.free(offset, size);
+                self.used -= size;
+            }
+        }
+    }
+}
+
+/// The memory blocks of a memory type used by resources with a given tiling.
+struct Pool {
+    /// The blocks of the pool.
+    blocks: Vec<Block>,
+    /// The dedicated allocations of the pool, as `(memory, size)` pairs.
+    dedicated: Vec<(vk::DeviceMemory, u64)>,
+}
+
+/// Statistics about the memory managed by an [`Allocator`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AllocatorStats {
+    /// The number of memory blocks shared by sub-allocations.
+    pub block_count: usize,
+    /// The number of dedicated allocations.
+    pub dedicated_count: usize,
+    /// The number of live allocations, including dedicated ones.
+    pub allocation_count: usize,
+    /// The number of bytes allocated from the backend.
+    pub reserved_bytes: u64,
+    /// The number of bytes used by live allocations, including alignment padding and rounding.
+    pub used_bytes: u64,
+}
+
+/// A general-purpose memory allocator which sub-allocates large memory blocks.
+///
+/// Memory blocks of host-visible memory types are persistently mapped.
+pub struct Allocator<B: AllocatorBackend = Device> {
+    /// The backend used to allocate memory.
+    backend: Arc<B>,
+    /// The parameters of the allocator.
+    desc: AllocatorDesc,
+    /// The memory pools, indexed by `memory_type * 2 + tiling`.
+    pools: Box<[Mutex<Pool>]>,
+    /// The number of memory objects currently allocated from the backend.
+    memory_count: AtomicU32,
+}
+
+impl<B: AllocatorBackend> Allocator<B> {
+    /// Creates a new [`Allocator`] on top of the provided backend.
+    pub fn new(backend: Arc<B>, mut desc: AllocatorDesc) -> Arc<Self> {
+        if desc.strategy == AllocationStrategy::Buddy {
+            desc.block_size = desc
+                .block_size
+                .max(1 << MIN_BUDDY_ORDER)
+                .next_power_of_two();
+        }
+
+        let pools = (0..backend.memory_properties().types.len() * 2)
+            .map(|_| {
+                Mutex::new(Pool {
+                    blocks: Vec::new(),
+                    dedicated: Vec::new(),
+                })
+            })
+            .collect();
+
+        Arc::new(Self {
+            backend,
+            desc,
+            pools,
+            memory_count: AtomicU32::new(0),
+        })
+    }
+
+    /// Allocates memory for a resource.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NoSuitableMemoryType`] is returned if no memory type allowed by the resource has
+    /// the required properties, and `ERROR_TOO_MANY_OBJECTS` if a new memory block is needed but
+    /// the `maxMemoryAllocationCount` limit has been reached.
+    pub fn allocate(self: &Arc<Self>, desc: &AllocationDesc) -> Result<Allocation<B>> {
+        let memory_properties = self.backend.memory_properties();
+        let memory_type = memory_properties
+            .find_memory_type(
+                desc.requirements.memory_type_bits,
+                desc.required,
+                desc.preferred,
+            )
+            .ok_or(Error::NoSuitableMemoryType)?;
+        let properties = memory_properties.types[memory_type as usize].properties;
+
+        let mut size = desc.requirements.size.max(1);
+        let mut alignment = desc.requirements.alignment.max(1);
+
+        // Keep allocations of non-coherent memory on distinct atoms, so that flushing or
+        // invalidating one never touches its neighbours.
+        if properties.contains(MemoryProperties::HOST_VISIBLE)
+            && !properties.contains(MemoryProperties::HOST_COHERENT)
+        {
+            let atom = self.backend.limits().non_coherent_atom_size.max(1);
+            alignment = alignment.max(atom);
+            size = size.next_multiple_of(atom);
+        }
+
+        let tiling = if self.backend.limits().buffer_image_granularity > 1 {
+            desc.tiling as usize
+        } else {
+            AllocationTiling::Linear as usize
+        };
+        let pool_index = memory_type as usize * 2 + tiling;
+        let mut pool = self.lock_pool(pool_index);
+
+        if desc.dedicated.is_some()
+            || size >= self.desc.dedicated_threshold
+            || size > self.desc.block_size
+            || alignment > self.desc.block_size
+        {
+            let (memory, mapped) = self.allocate_memory(memory_type, size, desc.dedicated)?;
+            pool.dedicated.push((memory, size));
+
+            return Ok(Allocation {
+                allocator: self.clone(),
+                memory,
+                memory_size: size,
+                memory_type,
+                pool: pool_index,
+                offset: 0,
+                size: desc.requirements.size,
+                tiling: desc.tiling,
+                mapped,
+                placement: Placement::Dedicated,
+            });
+        }
+
+        let existing = pool.blocks.iter_mut().find_map(|block| {
+            let (offset, order) = block.allocate(size, alignment)?;
+            Some((block.memory, block.mapped, offset, order))
+        });
+
+        let (memory, block_mapped, offset, order) = match existing {
+            Some(found) => found,
+            None => {
+                let (memory, mapped) =
+                    self.allocate_memory(memory_type, self.desc.block_size, None)?;
+                let allocator = match self.desc.strategy {
+                    AllocationStrategy::Linear => BlockAllocator::Linear { offset: 0 },
+                    AllocationStrategy::Buddy => BlockAllocator::Buddy(BuddyAllocator::new(
+                        self.desc.block_size.trailing_zeros(),
+                    )),
+                    AllocationStrategy::Tlsf => {
+                        BlockAllocator::Tlsf(TlsfAllocator::new(self.desc.block_size))
+                    }
+                };
+                let mut block = Block {
+                    memory,
+                    size: self.desc.block_size,
+                    mapped,
+                    allocator,
+                    allocations: BTreeMap::new(),
+                    used: 0,
+                };
+                let (offset, order) = block
+                    .allocate(size, alignment)
+                    .expect("allocation does not fit in an empty block");
+                pool.blocks.push(block);
+                (memory, mapped, offset, order)
+            }
+        };
+
+        Ok(Allocation {
+            allocator: self.clone(),
+            memory,
+            memory_size: self.desc.block_size,
+            memory_type,
+            pool: pool_index,
+            offset,
+            size: desc.requirements.size,
+            tiling: desc.tiling,
+            mapped: block_mapped.map(|ptr| MappedPtr(unsafe { ptr.0.add(offset as usize) })),
+            placement: Placement::Block { order },
+        })
+    }
+
+    /// Returns statistics about the memory of each memory type.
+    pub fn stats(&self) -> Box<[AllocatorStats]> {
+        let mut stats = vec![AllocatorStats::default(); self.pools.len() / 2];
+
+        for (index, pool) in self.pools.iter().enumerate() {
+            let pool = self.lock(pool);
+            let stats = &mut stats[index / 2];
+
+            stats.block_count += pool.blocks.len();
+            stats.dedicated_count += pool.dedicated.len();
+            stats.allocation_count += pool.dedicated.len();
+
+            for block in &pool.blocks {
+                stats.allocation_count += block.allocations.len();
+                stats.reserved_bytes += block.size;
+                stats.used_bytes += block.used;
+            }
+
+            for &(_, size) in &pool.dedicated {
+                stats.reserved_bytes += size;
+                stats.used_bytes += size;
+            }
+        }
+
+        stats.into_boxed_slice()
+    }
+
+    /// Returns statistics about the memory of all memory types.
+    pub fn total_stats(&self) -> AllocatorStats {
+        self.stats()
+            .iter()
+            .fold(AllocatorStats::default(), |acc, stats| AllocatorStats {
+                block_count: acc.block_count + stats.block_count,
+                dedicated_count: acc.dedicated_count + stats.dedicated_count,
+                allocation_count: acc.allocation_count + stats.allocation_count,
+                reserved_bytes: acc.reserved_bytes + stats.reserved_bytes,
+                used_bytes: acc.used_bytes + stats.used_bytes,
+            })
+    }
+
+    /// Returns a JSON document describing the layout of the memory managed by this allocator.
+    ///
+    /// This is meant for debugging purposes, and the format of the document may change.
+    pub fn dump_json(&self) -> String {
+        let mut out = String::new();
+        let strategy = match self.desc.strategy {
+            AllocationStrategy::Linear => "linear",
+            AllocationStrategy::Buddy => "buddy",
+            AllocationStrategy::Tlsf => "tlsf",
+        };
+
+        let _ = write!(
+            out,
+            "{{\"strategy\":\"{}\",\"block_size\":{},\"memory_types\":[",
+            strategy, self.desc.block_size
+        );
+
+        for (memory_type, ty) in self.backend.memory_properties().types.iter().enumerate() {
+            if memory_type != 0 {
+                out.push(',');
+            }
+
+            let _ = write!(
+                out,
+                "{{\"index\":{},\"properties\":{},\"heap_index\":{},\"blocks\":[",
+                memory_type,
+                ty.properties.bits(),
+                ty.heap_index
+            );
+
+            let pools = [
+                self.lock_pool(memory_type * 2),
+                self.lock_pool(memory_type * 2 + 1),
+            ];
+            let tilings = ["linear", "optimal"];
+
+            let mut first = true;
+            for (pool, tiling) in pools.iter().zip(tilings) {
+                for block in &pool.blocks {
+                    if !std::mem::take(&mut first) {
+                        out.push(',');
+                    }
+
+                    let _ = write!(
+                        out,
+                        "{{\"tiling\":\"{}\",\"size\":{},\"used\":{},\"mapped\":{},\"allocations\":[",
+                        tiling,
+                        block.size,
+                        block.used,
+                        block.mapped.is_some()
+                    );
+
+                    for (index, (offset, size)) in block.allocations.iter().enumerate() {
+                        if index != 0 {
+                            out.push(',');
+                        }
+                        let _ = write!(out, "{{\"offset\":{},\"size\":{}}}", offset, size);
+                    }
+
+                    out.push_str("]}");
+                }
+            }
+
+            out.push_str("],\"dedicated\":[");
+
+            let mut first = true;
+            for (pool, tiling) in pools.iter().zip(tilings) {
+                for &(_, size) in &pool.dedicated {
+                    if !std::mem::take(&mut first) {
+                        out.push(',');
+                    }
+                    let _ = write!(out, "{{\"tiling\":\"{}\",\"size\":{}}}", tiling, size);
+                }
+            }
+
+            out.push_str("]}");
+        }
+
+        out.push_str("]}");
+        out
+    }
+
+    /// Returns the backend of this allocator.
+    #[inline(always)]
+    pub fn backend(&self) -> &Arc<B> {
+        &self.backend
+    }
+
+    /// Allocates and, if possible, maps a new memory object from the backend.
+    fn allocate_memory(
+        &self,
+        memory_type: u32,
+        size: u64,
+        dedicated: Option<DedicatedResource>,
+    ) -> Result<(vk::DeviceMemory, Option<MappedPtr>)> {
+        let max_count = self.backend.limits().max_memory_allocation_count;
+        self.memory_count
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                (count < max_count).then_some(count + 1)
+            })
+            .map_err(|_| Error::Vulkan(vk::Result::ERROR_TOO_MANY_OBJECTS))?;
+
+        let memory = match self.backend.allocate_memory(memory_type, size, dedicated) {
+            Ok(memory) => memory,
+            Err(err) => {
+                self.memory_count.fetch_sub(1, Ordering::Relaxed);
+                return Err(err);
+            }
+        };
+
+        let properties = self.backend.memory_properties().types[memory_type as usize].properties;
+        if !properties.contains(MemoryProperties::HOST_VISIBLE) {
+            return Ok((memory, None));
+        }
+
+        match unsafe { self.backend.map_memory(memory) } {
+            Ok(ptr) => Ok((memory, Some(MappedPtr(ptr)))),
+            Err(err) => {
+                unsafe { self.free_memory(memory) };
+                Err(err)
+            }
+        }
+    }
+
+    /// Frees a memory object allocated by `allocate_memory`.
+    unsafe fn free_memory(&self, memory: vk::DeviceMemory) {
+        self.backend.free_memory(memory);
+        self.memory_count.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Frees the provided allocation.
+    fn free(&self, allocation: &Allocation<B>) {
+        let mut pool = self.lock_pool(allocation.pool);
+
+        match allocation.placement {
+            Placement::Dedicated => {
+                pool.dedicated
+                    .retain(|&(memory, _)| memory != allocation.memory);
+                unsafe { self.free_memory(allocation.memory) };
+            }
+            Placement::Block { order } => {
+                let index = pool
+                    .blocks
+                    .iter()
+                    .position(|block| block.memory == allocation.memory)
+                    .expect("allocation does not belong to any block");
+
+                let block = &mut pool.blocks[index];
+                block.free(allocation.offset, order);
+
+                // Keep the last block of the pool around to avoid thrashing.
+                if block.allocations.is_empty() && pool.blocks.len() > 1 {
+                    let block = pool.blocks.swap_remove(index);
+                    unsafe { self.free_memory(block.memory) };
+                }
+            }
+        }
+    }
+
+    /// Locks the pool at the provided index.
+    fn lock_pool(&self, index: usize) -> MutexGuard<'_, Pool> {
+        self.lock(&self.pools[index])
+    }
+
+    /// Locks the provided pool, ignoring poisoning.
+    fn lock<'a>(&self, pool: &'a Mutex<Pool>) -> MutexGuard<'a, Pool> {
+        pool.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<B: AllocatorBackend> Drop for Allocator<B> {
+    fn drop(&mut self) {
+        // Allocations keep the allocator alive, so only the blocks remain.
+        for pool in self.pools.iter_mut() {
+            let pool = pool.get_mut().unwrap_or_else(PoisonError::into_inner);
+            for block in pool.blocks.drain(..) {
+                unsafe { self.backend.free_memory(block.memory) };
+            }
+        }
+    }
+}
+
+/// Where an [`Allocation`] lives.
+#[derive(Debug, Clone, Copy)]
+enum Placement {
+    /// The allocation has its own memory object.
+    Dedicated,
+    /// The allocation is part of a block.
+    Block {
+        /// The buddy order of the allocation.
+        order: u32,
+    },
+}
+
+/// A range of memory allocated by an [`Allocator`].
+///
+/// The memory is returned to the allocator when this object is dropped.
+pub struct Allocation<B: AllocatorBackend = Device> {
+    /// The allocator that owns this allocation.
+    allocator: Arc<Allocator<B>>,
+    /// The memory object that the allocation is part of.
+    memory: vk::DeviceMemory,
+    /// The size of the memory object.
+    memory_size: u64,
+    /// The memory type of the allocation.
+    memory_type: u32,
+    /// The index of the pool that the allocation comes from.
+    pool: usize,
+    /// The offset of the allocation within its memory object.
+    offset: u64,
+    /// The requested size of the allocation.
+    size: u64,
+    /// How the resource of the allocation lays out its data in memory.
+    tiling: AllocationTiling,
+    /// A pointer to the first byte of the allocation, if its memory is host-visible.
+    mapped: Option<MappedPtr>,
+    /// Where the allocation lives.
+    placement: Placement,
+}
+
+impl<B: AllocatorBackend> Allocation<B> {
+    /// Returns the memory object that this allocation is part of.
+    #[inline(always)]
+    pub fn memory(&self) -> vk::DeviceMemory {
+        self.memory
+    }
+
+    /// Returns the offset of this allocation within its memory object.
+    #[inline(always)]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the size of this allocation, in bytes.
+    #[inline(always)]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the tiling that this allocation was made for.
+    #[inline(always)]
+    pub fn tiling(&self) -> AllocationTiling {
+        self.tiling
+    }
+
+    /// Returns the index of the memory type of this allocation.
+    #[inline(always)]
+    pub fn memory_type(&self) -> u32 {
+        self.memory_type
+    }
+
+    /// Returns the properties of the memory type of this allocation.
+    #[inline]
+    pub fn properties(&self) -> MemoryProperties {
+        self.allocator.backend.memory_properties().types[self.memory_type as usize].properties
+    }
+
+    /// Returns whether this allocation has its own memory object.
+    #[inline]
+    pub fn is_dedicated(&self) -> bool {
+        matches!(self.placement, Placement::Dedicated)
+    }
+
+    /// Returns a pointer to the first byte of this allocation, if its memory is host-visible.
+    #[inline]
+    pub fn mapped_ptr(&self) -> Option<NonNull<u8>> {
+        self.mapped.map(|ptr| ptr.0)
+    }
+
+    /// Returns the mapped content of this allocation, if its memory is host-visible.
+    #[inline]
+    pub fn mapped_slice(&self) -> Option<&[u8]> {
+        let ptr = self.mapped?.0.as_ptr();
+        Some(unsafe { std::slice::from_raw_parts(ptr, self.size as usize) })
+    }
+
+    /// Returns the mapped content of this allocation, if its memory is host-visible.
+    #[inline]
+    pub fn mapped_slice_mut(&mut self) -> Option<&mut [u8]> {
+        let ptr = self.mapped?.0.as_ptr();
+        Some(unsafe { std::slice::from_raw_parts_mut(ptr, self.size as usize) })
+    }
+
+    /// Makes host writes to this allocation visible to the device.
+    ///
+    /// This is a no-op for host-coherent memory.
+    #[doc(alias = "vkFlushMappedMemoryRanges")]
+    pub fn flush(&self) -> Result<()> {
+        match self.non_coherent_range() {
+            Some((offset, size)) => unsafe {
+                self.allocator
+                    .backend
+                    .flush_memory(self.memory, offset, size)
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Makes device writes to this allocation visible to the host.
+    ///
+    /// This is a no-op for host-coherent memory.
+    #[doc(alias = "vkInvalidateMappedMemoryRanges")]
+    pub fn invalidate(&mut self) -> Result<()> {
+        match self.non_coherent_range() {
+            Some((offset, size)) => unsafe {
+                self.allocator
+                    .backend
+                    .invalidate_memory(self.memory, offset, size)
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the allocator that owns this allocation.
+    #[inline(always)]
+    pub fn allocator(&self) -> &Arc<Allocator<B>> {
+        &self.allocator
+    }
+
+    /// Returns the range to flush or invalidate for this allocation, or `None` if its memory is
+    /// not mapped or host-coherent.
+    fn non_coherent_range(&self) -> Option<(u64, u64)> {
+        self.mapped?;
+
+        if self.properties().contains(MemoryProperties::HOST_COHERENT) {
+            return None;
+        }
+
+        let atom = self
+            .allocator
+            .backend
+            .limits()
+            .non_coherent_atom_size
+            .max(1);
+        let start = self.offset / atom * atom;
+        let end = (self.offset + self.size)
+            .next_multiple_of(atom)
+            .min(self.memory_size);
+
+        Some((start, end - start))
+    }
+}
+
+impl<B: AllocatorBackend> Drop for Allocation<B> {
+    fn drop(&mut self) {
+        self.allocator.free(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemoryHeap, MemoryHeapFlags, MemoryType};
+    use ash::vk::Handle;
+
+    /// A memory object of a [`FakeBackend`].
+    struct FakeMemory {
+        storage: Box<[u8]>,
+        dedicated: Option<DedicatedResource>,
+    }
+
+    /// A backend which allocates its memory on the host.
+    struct FakeBackend {
+        memory_properties: MemoryInfo,
+        limits: PhysicalDeviceLimits,
+        /// The live memory objects, indexed by handle.
+        allocations: Mutex<BTreeMap<u64, FakeMemory>>,
+        next_handle: AtomicU32,
+    }
+
+    impl FakeBackend {
+        /// Creates a backend with a device-local memory type and a host-visible one.
+        fn new(buffer_image_granularity: u64) -> Arc<Self> {
+            let heap = MemoryHeap {
+                size: 1 << 30,
+                flags: MemoryHeapFlags::DEVICE_LOCAL,
+            };
+
+            Arc::new(Self {
+                memory_properties: MemoryInfo {
+                    types: Box::new([
+                        MemoryType {
+                            properties: MemoryProperties::DEVICE_LOCAL,
+                            heap_index: 0,
+                        },
+                        MemoryType {
+                            properties: MemoryProperties::HOST_VISIBLE
+                                | MemoryProperties::HOST_COHERENT,
+                            heap_index: 0,
+                        },
+                    ]),
+                    heaps: Box::new([heap]),
+                },
+                limits: PhysicalDeviceLimits {
+                    max_memory_allocation_count: 16,
+                    buffer_image_granularity,
+                    non_coherent_atom_size: 64,
+                    max_sampler_anisotropy: 1.0,
+                    max_vertex_input_bindings: 16,
+                    max_vertex_input_attributes: 16,
+                    max_vertex_input_attribute_offset: 2047,
+                    max_vertex_input_binding_stride: 2048,
+                    max_tessellation_patch_size: 0,
+                    max_viewports: 1,
+                    max_color_attachments: 4,
+                    line_width_range: [1.0, 1.0],
+                },
+                allocations: Mutex::new(BTreeMap::new()),
+                next_handle: AtomicU32::new(1),
+            })
+        }
+
+        /// Returns the number of live memory objects.
+        fn memory_count(&self) -> usize {
+            self.allocations.lock().unwrap().len()
+        }
+
+        /// Returns the resource that the provided memory object is dedicated to.
+        fn dedicated_resource(&self, memory: vk::DeviceMemory) -> Option<DedicatedResource> {
+            self.allocations.lock().unwrap()[&memory.as_raw()].dedicated
+        }
+    }
+
+    unsafe impl AllocatorBackend for FakeBackend {
+        fn memory_properties(&self) -> &MemoryInfo {
+            &self.memory_properties
+        }
+
+        fn limits(&self) -> &PhysicalDeviceLimits {
+            &self.limits
+        }
+
+        fn allocate_memory(
+            &self,
+            _memory_type: u32,
+            size: u64,
+            dedicated: Option<DedicatedResource>,
+        ) -> Result<vk::DeviceMemory> {
+            let handle = self.next_handle.fetch_add(1, Ordering::Relaxed) as u64;
+            let storage = vec![0; size as usize].into_boxed_slice();
+            self.allocations
+                .lock()
+                .unwrap()
+                .insert(handle, FakeMemory { storage, dedicated });
+            Ok(vk::DeviceMemory::from_raw(handle))
+        }
+
+        unsafe fn free_memory(&self, memory: vk::DeviceMemory) {
+            let freed = self.allocations.lock().unwrap().remove(&memory.as_raw());
+            assert!(freed.is_some(), "double free");
+        }
+
+        unsafe fn map_memory(&self, memory: vk::DeviceMemory) -> Result<NonNull<u8>> {
+            let mut allocations = self.allocations.lock().unwrap();
+            let storage = &mut allocations.get_mut(&memory.as_raw()).unwrap().storage;
+            Ok(NonNull::new(storage.as_mut_ptr()).unwrap())
+        }
+
+        unsafe fn flush_memory(&self, _memory: vk::DeviceMemory, _: u64, _: u64) -> Result<()> {
+            Ok(())
+        }
+
+        unsafe fn invalidate_memory(
+            &self,
+            _memory: vk::DeviceMemory,
+            _: u64,
+            _: u64,
+        ) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn desc(size: u64, alignment: u64, tiling: AllocationTiling) -> AllocationDesc {
+        AllocationDesc {
+            requirements: MemoryRequirements {
+                size,
+                alignment,
+                memory_type_bits: 0b01,
+            },
+            required: MemoryProperties::DEVICE_LOCAL,
+            preferred: MemoryProperties::empty(),
+            tiling,
+            dedicated: None,
+        }
+    }
+
+    fn new_allocator(
+        backend: &Arc<FakeBackend>,
+        strategy: AllocationStrategy,
+    ) -> Arc<Allocator<FakeBackend>> {
+        Allocator::new(
+            backend.clone(),
+            AllocatorDesc {
+                strategy,
+                block_size: 4096,
+                dedicated_threshold: 2048,
+            },
+        )
+    }
+
+    #[test]
+    fn buddy_split_and_merge() {
+        let backend = FakeBackend::new(1);
+        let allocator = new_allocator(&backend, AllocationStrategy::Buddy);
+
+        // Small allocations are rounded up to the minimum order and split from the same block.
+        let a = allocator
+            .allocate(&desc(100, 1, AllocationTiling::Linear))
+            .unwrap();
+        let b = allocator
+            .allocate(&desc(256, 1, AllocationTiling::Linear))
+            .unwrap();
+        let c = allocator
+            .allocate(&desc(1000, 1024, AllocationTiling::Linear))
+            .unwrap();
+        assert_eq!((a.offset(), b.offset(), c.offset()), (0, 256, 1024));
+        assert_eq!(a.memory(), b.memory());
+        assert_eq!(a.memory(), c.memory());
+        assert_eq!(allocator.total_stats().used_bytes, 256 + 256 + 1024);
+
+        // The freed halves merge back, making the whole block available again.
+        drop((a, b, c));
+        assert_eq!(allocator.total_stats().used_bytes, 0);
+
+        let whole = allocator
+            .allocate(&desc(2047, 1, AllocationTiling::Linear))
+            .unwrap();
+        let other = allocator
+            .allocate(&desc(2048 - 256, 1, AllocationTiling::Linear))
+            .unwrap();
+        assert_eq!((whole.offset(), other.offset()), (0, 2048));
+        assert_eq!(allocator.total_stats().block_count, 1);
+        assert_eq!(backend.memory_count(), 1);
+    }
+
+    #[test]
+    fn tlsf_placement_and_coalescing() {
+        let backend = FakeBackend::new(1);
+        let allocator = new_allocator(&backend, AllocationStrategy::Tlsf);
+
+        // Allocations are not rounded up, and the padding of aligned ones remains free.
+        let a = allocator
+            .allocate(&desc(100, 1, AllocationTiling::Linear))
+            .unwrap();
+        let b = allocator
+            .allocate(&desc(300, 256, AllocationTiling::Linear))
+            .unwrap();
+        let c = allocator
+            .allocate(&desc(50, 1, AllocationTiling::Linear))
+            .unwrap();
+        assert_eq!(a.memory(), b.memory());
+        assert_eq!(a.memory(), c.memory());
+        assert_eq!(a.offset(), 0);
+        assert_eq!(b.offset() % 256, 0);
+        assert!(b.offset() >= 100);
+        assert!(c.offset() + 50 <= b.offset() || c.offset() >= b.offset() + 300);
+        assert!(c.offset() >= 100);
+        assert_eq!(allocator.total_stats().used_bytes, 450);
+
+        // Freed ranges merge with their free neighbours, making the whole block available.
+        drop(b);
+        drop((a, c));
+        assert_eq!(allocator.total_stats().used_bytes, 0);
+        let whole = allocator
+            .allocate(&desc(2047, 1, AllocationTiling::Linear))
+            .unwrap();
+        assert_eq!(whole.offset(), 0);
+
+        // The free space is exhausted exactly, without rounding.
+        let rest = allocator
+            .allocate(&desc(2049, 1, AllocationTiling::Linear))
+            .unwrap();
+        assert!(rest.is_dedicated());
+        let fill = allocator
+            .allocate(&desc(2000, 1, AllocationTiling::Linear))
+            .unwrap();
+        assert_eq!((fill.memory(), fill.offset()), (whole.memory(), 2047));
+        assert_eq!(allocator.total_stats().block_count, 1);
+    }
+
+    #[test]
+    fn tlsf_size_classes() {
+        assert_eq!(TlsfAllocator::class_of(0), (0, 0));
+        assert_eq!(TlsfAllocator::class_of(15), (0, 15));
+        assert_eq!(TlsfAllocator::class_of(16), (1, 0));
+        assert_eq!(TlsfAllocator::class_of(31), (1, 15));
+        assert_eq!(TlsfAllocator::class_of(32), (2, 0));
+        assert_eq!(TlsfAllocator::class_of(34), (2, 1));
+        assert_eq!(TlsfAllocator::class_of(4096), (9, 0));
+        assert_eq!(TlsfAllocator::class_of(4096 + 255), (9, 0));
+        assert_eq!(TlsfAllocator::class_of(4096 + 256), (9, 1));
+
+        // A search never returns a range smaller than requested.
+        let mut tlsf = TlsfAllocator::new(4096);
+        assert_eq!(tlsf.allocate(4096, 1), Some(0));
+        assert_eq!(tlsf.allocate(1, 1), None);
+        tlsf.free(0, 4096);
+        assert_eq!(tlsf.allocate(4097, 1), None);
+        assert_eq!(tlsf.allocate(1000, 1024), Some(0));
+        assert_eq!(tlsf.allocate(1000, 1024), Some(1024));
+    }
+
+    #[test]
+    fn linear_reset() {
+        let backend = FakeBackend::new(1);
+        let allocator = new_allocator(&backend, AllocationStrategy::Linear);
+
+        let a = allocator
+            .allocate(&desc(1000, 1, AllocationTiling::Linear))
+            .unwrap();
+        let b = allocator
+            .allocate(&desc(1000, 16, AllocationTiling::Linear))
+            .unwrap();
+        assert_eq!((a.offset(), b.offset()), (0, 1008));
+
+        // The space of a block is not reclaimed while some of its allocations are alive.
+        drop(a);
+        let c = allocator
+            .allocate(&desc(1000, 1, AllocationTiling::Linear))
+            .unwrap();
+        assert_eq!(c.offset(), 2008);
+        let d = allocator
+            .allocate(&desc(1500, 1, AllocationTiling::Linear))
+            .unwrap();
+        assert_ne!(d.memory(), b.memory());
+        assert_eq!(allocator.total_stats().block_count, 2);
+
+        // Empty blocks are released, except for the last block of the pool.
+        drop(d);
+        assert_eq!(allocator.total_stats().block_count, 1);
+
+        // Once they are all freed, allocations start over from the beginning of the block.
+        let memory = b.memory();
+        drop((b, c));
+        let e = allocator
+            .allocate(&desc(2000, 1, AllocationTiling::Linear))
+            .unwrap();
+        let f = allocator
+            .allocate(&desc(2000, 1, AllocationTiling::Linear))
+            .unwrap();
+        assert_eq!(e.offset(), 0);
+        assert_eq!(e.memory(), memory);
+        assert_eq!(f.offset(), 2000);
+        assert_eq!(f.memory(), memory);
+    }
+
+    #[test]
+    fn buffer_image_granularity_separates_tilings() {
+        let backend = FakeBackend::new(1024);
+        let allocator = new_allocator(&backend, AllocationStrategy::Buddy);
+
+        let linear = allocator
+            .allocate(&desc(256, 1, AllocationTiling::Linear))
+            .unwrap();
+        let optimal = allocator
+            .allocate(&desc(256, 1, AllocationTiling::Optimal))
+            .unwrap();
+        assert_ne!(linear.memory(), optimal.memory());
+        assert_eq!(allocator.total_stats().block_count, 2);
+
+        // Without a granularity constraint, both tilings share the same blocks.
+        let backend = FakeBackend::new(1);
+        let allocator = new_allocator(&backend, AllocationStrategy::Buddy);
+
+        let linear = allocator
+            .allocate(&desc(256, 1, AllocationTiling::Linear))
+            .unwrap();
+        let optimal = allocator
+            .allocate(&desc(256, 1, AllocationTiling::Optimal))
+            .unwrap();
+        assert_eq!(linear.memory(), optimal.memory());
+        assert_eq!(allocator.total_stats().block_count, 1);
+        // The tiling is still recorded, so that resources can check it when bound.
+        assert_eq!(linear.tiling(), AllocationTiling::Linear);
+        assert_eq!(optimal.tiling(), AllocationTiling::Optimal);
+    }
+
+    #[test]
+    fn dedicated_allocations() {
+        let backend = FakeBackend::new(1);
+        let allocator = new_allocator(&backend, AllocationStrategy::Buddy);
+
+        let below = allocator
+            .allocate(&desc(2047, 1, AllocationTiling::Linear))
+            .unwrap();
+        assert!(!below.is_dedicated());
+        assert_eq!(backend.dedicated_resource(below.memory()), None);
+
+        // Allocations reaching the threshold get their own memory object, which is not tied
+        // to any resource.
+        let threshold = allocator
+            .allocate(&desc(2048, 1, AllocationTiling::Linear))
+            .unwrap();
+        assert!(threshold.is_dedicated());
+        assert_eq!(threshold.offset(), 0);
+        assert_eq!(backend.dedicated_resource(threshold.memory()), None);
+
+        // Requested dedicated allocations are made for the provided resource.
+        let image = vk::Image::from_raw(42);
+        let requested = allocator
+            .allocate(&AllocationDesc {
+                dedicated: Some(DedicatedResource::Image(image)),
+                ..desc(16, 1, AllocationTiling::Optimal)
+            })
+            .unwrap();
+        assert!(requested.is_dedicated());
+        assert_eq!(
+            backend.dedicated_resource(requested.memory()),
+            Some(DedicatedResource::Image(image))
+        );
+
+        // Alignments larger than a block cannot be satisfied within one.
+        let aligned = allocator
+            .allocate(&desc(16, 8192, AllocationTiling::Linear))
+            .unwrap();
+        assert!(aligned.is_dedicated());
+        assert_eq!(aligned.offset(), 0);
+
+        assert_eq!(backend.memory_count(), 4);
+        drop((threshold, requested, aligned));
+        assert_eq!(backend.memory_count(), 1);
+    }
+
+    #[test]
+    fn stats() {
+        let backend = FakeBackend::new(1);
+        let allocator = new_allocator(&backend, AllocationStrategy::Buddy);
+
+        let small = allocator
+            .allocate(&desc(300, 1, AllocationTiling::Linear))
+            .unwrap();
+        let large = allocator
+            .allocate(&desc(3000, 1, AllocationTiling::Linear))
+            .unwrap();
+        let mapped = allocator
+            .allocate(&AllocationDesc {
+                requirements: MemoryRequirements {
+                    size: 100,
+                    alignment: 1,
+                    memory_type_bits: 0b11,
+                },
+                required: MemoryProperties::HOST_VISIBLE,
+                ..desc(0, 0, AllocationTiling::Linear)
+            })
+            .unwrap();
+        assert_eq!(mapped.memory_type(), 1);
+        assert!(mapped.mapped_slice().is_some());
+        assert!(small.mapped_slice().is_none());
+
+        let stats = allocator.stats();
+        assert_eq!(
+            stats[0],
+            AllocatorStats {
+                block_count: 1,
+                dedicated_count: 1,
+                allocation_count: 2,
+                reserved_bytes: 4096 + 3000,
+                used_bytes: 512 + 3000,
+            }
+        );
+        assert_eq!(
+            stats[1],
+            AllocatorStats {
+                block_count: 1,
+                dedicated_count: 0,
+                allocation_count: 1,
+                reserved_bytes: 4096,
+                used_bytes: 256,
+            }
+        );
+        assert_eq!(allocator.total_stats().allocation_count, 3);
+
+        drop((small, large, mapped));
+        let total = allocator.total_stats();
+        assert_eq!(total.allocation_count, 0);
+        assert_eq!(total.used_bytes, 0);
+        assert_eq!(total.dedicated_count, 0);
+    }
+}
@@ -0,0 +1,395 @@
+use std::mem::MaybeUninit;
+use std::sync::Arc;
+
+use ash::vk;
+use bitflags::bitflags;
+use bytemuck::Pod;
+
+use crate::memory::BoundMemory;
+use crate::{
+    Allocation, AllocationDesc, AllocationTiling, Allocator, Device, DeviceFeatures, DeviceMemory,
+    Error, MemoryProperties, MemoryRequirements, Result, SharingMode,
+};
+
+bitflags! {
+    /// Flags specifying a collection of [`BufferUsage`]s.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct BufferUsages: u32 {
+        const TRANSFER_SRC = vk::BufferUsageFlags::TRANSFER_SRC.as_raw();
+        const TRANSFER_DST = vk::BufferUsageFlags::TRANSFER_DST.as_raw();
+        const UNIFORM_TEXEL_BUFFER = vk::BufferUsageFlags::UNIFORM_TEXEL_BUFFER.as_raw();
+        const STORAGE_TEXEL_BUFFER = vk::BufferUsageFlags::STORAGE_TEXEL_BUFFER.as_raw();
+        const UNIFORM_BUFFER = vk::BufferUsageFlags::UNIFORM_BUFFER.as_raw();
+        const STORAGE_BUFFER = vk::BufferUsageFlags::STORAGE_BUFFER.as_raw();
+        const INDEX_BUFFER = vk::BufferUsageFlags::INDEX_BUFFER.as_raw();
+        const VERTEX_BUFFER = vk::BufferUsageFlags::VERTEX_BUFFER.as_raw();
+        const INDIRECT_BUFFER = vk::BufferUsageFlags::INDIRECT_BUFFER.as_raw();
+        const SHADER_DEVICE_ADDRESS = vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS.as_raw();
+    }
+}
+
+impl From<BufferUsage> for BufferUsages {
+    fn from(value: BufferUsage) -> Self {
+        match value {
+            BufferUsage::TransferSrc => Self::TRANSFER_SRC,
+            BufferUsage::TransferDst => Self::TRANSFER_DST,
+            BufferUsage::UniformTexelBuffer => Self::UNIFORM_TEXEL_BUFFER,
+            BufferUsage::StorageTexelBuffer => Self::STORAGE_TEXEL_BUFFER,
+            BufferUsage::UniformBuffer => Self::UNIFORM_BUFFER,
+            BufferUsage::StorageBuffer => Self::STORAGE_BUFFER,
+            BufferUsage::IndexBuffer => Self::INDEX_BUFFER,
+            BufferUsage::VertexBuffer => Self::VERTEX_BUFFER,
+            BufferUsage::IndirectBuffer => Self::INDIRECT_BUFFER,
+            BufferUsage::ShaderDeviceAddress => Self::SHADER_DEVICE_ADDRESS,
+        }
+    }
+}
+
+/// A possible usage for a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum BufferUsage {
+    TransferSrc = vk::BufferUsageFlags::TRANSFER_SRC.as_raw(),
+    TransferDst = vk::BufferUsageFlags::TRANSFER_DST.as_raw(),
+    UniformTexelBuffer = vk::BufferUsageFlags::UNIFORM_TEXEL_BUFFER.as_raw(),
+    StorageTexelBuffer = vk::BufferUsageFlags::STORAGE_TEXEL_BUFFER.as_raw(),
+    UniformBuffer = vk::BufferUsageFlags::UNIFORM_BUFFER.as_raw(),
+    StorageBuffer = vk::BufferUsageFlags::STORAGE_BUFFER.as_raw(),
+    IndexBuffer = vk::BufferUsageFlags::INDEX_BUFFER.as_raw(),
+    VertexBuffer = vk::BufferUsageFlags::VERTEX_BUFFER.as_raw(),
+    IndirectBuffer = vk::BufferUsageFlags::INDIRECT_BUFFER.as_raw(),
+    ShaderDeviceAddress = vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS.as_raw(),
+}
+
+/// The parameters passed to the [`Buffer::new`] function.
+#[derive(Debug, Clone)]
+#[doc(alias = "VkBufferCreateInfo")]
+pub struct BufferDesc<'a> {
+    /// The size of the buffer, in bytes.
+    pub size: u64,
+    /// The ways the buffer will be used.
+    pub usage: BufferUsages,
+    /// The sharing mode of the buffer.
+    pub sharing_mode: SharingMode<&'a [u32]>,
+}
+
+/// A linear array of data that can be used by the device.
+///
+/// The buffer is destroyed when this object is dropped, and the memory it is bound to is
+/// kept alive until then.
+pub struct Buffer {
+    /// The device that owns this buffer.
+    device: Arc<Device>,
+
+    /// The handle to the buffer.
+    handle: vk::Buffer,
+    /// The size of the buffer, in bytes.
+    size: u64,
+    /// The ways the buffer may be used.
+    usage: BufferUsages,
+    /// The memory that the buffer is bound to, if any.
+    memory: Option<BoundMemory>,
+}
+
+impl Buffer {
+    /// Creates a new [`Buffer`] instance from the provided handle.
+    ///
+    /// # Safety
+    ///
+    /// The provided handle must be valid and belong to the provided device. `size` and `usage`
+    /// must match the parameters used to create it. The created [`Buffer`] will take care of
+    /// destroying it when it is dropped.
+    pub unsafe fn from_handle(
+        device: Arc<Device>,
+        handle: vk::Buffer,
+        size: u64,
+        usage: BufferUsages,
+    ) -> Self {
+        Self {
+            device,
+            handle,
+            size,
+            usage,
+            memory: None,
+        }
+    }
+
+    /// Creates a new [`Buffer`].
+    ///
+    /// The buffer is not bound to any memory yet.
+    #[doc(alias = "vkCreateBuffer")]
+    pub fn new(device: Arc<Device>, desc: BufferDesc) -> Result<Self> {
+        let (sharing_mode, queue_families) = desc.sharing_mode.to_raw();
+
+        let create_info = vk::BufferCreateInfo {
+            flags: vk::BufferCreateFlags::empty(),
+            size: desc.size,
+            usage: vk::BufferUsageFlags::from_raw(desc.usage.bits()),
+            sharing_mode,
+            queue_family_index_count: queue_families.len() as u32,
+            p_queue_family_indices: queue_families.as_ptr(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::BUFFER_CREATE_INFO,
+        };
+
+        let mut handle = vk::Buffer::null();
+
+        let ret = unsafe {
+            (device.fns().create_buffer)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(unsafe { Self::from_handle(device, handle, desc.size, desc.usage) })
+    }
+
+    /// Creates a new host-visible [`Buffer`] initialized with the content of `data`.
+    ///
+    /// The buffer is exclusive to a single queue family, and its memory is allocated from the
+    /// provided allocator.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `data` is empty.
+    pub fn from_slice<T: Pod>(
+        allocator: &Arc<Allocator>,
+        usage: BufferUsages,
+        data: &[T],
+    ) -> Result<Self> {
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+        assert!(!bytes.is_empty(), "cannot create an empty buffer");
+
+        let mut buffer = Self::new(
+            allocator.backend().clone(),
+            BufferDesc {
+                size: bytes.len() as u64,
+                usage,
+                sharing_mode: SharingMode::Exclusive,
+            },
+        )?;
+
+        let mut allocation = allocator.allocate(&AllocationDesc {
+            requirements: buffer.memory_requirements(),
+            required: MemoryProperties::HOST_VISIBLE,
+            preferred: MemoryProperties::HOST_COHERENT,
+            tiling: AllocationTiling::Linear,
+            dedicated: None,
+        })?;
+
+        allocation
+            .mapped_slice_mut()
+            .expect("host-visible memory is not mapped")[..bytes.len()]
+            .copy_from_slice(bytes);
+        allocation.flush()?;
+
+        buffer.bind_allocation(allocation)?;
+        Ok(buffer)
+    }
+
+    /// Returns the memory requirements of this buffer.
+    #[doc(alias = "vkGetBufferMemoryRequirements")]
+    pub fn memory_requirements(&self) -> MemoryRequirements {
+        let mut requirements = MaybeUninit::<vk::MemoryRequirements>::uninit();
+
+        let requirements = unsafe {
+            (self.device.fns().get_buffer_memory_requirements)(
+                self.device.handle(),
+                self.handle,
+                requirements.as_mut_ptr(),
+            );
+            requirements.assume_init()
+        };
+
+        MemoryRequirements {
+            size: requirements.size,
+            alignment: requirements.alignment,
+            memory_type_bits: requirements.memory_type_bits,
+        }
+    }
+
+    /// Binds this buffer to the provided memory, starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the buffer is already bound to memory, if the memory belongs to
+    /// another device, or if it does not satisfy the requirements of the buffer.
+    #[doc(alias = "vkBindBufferMemory")]
+    pub fn bind_memory(&mut self, memory: Arc<DeviceMemory>, offset: u64) -> Result<()> {
+        self.check_binding(memory.device(), memory.memory_type(), offset, memory.size());
+        unsafe { self.bind_raw(memory.handle(), offset)? };
+        self.memory = Some(BoundMemory::Device(memory));
+        Ok(())
+    }
+
+    /// Binds this buffer to the provided allocation.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the buffer is already bound to memory, if the allocation was
+    /// made on another device or for [`AllocationTiling::Optimal`], or if it does not satisfy
+    /// the requirements of the buffer.
+    #[doc(alias = "vkBindBufferMemory")]
+    pub fn bind_allocation(&mut self, allocation: Allocation) -> Result<()> {
+        assert_eq!(
+            allocation.tiling(),
+            AllocationTiling::Linear,
+            "buffers must be bound to allocations made for linear tiling"
+        );
+        self.check_binding(
+            allocation.allocator().backend(),
+            allocation.memory_type(),
+            allocation.offset(),
+            allocation.offset() + allocation.size(),
+        );
+        unsafe { self.bind_raw(allocation.memory(), allocation.offset())? };
+        self.memory = Some(BoundMemory::Allocation(allocation));
+        Ok(())
+    }
+
+    /// Returns the device address of this buffer.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::MissingDeviceFeature`] is returned if the `bufferDeviceAddress` feature was
+    /// not enabled on the device.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the buffer was not created with the
+    /// [`SHADER_DEVICE_ADDRESS`](BufferUsages::SHADER_DEVICE_ADDRESS) usage, or if it is not
+    /// bound to memory yet.
+    #[doc(alias = "vkGetBufferDeviceAddress")]
+    pub fn device_address(&self) -> Result<u64> {
+        let get_address = self
+            .device
+            .fns()
+            .get_buffer_device_address
+            .filter(|_| {
+                self.device
+                    .features()
+                    .contains(DeviceFeatures::BUFFER_DEVICE_ADDRESS)
+            })
+            .ok_or(Error::MissingDeviceFeature(
+                DeviceFeatures::BUFFER_DEVICE_ADDRESS,
+            ))?;
+
+        assert!(
+            self.usage.contains(BufferUsages::SHADER_DEVICE_ADDRESS),
+            "buffer was not created with the SHADER_DEVICE_ADDRESS usage"
+        );
+        assert!(self.memory.is_some(), "buffer is not bound to memory");
+
+        let info = vk::BufferDeviceAddressInfo {
+            buffer: self.handle,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::BUFFER_DEVICE_ADDRESS_INFO,
+        };
+
+        Ok(unsafe { get_address(self.device.handle(), &info) })
+    }
+
+    /// Returns the allocation that this buffer is bound to, if it was bound with
+    /// [`bind_allocation`](Self::bind_allocation).
+    #[inline]
+    pub fn allocation(&self) -> Option<&Allocation> {
+        match &self.memory {
+            Some(BoundMemory::Allocation(allocation)) => Some(allocation),
+            _ => None,
+        }
+    }
+
+    /// Returns the allocation that this buffer is bound to, if it was bound with
+    /// [`bind_allocation`](Self::bind_allocation).
+    #[inline]
+    pub fn allocation_mut(&mut self) -> Option<&mut Allocation> {
+        match &mut self.memory {
+            Some(BoundMemory::Allocation(allocation)) => Some(allocation),
+            _ => None,
+        }
+    }
+
+    /// Returns the size of this buffer, in bytes.
+    #[inline(always)]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the ways this buffer may be used.
+    #[inline(always)]
+    pub fn usage(&self) -> BufferUsages {
+        self.usage
+    }
+
+    /// Returns the device that owns this buffer.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this buffer.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::Buffer {
+        self.handle
+    }
+
+    /// Checks that memory of the provided device and type, available up to `end`, can be bound
+    /// at `offset`.
+    fn check_binding(&self, device: &Arc<Device>, memory_type: u32, offset: u64, end: u64) {
+        assert!(
+            Arc::ptr_eq(device, &self.device),
+            "the memory does not belong to the same device as the buffer"
+        );
+        assert!(self.memory.is_none(), "buffer is already bound to memory");
+
+        let requirements = self.memory_requirements();
+        assert!(
+            requirements.memory_type_bits & (1 << memory_type) != 0,
+            "memory type not allowed for this buffer"
+        );
+        assert!(
+            offset.is_multiple_of(requirements.alignment.max(1)),
+            "memory offset is not properly aligned"
+        );
+        assert!(
+            offset + requirements.size <= end,
+            "memory is too small for this buffer"
+        );
+    }
+
+    /// Binds the buffer to the provided memory.
+    ///
+    /// # Safety
+    ///
+    /// The memory must satisfy the requirements of the buffer.
+    unsafe fn bind_raw(&self, memory: vk::DeviceMemory, offset: u64) -> Result<()> {
+        let ret = (self.device.fns().bind_buffer_memory)(
+            self.device.handle(),
+            self.handle,
+            memory,
+            offset,
+        );
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_buffer)(self.device.handle(), self.handle, std::ptr::null());
+        }
+    }
+}
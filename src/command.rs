@@ -0,0 +1,1303 @@
+use std::cell::Cell;
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use ash::vk;
+use bitflags::bitflags;
+use smallvec::SmallVec;
+
+use crate::{
+    Buffer, BufferBarrier, Device, DeviceFeatures, Error, Filter, Format, Image, ImageBarrier,
+    ImageLayout, ImageSubresourceLayers, ImageSubresourceRange, IndexType, MemoryBarrier,
+    PipelineBindPoint, PipelineStages, Rect, Result, SampleCount, ShaderStages, Viewport,
+};
+
+bitflags! {
+    /// Flags specifying how a [`CommandPool`] is used.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct CommandPoolFlags: u32 {
+        /// The command buffers allocated from the pool are short-lived.
+        const TRANSIENT = vk::CommandPoolCreateFlags::TRANSIENT.as_raw();
+        /// The command buffers allocated from the pool can be reset individually.
+        const RESET_COMMAND_BUFFER = vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER.as_raw();
+    }
+}
+
+bitflags! {
+    /// Flags specifying how a [`CommandBuffer`] is used once it has been recorded.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct CommandBufferUsages: u32 {
+        /// The command buffer is submitted only once, and then reset or freed.
+        const ONE_TIME_SUBMIT = vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT.as_raw();
+        /// The secondary command buffer is entirely executed within a render pass instance.
+        const RENDER_PASS_CONTINUE = vk::CommandBufferUsageFlags::RENDER_PASS_CONTINUE.as_raw();
+        /// The command buffer may be pending execution multiple times at once.
+        const SIMULTANEOUS_USE = vk::CommandBufferUsageFlags::SIMULTANEOUS_USE.as_raw();
+    }
+}
+
+/// The parameters passed to the [`CommandPool::new`] function.
+#[derive(Debug, Clone)]
+#[doc(alias = "VkCommandPoolCreateInfo")]
+pub struct CommandPoolDesc {
+    /// The queue family that the command buffers allocated from the pool are submitted to.
+    pub queue_family: u32,
+    /// How the pool is used.
+    pub flags: CommandPoolFlags,
+}
+
+/// The part of a [`CommandPool`] that is shared with its command buffers.
+struct PoolShared {
+    /// The device that owns the pool.
+    device: Arc<Device>,
+
+    /// The handle to the pool.
+    handle: vk::CommandPool,
+    /// The flags the pool was created with.
+    flags: CommandPoolFlags,
+    /// The queue family of the pool.
+    queue_family: u32,
+    /// The command buffers that have been dropped but not freed yet.
+    ///
+    /// Freeing a command buffer requires external synchronization on its pool, so command
+    /// buffers dropped on other threads are only freed by the thread owning the pool.
+    pending_free: Mutex<Vec<vk::CommandBuffer>>,
+}
+
+impl PoolShared {
+    /// Frees the command buffers that have been dropped since the last call.
+    ///
+    /// # Safety
+    ///
+    /// The caller must have exclusive access to the pool.
+    unsafe fn free_pending(&self) {
+        let pending = {
+            let mut pending_free = self
+                .pending_free
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            std::mem::take(&mut *pending_free)
+        };
+
+        if !pending.is_empty() {
+            (self.device.fns().free_command_buffers)(
+                self.device.handle(),
+                self.handle,
+                pending.len() as u32,
+                pending.as_ptr(),
+            );
+        }
+    }
+}
+
+impl Drop for PoolShared {
+    fn drop(&mut self) {
+        // Destroying the pool frees all of its command buffers.
+        unsafe {
+            (self.device.fns().destroy_command_pool)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
+
+/// A pool from which [`CommandBuffer`]s are allocated.
+///
+/// Vulkan requires access to a pool and to its command buffers to be externally synchronized.
+/// A [`CommandPool`] can therefore be sent to another thread, but not shared between threads.
+/// Command buffers record against a borrow of their pool, which keeps recording on the thread
+/// owning the pool, while executable command buffers can be sent anywhere.
+pub struct CommandPool {
+    /// The state shared with the command buffers of the pool.
+    shared: Arc<PoolShared>,
+    /// Makes the pool `!Sync`.
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+impl CommandPool {
+    /// Creates a new [`CommandPool`].
+    #[doc(alias = "vkCreateCommandPool")]
+    pub fn new(device: Arc<Device>, desc: CommandPoolDesc) -> Result<Self> {
+        let create_info = vk::CommandPoolCreateInfo {
+            flags: vk::CommandPoolCreateFlags::from_raw(desc.flags.bits()),
+            queue_family_index: desc.queue_family,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::COMMAND_POOL_CREATE_INFO,
+        };
+
+        let mut handle = vk::CommandPool::null();
+
+        let ret = unsafe {
+            (device.fns().create_command_pool)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self {
+            shared: Arc::new(PoolShared {
+                device,
+                handle,
+                flags: desc.flags,
+                queue_family: desc.queue_family,
+                pending_free: Mutex::new(Vec::new()),
+            }),
+            _not_sync: PhantomData,
+        })
+    }
+
+    /// Allocates a new primary [`CommandBuffer`] from this pool.
+    ///
+    /// Command buffers that were dropped since the last allocation are freed first.
+    #[doc(alias = "vkAllocateCommandBuffers")]
+    pub fn allocate(&self) -> Result<CommandBuffer<Initial>> {
+        self.allocate_raw(vk::CommandBufferLevel::PRIMARY)
+    }
+
+    /// Allocates a new secondary [`CommandBuffer`] from this pool.
+    ///
+    /// Secondary command buffers are executed by primary command buffers, through the
+    /// [`CommandBuffer::execute_commands`] function.
+    #[doc(alias = "vkAllocateCommandBuffers")]
+    pub fn allocate_secondary(&self) -> Result<CommandBuffer<Initial, Secondary>> {
+        self.allocate_raw(vk::CommandBufferLevel::SECONDARY)
+    }
+
+    /// Allocates a new command buffer of the provided level from this pool.
+    fn allocate_raw<L>(&self, level: vk::CommandBufferLevel) -> Result<CommandBuffer<Initial, L>> {
+        unsafe { self.shared.free_pending() };
+
+        let allocate_info = vk::CommandBufferAllocateInfo {
+            command_pool: self.shared.handle,
+            level,
+            command_buffer_count: 1,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::COMMAND_BUFFER_ALLOCATE_INFO,
+        };
+
+        let device = &self.shared.device;
+        let mut handle = vk::CommandBuffer::null();
+
+        let ret = unsafe {
+            (device.fns().allocate_command_buffers)(device.handle(), &allocate_info, &mut handle)
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(CommandBuffer {
+            raw: RawCommandBuffer {
+                pool: self.shared.clone(),
+                handle,
+            },
+            _state: PhantomData,
+        })
+    }
+
+    /// Resets this pool, moving all of its command buffers back to the initial state.
+    ///
+    /// # Safety
+    ///
+    /// None of the command buffers allocated from this pool may be pending execution, and
+    /// the [`Executable`] command buffers of the pool must not be submitted again before they
+    /// are recorded again.
+    #[doc(alias = "vkResetCommandPool")]
+    pub unsafe fn reset(&mut self, release_resources: bool) -> Result<()> {
+        self.shared.free_pending();
+
+        let flags = if release_resources {
+            vk::CommandPoolResetFlags::RELEASE_RESOURCES
+        } else {
+            vk::CommandPoolResetFlags::empty()
+        };
+
+        let device = &self.shared.device;
+        let ret = (device.fns().reset_command_pool)(device.handle(), self.shared.handle, flags);
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the flags this pool was created with.
+    #[inline(always)]
+    pub fn flags(&self) -> CommandPoolFlags {
+        self.shared.flags
+    }
+
+    /// Returns the queue family of this pool.
+    #[inline(always)]
+    pub fn queue_family(&self) -> u32 {
+        self.shared.queue_family
+    }
+
+    /// Returns the device that owns this pool.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.shared.device
+    }
+
+    /// Returns the Vulkan handle for this pool.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::CommandPool {
+        self.shared.handle
+    }
+}
+
+/// A command buffer handle, which is given back to its pool when dropped.
+struct RawCommandBuffer {
+    /// The pool that the command buffer was allocated from.
+    pool: Arc<PoolShared>,
+    /// The handle to the command buffer.
+    handle: vk::CommandBuffer,
+}
+
+impl Drop for RawCommandBuffer {
+    fn drop(&mut self) {
+        self.pool
+            .pending_free
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(self.handle);
+    }
+}
+
+/// The state of a [`CommandBuffer`] that can begin recording.
+#[derive(Debug)]
+pub struct Initial;
+
+/// The state of a [`CommandBuffer`] that is recording commands.
+///
+/// The state borrows the pool of the command buffer, which keeps the command buffer on the
+/// thread owning the pool until the recording ends.
+#[derive(Debug)]
+pub struct Recording<'p>(PhantomData<&'p CommandPool>);
+
+/// The state of a [`CommandBuffer`] that has been recorded and can be submitted.
+#[derive(Debug)]
+pub struct Executable;
+
+/// The level of a [`CommandBuffer`] that is submitted to queues.
+#[derive(Debug)]
+pub struct Primary;
+
+/// The level of a [`CommandBuffer`] that is executed by primary command buffers.
+#[derive(Debug)]
+pub struct Secondary;
+
+/// The state inherited by a secondary [`CommandBuffer`] from the render pass instance it is
+/// executed within.
+#[derive(Debug, Clone)]
+#[doc(alias = "VkCommandBufferInheritanceInfo")]
+pub enum CommandBufferInheritance<'a> {
+    /// The command buffer is executed within a subpass of a render pass.
+    RenderPass {
+        /// The render pass that the command buffer is executed within.
+        render_pass: vk::RenderPass,
+        /// The index of the subpass that the command buffer is executed within.
+        subpass: u32,
+        /// The framebuffer that is rendered to, if it is known.
+        framebuffer: Option<vk::Framebuffer>,
+    },
+    /// The command buffer is executed within a dynamic render pass.
+    ///
+    /// This requires the [`DeviceFeatures::DYNAMIC_RENDERING`] feature.
+    #[doc(alias = "VkCommandBufferInheritanceRenderingInfo")]
+    Rendering {
+        /// The formats of the color attachments.
+        color_formats: &'a [Format],
+        /// The format of the depth attachment, if any.
+        depth_format: Option<Format>,
+        /// The format of the stencil attachment, if any.
+        stencil_format: Option<Format>,
+        /// The number of samples of the attachments.
+        samples: SampleCount,
+    },
+}
+
+/// A buffer of commands to be executed by a queue.
+///
+/// The type parameters track the state of the command buffer ([`Initial`], [`Recording`] or
+/// [`Executable`]) and its level ([`Primary`] or [`Secondary`]). Dropping a command buffer
+/// gives it back to its pool, which frees it the next time it allocates or is reset. A command
+/// buffer must not be dropped while it is pending execution.
+///
+/// Commands that set state are safe to record. Commands that reference resources, or that
+/// execute work relying on bound state, are `unsafe`: the command buffer does not keep the
+/// resources alive, so they must outlive its execution, and the ranges and layouts passed to
+/// the commands are not validated.
+pub struct CommandBuffer<S, L = Primary> {
+    /// The handle and the pool of the command buffer.
+    raw: RawCommandBuffer,
+    /// The state and the level of the command buffer.
+    _state: PhantomData<(S, L)>,
+}
+
+impl<S, L> CommandBuffer<S, L> {
+    /// Returns the device that owns this command buffer.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.raw.pool.device
+    }
+
+    /// Returns the queue family that this command buffer can be submitted to.
+    #[inline(always)]
+    pub fn queue_family(&self) -> u32 {
+        self.raw.pool.queue_family
+    }
+
+    /// Returns the Vulkan handle for this command buffer.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::CommandBuffer {
+        self.raw.handle
+    }
+}
+
+impl<L> CommandBuffer<Initial, L> {
+    /// Begins recording this command buffer, with the provided inheritance information.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `pool` is not the pool that the command buffer was allocated
+    /// from.
+    fn begin_raw<'p>(
+        self,
+        pool: &'p CommandPool,
+        usage: CommandBufferUsages,
+        inheritance_info: Option<&vk::CommandBufferInheritanceInfo>,
+    ) -> Result<CommandBuffer<Recording<'p>, L>> {
+        assert!(
+            Arc::ptr_eq(&pool.shared, &self.raw.pool),
+            "the command buffer was not allocated from this pool"
+        );
+
+        let begin_info = vk::CommandBufferBeginInfo {
+            flags: vk::CommandBufferUsageFlags::from_raw(usage.bits()),
+            p_inheritance_info: inheritance_info.map_or(std::ptr::null(), |info| info),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::COMMAND_BUFFER_BEGIN_INFO,
+        };
+
+        let ret =
+            unsafe { (self.device().fns().begin_command_buffer)(self.raw.handle, &begin_info) };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(CommandBuffer {
+            raw: self.raw,
+            _state: PhantomData,
+        })
+    }
+}
+
+impl CommandBuffer<Initial> {
+    /// Begins recording this command buffer.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `pool` is not the pool that the command buffer was allocated
+    /// from.
+    #[doc(alias = "vkBeginCommandBuffer")]
+    pub fn begin(
+        self,
+        pool: &CommandPool,
+        usage: CommandBufferUsages,
+    ) -> Result<CommandBuffer<Recording<'_>>> {
+        self.begin_raw(pool, usage, None)
+    }
+}
+
+impl CommandBuffer<Initial, Secondary> {
+    /// Begins recording this secondary command buffer.
+    ///
+    /// When `inheritance` is provided, the command buffer is entirely executed within the
+    /// provided render pass instance.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::MissingDeviceFeature`] if the command buffer inherits a
+    /// dynamic render pass but the [`DeviceFeatures::DYNAMIC_RENDERING`] feature is not
+    /// enabled.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `pool` is not the pool that the command buffer was allocated
+    /// from.
+    #[doc(alias = "vkBeginCommandBuffer")]
+    pub fn begin<'p>(
+        self,
+        pool: &'p CommandPool,
+        mut usage: CommandBufferUsages,
+        inheritance: Option<&CommandBufferInheritance>,
+    ) -> Result<CommandBuffer<Recording<'p>, Secondary>> {
+        let mut inheritance_info = vk::CommandBufferInheritanceInfo::default();
+        let mut rendering_info = vk::CommandBufferInheritanceRenderingInfoKHR::default();
+        let color_formats: SmallVec<[_; 4]>;
+
+        match inheritance {
+            None => (),
+            Some(CommandBufferInheritance::RenderPass {
+                render_pass,
+                subpass,
+                framebuffer,
+            }) => {
+                inheritance_info.render_pass = *render_pass;
+                inheritance_info.subpass = *subpass;
+                inheritance_info.framebuffer = framebuffer.unwrap_or_default();
+            }
+            Some(CommandBufferInheritance::Rendering {
+                color_formats: formats,
+                depth_format,
+                stencil_format,
+                samples,
+            }) => {
+                if !self
+                    .device()
+                    .features()
+                    .contains(DeviceFeatures::DYNAMIC_RENDERING)
+                {
+                    return Err(Error::MissingDeviceFeature(
+                        DeviceFeatures::DYNAMIC_RENDERING,
+                    ));
+                }
+
+                color_formats = formats
+                    .iter()
+                    .map(|&format| vk::Format::from_raw(format as i32))
+                    .collect();
+
+                rendering_info.color_attachment_count = color_formats.len() as u32;
+                rendering_info.p_color_attachment_formats = color_formats.as_ptr();
+                rendering_info.depth_attachment_format =
+                    depth_format.map_or(vk::Format::UNDEFINED, |f| vk::Format::from_raw(f as i32));
+                rendering_info.stencil_attachment_format = stencil_format
+                    .map_or(vk::Format::UNDEFINED, |f| vk::Format::from_raw(f as i32));
+                rendering_info.rasterization_samples =
+                    vk::SampleCountFlags::from_raw(*samples as u32);
+
+                inheritance_info.p_next = &rendering_info as *const _ as *const c_void;
+            }
+        }
+
+        if inheritance.is_some() {
+            usage |= CommandBufferUsages::RENDER_PASS_CONTINUE;
+        }
+
+        self.begin_raw(pool, usage, Some(&inheritance_info))
+    }
+}
+
+impl<L> CommandBuffer<Executable, L> {
+    /// Resets this command buffer to the initial state, so that it can be recorded again.
+    ///
+    /// Like [`begin`](CommandBuffer::begin), this borrows the pool of the command buffer, which
+    /// keeps the pool from being used on another thread during the reset.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `pool` is not the pool that the command buffer was allocated
+    /// from, or if it was not created with [`CommandPoolFlags::RESET_COMMAND_BUFFER`].
+    ///
+    /// # Safety
+    ///
+    /// The command buffer must not be pending execution.
+    #[doc(alias = "vkResetCommandBuffer")]
+    pub unsafe fn reset(
+        self,
+        pool: &CommandPool,
+        release_resources: bool,
+    ) -> Result<CommandBuffer<Initial, L>> {
+        assert!(
+            Arc::ptr_eq(&pool.shared, &self.raw.pool),
+            "the command buffer was not allocated from this pool"
+        );
+        assert!(
+            self.raw
+                .pool
+                .flags
+                .contains(CommandPoolFlags::RESET_COMMAND_BUFFER),
+            "the command pool was not created with the RESET_COMMAND_BUFFER flag"
+        );
+
+        let flags = if release_resources {
+            vk::CommandBufferResetFlags::RELEASE_RESOURCES
+        } else {
+            vk::CommandBufferResetFlags::empty()
+        };
+
+        let ret = (self.device().fns().reset_command_buffer)(self.raw.handle, flags);
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(CommandBuffer {
+            raw: self.raw,
+            _state: PhantomData,
+        })
+    }
+}
+
+/// A region copied between two buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkBufferCopy")]
+pub struct BufferCopy {
+    /// The offset of the region in the source buffer.
+    pub src_offset: u64,
+    /// The offset of the region in the destination buffer.
+    pub dst_offset: u64,
+    /// The size of the region.
+    pub size: u64,
+}
+
+/// A region copied between a buffer and an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkBufferImageCopy")]
+pub struct BufferImageCopy {
+    /// The offset of the region in the buffer.
+    pub buffer_offset: u64,
+    /// The number of texels between two rows of the region in the buffer, or zero if the rows
+    /// are tightly packed.
+    pub buffer_row_length: u32,
+    /// The number of rows between two slices of the region in the buffer, or zero if the
+    /// slices are tightly packed.
+    pub buffer_image_height: u32,
+    /// The subresource of the image that the region belongs to.
+    pub image_subresource: ImageSubresourceLayers,
+    /// The offset of the region in the image, in texels.
+    pub image_offset: [i32; 3],
+    /// The size of the region, in texels.
+    pub image_extent: [u32; 3],
+}
+
+impl BufferImageCopy {
+    /// Converts this region into its raw Vulkan representation.
+    fn to_raw(self) -> vk::BufferImageCopy {
+        vk::BufferImageCopy {
+            buffer_offset: self.buffer_offset,
+            buffer_row_length: self.buffer_row_length,
+            buffer_image_height: self.buffer_image_height,
+            image_subresource: self.image_subresource.to_raw(),
+            image_offset: offset_to_raw(self.image_offset),
+            image_extent: extent_to_raw(self.image_extent),
+        }
+    }
+}
+
+/// A region copied between two images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkImageCopy")]
+pub struct ImageCopy {
+    /// The subresource of the source image that the region belongs to.
+    pub src_subresource: ImageSubresourceLayers,
+    /// The offset of the region in the source image, in texels.
+    pub src_offset: [i32; 3],
+    /// The subresource of the destination image that the region belongs to.
+    pub dst_subresource: ImageSubresourceLayers,
+    /// The offset of the region in the destination image, in texels.
+    pub dst_offset: [i32; 3],
+    /// The size of the region, in texels.
+    pub extent: [u32; 3],
+}
+
+impl ImageCopy {
+    /// Converts this region into its raw Vulkan representation.
+    fn to_raw(self) -> vk::ImageCopy {
+        vk::ImageCopy {
+            src_subresource: self.src_subresource.to_raw(),
+            src_offset: offset_to_raw(self.src_offset),
+            dst_subresource: self.dst_subresource.to_raw(),
+            dst_offset: offset_to_raw(self.dst_offset),
+            extent: extent_to_raw(self.extent),
+        }
+    }
+}
+
+/// A region blitted between two images, which may be scaled and converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkImageBlit")]
+pub struct ImageBlit {
+    /// The subresource of the source image that the region belongs to.
+    pub src_subresource: ImageSubresourceLayers,
+    /// The opposite corners of the region in the source image, in texels.
+    pub src_offsets: [[i32; 3]; 2],
+    /// The subresource of the destination image that the region belongs to.
+    pub dst_subresource: ImageSubresourceLayers,
+    /// The opposite corners of the region in the destination image, in texels.
+    pub dst_offsets: [[i32; 3]; 2],
+}
+
+impl ImageBlit {
+    /// Converts this region into its raw Vulkan representation.
+    fn to_raw(self) -> vk::ImageBlit {
+        vk::ImageBlit {
+            src_subresource: self.src_subresource.to_raw(),
+            src_offsets: self.src_offsets.map(offset_to_raw),
+            dst_subresource: self.dst_subresource.to_raw(),
+            dst_offsets: self.dst_offsets.map(offset_to_raw),
+        }
+    }
+}
+
+/// A value that a color image is cleared to.
+///
+/// The variant must match the numeric format of the image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[doc(alias = "VkClearColorValue")]
+pub enum ClearColor {
+    Float([f32; 4]),
+    Int([i32; 4]),
+    Uint([u32; 4]),
+}
+
+impl ClearColor {
+    /// Converts this value into its raw Vulkan representation.
+    pub(crate) fn to_raw(self) -> vk::ClearColorValue {
+        match self {
+            Self::Float(float32) => vk::ClearColorValue { float32 },
+            Self::Int(int32) => vk::ClearColorValue { int32 },
+            Self::Uint(uint32) => vk::ClearColorValue { uint32 },
+        }
+    }
+}
+
+fn offset_to_raw([x, y, z]: [i32; 3]) -> vk::Offset3D {
+    vk::Offset3D { x, y, z }
+}
+
+fn extent_to_raw([width, height, depth]: [u32; 3]) -> vk::Extent3D {
+    vk::Extent3D {
+        width,
+        height,
+        depth,
+    }
+}
+
+impl<'p, L> CommandBuffer<Recording<'p>, L> {
+    /// Ends the recording of this command buffer.
+    #[doc(alias = "vkEndCommandBuffer")]
+    pub fn end(self) -> Result<CommandBuffer<Executable, L>> {
+        let ret = unsafe { (self.device().fns().end_command_buffer)(self.raw.handle) };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(CommandBuffer {
+            raw: self.raw,
+            _state: PhantomData,
+        })
+    }
+
+    /// Copies regions of a buffer into another buffer.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dst` must outlive the execution of the command buffer, and the regions must be in
+    /// bounds of both buffers without overlapping each other.
+    #[doc(alias = "vkCmdCopyBuffer")]
+    pub unsafe fn copy_buffer(&mut self, src: &Buffer, dst: &Buffer, regions: &[BufferCopy]) {
+        if regions.is_empty() {
+            return;
+        }
+
+        let regions: SmallVec<[_; 4]> = regions
+            .iter()
+            .map(|region| vk::BufferCopy {
+                src_offset: region.src_offset,
+                dst_offset: region.dst_offset,
+                size: region.size,
+            })
+            .collect();
+
+        (self.device().fns().cmd_copy_buffer)(
+            self.raw.handle,
+            src.handle(),
+            dst.handle(),
+            regions.len() as u32,
+            regions.as_ptr(),
+        );
+    }
+
+    /// Copies regions of a buffer into an image.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dst` must outlive the execution of the command buffer, the regions must be in
+    /// bounds of both resources, and `dst` must be in `dst_layout` when the command executes.
+    #[doc(alias = "vkCmdCopyBufferToImage")]
+    pub unsafe fn copy_buffer_to_image(
+        &mut self,
+        src: &Buffer,
+        dst: &Image,
+        dst_layout: ImageLayout,
+        regions: &[BufferImageCopy],
+    ) {
+        if regions.is_empty() {
+            return;
+        }
+
+        let regions: SmallVec<[_; 4]> = regions.iter().map(|region| region.to_raw()).collect();
+
+        (self.device().fns().cmd_copy_buffer_to_image)(
+            self.raw.handle,
+            src.handle(),
+            dst.handle(),
+            vk::ImageLayout::from_raw(dst_layout as i32),
+            regions.len() as u32,
+            regions.as_ptr(),
+        );
+    }
+
+    /// Copies regions of an image into a buffer.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dst` must outlive the execution of the command buffer, the regions must be in
+    /// bounds of both resources, and `src` must be in `src_layout` when the command executes.
+    #[doc(alias = "vkCmdCopyImageToBuffer")]
+    pub unsafe fn copy_image_to_buffer(
+        &mut self,
+        src: &Image,
+        src_layout: ImageLayout,
+        dst: &Buffer,
+        regions: &[BufferImageCopy],
+    ) {
+        if regions.is_empty() {
+            return;
+        }
+
+        let regions: SmallVec<[_; 4]> = regions.iter().map(|region| region.to_raw()).collect();
+
+        (self.device().fns().cmd_copy_image_to_buffer)(
+            self.raw.handle,
+            src.handle(),
+            vk::ImageLayout::from_raw(src_layout as i32),
+            dst.handle(),
+            regions.len() as u32,
+            regions.as_ptr(),
+        );
+    }
+
+    /// Copies regions of an image into another image.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dst` must outlive the execution of the command buffer, the regions must be in
+    /// bounds of both images, and the images must be in `src_layout` and `dst_layout` when the
+    /// command executes.
+    #[doc(alias = "vkCmdCopyImage")]
+    pub unsafe fn copy_image(
+        &mut self,
+        src: &Image,
+        src_layout: ImageLayout,
+        dst: &Image,
+        dst_layout: ImageLayout,
+        regions: &[ImageCopy],
+    ) {
+        if regions.is_empty() {
+            return;
+        }
+
+        let regions: SmallVec<[_; 4]> = regions.iter().map(|region| region.to_raw()).collect();
+
+        (self.device().fns().cmd_copy_image)(
+            self.raw.handle,
+            src.handle(),
+            vk::ImageLayout::from_raw(src_layout as i32),
+            dst.handle(),
+            vk::ImageLayout::from_raw(dst_layout as i32),
+            regions.len() as u32,
+            regions.as_ptr(),
+        );
+    }
+
+    /// Copies regions of an image into another image, scaling and converting the texels as
+    /// needed.
+    ///
+    /// # Safety
+    ///
+    /// `src` and `dst` must outlive the execution of the command buffer, the regions must be in
+    /// bounds of both images, and the images must be in `src_layout` and `dst_layout` when the
+    /// command executes.
+    #[doc(alias = "vkCmdBlitImage")]
+    pub unsafe fn blit_image(
+        &mut self,
+        src: &Image,
+        src_layout: ImageLayout,
+        dst: &Image,
+        dst_layout: ImageLayout,
+        regions: &[ImageBlit],
+        filter: Filter,
+    ) {
+        if regions.is_empty() {
+            return;
+        }
+
+        let regions: SmallVec<[_; 4]> = regions.iter().map(|region| region.to_raw()).collect();
+
+        (self.device().fns().cmd_blit_image)(
+            self.raw.handle,
+            src.handle(),
+            vk::ImageLayout::from_raw(src_layout as i32),
+            dst.handle(),
+            vk::ImageLayout::from_raw(dst_layout as i32),
+            regions.len() as u32,
+            regions.as_ptr(),
+            vk::Filter::from_raw(filter as i32),
+        );
+    }
+
+    /// Clears ranges of a color image.
+    ///
+    /// # Safety
+    ///
+    /// `image` must outlive the execution of the command buffer, the ranges must be in bounds of
+    /// the image, and the image must be in `layout` when the command executes.
+    #[doc(alias = "vkCmdClearColorImage")]
+    pub unsafe fn clear_color_image(
+        &mut self,
+        image: &Image,
+        layout: ImageLayout,
+        color: ClearColor,
+        ranges: &[ImageSubresourceRange],
+    ) {
+        if ranges.is_empty() {
+            return;
+        }
+
+        let color = color.to_raw();
+        let ranges: SmallVec<[_; 4]> = ranges.iter().map(|range| range.to_raw()).collect();
+
+        (self.device().fns().cmd_clear_color_image)(
+            self.raw.handle,
+            image.handle(),
+            vk::ImageLayout::from_raw(layout as i32),
+            &color,
+            ranges.len() as u32,
+            ranges.as_ptr(),
+        );
+    }
+
+    /// Clears ranges of a depth/stencil image.
+    ///
+    /// # Safety
+    ///
+    /// `image` must outlive the execution of the command buffer, the ranges must be in bounds of
+    /// the image, and the image must be in `layout` when the command executes.
+    #[doc(alias = "vkCmdClearDepthStencilImage")]
+    pub unsafe fn clear_depth_stencil_image(
+        &mut self,
+        image: &Image,
+        layout: ImageLayout,
+        depth: f32,
+        stencil: u32,
+        ranges: &[ImageSubresourceRange],
+    ) {
+        if ranges.is_empty() {
+            return;
+        }
+
+        let value = vk::ClearDepthStencilValue { depth, stencil };
+        let ranges: SmallVec<[_; 4]> = ranges.iter().map(|range| range.to_raw()).collect();
+
+        (self.device().fns().cmd_clear_depth_stencil_image)(
+            self.raw.handle,
+            image.handle(),
+            vk::ImageLayout::from_raw(layout as i32),
+            &value,
+            ranges.len() as u32,
+            ranges.as_ptr(),
+        );
+    }
+
+    /// Fills a range of a buffer with a repeated 32-bit value.
+    ///
+    /// `size` may be `vk::WHOLE_SIZE` to fill the buffer up to its end.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must outlive the execution of the command buffer, and the filled range must be in
+    /// bounds of the buffer.
+    #[doc(alias = "vkCmdFillBuffer")]
+    pub unsafe fn fill_buffer(&mut self, buffer: &Buffer, offset: u64, size: u64, data: u32) {
+        (self.device().fns().cmd_fill_buffer)(self.raw.handle, buffer.handle(), offset, size, data);
+    }
+
+    /// Inserts a dependency between the commands recorded before and after this one.
+    ///
+    /// # Safety
+    ///
+    /// The buffers and images referenced by the barriers must outlive the execution of the
+    /// command buffer, and the ranges and layouts of the barriers must match their state when the
+    /// command executes.
+    #[doc(alias = "vkCmdPipelineBarrier")]
+    pub unsafe fn pipeline_barrier(
+        &mut self,
+        src_stages: PipelineStages,
+        dst_stages: PipelineStages,
+        memory_barriers: &[MemoryBarrier],
+        buffer_barriers: &[BufferBarrier],
+        image_barriers: &[ImageBarrier],
+    ) {
+        let memory_barriers: SmallVec<[_; 2]> = memory_barriers
+            .iter()
+            .map(|barrier| barrier.to_raw())
+            .collect();
+        let buffer_barriers: SmallVec<[_; 4]> = buffer_barriers
+            .iter()
+            .map(|barrier| barrier.to_raw())
+            .collect();
+        let image_barriers: SmallVec<[_; 4]> = image_barriers
+            .iter()
+            .map(|barrier| barrier.to_raw())
+            .collect();
+
+        (self.device().fns().cmd_pipeline_barrier)(
+            self.raw.handle,
+            vk::PipelineStageFlags::from_raw(src_stages.bits()),
+            vk::PipelineStageFlags::from_raw(dst_stages.bits()),
+            vk::DependencyFlags::empty(),
+            memory_barriers.len() as u32,
+            memory_barriers.as_ptr(),
+            buffer_barriers.len() as u32,
+            buffer_barriers.as_ptr(),
+            image_barriers.len() as u32,
+            image_barriers.as_ptr(),
+        );
+    }
+
+    /// Binds a pipeline to the command buffer.
+    ///
+    /// # Safety
+    ///
+    /// `pipeline` must be a valid pipeline of the device, created for `bind_point`, which
+    /// outlives the execution of the command buffer.
+    #[doc(alias = "vkCmdBindPipeline")]
+    pub unsafe fn bind_pipeline(&mut self, bind_point: PipelineBindPoint, pipeline: vk::Pipeline) {
+        (self.device().fns().cmd_bind_pipeline)(
+            self.raw.handle,
+            vk::PipelineBindPoint::from_raw(bind_point as i32),
+            pipeline,
+        );
+    }
+
+    /// Binds descriptor sets to the command buffer, starting at set `first_set`.
+    ///
+    /// # Safety
+    ///
+    /// `layout` and `sets` must be valid objects of the device which outlive the execution of
+    /// the command buffer, and `dynamic_offsets` must provide one offset per dynamic
+    /// descriptor of the sets.
+    #[doc(alias = "vkCmdBindDescriptorSets")]
+    pub unsafe fn bind_descriptor_sets(
+        &mut self,
+        bind_point: PipelineBindPoint,
+        layout: vk::PipelineLayout,
+        first_set: u32,
+        sets: &[vk::DescriptorSet],
+        dynamic_offsets: &[u32],
+    ) {
+        if sets.is_empty() {
+            return;
+        }
+
+        (self.device().fns().cmd_bind_descriptor_sets)(
+            self.raw.handle,
+            vk::PipelineBindPoint::from_raw(bind_point as i32),
+            layout,
+            first_set,
+            sets.len() as u32,
+            sets.as_ptr(),
+            dynamic_offsets.len() as u32,
+            dynamic_offsets.as_ptr(),
+        );
+    }
+
+    /// Updates the push constants of the provided stages, starting at byte `offset`.
+    ///
+    /// # Safety
+    ///
+    /// `layout` must be a valid pipeline layout of the device whose push constant ranges
+    /// cover the updated bytes for `stages`.
+    #[doc(alias = "vkCmdPushConstants")]
+    pub unsafe fn push_constants(
+        &mut self,
+        layout: vk::PipelineLayout,
+        stages: ShaderStages,
+        offset: u32,
+        data: &[u8],
+    ) {
+        if data.is_empty() {
+            return;
+        }
+
+        (self.device().fns().cmd_push_constants)(
+            self.raw.handle,
+            layout,
+            vk::ShaderStageFlags::from_raw(stages.bits()),
+            offset,
+            data.len() as u32,
+            data.as_ptr().cast(),
+        );
+    }
+
+    /// Binds vertex buffers to the command buffer, starting at binding `first_binding`.
+    ///
+    /// Each buffer is paired with the offset at which its vertex data starts.
+    ///
+    /// # Safety
+    ///
+    /// The buffers must outlive the execution of the command buffer, and the offsets must be in
+    /// bounds of their buffer.
+    #[doc(alias = "vkCmdBindVertexBuffers")]
+    pub unsafe fn bind_vertex_buffers(&mut self, first_binding: u32, buffers: &[(&Buffer, u64)]) {
+        if buffers.is_empty() {
+            return;
+        }
+
+        let (handles, offsets): (SmallVec<[_; 4]>, SmallVec<[_; 4]>) = buffers
+            .iter()
+            .map(|(buffer, offset)| (buffer.handle(), *offset))
+            .unzip();
+
+        (self.device().fns().cmd_bind_vertex_buffers)(
+            self.raw.handle,
+            first_binding,
+            handles.len() as u32,
+            handles.as_ptr(),
+            offsets.as_ptr(),
+        );
+    }
+
+    /// Binds an index buffer to the command buffer.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must outlive the execution of the command buffer, and `offset` must be in bounds of
+    /// the buffer.
+    #[doc(alias = "vkCmdBindIndexBuffer")]
+    pub unsafe fn bind_index_buffer(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        index_type: IndexType,
+    ) {
+        (self.device().fns().cmd_bind_index_buffer)(
+            self.raw.handle,
+            buffer.handle(),
+            offset,
+            vk::IndexType::from_raw(index_type as i32),
+        );
+    }
+
+    /// Sets the viewports of the command buffer, starting at viewport `first`.
+    #[doc(alias = "vkCmdSetViewport")]
+    pub fn set_viewports(&mut self, first: u32, viewports: &[Viewport]) {
+        if viewports.is_empty() {
+            return;
+        }
+
+        let viewports: SmallVec<[_; 1]> =
+            viewports.iter().map(|viewport| viewport.to_raw()).collect();
+
+        unsafe {
+            (self.device().fns().cmd_set_viewport)(
+                self.raw.handle,
+                first,
+                viewports.len() as u32,
+                viewports.as_ptr(),
+            );
+        }
+    }
+
+    /// Sets the scissor rectangles of the command buffer, starting at scissor `first`.
+    #[doc(alias = "vkCmdSetScissor")]
+    pub fn set_scissors(&mut self, first: u32, scissors: &[Rect]) {
+        if scissors.is_empty() {
+            return;
+        }
+
+        let scissors: SmallVec<[_; 1]> = scissors.iter().map(|scissor| scissor.to_raw()).collect();
+
+        unsafe {
+            (self.device().fns().cmd_set_scissor)(
+                self.raw.handle,
+                first,
+                scissors.len() as u32,
+                scissors.as_ptr(),
+            );
+        }
+    }
+
+    /// Draws primitives from the bound vertex buffers.
+    ///
+    /// # Safety
+    ///
+    /// A graphics pipeline must be bound, and the state and resources that it uses must be valid
+    /// for this draw when the command executes.
+    #[doc(alias = "vkCmdDraw")]
+    pub unsafe fn draw(
+        &mut self,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) {
+        (self.device().fns().cmd_draw)(
+            self.raw.handle,
+            vertex_count,
+            instance_count,
+            first_vertex,
+            first_instance,
+        );
+    }
+
+    /// Draws indexed primitives from the bound index and vertex buffers.
+    ///
+    /// # Safety
+    ///
+    /// A graphics pipeline and an index buffer must be bound, and the state and resources that
+    /// they use must be valid for this draw when the command executes.
+    #[doc(alias = "vkCmdDrawIndexed")]
+    pub unsafe fn draw_indexed(
+        &mut self,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) {
+        (self.device().fns().cmd_draw_indexed)(
+            self.raw.handle,
+            index_count,
+            instance_count,
+            first_index,
+            vertex_offset,
+            first_instance,
+        );
+    }
+
+    /// Draws primitives, reading the parameters of `draw_count` draws from a buffer.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must outlive the execution of the command buffer, and the parameters of the draws
+    /// must be in bounds of it. A graphics pipeline must be bound, and the state and resources
+    /// that it uses must be valid for these draws when the command executes.
+    #[doc(alias = "vkCmdDrawIndirect")]
+    pub unsafe fn draw_indirect(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        (self.device().fns().cmd_draw_indirect)(
+            self.raw.handle,
+            buffer.handle(),
+            offset,
+            draw_count,
+            stride,
+        );
+    }
+
+    /// Draws indexed primitives, reading the parameters of `draw_count` draws from a buffer.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must outlive the execution of the command buffer, and the parameters of the draws
+    /// must be in bounds of it. A graphics pipeline and an index buffer must be bound, and the
+    /// state and resources that they use must be valid for these draws when the command executes.
+    #[doc(alias = "vkCmdDrawIndexedIndirect")]
+    pub unsafe fn draw_indexed_indirect(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        (self.device().fns().cmd_draw_indexed_indirect)(
+            self.raw.handle,
+            buffer.handle(),
+            offset,
+            draw_count,
+            stride,
+        );
+    }
+
+    /// Dispatches compute work groups.
+    ///
+    /// # Safety
+    ///
+    /// A compute pipeline must be bound, and the resources that it uses must be valid for this
+    /// dispatch when the command executes.
+    #[doc(alias = "vkCmdDispatch")]
+    pub unsafe fn dispatch(&mut self, group_count: [u32; 3]) {
+        (self.device().fns().cmd_dispatch)(
+            self.raw.handle,
+            group_count[0],
+            group_count[1],
+            group_count[2],
+        );
+    }
+
+    /// Dispatches compute work groups, reading the group count from a buffer.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must outlive the execution of the command buffer, and the group count must be in
+    /// bounds of it. A compute pipeline must be bound, and the resources that it uses must be
+    /// valid for this dispatch when the command executes.
+    #[doc(alias = "vkCmdDispatchIndirect")]
+    pub unsafe fn dispatch_indirect(&mut self, buffer: &Buffer, offset: u64) {
+        (self.device().fns().cmd_dispatch_indirect)(self.raw.handle, buffer.handle(), offset);
+    }
+}
+
+impl CommandBuffer<Recording<'_>> {
+    /// Executes secondary command buffers from this primary command buffer.
+    ///
+    /// # Safety
+    ///
+    /// The secondary command buffers must not be pending execution in another primary command
+    /// buffer unless they were recorded with [`CommandBufferUsages::SIMULTANEOUS_USE`], and
+    /// the ones that inherit a render pass instance must be executed within a compatible one.
+    #[doc(alias = "vkCmdExecuteCommands")]
+    pub unsafe fn execute_commands(
+        &mut self,
+        command_buffers: &[&CommandBuffer<Executable, Secondary>],
+    ) {
+        if command_buffers.is_empty() {
+            return;
+        }
+
+        let handles: SmallVec<[_; 8]> = command_buffers
+            .iter()
+            .map(|command_buffer| command_buffer.handle())
+            .collect();
+
+        (self.device().fns().cmd_execute_commands)(
+            self.raw.handle,
+            handles.len() as u32,
+            handles.as_ptr(),
+        );
+    }
+}
@@ -0,0 +1,476 @@
+use std::ffi::CString;
+use std::sync::Arc;
+
+use ash::vk;
+
+use crate::pipeline::Specialization;
+use crate::{
+    CommandBuffer, Device, DeviceExtensions, Error, PipelineBindPoint, PipelineLayout, Recording,
+    Result, ShaderModule, ShaderStage, SpecializationValue, WorkgroupSize,
+};
+
+impl WorkgroupSize {
+    /// Returns the number of workgroups to dispatch so that at least `invocations`
+    /// invocations run along each dimension.
+    pub fn group_count(&self, invocations: [u32; 3]) -> [u32; 3] {
+        group_count(self.size, invocations)
+    }
+}
+
+/// Divides `invocations` by `size` along each dimension, rounding up.
+fn group_count(size: [u32; 3], invocations: [u32; 3]) -> [u32; 3] {
+    [
+        invocations[0].div_ceil(size[0]),
+        invocations[1].div_ceil(size[1]),
+        invocations[2].div_ceil(size[2]),
+    ]
+}
+
+/// The parameters passed to the [`ComputePipeline::new`] function.
+#[derive(Clone, Copy)]
+#[doc(alias = "VkComputePipelineCreateInfo")]
+pub struct ComputePipelineDesc<'a> {
+    /// The module containing the compute shader.
+    pub module: &'a ShaderModule,
+    /// The name of the entry point of the compute shader.
+    pub entry_point: &'a str,
+    /// The values of the specialization constants, as `(constant_id, value)` pairs.
+    pub specialization: &'a [(u32, SpecializationValue)],
+    /// The layout of the resources accessed by the shader.
+    pub layout: &'a PipelineLayout,
+    /// Whether the pipeline may be used with [`CommandBuffer::dispatch_base`].
+    pub dispatch_base: bool,
+}
+
+/// A pipeline which runs a compute shader.
+pub struct ComputePipeline {
+    /// The device that owns this pipeline.
+    device: Arc<Device>,
+
+    /// The handle to the pipeline.
+    handle: vk::Pipeline,
+    /// The handle to the layout of the pipeline.
+    layout: vk::PipelineLayout,
+    /// The size of the workgroups, after specialization.
+    workgroup_size: [u32; 3],
+}
+
+impl ComputePipeline {
+    /// Creates a new [`ComputePipeline`].
+    ///
+    /// The entry point is looked up in the module before the driver is called.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidSpirv`] if the module does not declare a compute
+    /// entry point with the provided name.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if a specialization constant declared by the shader is given a
+    /// value of a different size than its type.
+    #[doc(alias = "vkCreateComputePipelines")]
+    pub fn new(desc: ComputePipelineDesc) -> Result<Self> {
+        let module = desc.module;
+        let device = module.device();
+
+        let reflection = module.reflect(desc.entry_point, ShaderStage::Compute)?;
+        let specialization = Specialization::new(desc.specialization, &reflection);
+        let specialization_info = specialization.to_raw();
+
+        let workgroup_size = reflection.workgroup_size().map_or([1; 3], |size| {
+            let mut result = size.size;
+            for (dim, spec_id) in size.spec_ids.iter().enumerate() {
+                let value = desc
+                    .specialization
+                    .iter()
+                    .find(|(id, _)| Some(*id) == *spec_id)
+                    .and_then(|(_, value)| value.as_u64());
+                if let Some(value) = value {
+                    result[dim] = value as u32;
+                }
+            }
+            result
+        });
+
+        // The entry point was found in the module, so its name cannot contain a nul byte.
+        let name = CString::new(desc.entry_point).unwrap();
+
+        let create_info = vk::ComputePipelineCreateInfo {
+            flags: if desc.dispatch_base {
+                vk::PipelineCreateFlags::DISPATCH_BASE
+            } else {
+                vk::PipelineCreateFlags::empty()
+            },
+            stage: vk::PipelineShaderStageCreateInfo {
+                flags: vk::PipelineShaderStageCreateFlags::empty(),
+                stage: vk::ShaderStageFlags::COMPUTE,
+                module: module.handle(),
+                p_name: name.as_ptr(),
+                p_specialization_info: &specialization_info,
+
+                p_next: std::ptr::null(),
+                s_type: vk::StructureType::PIPELINE_SHADER_STAGE_CREATE_INFO,
+            },
+            layout: desc.layout.handle(),
+            base_pipeline_handle: vk::Pipeline::null(),
+            base_pipeline_index: -1,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::COMPUTE_PIPELINE_CREATE_INFO,
+        };
+
+        let mut handle = vk::Pipeline::null();
+
+        let ret = unsafe {
+            (device.fns().create_compute_pipelines)(
+                device.handle(),
+                vk::PipelineCache::null(),
+                1,
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self {
+            device: device.clone(),
+            handle,
+            layout: desc.layout.handle(),
+            workgroup_size,
+        })
+    }
+
+    /// Returns the size of the workgroups of this pipeline, after specialization.
+    ///
+    /// This is `[1, 1, 1]` if the module does not declare the size of its workgroups.
+    #[inline(always)]
+    pub fn workgroup_size(&self) -> [u32; 3] {
+        self.workgroup_size
+    }
+
+    /// Returns the number of workgroups to dispatch so that at least `invocations`
+    /// invocations run along each dimension.
+    #[inline]
+    pub fn group_count(&self, invocations: [u32; 3]) -> [u32; 3] {
+        group_count(self.workgroup_size, invocations)
+    }
+
+    /// Returns the handle to the layout of this pipeline.
+    ///
+    /// The pipeline does not keep its layout alive.
+    #[inline(always)]
+    pub fn layout(&self) -> vk::PipelineLayout {
+        self.layout
+    }
+
+    /// Returns the device that owns this pipeline.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this pipeline.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::Pipeline {
+        self.handle
+    }
+}
+
+impl Drop for ComputePipeline {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_pipeline)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
+
+impl<L> CommandBuffer<Recording<'_>, L> {
+    /// Binds a compute pipeline to the command buffer.
+    ///
+    /// # Safety
+    ///
+    /// `pipeline` must outlive the execution of the command buffer.
+    #[doc(alias = "vkCmdBindPipeline")]
+    pub unsafe fn bind_compute_pipeline(&mut self, pipeline: &ComputePipeline) {
+        self.bind_pipeline(PipelineBindPoint::Compute, pipeline.handle());
+    }
+
+    /// Dispatches compute work groups, whose workgroup ids start at `base_group` instead of
+    /// zero.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::MissingDeviceExtension`] if the device does not support
+    /// Vulkan 1.1 and the `VK_KHR_device_group` extension is not enabled.
+    ///
+    /// # Safety
+    ///
+    /// A compute pipeline created with [`ComputePipelineDesc::dispatch_base`] must be bound, and
+    /// the resources that it uses must be valid for this dispatch when the command executes.
+    #[doc(alias = "vkCmdDispatchBase")]
+    pub unsafe fn dispatch_base(
+        &mut self,
+        base_group: [u32; 3],
+        group_count: [u32; 3],
+    ) -> Result<()> {
+        let dispatch_base =
+            self.device()
+                .fns()
+                .cmd_dispatch_base
+                .ok_or(Error::MissingDeviceExtension(
+                    DeviceExtensions::DEVICE_GROUP,
+                ))?;
+
+        dispatch_base(
+            self.handle(),
+            base_group[0],
+            base_group[1],
+            base_group[2],
+            group_count[0],
+            group_count[1],
+            group_count[2],
+        );
+
+        Ok(())
+    }
+
+    /// Dispatches enough compute work groups of the bound pipeline to run at least
+    /// `invocations` invocations along each dimension.
+    ///
+    /// # Safety
+    ///
+    /// `pipeline` must be bound, and the resources that it uses must be valid for this dispatch
+    /// when the command executes.
+    #[inline]
+    pub unsafe fn dispatch_invocations(
+        &mut self,
+        pipeline: &ComputePipeline,
+        invocations: [u32; 3],
+    ) {
+        self.dispatch(pipeline.group_count(invocations));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        Accesses, Allocator, AllocatorDesc, Buffer, BufferUsages, CommandBufferUsages, CommandPool,
+        CommandPoolDesc, CommandPoolFlags, DeviceFeatures, Fence, MemoryBarrier, PipelineStages,
+        PushConstantRange, ShaderReflection, ShaderStages, SubmitDesc,
+    };
+
+    /// A compute shader with workgroups of 64 invocations, each of which writes twice its
+    /// global invocation index at that index of an array of `u32`. The address of the array
+    /// is read from the push constants.
+    #[rustfmt::skip]
+    const DOUBLE_INDEX: &[u32] = &[
+        // Header: magic number, version 1.3, generator, bound, schema.
+        0x0723_0203, 0x0001_0300, 0, 25, 0,
+        // OpCapability Shader
+        0x0002_0011, 1,
+        // OpCapability PhysicalStorageBufferAddresses
+        0x0002_0011, 5347,
+        // OpExtension "SPV_KHR_physical_storage_buffer"
+        0x0009_000a, 0x5f56_5053, 0x5f52_484b, 0x7379_6870, 0x6c61_6369, 0x6f74_735f,
+        0x6567_6172, 0x6675_625f, 0x0072_6566,
+        // OpMemoryModel PhysicalStorageBuffer64 GLSL450
+        0x0003_000e, 5348, 1,
+        // OpEntryPoint GLCompute %17 "main" %6
+        0x0006_000f, 5, 17, 0x6e69_616d, 0, 6,
+        // OpExecutionMode %17 LocalSize 64 1 1
+        0x0006_0010, 17, 17, 64, 1, 1,
+        // OpDecorate %6 BuiltIn GlobalInvocationId
+        0x0004_0047, 6, 11, 28,
+        // OpDecorate %7 ArrayStride 4
+        0x0004_0047, 7, 6, 4,
+        // OpDecorate %8 Block
+        0x0003_0047, 8, 2,
+        // OpMemberDecorate %8 0 Offset 0
+        0x0005_0048, 8, 0, 35, 0,
+        // OpDecorate %10 Block
+        0x0003_0047, 10, 2,
+        // OpMemberDecorate %10 0 Offset 0
+        0x0005_0048, 10, 0, 35, 0,
+        // %1 = OpTypeVoid
+        0x0002_0013, 1,
+        // %2 = OpTypeFunction %1
+        0x0003_0021, 2, 1,
+        // %3 = OpTypeInt 32 0
+        0x0004_0015, 3, 32, 0,
+        // %4 = OpTypeVector %3 3
+        0x0004_0017, 4, 3, 3,
+        // %5 = OpTypePointer Input %4
+        0x0004_0020, 5, 1, 4,
+        // %6 = OpVariable %5 Input
+        0x0004_003b, 5, 6, 1,
+        // %7 = OpTypeRuntimeArray %3
+        0x0003_001d, 7, 3,
+        // %8 = OpTypeStruct %7
+        0x0003_001e, 8, 7,
+        // %9 = OpTypePointer PhysicalStorageBuffer %8
+        0x0004_0020, 9, 5349, 8,
+        // %10 = OpTypeStruct %9
+        0x0003_001e, 10, 9,
+        // %11 = OpTypePointer PushConstant %10
+        0x0004_0020, 11, 9, 10,
+        // %12 = OpVariable %11 PushConstant
+        0x0004_003b, 11, 12, 9,
+        // %13 = OpTypePointer PushConstant %9
+        0x0004_0020, 13, 9, 9,
+        // %14 = OpTypePointer PhysicalStorageBuffer %3
+        0x0004_0020, 14, 5349, 3,
+        // %15 = OpConstant %3 0
+        0x0004_002b, 3, 15, 0,
+        // %16 = OpConstant %3 2
+        0x0004_002b, 3, 16, 2,
+        // %17 = OpFunction %1 None %2
+        0x0005_0036, 1, 17, 0, 2,
+        // %18 = OpLabel
+        0x0002_00f8, 18,
+        // %19 = OpLoad %4 %6
+        0x0004_003d, 4, 19, 6,
+        // %20 = OpCompositeExtract %3 %19 0
+        0x0005_0051, 3, 20, 19, 0,
+        // %21 = OpAccessChain %13 %12 %15
+        0x0005_0041, 13, 21, 12, 15,
+        // %22 = OpLoad %9 %21
+        0x0004_003d, 9, 22, 21,
+        // %23 = OpAccessChain %14 %22 %15 %20
+        0x0006_0041, 14, 23, 22, 15, 20,
+        // %24 = OpIMul %3 %20 %16
+        0x0005_0084, 3, 24, 20, 16,
+        // OpStore %23 %24 Aligned 4
+        0x0005_003e, 23, 24, 2, 4,
+        // OpReturn
+        0x0001_00fd,
+        // OpFunctionEnd
+        0x0001_0038,
+    ];
+
+    /// The number of invocations dispatched by the tests.
+    const INVOCATIONS: u32 = 256;
+
+    #[test]
+    fn hand_assembled_shader_reflects() {
+        let reflection = ShaderReflection::new(DOUBLE_INDEX, "main", ShaderStage::Compute).unwrap();
+        let size = reflection.workgroup_size().unwrap();
+
+        assert_eq!(size.size, [64, 1, 1]);
+        assert_eq!(group_count(size.size, [INVOCATIONS, 1, 1]), [4, 1, 1]);
+        assert_eq!(group_count(size.size, [INVOCATIONS + 1, 1, 1]), [5, 1, 1]);
+    }
+
+    // Without lavapipe, this returns early and passes without testing anything, so it only
+    // runs when requested with `cargo test -- --ignored` on a machine that has the driver.
+    #[test]
+    #[ignore = "requires the lavapipe driver"]
+    fn dispatch_on_lavapipe() {
+        let Some((device, mut queue)) =
+            crate::lavapipe::device(DeviceFeatures::BUFFER_DEVICE_ADDRESS)
+        else {
+            return;
+        };
+
+        let allocator = Allocator::new(device.clone(), AllocatorDesc::default());
+        let mut buffer = Buffer::from_slice(
+            &allocator,
+            BufferUsages::STORAGE_BUFFER | BufferUsages::SHADER_DEVICE_ADDRESS,
+            &[0u32; INVOCATIONS as usize],
+        )
+        .unwrap();
+        let address = buffer.device_address().unwrap();
+
+        let module = ShaderModule::from_spirv(device.clone(), DOUBLE_INDEX).unwrap();
+        let layout = PipelineLayout::new(
+            device.clone(),
+            &[],
+            &[PushConstantRange {
+                stages: ShaderStages::COMPUTE,
+                offset: 0,
+                size: 8,
+            }],
+        )
+        .unwrap();
+        let pipeline = ComputePipeline::new(ComputePipelineDesc {
+            module: &module,
+            entry_point: "main",
+            specialization: &[],
+            layout: &layout,
+            dispatch_base: false,
+        })
+        .unwrap();
+        assert_eq!(pipeline.workgroup_size(), [64, 1, 1]);
+
+        let pool = CommandPool::new(
+            device.clone(),
+            CommandPoolDesc {
+                queue_family: crate::lavapipe::QUEUE_FAMILY,
+                flags: CommandPoolFlags::TRANSIENT,
+            },
+        )
+        .unwrap();
+        let mut commands = pool
+            .allocate()
+            .unwrap()
+            .begin(&pool, CommandBufferUsages::ONE_TIME_SUBMIT)
+            .unwrap();
+
+        // The buffer, the pipeline and the layout outlive the wait on the fence below.
+        unsafe {
+            commands.bind_compute_pipeline(&pipeline);
+            commands.push_constants(
+                layout.handle(),
+                ShaderStages::COMPUTE,
+                0,
+                &address.to_ne_bytes(),
+            );
+            commands.dispatch_invocations(&pipeline, [INVOCATIONS, 1, 1]);
+            commands.pipeline_barrier(
+                PipelineStages::COMPUTE_SHADER,
+                PipelineStages::HOST,
+                &[MemoryBarrier {
+                    src_access: Accesses::SHADER_WRITE,
+                    dst_access: Accesses::HOST_READ,
+                }],
+                &[],
+                &[],
+            );
+        }
+        let commands = commands.end().unwrap();
+
+        let mut fence = Fence::new(device, false).unwrap();
+        unsafe {
+            queue
+                .submit(
+                    &[SubmitDesc {
+                        command_buffers: &[&commands],
+                        ..Default::default()
+                    }],
+                    Some(&mut fence),
+                )
+                .unwrap();
+        }
+        assert!(fence.wait(Duration::MAX).unwrap());
+
+        let allocation = buffer.allocation_mut().unwrap();
+        allocation.invalidate().unwrap();
+        let bytes = &allocation.mapped_slice().unwrap()[..INVOCATIONS as usize * 4];
+        let values: &[u32] = bytemuck::cast_slice(bytes);
+
+        for (index, &value) in values.iter().enumerate() {
+            assert_eq!(value, index as u32 * 2);
+        }
+    }
+}
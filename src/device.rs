@@ -1,11 +1,11 @@
-use std::ffi::CStr;
-use std::sync::Arc;
+use std::ffi::{c_void, CStr};
+use std::sync::{Arc, Mutex};
 
 use ash::vk;
 use bitflags::bitflags;
 use smallvec::SmallVec;
 
-use crate::{Instance, PhysicalDevice, Result};
+use crate::{vk_ext, Error, Instance, MemoryInfo, PhysicalDevice, PhysicalDeviceLimits, Result};
 
 bitflags! {
     /// A set of device extensions.
@@ -13,6 +13,26 @@ bitflags! {
     pub struct DeviceExtensions: u32 {
         /// The `VK_KHR_swapchain` extension.
         const SWAPCHAIN = 1 << 0;
+        /// The `VK_EXT_memory_budget` extension.
+        const MEMORY_BUDGET = 1 << 1;
+        /// The `VK_KHR_dynamic_rendering` extension.
+        const DYNAMIC_RENDERING = 1 << 2;
+        /// The `VK_KHR_timeline_semaphore` extension.
+        const TIMELINE_SEMAPHORE = 1 << 3;
+        /// The `VK_KHR_synchronization2` extension.
+        const SYNCHRONIZATION_2 = 1 << 4;
+        /// The `VK_KHR_imageless_framebuffer` extension.
+        const IMAGELESS_FRAMEBUFFER = 1 << 5;
+        /// The `VK_EXT_extended_dynamic_state` extension.
+        const EXTENDED_DYNAMIC_STATE = 1 << 6;
+        /// The `VK_EXT_extended_dynamic_state2` extension.
+        const EXTENDED_DYNAMIC_STATE_2 = 1 << 7;
+        /// The `VK_KHR_device_group` extension.
+        const DEVICE_GROUP = 1 << 8;
+        /// The `VK_EXT_extended_dynamic_state3` extension.
+        const EXTENDED_DYNAMIC_STATE_3 = 1 << 9;
+        /// The `VK_KHR_dedicated_allocation` extension.
+        const DEDICATED_ALLOCATION = 1 << 10;
     }
 }
 
@@ -25,11 +45,157 @@ impl DeviceExtensions {
     pub fn name(self) -> &'static CStr {
         match self {
             Self::SWAPCHAIN => vk::KhrSwapchainFn::name(),
+            Self::MEMORY_BUDGET => vk::ExtMemoryBudgetFn::name(),
+            Self::DYNAMIC_RENDERING => vk::KhrDynamicRenderingFn::name(),
+            Self::TIMELINE_SEMAPHORE => vk::KhrTimelineSemaphoreFn::name(),
+            Self::SYNCHRONIZATION_2 => vk::KhrSynchronization2Fn::name(),
+            Self::IMAGELESS_FRAMEBUFFER => vk::KhrImagelessFramebufferFn::name(),
+            Self::EXTENDED_DYNAMIC_STATE => vk::ExtExtendedDynamicStateFn::name(),
+            Self::EXTENDED_DYNAMIC_STATE_2 => vk::ExtExtendedDynamicState2Fn::name(),
+            Self::DEVICE_GROUP => vk::KhrDeviceGroupFn::name(),
+            Self::EXTENDED_DYNAMIC_STATE_3 => vk_ext::EXT_EXTENDED_DYNAMIC_STATE_3_NAME,
+            Self::DEDICATED_ALLOCATION => vk::KhrDedicatedAllocationFn::name(),
             _ => panic!("unknown device extension"),
         }
     }
 }
 
+bitflags! {
+    /// A set of optional device features.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct DeviceFeatures: u32 {
+        /// The `bufferDeviceAddress` feature, which allows retrieving the address of buffers.
+        ///
+        /// This requires Vulkan 1.2.
+        const BUFFER_DEVICE_ADDRESS = 1 << 0;
+        /// The `samplerAnisotropy` feature, which allows samplers to use anisotropic filtering.
+        const SAMPLER_ANISOTROPY = 1 << 1;
+        /// The `samplerYcbcrConversion` feature, which allows sampling from YCbCr images.
+        ///
+        /// This requires Vulkan 1.1.
+        const SAMPLER_YCBCR_CONVERSION = 1 << 2;
+        /// The `dynamicRendering` feature, which allows rendering without render pass objects.
+        ///
+        /// This requires the [`DeviceExtensions::DYNAMIC_RENDERING`] extension.
+        const DYNAMIC_RENDERING = 1 << 3;
+        /// The `timelineSemaphore` feature, which allows creating [`TimelineSemaphore`]s.
+        ///
+        /// This requires Vulkan 1.2 or the [`DeviceExtensions::TIMELINE_SEMAPHORE`] extension.
+        ///
+        /// [`TimelineSemaphore`]: crate::TimelineSemaphore
+        const TIMELINE_SEMAPHORE = 1 << 4;
+        /// The `synchronization2` feature, which allows recording barriers with
+        /// [`PipelineStages2`] and [`Accesses2`].
+        ///
+        /// This requires the [`DeviceExtensions::SYNCHRONIZATION_2`] extension. Without it,
+        /// those barriers are lowered to legacy barriers.
+        ///
+        /// [`PipelineStages2`]: crate::PipelineStages2
+        /// [`Accesses2`]: crate::Accesses2
+        const SYNCHRONIZATION_2 = 1 << 5;
+        /// The `imagelessFramebuffer` feature, which allows creating [`Framebuffer`]s whose
+        /// image views are only provided when a render pass instance begins.
+        ///
+        /// This requires Vulkan 1.2 or the [`DeviceExtensions::IMAGELESS_FRAMEBUFFER`]
+        /// extension.
+        ///
+        /// [`Framebuffer`]: crate::Framebuffer
+        const IMAGELESS_FRAMEBUFFER = 1 << 6;
+        /// The `extendedDynamicState` feature, which allows more of the state of graphics
+        /// pipelines to be set while recording command buffers.
+        ///
+        /// This requires Vulkan 1.3 or the [`DeviceExtensions::EXTENDED_DYNAMIC_STATE`]
+        /// extension.
+        const EXTENDED_DYNAMIC_STATE = 1 << 7;
+        /// The `extendedDynamicState2` feature, which allows the rasterizer discard, depth
+        /// bias and primitive restart enables to be set while recording command buffers.
+        ///
+        /// This requires Vulkan 1.3 or the [`DeviceExtensions::EXTENDED_DYNAMIC_STATE_2`]
+        /// extension.
+        const EXTENDED_DYNAMIC_STATE_2 = 1 << 8;
+        /// The `geometryShader` feature, which allows graphics pipelines to have a geometry
+        /// shader.
+        const GEOMETRY_SHADER = 1 << 9;
+        /// The `tessellationShader` feature, which allows graphics pipelines to have
+        /// tessellation shaders.
+        const TESSELLATION_SHADER = 1 << 10;
+        /// The `independentBlend` feature, which allows the color attachments of a graphics
+        /// pipeline to be blended differently.
+        const INDEPENDENT_BLEND = 1 << 11;
+        /// The `logicOp` feature, which allows graphics pipelines to combine colors with a
+        /// logical operation.
+        const LOGIC_OP = 1 << 12;
+        /// The `fillModeNonSolid` feature, which allows polygons to be rasterized as lines or
+        /// points.
+        const FILL_MODE_NON_SOLID = 1 << 13;
+        /// The `depthClamp` feature, which allows depth values to be clamped instead of
+        /// clipping primitives.
+        const DEPTH_CLAMP = 1 << 14;
+        /// The `depthBounds` feature, which allows the depth bounds test to be enabled.
+        const DEPTH_BOUNDS = 1 << 15;
+        /// The `wideLines` feature, which allows lines to be wider than one pixel.
+        const WIDE_LINES = 1 << 16;
+        /// The `sampleRateShading` feature, which allows fragment shaders to run once per
+        /// sample.
+        const SAMPLE_RATE_SHADING = 1 << 17;
+        /// The `multiViewport` feature, which allows graphics pipelines to use more than one
+        /// viewport.
+        const MULTI_VIEWPORT = 1 << 18;
+        /// The `extendedDynamicState3DepthClampEnable` feature, which allows the depth clamp enable
+        /// to be set while recording command buffers.
+        ///
+        /// This requires the [`DeviceExtensions::EXTENDED_DYNAMIC_STATE_3`] extension.
+        const EXTENDED_DYNAMIC_STATE_3_DEPTH_CLAMP_ENABLE = 1 << 19;
+        /// The `extendedDynamicState3PolygonMode` feature, which allows the polygon mode to be set
+        /// while recording command buffers.
+        ///
+        /// This requires the [`DeviceExtensions::EXTENDED_DYNAMIC_STATE_3`] extension.
+        const EXTENDED_DYNAMIC_STATE_3_POLYGON_MODE = 1 << 20;
+        /// The `extendedDynamicState3RasterizationSamples` feature, which allows the number of
+        /// rasterization samples to be set while recording command buffers.
+        ///
+        /// This requires the [`DeviceExtensions::EXTENDED_DYNAMIC_STATE_3`] extension.
+        const EXTENDED_DYNAMIC_STATE_3_RASTERIZATION_SAMPLES = 1 << 21;
+        /// The `extendedDynamicState3AlphaToCoverageEnable` feature, which allows the alpha to
+        /// coverage enable to be set while recording command buffers.
+        ///
+        /// This requires the [`DeviceExtensions::EXTENDED_DYNAMIC_STATE_3`] extension.
+        const EXTENDED_DYNAMIC_STATE_3_ALPHA_TO_COVERAGE_ENABLE = 1 << 22;
+        /// The `extendedDynamicState3LogicOpEnable` feature, which allows the logical operation
+        /// enable to be set while recording command buffers.
+        ///
+        /// This requires the [`DeviceExtensions::EXTENDED_DYNAMIC_STATE_3`] extension.
+        const EXTENDED_DYNAMIC_STATE_3_LOGIC_OP_ENABLE = 1 << 23;
+        /// The `extendedDynamicState3ColorBlendEnable` feature, which allows the blend enables of
+        /// color attachments to be set while recording command buffers.
+        ///
+        /// This requires the [`DeviceExtensions::EXTENDED_DYNAMIC_STATE_3`] extension.
+        const EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_ENABLE = 1 << 24;
+        /// The `extendedDynamicState3ColorBlendEquation` feature, which allows the blend equations
+        /// of color attachments to be set while recording command buffers.
+        ///
+        /// This requires the [`DeviceExtensions::EXTENDED_DYNAMIC_STATE_3`] extension.
+        const EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_EQUATION = 1 << 25;
+        /// The `extendedDynamicState3ColorWriteMask` feature, which allows the write masks of color
+        /// attachments to be set while recording command buffers.
+        ///
+        /// This requires the [`DeviceExtensions::EXTENDED_DYNAMIC_STATE_3`] extension.
+        const EXTENDED_DYNAMIC_STATE_3_COLOR_WRITE_MASK = 1 << 26;
+    }
+}
+
+impl DeviceFeatures {
+    /// Every feature of the `VK_EXT_extended_dynamic_state3` extension that this crate exposes.
+    pub const EXTENDED_DYNAMIC_STATE_3: Self = Self::EXTENDED_DYNAMIC_STATE_3_DEPTH_CLAMP_ENABLE
+        .union(Self::EXTENDED_DYNAMIC_STATE_3_POLYGON_MODE)
+        .union(Self::EXTENDED_DYNAMIC_STATE_3_RASTERIZATION_SAMPLES)
+        .union(Self::EXTENDED_DYNAMIC_STATE_3_ALPHA_TO_COVERAGE_ENABLE)
+        .union(Self::EXTENDED_DYNAMIC_STATE_3_LOGIC_OP_ENABLE)
+        .union(Self::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_ENABLE)
+        .union(Self::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_EQUATION)
+        .union(Self::EXTENDED_DYNAMIC_STATE_3_COLOR_WRITE_MASK);
+}
+
 /// Describes a queue family with which a connection must be established.
 #[derive(Debug, Clone)]
 pub struct QueueFamilyDesc<'a> {
@@ -47,6 +213,8 @@ pub struct QueueFamilyDesc<'a> {
 pub struct DeviceDesc<'a> {
     /// A set of device extension that must be enabled for the device.
     pub extensions: DeviceExtensions,
+    /// A set of optional features that must be enabled for the device.
+    pub features: DeviceFeatures,
     /// The queue families that must be created for the device.
     pub queue_families: &'a [QueueFamilyDesc<'a>],
 }
@@ -55,14 +223,133 @@ pub struct DeviceDesc<'a> {
 #[derive(Debug, Clone)]
 pub struct DeviceFns {
     pub destroy_device: vk::PFN_vkDestroyDevice,
-    pub create_swapchain: vk::PFN_vkCreateSwapchainKHR,
-    pub destroy_swapchain: vk::PFN_vkDestroySwapchainKHR,
+    pub create_swapchain: Option<vk::PFN_vkCreateSwapchainKHR>,
+    pub destroy_swapchain: Option<vk::PFN_vkDestroySwapchainKHR>,
+    pub allocate_memory: vk::PFN_vkAllocateMemory,
+    pub free_memory: vk::PFN_vkFreeMemory,
+    pub map_memory: vk::PFN_vkMapMemory,
+    pub unmap_memory: vk::PFN_vkUnmapMemory,
+    pub flush_mapped_memory_ranges: vk::PFN_vkFlushMappedMemoryRanges,
+    pub invalidate_mapped_memory_ranges: vk::PFN_vkInvalidateMappedMemoryRanges,
+    pub create_buffer: vk::PFN_vkCreateBuffer,
+    pub destroy_buffer: vk::PFN_vkDestroyBuffer,
+    pub get_buffer_memory_requirements: vk::PFN_vkGetBufferMemoryRequirements,
+    pub bind_buffer_memory: vk::PFN_vkBindBufferMemory,
+    pub get_buffer_device_address: Option<vk::PFN_vkGetBufferDeviceAddress>,
+    pub get_swapchain_images: Option<vk::PFN_vkGetSwapchainImagesKHR>,
+    pub acquire_next_image: Option<vk::PFN_vkAcquireNextImageKHR>,
+    pub queue_present: Option<vk::PFN_vkQueuePresentKHR>,
+    pub create_image: vk::PFN_vkCreateImage,
+    pub destroy_image: vk::PFN_vkDestroyImage,
+    pub get_image_memory_requirements: vk::PFN_vkGetImageMemoryRequirements,
+    pub bind_image_memory: vk::PFN_vkBindImageMemory,
+    pub get_image_subresource_layout: vk::PFN_vkGetImageSubresourceLayout,
+    pub create_image_view: vk::PFN_vkCreateImageView,
+    pub destroy_image_view: vk::PFN_vkDestroyImageView,
+    pub create_sampler: vk::PFN_vkCreateSampler,
+    pub destroy_sampler: vk::PFN_vkDestroySampler,
+    pub create_sampler_ycbcr_conversion: Option<vk::PFN_vkCreateSamplerYcbcrConversion>,
+    pub destroy_sampler_ycbcr_conversion: Option<vk::PFN_vkDestroySamplerYcbcrConversion>,
+    pub create_command_pool: vk::PFN_vkCreateCommandPool,
+    pub destroy_command_pool: vk::PFN_vkDestroyCommandPool,
+    pub reset_command_pool: vk::PFN_vkResetCommandPool,
+    pub allocate_command_buffers: vk::PFN_vkAllocateCommandBuffers,
+    pub free_command_buffers: vk::PFN_vkFreeCommandBuffers,
+    pub begin_command_buffer: vk::PFN_vkBeginCommandBuffer,
+    pub end_command_buffer: vk::PFN_vkEndCommandBuffer,
+    pub cmd_copy_buffer: vk::PFN_vkCmdCopyBuffer,
+    pub cmd_copy_buffer_to_image: vk::PFN_vkCmdCopyBufferToImage,
+    pub cmd_copy_image_to_buffer: vk::PFN_vkCmdCopyImageToBuffer,
+    pub cmd_copy_image: vk::PFN_vkCmdCopyImage,
+    pub cmd_blit_image: vk::PFN_vkCmdBlitImage,
+    pub cmd_clear_color_image: vk::PFN_vkCmdClearColorImage,
+    pub cmd_clear_depth_stencil_image: vk::PFN_vkCmdClearDepthStencilImage,
+    pub cmd_fill_buffer: vk::PFN_vkCmdFillBuffer,
+    pub cmd_pipeline_barrier: vk::PFN_vkCmdPipelineBarrier,
+    pub cmd_draw: vk::PFN_vkCmdDraw,
+    pub cmd_draw_indexed: vk::PFN_vkCmdDrawIndexed,
+    pub cmd_draw_indirect: vk::PFN_vkCmdDrawIndirect,
+    pub cmd_draw_indexed_indirect: vk::PFN_vkCmdDrawIndexedIndirect,
+    pub cmd_dispatch: vk::PFN_vkCmdDispatch,
+    pub cmd_dispatch_indirect: vk::PFN_vkCmdDispatchIndirect,
+    pub cmd_bind_pipeline: vk::PFN_vkCmdBindPipeline,
+    pub cmd_bind_descriptor_sets: vk::PFN_vkCmdBindDescriptorSets,
+    pub cmd_bind_vertex_buffers: vk::PFN_vkCmdBindVertexBuffers,
+    pub cmd_bind_index_buffer: vk::PFN_vkCmdBindIndexBuffer,
+    pub cmd_push_constants: vk::PFN_vkCmdPushConstants,
+    pub cmd_set_viewport: vk::PFN_vkCmdSetViewport,
+    pub cmd_set_scissor: vk::PFN_vkCmdSetScissor,
+    pub reset_command_buffer: vk::PFN_vkResetCommandBuffer,
+    pub cmd_execute_commands: vk::PFN_vkCmdExecuteCommands,
+    pub get_fence_status: vk::PFN_vkGetFenceStatus,
+    pub get_device_queue: vk::PFN_vkGetDeviceQueue,
+    pub queue_submit: vk::PFN_vkQueueSubmit,
+    pub queue_wait_idle: vk::PFN_vkQueueWaitIdle,
+    pub create_fence: vk::PFN_vkCreateFence,
+    pub destroy_fence: vk::PFN_vkDestroyFence,
+    pub reset_fences: vk::PFN_vkResetFences,
+    pub wait_for_fences: vk::PFN_vkWaitForFences,
+    pub create_semaphore: vk::PFN_vkCreateSemaphore,
+    pub destroy_semaphore: vk::PFN_vkDestroySemaphore,
+    pub get_semaphore_counter_value: Option<vk::PFN_vkGetSemaphoreCounterValue>,
+    pub wait_semaphores: Option<vk::PFN_vkWaitSemaphores>,
+    pub signal_semaphore: Option<vk::PFN_vkSignalSemaphore>,
+    pub cmd_pipeline_barrier2: Option<vk::PFN_vkCmdPipelineBarrier2KHR>,
+    pub cmd_begin_rendering: Option<vk::PFN_vkCmdBeginRenderingKHR>,
+    pub cmd_end_rendering: Option<vk::PFN_vkCmdEndRenderingKHR>,
+    pub create_render_pass: vk::PFN_vkCreateRenderPass,
+    pub destroy_render_pass: vk::PFN_vkDestroyRenderPass,
+    pub create_framebuffer: vk::PFN_vkCreateFramebuffer,
+    pub destroy_framebuffer: vk::PFN_vkDestroyFramebuffer,
+    pub cmd_begin_render_pass: vk::PFN_vkCmdBeginRenderPass,
+    pub cmd_next_subpass: vk::PFN_vkCmdNextSubpass,
+    pub cmd_end_render_pass: vk::PFN_vkCmdEndRenderPass,
+    pub create_shader_module: vk::PFN_vkCreateShaderModule,
+    pub destroy_shader_module: vk::PFN_vkDestroyShaderModule,
+    pub create_descriptor_set_layout: vk::PFN_vkCreateDescriptorSetLayout,
+    pub destroy_descriptor_set_layout: vk::PFN_vkDestroyDescriptorSetLayout,
+    pub create_pipeline_layout: vk::PFN_vkCreatePipelineLayout,
+    pub destroy_pipeline_layout: vk::PFN_vkDestroyPipelineLayout,
+    pub create_compute_pipelines: vk::PFN_vkCreateComputePipelines,
+    pub destroy_pipeline: vk::PFN_vkDestroyPipeline,
+    pub cmd_dispatch_base: Option<vk::PFN_vkCmdDispatchBase>,
+    pub create_graphics_pipelines: vk::PFN_vkCreateGraphicsPipelines,
+    pub cmd_set_line_width: vk::PFN_vkCmdSetLineWidth,
+    pub cmd_set_depth_bias: vk::PFN_vkCmdSetDepthBias,
+    pub cmd_set_blend_constants: vk::PFN_vkCmdSetBlendConstants,
+    pub cmd_set_depth_bounds: vk::PFN_vkCmdSetDepthBounds,
+    pub cmd_set_stencil_compare_mask: vk::PFN_vkCmdSetStencilCompareMask,
+    pub cmd_set_stencil_write_mask: vk::PFN_vkCmdSetStencilWriteMask,
+    pub cmd_set_stencil_reference: vk::PFN_vkCmdSetStencilReference,
+    pub cmd_set_cull_mode: Option<vk::PFN_vkCmdSetCullModeEXT>,
+    pub cmd_set_front_face: Option<vk::PFN_vkCmdSetFrontFaceEXT>,
+    pub cmd_set_primitive_topology: Option<vk::PFN_vkCmdSetPrimitiveTopologyEXT>,
+    pub cmd_set_viewport_with_count: Option<vk::PFN_vkCmdSetViewportWithCountEXT>,
+    pub cmd_set_scissor_with_count: Option<vk::PFN_vkCmdSetScissorWithCountEXT>,
+    pub cmd_set_depth_test_enable: Option<vk::PFN_vkCmdSetDepthTestEnableEXT>,
+    pub cmd_set_depth_write_enable: Option<vk::PFN_vkCmdSetDepthWriteEnableEXT>,
+    pub cmd_set_depth_compare_op: Option<vk::PFN_vkCmdSetDepthCompareOpEXT>,
+    pub cmd_set_depth_bounds_test_enable: Option<vk::PFN_vkCmdSetDepthBoundsTestEnableEXT>,
+    pub cmd_set_stencil_test_enable: Option<vk::PFN_vkCmdSetStencilTestEnableEXT>,
+    pub cmd_set_stencil_op: Option<vk::PFN_vkCmdSetStencilOpEXT>,
+    pub cmd_set_rasterizer_discard_enable: Option<vk::PFN_vkCmdSetRasterizerDiscardEnableEXT>,
+    pub cmd_set_depth_bias_enable: Option<vk::PFN_vkCmdSetDepthBiasEnableEXT>,
+    pub cmd_set_primitive_restart_enable: Option<vk::PFN_vkCmdSetPrimitiveRestartEnableEXT>,
+    pub cmd_set_depth_clamp_enable: Option<vk_ext::PFN_vkCmdSetDepthClampEnableEXT>,
+    pub cmd_set_polygon_mode: Option<vk_ext::PFN_vkCmdSetPolygonModeEXT>,
+    pub cmd_set_rasterization_samples: Option<vk_ext::PFN_vkCmdSetRasterizationSamplesEXT>,
+    pub cmd_set_alpha_to_coverage_enable: Option<vk_ext::PFN_vkCmdSetAlphaToCoverageEnableEXT>,
+    pub cmd_set_logic_op_enable: Option<vk_ext::PFN_vkCmdSetLogicOpEnableEXT>,
+    pub cmd_set_color_blend_enable: Option<vk_ext::PFN_vkCmdSetColorBlendEnableEXT>,
+    pub cmd_set_color_blend_equation: Option<vk_ext::PFN_vkCmdSetColorBlendEquationEXT>,
+    pub cmd_set_color_write_mask: Option<vk_ext::PFN_vkCmdSetColorWriteMaskEXT>,
 }
 
 impl DeviceFns {
     /// # Safety
     ///
     /// The provided device must be associated with the given instance.
+    #[allow(clippy::missing_transmute_annotations)]
     unsafe fn load(instance: &Instance, device: vk::Device) -> Self {
         macro_rules! load {
             ($name:ident) => {
@@ -73,40 +360,303 @@ impl DeviceFns {
             };
         }
 
+        // Functions that were promoted to the core API might only be available through their
+        // extension name on older implementations.
+        let get_buffer_device_address: Option<vk::PFN_vkGetBufferDeviceAddress> =
+            load!(vkGetBufferDeviceAddress);
+        let get_buffer_device_address_khr: Option<vk::PFN_vkGetBufferDeviceAddress> =
+            load!(vkGetBufferDeviceAddressKHR);
+        let create_sampler_ycbcr_conversion: Option<vk::PFN_vkCreateSamplerYcbcrConversion> =
+            load!(vkCreateSamplerYcbcrConversion);
+        let create_sampler_ycbcr_conversion_khr: Option<vk::PFN_vkCreateSamplerYcbcrConversion> =
+            load!(vkCreateSamplerYcbcrConversionKHR);
+        let destroy_sampler_ycbcr_conversion: Option<vk::PFN_vkDestroySamplerYcbcrConversion> =
+            load!(vkDestroySamplerYcbcrConversion);
+        let destroy_sampler_ycbcr_conversion_khr: Option<vk::PFN_vkDestroySamplerYcbcrConversion> =
+            load!(vkDestroySamplerYcbcrConversionKHR);
+        let get_semaphore_counter_value: Option<vk::PFN_vkGetSemaphoreCounterValue> =
+            load!(vkGetSemaphoreCounterValue);
+        let get_semaphore_counter_value_khr: Option<vk::PFN_vkGetSemaphoreCounterValue> =
+            load!(vkGetSemaphoreCounterValueKHR);
+        let wait_semaphores: Option<vk::PFN_vkWaitSemaphores> = load!(vkWaitSemaphores);
+        let wait_semaphores_khr: Option<vk::PFN_vkWaitSemaphores> = load!(vkWaitSemaphoresKHR);
+        let signal_semaphore: Option<vk::PFN_vkSignalSemaphore> = load!(vkSignalSemaphore);
+        let signal_semaphore_khr: Option<vk::PFN_vkSignalSemaphore> = load!(vkSignalSemaphoreKHR);
+        let cmd_pipeline_barrier2: Option<vk::PFN_vkCmdPipelineBarrier2KHR> =
+            load!(vkCmdPipelineBarrier2);
+        let cmd_pipeline_barrier2_khr: Option<vk::PFN_vkCmdPipelineBarrier2KHR> =
+            load!(vkCmdPipelineBarrier2KHR);
+        let cmd_begin_rendering: Option<vk::PFN_vkCmdBeginRenderingKHR> =
+            load!(vkCmdBeginRendering);
+        let cmd_begin_rendering_khr: Option<vk::PFN_vkCmdBeginRenderingKHR> =
+            load!(vkCmdBeginRenderingKHR);
+        let cmd_end_rendering: Option<vk::PFN_vkCmdEndRenderingKHR> = load!(vkCmdEndRendering);
+        let cmd_end_rendering_khr: Option<vk::PFN_vkCmdEndRenderingKHR> =
+            load!(vkCmdEndRenderingKHR);
+        let cmd_dispatch_base: Option<vk::PFN_vkCmdDispatchBase> = load!(vkCmdDispatchBase);
+        let cmd_dispatch_base_khr: Option<vk::PFN_vkCmdDispatchBase> = load!(vkCmdDispatchBaseKHR);
+        let cmd_set_cull_mode: Option<vk::PFN_vkCmdSetCullModeEXT> = load!(vkCmdSetCullMode);
+        let cmd_set_cull_mode_ext: Option<vk::PFN_vkCmdSetCullModeEXT> = load!(vkCmdSetCullModeEXT);
+        let cmd_set_front_face: Option<vk::PFN_vkCmdSetFrontFaceEXT> = load!(vkCmdSetFrontFace);
+        let cmd_set_front_face_ext: Option<vk::PFN_vkCmdSetFrontFaceEXT> =
+            load!(vkCmdSetFrontFaceEXT);
+        let cmd_set_primitive_topology: Option<vk::PFN_vkCmdSetPrimitiveTopologyEXT> =
+            load!(vkCmdSetPrimitiveTopology);
+        let cmd_set_primitive_topology_ext: Option<vk::PFN_vkCmdSetPrimitiveTopologyEXT> =
+            load!(vkCmdSetPrimitiveTopologyEXT);
+        let cmd_set_viewport_with_count: Option<vk::PFN_vkCmdSetViewportWithCountEXT> =
+            load!(vkCmdSetViewportWithCount);
+        let cmd_set_viewport_with_count_ext: Option<vk::PFN_vkCmdSetViewportWithCountEXT> =
+            load!(vkCmdSetViewportWithCountEXT);
+        let cmd_set_scissor_with_count: Option<vk::PFN_vkCmdSetScissorWithCountEXT> =
+            load!(vkCmdSetScissorWithCount);
+        let cmd_set_scissor_with_count_ext: Option<vk::PFN_vkCmdSetScissorWithCountEXT> =
+            load!(vkCmdSetScissorWithCountEXT);
+        let cmd_set_depth_test_enable: Option<vk::PFN_vkCmdSetDepthTestEnableEXT> =
+            load!(vkCmdSetDepthTestEnable);
+        let cmd_set_depth_test_enable_ext: Option<vk::PFN_vkCmdSetDepthTestEnableEXT> =
+            load!(vkCmdSetDepthTestEnableEXT);
+        let cmd_set_depth_write_enable: Option<vk::PFN_vkCmdSetDepthWriteEnableEXT> =
+            load!(vkCmdSetDepthWriteEnable);
+        let cmd_set_depth_write_enable_ext: Option<vk::PFN_vkCmdSetDepthWriteEnableEXT> =
+            load!(vkCmdSetDepthWriteEnableEXT);
+        let cmd_set_depth_compare_op: Option<vk::PFN_vkCmdSetDepthCompareOpEXT> =
+            load!(vkCmdSetDepthCompareOp);
+        let cmd_set_depth_compare_op_ext: Option<vk::PFN_vkCmdSetDepthCompareOpEXT> =
+            load!(vkCmdSetDepthCompareOpEXT);
+        let cmd_set_depth_bounds_test_enable: Option<vk::PFN_vkCmdSetDepthBoundsTestEnableEXT> =
+            load!(vkCmdSetDepthBoundsTestEnable);
+        let cmd_set_depth_bounds_test_enable_ext: Option<vk::PFN_vkCmdSetDepthBoundsTestEnableEXT> =
+            load!(vkCmdSetDepthBoundsTestEnableEXT);
+        let cmd_set_stencil_test_enable: Option<vk::PFN_vkCmdSetStencilTestEnableEXT> =
+            load!(vkCmdSetStencilTestEnable);
+        let cmd_set_stencil_test_enable_ext: Option<vk::PFN_vkCmdSetStencilTestEnableEXT> =
+            load!(vkCmdSetStencilTestEnableEXT);
+        let cmd_set_stencil_op: Option<vk::PFN_vkCmdSetStencilOpEXT> = load!(vkCmdSetStencilOp);
+        let cmd_set_stencil_op_ext: Option<vk::PFN_vkCmdSetStencilOpEXT> =
+            load!(vkCmdSetStencilOpEXT);
+        let cmd_set_rasterizer_discard_enable: Option<vk::PFN_vkCmdSetRasterizerDiscardEnableEXT> =
+            load!(vkCmdSetRasterizerDiscardEnable);
+        let cmd_set_rasterizer_discard_enable_ext: Option<
+            vk::PFN_vkCmdSetRasterizerDiscardEnableEXT,
+        > = load!(vkCmdSetRasterizerDiscardEnableEXT);
+        let cmd_set_depth_bias_enable: Option<vk::PFN_vkCmdSetDepthBiasEnableEXT> =
+            load!(vkCmdSetDepthBiasEnable);
+        let cmd_set_depth_bias_enable_ext: Option<vk::PFN_vkCmdSetDepthBiasEnableEXT> =
+            load!(vkCmdSetDepthBiasEnableEXT);
+        let cmd_set_primitive_restart_enable: Option<vk::PFN_vkCmdSetPrimitiveRestartEnableEXT> =
+            load!(vkCmdSetPrimitiveRestartEnable);
+        let cmd_set_primitive_restart_enable_ext: Option<
+            vk::PFN_vkCmdSetPrimitiveRestartEnableEXT,
+        > = load!(vkCmdSetPrimitiveRestartEnableEXT);
+
         Self {
             destroy_device: load!(vkDestroyDevice),
             create_swapchain: load!(vkCreateSwapchainKHR),
             destroy_swapchain: load!(vkDestroySwapchainKHR),
+            allocate_memory: load!(vkAllocateMemory),
+            free_memory: load!(vkFreeMemory),
+            map_memory: load!(vkMapMemory),
+            unmap_memory: load!(vkUnmapMemory),
+            flush_mapped_memory_ranges: load!(vkFlushMappedMemoryRanges),
+            invalidate_mapped_memory_ranges: load!(vkInvalidateMappedMemoryRanges),
+            create_buffer: load!(vkCreateBuffer),
+            destroy_buffer: load!(vkDestroyBuffer),
+            get_buffer_memory_requirements: load!(vkGetBufferMemoryRequirements),
+            bind_buffer_memory: load!(vkBindBufferMemory),
+            get_buffer_device_address: get_buffer_device_address.or(get_buffer_device_address_khr),
+            get_swapchain_images: load!(vkGetSwapchainImagesKHR),
+            acquire_next_image: load!(vkAcquireNextImageKHR),
+            queue_present: load!(vkQueuePresentKHR),
+            create_image: load!(vkCreateImage),
+            destroy_image: load!(vkDestroyImage),
+            get_image_memory_requirements: load!(vkGetImageMemoryRequirements),
+            bind_image_memory: load!(vkBindImageMemory),
+            get_image_subresource_layout: load!(vkGetImageSubresourceLayout),
+            create_image_view: load!(vkCreateImageView),
+            destroy_image_view: load!(vkDestroyImageView),
+            create_sampler: load!(vkCreateSampler),
+            destroy_sampler: load!(vkDestroySampler),
+            create_sampler_ycbcr_conversion: create_sampler_ycbcr_conversion
+                .or(create_sampler_ycbcr_conversion_khr),
+            destroy_sampler_ycbcr_conversion: destroy_sampler_ycbcr_conversion
+                .or(destroy_sampler_ycbcr_conversion_khr),
+            create_command_pool: load!(vkCreateCommandPool),
+            destroy_command_pool: load!(vkDestroyCommandPool),
+            reset_command_pool: load!(vkResetCommandPool),
+            allocate_command_buffers: load!(vkAllocateCommandBuffers),
+            free_command_buffers: load!(vkFreeCommandBuffers),
+            begin_command_buffer: load!(vkBeginCommandBuffer),
+            end_command_buffer: load!(vkEndCommandBuffer),
+            cmd_copy_buffer: load!(vkCmdCopyBuffer),
+            cmd_copy_buffer_to_image: load!(vkCmdCopyBufferToImage),
+            cmd_copy_image_to_buffer: load!(vkCmdCopyImageToBuffer),
+            cmd_copy_image: load!(vkCmdCopyImage),
+            cmd_blit_image: load!(vkCmdBlitImage),
+            cmd_clear_color_image: load!(vkCmdClearColorImage),
+            cmd_clear_depth_stencil_image: load!(vkCmdClearDepthStencilImage),
+            cmd_fill_buffer: load!(vkCmdFillBuffer),
+            cmd_pipeline_barrier: load!(vkCmdPipelineBarrier),
+            cmd_draw: load!(vkCmdDraw),
+            cmd_draw_indexed: load!(vkCmdDrawIndexed),
+            cmd_draw_indirect: load!(vkCmdDrawIndirect),
+            cmd_draw_indexed_indirect: load!(vkCmdDrawIndexedIndirect),
+            cmd_dispatch: load!(vkCmdDispatch),
+            cmd_dispatch_indirect: load!(vkCmdDispatchIndirect),
+            cmd_bind_pipeline: load!(vkCmdBindPipeline),
+            cmd_bind_descriptor_sets: load!(vkCmdBindDescriptorSets),
+            cmd_bind_vertex_buffers: load!(vkCmdBindVertexBuffers),
+            cmd_bind_index_buffer: load!(vkCmdBindIndexBuffer),
+            cmd_push_constants: load!(vkCmdPushConstants),
+            cmd_set_viewport: load!(vkCmdSetViewport),
+            cmd_set_scissor: load!(vkCmdSetScissor),
+            reset_command_buffer: load!(vkResetCommandBuffer),
+            cmd_execute_commands: load!(vkCmdExecuteCommands),
+            get_fence_status: load!(vkGetFenceStatus),
+            get_device_queue: load!(vkGetDeviceQueue),
+            queue_submit: load!(vkQueueSubmit),
+            queue_wait_idle: load!(vkQueueWaitIdle),
+            create_fence: load!(vkCreateFence),
+            destroy_fence: load!(vkDestroyFence),
+            reset_fences: load!(vkResetFences),
+            wait_for_fences: load!(vkWaitForFences),
+            create_semaphore: load!(vkCreateSemaphore),
+            destroy_semaphore: load!(vkDestroySemaphore),
+            get_semaphore_counter_value: get_semaphore_counter_value
+                .or(get_semaphore_counter_value_khr),
+            wait_semaphores: wait_semaphores.or(wait_semaphores_khr),
+            signal_semaphore: signal_semaphore.or(signal_semaphore_khr),
+            cmd_pipeline_barrier2: cmd_pipeline_barrier2.or(cmd_pipeline_barrier2_khr),
+            cmd_begin_rendering: cmd_begin_rendering.or(cmd_begin_rendering_khr),
+            cmd_end_rendering: cmd_end_rendering.or(cmd_end_rendering_khr),
+            create_render_pass: load!(vkCreateRenderPass),
+            destroy_render_pass: load!(vkDestroyRenderPass),
+            create_framebuffer: load!(vkCreateFramebuffer),
+            destroy_framebuffer: load!(vkDestroyFramebuffer),
+            cmd_begin_render_pass: load!(vkCmdBeginRenderPass),
+            cmd_next_subpass: load!(vkCmdNextSubpass),
+            cmd_end_render_pass: load!(vkCmdEndRenderPass),
+            create_shader_module: load!(vkCreateShaderModule),
+            destroy_shader_module: load!(vkDestroyShaderModule),
+            create_descriptor_set_layout: load!(vkCreateDescriptorSetLayout),
+            destroy_descriptor_set_layout: load!(vkDestroyDescriptorSetLayout),
+            create_pipeline_layout: load!(vkCreatePipelineLayout),
+            destroy_pipeline_layout: load!(vkDestroyPipelineLayout),
+            create_compute_pipelines: load!(vkCreateComputePipelines),
+            destroy_pipeline: load!(vkDestroyPipeline),
+            cmd_dispatch_base: cmd_dispatch_base.or(cmd_dispatch_base_khr),
+            create_graphics_pipelines: load!(vkCreateGraphicsPipelines),
+            cmd_set_line_width: load!(vkCmdSetLineWidth),
+            cmd_set_depth_bias: load!(vkCmdSetDepthBias),
+            cmd_set_blend_constants: load!(vkCmdSetBlendConstants),
+            cmd_set_depth_bounds: load!(vkCmdSetDepthBounds),
+            cmd_set_stencil_compare_mask: load!(vkCmdSetStencilCompareMask),
+            cmd_set_stencil_write_mask: load!(vkCmdSetStencilWriteMask),
+            cmd_set_stencil_reference: load!(vkCmdSetStencilReference),
+            cmd_set_cull_mode: cmd_set_cull_mode.or(cmd_set_cull_mode_ext),
+            cmd_set_front_face: cmd_set_front_face.or(cmd_set_front_face_ext),
+            cmd_set_primitive_topology: cmd_set_primitive_topology
+                .or(cmd_set_primitive_topology_ext),
+            cmd_set_viewport_with_count: cmd_set_viewport_with_count
+                .or(cmd_set_viewport_with_count_ext),
+            cmd_set_scissor_with_count: cmd_set_scissor_with_count
+                .or(cmd_set_scissor_with_count_ext),
+            cmd_set_depth_test_enable: cmd_set_depth_test_enable.or(cmd_set_depth_test_enable_ext),
+            cmd_set_depth_write_enable: cmd_set_depth_write_enable
+                .or(cmd_set_depth_write_enable_ext),
+            cmd_set_depth_compare_op: cmd_set_depth_compare_op.or(cmd_set_depth_compare_op_ext),
+            cmd_set_depth_bounds_test_enable: cmd_set_depth_bounds_test_enable
+                .or(cmd_set_depth_bounds_test_enable_ext),
+            cmd_set_stencil_test_enable: cmd_set_stencil_test_enable
+                .or(cmd_set_stencil_test_enable_ext),
+            cmd_set_stencil_op: cmd_set_stencil_op.or(cmd_set_stencil_op_ext),
+            cmd_set_rasterizer_discard_enable: cmd_set_rasterizer_discard_enable
+                .or(cmd_set_rasterizer_discard_enable_ext),
+            cmd_set_depth_bias_enable: cmd_set_depth_bias_enable.or(cmd_set_depth_bias_enable_ext),
+            cmd_set_primitive_restart_enable: cmd_set_primitive_restart_enable
+                .or(cmd_set_primitive_restart_enable_ext),
+            cmd_set_depth_clamp_enable: load!(vkCmdSetDepthClampEnableEXT),
+            cmd_set_polygon_mode: load!(vkCmdSetPolygonModeEXT),
+            cmd_set_rasterization_samples: load!(vkCmdSetRasterizationSamplesEXT),
+            cmd_set_alpha_to_coverage_enable: load!(vkCmdSetAlphaToCoverageEnableEXT),
+            cmd_set_logic_op_enable: load!(vkCmdSetLogicOpEnableEXT),
+            cmd_set_color_blend_enable: load!(vkCmdSetColorBlendEnableEXT),
+            cmd_set_color_blend_equation: load!(vkCmdSetColorBlendEquationEXT),
+            cmd_set_color_write_mask: load!(vkCmdSetColorWriteMaskEXT),
         }
     }
 }
 
 /// An open connection to a GPU device.
 pub struct Device {
-    /// Creates a new [`Device`] instance.
-    instance: Arc<Instance>,
+    /// The physical device that this device was created from.
+    physical_device: PhysicalDevice,
     /// The handle to the device.
     handle: vk::Device,
     /// The functions that have been loaded for this device.
     fns: DeviceFns,
+
+    /// The version of Vulkan supported by the device.
+    api_version: u32,
+    /// The extensions that were enabled for the device.
+    extensions: DeviceExtensions,
+    /// The optional features that were enabled for the device.
+    features: DeviceFeatures,
+    /// The memory types and heaps of the physical device.
+    memory_properties: MemoryInfo,
+    /// The limits of the physical device.
+    limits: PhysicalDeviceLimits,
+    /// The number of queues created for each queue family, by family index.
+    queue_counts: Box<[(u32, u32)]>,
+    /// The queues that are currently retrieved as [`Queue`](crate::Queue) instances.
+    pub(crate) taken_queues: Mutex<Vec<(u32, u32)>>,
 }
 
 impl Device {
-    /// Creates a new [`Device`] instance from the provided instance and handle.
+    /// Creates a new [`Device`] instance from the provided physical device and handle.
     ///
     /// # Safety
     ///
-    /// The provided handle must be valid and belong to the provided instance.
-    pub unsafe fn from_handle(instance: Arc<Instance>, handle: vk::Device) -> Arc<Self> {
+    /// The provided handle must be valid and must have been created from the provided physical
+    /// device, with the provided extensions and features enabled and the provided queue
+    /// families.
+    pub unsafe fn from_handle(
+        physical_device: PhysicalDevice,
+        handle: vk::Device,
+        extensions: DeviceExtensions,
+        features: DeviceFeatures,
+        queue_families: &[QueueFamilyDesc],
+    ) -> Arc<Self> {
         Arc::new(Self {
-            fns: DeviceFns::load(&instance, handle),
-            instance,
+            fns: DeviceFns::load(physical_device.instance(), handle),
+            api_version: api_version(&physical_device),
+            extensions,
+            features,
+            memory_properties: physical_device.memory_properties(),
+            limits: physical_device.properties().limits,
+            queue_counts: queue_families
+                .iter()
+                .map(|f| (f.index, f.priorities.len() as u32))
+                .collect(),
+            taken_queues: Mutex::new(Vec::new()),
+            physical_device,
             handle,
         })
     }
 
     /// Creates a new [`Device`].
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::MissingDeviceExtension`] if a feature is requested without
+    /// the extension that it requires, or a Vulkan error if the device cannot be created.
     pub fn new(physical_device: PhysicalDevice, desc: DeviceDesc) -> Result<Arc<Self>> {
+        check_feature_extensions(
+            api_version(&physical_device),
+            desc.extensions,
+            desc.features,
+        )?;
+
         let extensions = desc
             .extensions
             .iter()
@@ -126,6 +676,147 @@ impl Device {
             })
             .collect::<SmallVec<[vk::DeviceQueueCreateInfo; 2]>>();
 
+        // Optional features are enabled by chaining their feature structures.
+        let mut p_next: *const c_void = std::ptr::null();
+
+        let mut buffer_device_address = vk::PhysicalDeviceBufferDeviceAddressFeatures {
+            buffer_device_address: vk::TRUE,
+            ..Default::default()
+        };
+        if desc
+            .features
+            .contains(DeviceFeatures::BUFFER_DEVICE_ADDRESS)
+        {
+            buffer_device_address.p_next = p_next as *mut c_void;
+            p_next = &buffer_device_address as *const _ as *const c_void;
+        }
+
+        let mut sampler_ycbcr_conversion = vk::PhysicalDeviceSamplerYcbcrConversionFeatures {
+            sampler_ycbcr_conversion: vk::TRUE,
+            ..Default::default()
+        };
+        if desc
+            .features
+            .contains(DeviceFeatures::SAMPLER_YCBCR_CONVERSION)
+        {
+            sampler_ycbcr_conversion.p_next = p_next as *mut c_void;
+            p_next = &sampler_ycbcr_conversion as *const _ as *const c_void;
+        }
+
+        let mut dynamic_rendering = vk::PhysicalDeviceDynamicRenderingFeaturesKHR {
+            dynamic_rendering: vk::TRUE,
+            ..Default::default()
+        };
+        if desc.features.contains(DeviceFeatures::DYNAMIC_RENDERING) {
+            dynamic_rendering.p_next = p_next as *mut c_void;
+            p_next = &dynamic_rendering as *const _ as *const c_void;
+        }
+
+        let mut timeline_semaphore = vk::PhysicalDeviceTimelineSemaphoreFeatures {
+            timeline_semaphore: vk::TRUE,
+            ..Default::default()
+        };
+        if desc.features.contains(DeviceFeatures::TIMELINE_SEMAPHORE) {
+            timeline_semaphore.p_next = p_next as *mut c_void;
+            p_next = &timeline_semaphore as *const _ as *const c_void;
+        }
+
+        let mut synchronization_2 = vk::PhysicalDeviceSynchronization2FeaturesKHR {
+            synchronization2: vk::TRUE,
+            ..Default::default()
+        };
+        if desc.features.contains(DeviceFeatures::SYNCHRONIZATION_2) {
+            synchronization_2.p_next = p_next as *mut c_void;
+            p_next = &synchronization_2 as *const _ as *const c_void;
+        }
+
+        let mut imageless_framebuffer = vk::PhysicalDeviceImagelessFramebufferFeatures {
+            imageless_framebuffer: vk::TRUE,
+            ..Default::default()
+        };
+        if desc
+            .features
+            .contains(DeviceFeatures::IMAGELESS_FRAMEBUFFER)
+        {
+            imageless_framebuffer.p_next = p_next as *mut c_void;
+            p_next = &imageless_framebuffer as *const _ as *const c_void;
+        }
+
+        let mut extended_dynamic_state = vk::PhysicalDeviceExtendedDynamicStateFeaturesEXT {
+            extended_dynamic_state: vk::TRUE,
+            ..Default::default()
+        };
+        if desc
+            .features
+            .contains(DeviceFeatures::EXTENDED_DYNAMIC_STATE)
+        {
+            extended_dynamic_state.p_next = p_next as *mut c_void;
+            p_next = &extended_dynamic_state as *const _ as *const c_void;
+        }
+
+        let mut extended_dynamic_state_2 = vk::PhysicalDeviceExtendedDynamicState2FeaturesEXT {
+            extended_dynamic_state2: vk::TRUE,
+            ..Default::default()
+        };
+        if desc
+            .features
+            .contains(DeviceFeatures::EXTENDED_DYNAMIC_STATE_2)
+        {
+            extended_dynamic_state_2.p_next = p_next as *mut c_void;
+            p_next = &extended_dynamic_state_2 as *const _ as *const c_void;
+        }
+
+        let has_feature = |feature| desc.features.contains(feature) as vk::Bool32;
+        let mut extended_dynamic_state_3 = vk_ext::PhysicalDeviceExtendedDynamicState3FeaturesEXT {
+            extended_dynamic_state3_depth_clamp_enable: has_feature(
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_DEPTH_CLAMP_ENABLE,
+            ),
+            extended_dynamic_state3_polygon_mode: has_feature(
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_POLYGON_MODE,
+            ),
+            extended_dynamic_state3_rasterization_samples: has_feature(
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_RASTERIZATION_SAMPLES,
+            ),
+            extended_dynamic_state3_alpha_to_coverage_enable: has_feature(
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_ALPHA_TO_COVERAGE_ENABLE,
+            ),
+            extended_dynamic_state3_logic_op_enable: has_feature(
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_LOGIC_OP_ENABLE,
+            ),
+            extended_dynamic_state3_color_blend_enable: has_feature(
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_ENABLE,
+            ),
+            extended_dynamic_state3_color_blend_equation: has_feature(
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_EQUATION,
+            ),
+            extended_dynamic_state3_color_write_mask: has_feature(
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_WRITE_MASK,
+            ),
+            ..Default::default()
+        };
+        if desc
+            .features
+            .intersects(DeviceFeatures::EXTENDED_DYNAMIC_STATE_3)
+        {
+            extended_dynamic_state_3.p_next = p_next as *mut c_void;
+            p_next = &extended_dynamic_state_3 as *const _ as *const c_void;
+        }
+
+        let enabled_features = vk::PhysicalDeviceFeatures {
+            sampler_anisotropy: desc.features.contains(DeviceFeatures::SAMPLER_ANISOTROPY) as _,
+            geometry_shader: desc.features.contains(DeviceFeatures::GEOMETRY_SHADER) as _,
+            tessellation_shader: desc.features.contains(DeviceFeatures::TESSELLATION_SHADER) as _,
+            independent_blend: desc.features.contains(DeviceFeatures::INDEPENDENT_BLEND) as _,
+            logic_op: desc.features.contains(DeviceFeatures::LOGIC_OP) as _,
+            fill_mode_non_solid: desc.features.contains(DeviceFeatures::FILL_MODE_NON_SOLID) as _,
+            depth_clamp: desc.features.contains(DeviceFeatures::DEPTH_CLAMP) as _,
+            depth_bounds: desc.features.contains(DeviceFeatures::DEPTH_BOUNDS) as _,
+            wide_lines: desc.features.contains(DeviceFeatures::WIDE_LINES) as _,
+            sample_rate_shading: desc.features.contains(DeviceFeatures::SAMPLE_RATE_SHADING) as _,
+            multi_viewport: desc.features.contains(DeviceFeatures::MULTI_VIEWPORT) as _,
+            ..Default::default()
+        };
+
         let create_info = vk::DeviceCreateInfo {
             enabled_extension_count: extensions.len() as u32,
             pp_enabled_extension_names: extensions.as_ptr(),
@@ -133,9 +824,9 @@ impl Device {
             pp_enabled_layer_names: std::ptr::null(),
             p_queue_create_infos: queue_create_infos.as_ptr(),
             queue_create_info_count: queue_create_infos.len() as u32,
-            p_enabled_features: std::ptr::null(),
+            p_enabled_features: &enabled_features,
             flags: vk::DeviceCreateFlags::empty(),
-            p_next: std::ptr::null(),
+            p_next,
             s_type: vk::StructureType::DEVICE_CREATE_INFO,
         };
 
@@ -154,13 +845,69 @@ impl Device {
             return Err(ret.into());
         }
 
-        Ok(unsafe { Self::from_handle(physical_device.instance().clone(), handle) })
+        Ok(unsafe {
+            Self::from_handle(
+                physical_device,
+                handle,
+                desc.extensions,
+                desc.features,
+                desc.queue_families,
+            )
+        })
     }
 
     /// Returns the parent [`Instance`] of this [`Device`].
     #[inline(always)]
     pub fn instance(&self) -> &Arc<Instance> {
-        &self.instance
+        self.physical_device.instance()
+    }
+
+    /// Returns the [`PhysicalDevice`] that this [`Device`] was created from.
+    #[inline(always)]
+    pub fn physical_device(&self) -> &PhysicalDevice {
+        &self.physical_device
+    }
+
+    /// Returns the version of Vulkan supported by this device, which is the lowest of the
+    /// versions supported by the physical device and by the instance.
+    #[inline(always)]
+    pub fn api_version(&self) -> u32 {
+        self.api_version
+    }
+
+    /// Returns the extensions that were enabled for this device.
+    #[inline(always)]
+    pub fn extensions(&self) -> DeviceExtensions {
+        self.extensions
+    }
+
+    /// Returns the optional features that were enabled for this device.
+    pub fn features(&self) -> DeviceFeatures {
+        self.features
+    }
+
+    /// Returns the memory types and heaps of the physical device.
+    ///
+    /// This is cached when the device is created.
+    #[inline(always)]
+    pub fn memory_properties(&self) -> &MemoryInfo {
+        &self.memory_properties
+    }
+
+    /// Returns the limits of the physical device.
+    ///
+    /// This is cached when the device is created.
+    #[inline(always)]
+    pub fn limits(&self) -> &PhysicalDeviceLimits {
+        &self.limits
+    }
+
+    /// Returns the number of queues that were created for the provided queue family.
+    pub fn queue_count(&self, family: u32) -> u32 {
+        self.queue_counts
+            .iter()
+            .find(|&&(index, _)| index == family)
+            .map_or(0, |&(_, count)| count)
     }
 
     /// Returns the handle to the device.
@@ -175,3 +922,119 @@ impl Device {
         &self.fns
     }
 }
+
+/// Returns the version of Vulkan supported by a device created from `physical_device`, which is
+/// the lowest of the versions supported by the physical device and by the instance.
+fn api_version(physical_device: &PhysicalDevice) -> u32 {
+    // The instance is created with the highest version that its loader supports.
+    let instance_version = physical_device
+        .instance()
+        .library()
+        .enumerate_instance_version()
+        .unwrap_or(vk::API_VERSION_1_0);
+
+    physical_device
+        .properties()
+        .api_version
+        .min(instance_version)
+}
+
+/// Checks that every feature in `features` can be enabled on a device supporting `api_version`
+/// with `extensions` enabled.
+fn check_feature_extensions(
+    api_version: u32,
+    extensions: DeviceExtensions,
+    features: DeviceFeatures,
+) -> Result<()> {
+    let mut required = DeviceExtensions::empty();
+    if api_version < vk_ext::API_VERSION_1_3 {
+        if features.contains(DeviceFeatures::EXTENDED_DYNAMIC_STATE) {
+            required |= DeviceExtensions::EXTENDED_DYNAMIC_STATE;
+        }
+        if features.contains(DeviceFeatures::EXTENDED_DYNAMIC_STATE_2) {
+            required |= DeviceExtensions::EXTENDED_DYNAMIC_STATE_2;
+        }
+    }
+    if features.intersects(DeviceFeatures::EXTENDED_DYNAMIC_STATE_3) {
+        required |= DeviceExtensions::EXTENDED_DYNAMIC_STATE_3;
+    }
+
+    // Report the missing extensions one at a time, as their names are displayed individually.
+    match (required - extensions).iter().next() {
+        Some(missing) => Err(Error::MissingDeviceExtension(missing)),
+        None => Ok(()),
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        unsafe {
+            (self.fns.destroy_device)(self.handle, std::ptr::null());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const API_VERSION_1_2: u32 = vk::make_api_version(0, 1, 2, 0);
+
+    #[test]
+    fn extended_dynamic_state_extensions() {
+        let eds = DeviceFeatures::EXTENDED_DYNAMIC_STATE;
+        let eds2 = DeviceFeatures::EXTENDED_DYNAMIC_STATE_2;
+        let missing = |ext| Err(Error::MissingDeviceExtension(ext));
+
+        assert_eq!(
+            check_feature_extensions(API_VERSION_1_2, DeviceExtensions::empty(), eds),
+            missing(DeviceExtensions::EXTENDED_DYNAMIC_STATE)
+        );
+        assert_eq!(
+            check_feature_extensions(API_VERSION_1_2, DeviceExtensions::empty(), eds2),
+            missing(DeviceExtensions::EXTENDED_DYNAMIC_STATE_2)
+        );
+        assert_eq!(
+            check_feature_extensions(
+                API_VERSION_1_2,
+                DeviceExtensions::EXTENDED_DYNAMIC_STATE
+                    | DeviceExtensions::EXTENDED_DYNAMIC_STATE_2,
+                eds | eds2
+            ),
+            Ok(())
+        );
+        // Both features are core in Vulkan 1.3.
+        assert_eq!(
+            check_feature_extensions(
+                vk_ext::API_VERSION_1_3,
+                DeviceExtensions::empty(),
+                eds | eds2
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn extended_dynamic_state_3_extension() {
+        let polygon_mode = DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_POLYGON_MODE;
+
+        assert_eq!(
+            check_feature_extensions(
+                vk_ext::API_VERSION_1_3,
+                DeviceExtensions::empty(),
+                polygon_mode
+            ),
+            Err(Error::MissingDeviceExtension(
+                DeviceExtensions::EXTENDED_DYNAMIC_STATE_3
+            ))
+        );
+        assert_eq!(
+            check_feature_extensions(
+                API_VERSION_1_2,
+                DeviceExtensions::EXTENDED_DYNAMIC_STATE_3,
+                polygon_mode
+            ),
+            Ok(())
+        );
+    }
+}
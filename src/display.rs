@@ -0,0 +1,453 @@
+use std::ffi::CStr;
+use std::mem::MaybeUninit;
+
+use ash::vk;
+use bitflags::bitflags;
+use smallvec::SmallVec;
+
+use crate::{
+    Error, InstanceExtensions, PhysicalDevice, Result, SurfaceTransform, SurfaceTransforms,
+};
+
+/// A display attached to a [`PhysicalDevice`].
+///
+/// Displays are owned by the physical device they are attached to. They don't need to be
+/// destroyed explicitly.
+#[derive(Debug, Clone)]
+pub struct Display {
+    /// The physical device that the display is attached to.
+    physical_device: PhysicalDevice,
+
+    /// The handle to the display.
+    handle: vk::DisplayKHR,
+}
+
+impl Display {
+    /// Creates a new [`Display`] instance.
+    ///
+    /// # Safety
+    ///
+    /// The provided handle must be valid and belong to the provided physical device.
+    #[inline]
+    pub unsafe fn from_handle(physical_device: PhysicalDevice, handle: vk::DisplayKHR) -> Self {
+        Self {
+            physical_device,
+            handle,
+        }
+    }
+
+    /// Returns the list of display modes that are natively supported by this display.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the `VK_KHR_display`
+    /// extension is not enabled.
+    #[doc(alias = "vkGetDisplayModePropertiesKHR")]
+    pub fn mode_properties(&self) -> Result<impl Iterator<Item = DisplayModeProperties>> {
+        let get_fn = self
+            .physical_device
+            .instance()
+            .fns()
+            .get_display_mode_properties
+            .ok_or(Error::MissingInstanceExtension(InstanceExtensions::DISPLAY))?;
+
+        let mut list = SmallVec::<[vk::DisplayModePropertiesKHR; 8]>::new();
+
+        let ret = unsafe {
+            crate::utility::read_into_vector(&mut list, |count, data| {
+                get_fn(self.physical_device.handle(), self.handle, count, data)
+            })
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        let this = self.clone();
+        let iter = list.into_iter().map(move |props| DisplayModeProperties {
+            mode: unsafe { DisplayMode::from_handle(this.clone(), props.display_mode) },
+            params: DisplayModeParams::from_raw(&props.parameters),
+        });
+
+        Ok(iter)
+    }
+
+    /// Creates a new display mode for this display.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the `VK_KHR_display`
+    /// extension is not enabled.
+    #[doc(alias = "vkCreateDisplayModeKHR")]
+    pub fn create_mode(&self, params: DisplayModeParams) -> Result<DisplayMode> {
+        let create_fn = self
+            .physical_device
+            .instance()
+            .fns()
+            .create_display_mode
+            .ok_or(Error::MissingInstanceExtension(InstanceExtensions::DISPLAY))?;
+
+        let create_info = vk::DisplayModeCreateInfoKHR {
+            parameters: vk::DisplayModeParametersKHR {
+                visible_region: vk::Extent2D {
+                    width: params.visible_region[0],
+                    height: params.visible_region[1],
+                },
+                refresh_rate: params.refresh_rate,
+            },
+            flags: vk::DisplayModeCreateFlagsKHR::empty(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::DISPLAY_MODE_CREATE_INFO_KHR,
+        };
+
+        let mut handle = vk::DisplayModeKHR::null();
+
+        let ret = unsafe {
+            create_fn(
+                self.physical_device.handle(),
+                self.handle,
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(unsafe { DisplayMode::from_handle(self.clone(), handle) })
+    }
+
+    /// Acquires exclusive control of this display, using the provided DRM master file
+    /// descriptor.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the
+    /// `VK_EXT_acquire_drm_display` extension is not enabled.
+    #[doc(alias = "vkAcquireDrmDisplayEXT")]
+    pub fn acquire_drm(&self, drm_fd: i32) -> Result<()> {
+        let acquire_fn = self
+            .physical_device
+            .instance()
+            .fns()
+            .acquire_drm_display
+            .ok_or(Error::MissingInstanceExtension(
+                InstanceExtensions::ACQUIRE_DRM_DISPLAY,
+            ))?;
+
+        let ret = unsafe { acquire_fn(self.physical_device.handle(), drm_fd, self.handle) };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+
+    /// Releases the control of this display previously acquired by the application.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the
+    /// `VK_EXT_direct_mode_display` extension is not enabled.
+    #[doc(alias = "vkReleaseDisplayEXT")]
+    pub fn release(&self) -> Result<()> {
+        let release_fn = self
+            .physical_device
+            .instance()
+            .fns()
+            .release_display
+            .ok_or(Error::MissingInstanceExtension(
+                InstanceExtensions::DIRECT_MODE_DISPLAY,
+            ))?;
+
+        let ret = unsafe { release_fn(self.physical_device.handle(), self.handle) };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the physical device that this display is attached to.
+    #[inline(always)]
+    pub fn physical_device(&self) -> &PhysicalDevice {
+        &self.physical_device
+    }
+
+    /// Returns the Vulkan handle for this display.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::DisplayKHR {
+        self.handle
+    }
+}
+
+/// A mode (resolution and refresh rate) that a [`Display`] can operate in.
+///
+/// Like displays, display modes are owned by their parent physical device and don't need to be
+/// destroyed explicitly.
+#[derive(Debug, Clone)]
+pub struct DisplayMode {
+    /// The display that this mode belongs to.
+    display: Display,
+
+    /// The handle to the display mode.
+    handle: vk::DisplayModeKHR,
+}
+
+impl DisplayMode {
+    /// Creates a new [`DisplayMode`] instance.
+    ///
+    /// # Safety
+    ///
+    /// The provided handle must be valid and belong to the provided display.
+    #[inline]
+    pub unsafe fn from_handle(display: Display, handle: vk::DisplayModeKHR) -> Self {
+        Self { display, handle }
+    }
+
+    /// Returns the capabilities of the plane at index `plane_index` when used with this display
+    /// mode.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the `VK_KHR_display`
+    /// extension is not enabled.
+    #[doc(alias = "vkGetDisplayPlaneCapabilitiesKHR")]
+    pub fn plane_capabilities(&self, plane_index: u32) -> Result<DisplayPlaneCaps> {
+        let physical_device = self.display.physical_device();
+
+        let get_fn = physical_device
+            .instance()
+            .fns()
+            .get_display_plane_capabilities
+            .ok_or(Error::MissingInstanceExtension(InstanceExtensions::DISPLAY))?;
+
+        let mut caps = MaybeUninit::<vk::DisplayPlaneCapabilitiesKHR>::uninit();
+
+        let ret = unsafe {
+            get_fn(
+                physical_device.handle(),
+                self.handle,
+                plane_index,
+                caps.as_mut_ptr(),
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        let caps = unsafe { caps.assume_init_ref() };
+
+        Ok(DisplayPlaneCaps {
+            supported_alpha: DisplayPlaneAlphas::from_bits_retain(caps.supported_alpha.as_raw()),
+            min_src_position: [caps.min_src_position.x, caps.min_src_position.y],
+            max_src_position: [caps.max_src_position.x, caps.max_src_position.y],
+            min_src_extent: [caps.min_src_extent.width, caps.min_src_extent.height],
+            max_src_extent: [caps.max_src_extent.width, caps.max_src_extent.height],
+            min_dst_position: [caps.min_dst_position.x, caps.min_dst_position.y],
+            max_dst_position: [caps.max_dst_position.x, caps.max_dst_position.y],
+            min_dst_extent: [caps.min_dst_extent.width, caps.min_dst_extent.height],
+            max_dst_extent: [caps.max_dst_extent.width, caps.max_dst_extent.height],
+        })
+    }
+
+    /// Returns the display that this mode belongs to.
+    #[inline(always)]
+    pub fn display(&self) -> &Display {
+        &self.display
+    }
+
+    /// Returns the Vulkan handle for this display mode.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::DisplayModeKHR {
+        self.handle
+    }
+}
+
+/// The parameters of a [`DisplayMode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkDisplayModeParametersKHR")]
+pub struct DisplayModeParams {
+    /// The visible region of the display, in pixels.
+    pub visible_region: [u32; 2],
+    /// The number of times the display is refreshed each second, multiplied by 1000.
+    pub refresh_rate: u32,
+}
+
+impl DisplayModeParams {
+    /// Converts the provided raw Vulkan parameters into a [`DisplayModeParams`].
+    fn from_raw(raw: &vk::DisplayModeParametersKHR) -> Self {
+        Self {
+            visible_region: [raw.visible_region.width, raw.visible_region.height],
+            refresh_rate: raw.refresh_rate,
+        }
+    }
+}
+
+/// Information about a [`DisplayMode`], as returned by [`Display::mode_properties`].
+#[derive(Debug, Clone)]
+pub struct DisplayModeProperties {
+    /// The display mode itself.
+    pub mode: DisplayMode,
+    /// The parameters of the display mode.
+    pub params: DisplayModeParams,
+}
+
+/// Information about a [`Display`], as returned by [`PhysicalDevice::display_properties`].
+#[derive(Debug, Clone)]
+pub struct DisplayProperties {
+    /// The display itself.
+    pub display: Display,
+    /// A human-readable name for the display.
+    pub name: Box<str>,
+    /// The physical size of the display, in millimeters.
+    pub physical_dimensions: [u32; 2],
+    /// The native (or preferred) resolution of the display.
+    pub physical_resolution: [u32; 2],
+    /// The transforms that are supported by the display.
+    pub supported_transforms: SurfaceTransforms,
+    /// Whether the planes of the display can have their z-order changed.
+    pub plane_reorder_possible: bool,
+    /// Whether the display is able to self-refresh and only needs to be updated when its
+    /// content changes.
+    pub persistent_content: bool,
+}
+
+impl DisplayProperties {
+    /// Converts the provided raw Vulkan properties into a [`DisplayProperties`].
+    ///
+    /// # Safety
+    ///
+    /// The handles and pointers referenced by `raw` must be valid and belong to the provided
+    /// physical device.
+    pub(crate) unsafe fn from_raw(
+        physical_device: PhysicalDevice,
+        raw: &vk::DisplayPropertiesKHR,
+    ) -> Self {
+        let name = if raw.display_name.is_null() {
+            Box::default()
+        } else {
+            CStr::from_ptr(raw.display_name)
+                .to_str()
+                .unwrap_or_default()
+                .into()
+        };
+
+        Self {
+            display: Display::from_handle(physical_device, raw.display),
+            name,
+            physical_dimensions: [
+                raw.physical_dimensions.width,
+                raw.physical_dimensions.height,
+            ],
+            physical_resolution: [
+                raw.physical_resolution.width,
+                raw.physical_resolution.height,
+            ],
+            supported_transforms: SurfaceTransforms::from_bits_retain(
+                raw.supported_transforms.as_raw(),
+            ),
+            plane_reorder_possible: raw.plane_reorder_possible != vk::FALSE,
+            persistent_content: raw.persistent_content != vk::FALSE,
+        }
+    }
+}
+
+/// Information about a display plane, as returned by
+/// [`PhysicalDevice::display_plane_properties`].
+#[derive(Debug, Clone)]
+pub struct DisplayPlaneProperties {
+    /// The display that the plane is currently associated with, if any.
+    pub current_display: Option<Display>,
+    /// The current z-order of the plane.
+    pub current_stack_index: u32,
+}
+
+/// Represents the capabilities of a display plane, when used with a specific [`DisplayMode`].
+#[derive(Debug, Clone)]
+#[doc(alias = "VkDisplayPlaneCapabilitiesKHR")]
+pub struct DisplayPlaneCaps {
+    /// A bitmask of [`DisplayPlaneAlpha`]s that are supported by the plane.
+    pub supported_alpha: DisplayPlaneAlphas,
+    /// The minimum source rectangle offset supported by the plane.
+    pub min_src_position: [i32; 2],
+    /// The maximum source rectangle offset supported by the plane.
+    pub max_src_position: [i32; 2],
+    /// The minimum source rectangle size supported by the plane.
+    pub min_src_extent: [u32; 2],
+    /// The maximum source rectangle size supported by the plane.
+    pub max_src_extent: [u32; 2],
+    /// The minimum destination rectangle offset supported by the plane.
+    pub min_dst_position: [i32; 2],
+    /// The maximum destination rectangle offset supported by the plane.
+    pub max_dst_position: [i32; 2],
+    /// The minimum destination rectangle size supported by the plane.
+    pub min_dst_extent: [u32; 2],
+    /// The maximum destination rectangle size supported by the plane.
+    pub max_dst_extent: [u32; 2],
+}
+
+bitflags! {
+    /// A set of [`DisplayPlaneAlpha`] modes.
+    ///
+    /// More information can be found in the documentation for [`DisplayPlaneAlpha`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct DisplayPlaneAlphas: u32 {
+        const OPAQUE = vk::DisplayPlaneAlphaFlagsKHR::OPAQUE.as_raw();
+        const GLOBAL = vk::DisplayPlaneAlphaFlagsKHR::GLOBAL.as_raw();
+        const PER_PIXEL = vk::DisplayPlaneAlphaFlagsKHR::PER_PIXEL.as_raw();
+        const PER_PIXEL_PREMULTIPLIED = vk::DisplayPlaneAlphaFlagsKHR::PER_PIXEL_PREMULTIPLIED.as_raw();
+    }
+}
+
+impl From<DisplayPlaneAlpha> for DisplayPlaneAlphas {
+    fn from(value: DisplayPlaneAlpha) -> Self {
+        match value {
+            DisplayPlaneAlpha::Opaque => Self::OPAQUE,
+            DisplayPlaneAlpha::Global => Self::GLOBAL,
+            DisplayPlaneAlpha::PerPixel => Self::PER_PIXEL,
+            DisplayPlaneAlpha::PerPixelPremultiplied => Self::PER_PIXEL_PREMULTIPLIED,
+        }
+    }
+}
+
+/// The way a display plane is blended with the planes below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum DisplayPlaneAlpha {
+    /// The plane is opaque.
+    Opaque = vk::DisplayPlaneAlphaFlagsKHR::OPAQUE.as_raw(),
+    /// The plane uses the global alpha value specified at surface creation.
+    Global = vk::DisplayPlaneAlphaFlagsKHR::GLOBAL.as_raw(),
+    /// The plane uses the alpha component of its pixels.
+    PerPixel = vk::DisplayPlaneAlphaFlagsKHR::PER_PIXEL.as_raw(),
+    /// The plane uses the alpha component of its pixels, which are assumed to be
+    /// pre-multiplied.
+    PerPixelPremultiplied = vk::DisplayPlaneAlphaFlagsKHR::PER_PIXEL_PREMULTIPLIED.as_raw(),
+}
+
+/// Describes how to create a [`Surface`](crate::Surface) from a display plane.
+#[derive(Debug, Clone)]
+#[doc(alias = "VkDisplaySurfaceCreateInfoKHR")]
+pub struct DisplaySurfaceDesc<'a> {
+    /// The display mode to use when presenting to the surface.
+    pub mode: &'a DisplayMode,
+    /// The index of the plane on which the surface appears.
+    pub plane_index: u32,
+    /// The z-order of the plane.
+    pub plane_stack_index: u32,
+    /// The transform to apply to the images as part of the scanout operation.
+    pub transform: SurfaceTransform,
+    /// The global alpha value, used when `alpha_mode` is [`DisplayPlaneAlpha::Global`].
+    pub global_alpha: f32,
+    /// The way the plane is blended with the planes below it.
+    pub alpha_mode: DisplayPlaneAlpha,
+    /// The size of the presentable images of the surface.
+    pub image_extent: [u32; 2],
+}
@@ -1,15 +1,104 @@
 use ash::vk;
 
-/// An error that might occur while creating a [`Library`] instance.
-#[derive(Debug, Clone, Copy)]
-pub struct Error(vk::Result);
+use crate::{DeviceExtensions, DeviceFeatures, DeviceLimit, InstanceExtensions, SpirvError};
+
+/// An error that might occur when calling into the Vulkan API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// A Vulkan function returned an error code.
+    Vulkan(vk::Result),
+    /// The function requires an instance extension that was not enabled when the instance was
+    /// created.
+    MissingInstanceExtension(InstanceExtensions),
+    /// The function requires a device extension that is either not supported by the physical
+    /// device, or was not enabled when the device was created.
+    MissingDeviceExtension(DeviceExtensions),
+    /// The function requires a device feature that was not enabled when the device was created.
+    MissingDeviceFeature(DeviceFeatures),
+    /// The request exceeds a limit of the physical device.
+    LimitExceeded(DeviceLimit),
+    /// The provided window or display handle has no Vulkan surface counterpart on the current
+    /// platform.
+    UnsupportedWindowHandle,
+    /// The window or display handle of a window is not currently available.
+    WindowHandleUnavailable,
+    /// The function must be called on the main thread of the application, such as when it
+    /// accesses the view of an AppKit window.
+    NotMainThread,
+    /// None of the memory types allowed for a resource have the required properties.
+    NoSuitableMemoryType,
+    /// The work requires a queue, or a command pool for its queue family, which was not
+    /// provided.
+    MissingQueue,
+    /// The provided code is not a valid SPIR-V module.
+    InvalidSpirv(SpirvError),
+    /// An I/O error occurred while reading a file.
+    Io(std::io::ErrorKind),
+}
 
 impl From<vk::Result> for Error {
     #[inline]
     fn from(value: vk::Result) -> Self {
-        Self(value)
+        Self::Vulkan(value)
     }
 }
 
+impl From<SpirvError> for Error {
+    #[inline]
+    fn from(value: SpirvError) -> Self {
+        Self::InvalidSpirv(value)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value.kind())
+    }
+}
+
+#[cfg(feature = "raw-window-handle")]
+impl From<raw_window_handle::HandleError> for Error {
+    fn from(value: raw_window_handle::HandleError) -> Self {
+        match value {
+            raw_window_handle::HandleError::NotSupported => Self::UnsupportedWindowHandle,
+            _ => Self::WindowHandleUnavailable,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Vulkan(ret) => write!(f, "Vulkan error: {}", ret),
+            Self::MissingInstanceExtension(ext) => write!(
+                f,
+                "the {} extension is not enabled",
+                ext.name().to_string_lossy()
+            ),
+            Self::MissingDeviceExtension(ext) => write!(
+                f,
+                "the {} extension is not available",
+                ext.name().to_string_lossy()
+            ),
+            Self::MissingDeviceFeature(features) => {
+                write!(f, "missing device features: {:?}", features)
+            }
+            Self::LimitExceeded(limit) => {
+                write!(f, "the {} limit of the device is exceeded", limit.name())
+            }
+            Self::UnsupportedWindowHandle => write!(f, "unsupported window handle"),
+            Self::WindowHandleUnavailable => write!(f, "window handle unavailable"),
+            Self::NotMainThread => write!(f, "not called on the main thread"),
+            Self::NoSuitableMemoryType => write!(f, "no suitable memory type"),
+            Self::MissingQueue => write!(f, "a required queue was not provided"),
+            Self::InvalidSpirv(err) => write!(f, "invalid SPIR-V: {}", err),
+            Self::Io(kind) => write!(f, "I/O error: {}", kind),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 /// The result type for the crate.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -1,24 +1,96 @@
 use ash::vk;
 
+use crate::ImageAspects;
+
 /// The encoding format of a color.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(i32)]
 pub enum Format {
+    R8Unorm = vk::Format::R8_UNORM.as_raw(),
+    Rg8Unorm = vk::Format::R8G8_UNORM.as_raw(),
     Rgba8Unorm = vk::Format::R8G8B8A8_UNORM.as_raw(),
     Bgra8Unorm = vk::Format::B8G8R8A8_UNORM.as_raw(),
-    Srgb8Srgb = vk::Format::B8G8R8A8_SRGB.as_raw(),
-    Bgra8Srgb = vk::Format::R8G8B8A8_SRGB.as_raw(),
+    Rgba8Srgb = vk::Format::R8G8B8A8_SRGB.as_raw(),
+    Bgra8Srgb = vk::Format::B8G8R8A8_SRGB.as_raw(),
+    A2Bgr10Unorm = vk::Format::A2B10G10R10_UNORM_PACK32.as_raw(),
+    R16Float = vk::Format::R16_SFLOAT.as_raw(),
+    Rg16Float = vk::Format::R16G16_SFLOAT.as_raw(),
+    Rgba16Float = vk::Format::R16G16B16A16_SFLOAT.as_raw(),
+    R32Uint = vk::Format::R32_UINT.as_raw(),
+    R32Sint = vk::Format::R32_SINT.as_raw(),
+    Rg32Uint = vk::Format::R32G32_UINT.as_raw(),
+    Rg32Sint = vk::Format::R32G32_SINT.as_raw(),
+    Rgb32Uint = vk::Format::R32G32B32_UINT.as_raw(),
+    Rgb32Sint = vk::Format::R32G32B32_SINT.as_raw(),
+    Rgba32Uint = vk::Format::R32G32B32A32_UINT.as_raw(),
+    Rgba32Sint = vk::Format::R32G32B32A32_SINT.as_raw(),
+    R32Float = vk::Format::R32_SFLOAT.as_raw(),
+    Rg32Float = vk::Format::R32G32_SFLOAT.as_raw(),
+    Rgb32Float = vk::Format::R32G32B32_SFLOAT.as_raw(),
+    Rgba32Float = vk::Format::R32G32B32A32_SFLOAT.as_raw(),
+    D16Unorm = vk::Format::D16_UNORM.as_raw(),
+    D32Float = vk::Format::D32_SFLOAT.as_raw(),
+    S8Uint = vk::Format::S8_UINT.as_raw(),
+    D24UnormS8Uint = vk::Format::D24_UNORM_S8_UINT.as_raw(),
+    D32FloatS8Uint = vk::Format::D32_SFLOAT_S8_UINT.as_raw(),
+    /// A two-plane YCbCr format with a full-resolution luma plane and a half-resolution
+    /// interleaved chroma plane (NV12).
+    G8B8R8TwoPlane420Unorm = vk::Format::G8_B8R8_2PLANE_420_UNORM.as_raw(),
+    /// A three-plane YCbCr format with a full-resolution luma plane and two half-resolution
+    /// chroma planes (I420).
+    G8B8R8ThreePlane420Unorm = vk::Format::G8_B8_R8_3PLANE_420_UNORM.as_raw(),
 }
 
 impl Format {
-    /// Converts the provided raw Vulkan format and turns it into a [`Format`].
-    pub(crate) fn from_raw(raw: vk::Format) -> Self {
-        match raw {
+    /// Converts the provided raw Vulkan format into a [`Format`], if it is known to this
+    /// crate.
+    pub(crate) fn from_raw(raw: vk::Format) -> Option<Self> {
+        Some(match raw {
+            vk::Format::R8_UNORM => Self::R8Unorm,
+            vk::Format::R8G8_UNORM => Self::Rg8Unorm,
             vk::Format::R8G8B8A8_UNORM => Self::Rgba8Unorm,
             vk::Format::B8G8R8A8_UNORM => Self::Bgra8Unorm,
-            vk::Format::R8G8B8A8_SRGB => Self::Srgb8Srgb,
+            vk::Format::R8G8B8A8_SRGB => Self::Rgba8Srgb,
             vk::Format::B8G8R8A8_SRGB => Self::Bgra8Srgb,
-            _ => unreachable!("unsupported format: {}", raw.as_raw()),
+            vk::Format::A2B10G10R10_UNORM_PACK32 => Self::A2Bgr10Unorm,
+            vk::Format::R16_SFLOAT => Self::R16Float,
+            vk::Format::R16G16_SFLOAT => Self::Rg16Float,
+            vk::Format::R16G16B16A16_SFLOAT => Self::Rgba16Float,
+            vk::Format::R32_UINT => Self::R32Uint,
+            vk::Format::R32_SINT => Self::R32Sint,
+            vk::Format::R32G32_UINT => Self::Rg32Uint,
+            vk::Format::R32G32_SINT => Self::Rg32Sint,
+            vk::Format::R32G32B32_UINT => Self::Rgb32Uint,
+            vk::Format::R32G32B32_SINT => Self::Rgb32Sint,
+            vk::Format::R32G32B32A32_UINT => Self::Rgba32Uint,
+            vk::Format::R32G32B32A32_SINT => Self::Rgba32Sint,
+            vk::Format::R32_SFLOAT => Self::R32Float,
+            vk::Format::R32G32_SFLOAT => Self::Rg32Float,
+            vk::Format::R32G32B32_SFLOAT => Self::Rgb32Float,
+            vk::Format::R32G32B32A32_SFLOAT => Self::Rgba32Float,
+            vk::Format::D16_UNORM => Self::D16Unorm,
+            vk::Format::D32_SFLOAT => Self::D32Float,
+            vk::Format::S8_UINT => Self::S8Uint,
+            vk::Format::D24_UNORM_S8_UINT => Self::D24UnormS8Uint,
+            vk::Format::D32_SFLOAT_S8_UINT => Self::D32FloatS8Uint,
+            vk::Format::G8_B8R8_2PLANE_420_UNORM => Self::G8B8R8TwoPlane420Unorm,
+            vk::Format::G8_B8_R8_3PLANE_420_UNORM => Self::G8B8R8ThreePlane420Unorm,
+            _ => return None,
+        })
+    }
+
+    /// Returns the aspects that refer to the whole content of images of this format.
+    ///
+    /// Multi-planar formats use the [`COLOR`](ImageAspects::COLOR) aspect, while their
+    /// individual planes can be referred to with the `PLANE_*` aspects.
+    pub fn aspects(self) -> ImageAspects {
+        match self {
+            Self::D16Unorm | Self::D32Float => ImageAspects::DEPTH,
+            Self::S8Uint => ImageAspects::STENCIL,
+            Self::D24UnormS8Uint | Self::D32FloatS8Uint => {
+                ImageAspects::DEPTH | ImageAspects::STENCIL
+            }
+            _ => ImageAspects::COLOR,
         }
     }
 }
@@ -31,11 +103,12 @@ pub enum ColorSpace {
 }
 
 impl ColorSpace {
-    /// Converts the provided raw Vulkan color space and turns it into a [`ColorSpace`].
-    pub(crate) fn from_raw(raw: vk::ColorSpaceKHR) -> Self {
+    /// Converts the provided raw Vulkan color space into a [`ColorSpace`], if it is known to
+    /// this crate.
+    pub(crate) fn from_raw(raw: vk::ColorSpaceKHR) -> Option<Self> {
         match raw {
-            vk::ColorSpaceKHR::SRGB_NONLINEAR => Self::Srgb,
-            _ => unreachable!("unsupported color space: {}", raw.as_raw()),
+            vk::ColorSpaceKHR::SRGB_NONLINEAR => Some(Self::Srgb),
+            _ => None,
         }
     }
 }
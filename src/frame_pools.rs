@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::ThreadId;
+
+use crate::{CommandPool, CommandPoolDesc, Device, Fence, Result};
+
+/// The command pools of a frame.
+struct Frame {
+    /// The fence that signals once the command buffers of the frame have finished executing,
+    /// if one was provided.
+    fence: Option<Arc<Fence>>,
+    /// The command pools that are not currently in use, by thread.
+    pools: HashMap<ThreadId, Vec<CommandPool>>,
+}
+
+/// A set of [`CommandPool`]s keyed by thread and by frame in flight.
+///
+/// Each thread recording commands for a frame checks out its own pool, which it records into
+/// without any locking. Once the fence of a frame signals, the pools of the frame are reset
+/// and their command buffers are recycled for a later frame.
+pub struct FrameCommandPools {
+    /// The device that owns the pools.
+    device: Arc<Device>,
+    /// The parameters used to create new pools.
+    desc: CommandPoolDesc,
+    /// The pools of each frame in flight.
+    frames: Box<[Mutex<Frame>]>,
+}
+
+impl FrameCommandPools {
+    /// Creates a new [`FrameCommandPools`] instance for `frame_count` frames in flight.
+    ///
+    /// Pools are created lazily, the first time a thread needs one for a given frame.
+    pub fn new(device: Arc<Device>, desc: CommandPoolDesc, frame_count: usize) -> Self {
+        let frames = (0..frame_count)
+            .map(|_| {
+                Mutex::new(Frame {
+                    fence: None,
+                    pools: HashMap::new(),
+                })
+            })
+            .collect();
+
+        Self {
+            device,
+            desc,
+            frames,
+        }
+    }
+
+    /// Checks out a pool of the current thread for the provided frame.
+    ///
+    /// The pool is given back when the returned guard is dropped. A thread may check out
+    /// multiple pools for the same frame at once.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `frame` is not lower than the number of frames in flight.
+    pub fn pool(&self, frame: usize) -> Result<FrameCommandPool<'_>> {
+        let thread = std::thread::current().id();
+
+        let pool = self.frames[frame]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pools
+            .get_mut(&thread)
+            .and_then(Vec::pop);
+
+        let pool = match pool {
+            Some(pool) => pool,
+            None => CommandPool::new(self.device.clone(), self.desc.clone())?,
+        };
+
+        Ok(FrameCommandPool {
+            owner: self,
+            frame,
+            thread,
+            pool: Some(pool),
+        })
+    }
+
+    /// Sets the fence that signals once the command buffers of the provided frame have
+    /// finished executing.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `frame` is not lower than the number of frames in flight.
+    ///
+    /// # Safety
+    ///
+    /// `fence` must be signaled by the last submission of the command buffers of the frame,
+    /// and must not be reset before the next call to [`FrameCommandPools::recycle`] for that
+    /// frame. Those command buffers must not be submitted again once the frame has been
+    /// recycled.
+    pub unsafe fn set_fence(&self, frame: usize, fence: Arc<Fence>) {
+        self.frames[frame]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .fence = Some(fence);
+    }
+
+    /// Recycles the command buffers of the provided frame if its fence has signaled.
+    ///
+    /// Returns whether the frame was recycled. Pools that are checked out during this call are
+    /// not reset.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `frame` is not lower than the number of frames in flight.
+    ///
+    /// # Safety
+    ///
+    /// If no fence was set for the frame since it was last recycled, none of the command
+    /// buffers allocated from its pools may be pending execution.
+    #[doc(alias = "vkGetFenceStatus")]
+    pub unsafe fn recycle(&self, frame: usize) -> Result<bool> {
+        let mut frame = self.frames[frame]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(fence) = &frame.fence {
+            if !fence.status()? {
+                return Ok(false);
+            }
+
+            frame.fence = None;
+        }
+
+        // The fence has signaled, so none of the command buffers are pending execution.
+        for pool in frame.pools.values_mut().flatten() {
+            pool.reset(false)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Returns the number of frames in flight.
+    #[inline(always)]
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns the device that owns the pools.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+/// A [`CommandPool`] checked out from [`FrameCommandPools`] by the current thread.
+pub struct FrameCommandPool<'a> {
+    /// The set of pools that the pool was checked out from.
+    owner: &'a FrameCommandPools,
+    /// The frame that the pool belongs to.
+    frame: usize,
+    /// The thread that checked out the pool.
+    thread: ThreadId,
+    /// The pool, which is only taken when the guard is dropped.
+    pool: Option<CommandPool>,
+}
+
+impl FrameCommandPool<'_> {
+    /// Returns the frame that this pool belongs to.
+    #[inline(always)]
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+}
+
+impl Deref for FrameCommandPool<'_> {
+    type Target = CommandPool;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.pool.as_ref().unwrap()
+    }
+}
+
+impl Drop for FrameCommandPool<'_> {
+    fn drop(&mut self) {
+        let pool = self.pool.take().unwrap();
+
+        self.owner.frames[self.frame]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pools
+            .entry(self.thread)
+            .or_default()
+            .push(pool);
+    }
+}
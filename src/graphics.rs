@@ -0,0 +1,2367 @@
+use std::ffi::CString;
+use std::sync::Arc;
+
+use ash::vk;
+use bitflags::bitflags;
+use smallvec::SmallVec;
+
+use crate::pipeline::Specialization;
+use crate::{
+    vk_ext, CommandBuffer, CompareOp, Device, DeviceFeatures, DeviceLimit, Error, Format,
+    PhysicalDeviceLimits, PipelineBindPoint, PipelineLayout, PipelineRenderingDesc, Recording,
+    Rect, RenderPass, Result, SampleCount, ShaderModule, ShaderStage, SpecializationValue,
+    Viewport,
+};
+
+/// A shader stage of a graphics pipeline.
+#[derive(Clone, Copy)]
+#[doc(alias = "VkPipelineShaderStageCreateInfo")]
+pub struct ShaderStageDesc<'a> {
+    /// The stage that the shader runs in.
+    pub stage: ShaderStage,
+    /// The module containing the shader.
+    pub module: &'a ShaderModule,
+    /// The name of the entry point of the shader.
+    pub entry_point: &'a str,
+    /// The values of the specialization constants, as `(constant_id, value)` pairs.
+    pub specialization: &'a [(u32, SpecializationValue)],
+}
+
+impl<'a> ShaderStageDesc<'a> {
+    /// Creates a new [`ShaderStageDesc`] without specialization constants.
+    #[inline]
+    pub fn new(stage: ShaderStage, module: &'a ShaderModule, entry_point: &'a str) -> Self {
+        Self {
+            stage,
+            module,
+            entry_point,
+            specialization: &[],
+        }
+    }
+}
+
+/// How often the vertex attributes of a binding advance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkVertexInputRate")]
+pub enum VertexInputRate {
+    /// The attributes advance with each vertex.
+    Vertex = vk::VertexInputRate::VERTEX.as_raw(),
+    /// The attributes advance with each instance.
+    Instance = vk::VertexInputRate::INSTANCE.as_raw(),
+}
+
+/// A vertex buffer binding that a graphics pipeline reads from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkVertexInputBindingDescription")]
+pub struct VertexBinding {
+    /// The binding number, which matches the index passed when binding vertex buffers.
+    pub binding: u32,
+    /// The distance, in bytes, between two consecutive elements of the buffer.
+    pub stride: u32,
+    /// How often the attributes of the binding advance.
+    pub input_rate: VertexInputRate,
+}
+
+/// A vertex attribute, which feeds a vertex shader input from a [`VertexBinding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkVertexInputAttributeDescription")]
+pub struct VertexAttribute {
+    /// The location of the vertex shader input.
+    pub location: u32,
+    /// The binding that the attribute is read from.
+    pub binding: u32,
+    /// The format of the attribute in the buffer.
+    pub format: Format,
+    /// The offset of the attribute within an element of the binding, in bytes.
+    pub offset: u32,
+}
+
+/// The layout of the vertex buffers read by a graphics pipeline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[doc(alias = "VkPipelineVertexInputStateCreateInfo")]
+pub struct VertexInputState<'a> {
+    /// The vertex buffer bindings.
+    pub bindings: &'a [VertexBinding],
+    /// The vertex attributes.
+    pub attributes: &'a [VertexAttribute],
+}
+
+/// How vertices are assembled into primitives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkPrimitiveTopology")]
+pub enum PrimitiveTopology {
+    PointList = vk::PrimitiveTopology::POINT_LIST.as_raw(),
+    LineList = vk::PrimitiveTopology::LINE_LIST.as_raw(),
+    LineStrip = vk::PrimitiveTopology::LINE_STRIP.as_raw(),
+    TriangleList = vk::PrimitiveTopology::TRIANGLE_LIST.as_raw(),
+    TriangleStrip = vk::PrimitiveTopology::TRIANGLE_STRIP.as_raw(),
+    TriangleFan = vk::PrimitiveTopology::TRIANGLE_FAN.as_raw(),
+    LineListWithAdjacency = vk::PrimitiveTopology::LINE_LIST_WITH_ADJACENCY.as_raw(),
+    LineStripWithAdjacency = vk::PrimitiveTopology::LINE_STRIP_WITH_ADJACENCY.as_raw(),
+    TriangleListWithAdjacency = vk::PrimitiveTopology::TRIANGLE_LIST_WITH_ADJACENCY.as_raw(),
+    TriangleStripWithAdjacency = vk::PrimitiveTopology::TRIANGLE_STRIP_WITH_ADJACENCY.as_raw(),
+    /// Vertices are grouped into patches, which are consumed by tessellation shaders.
+    PatchList = vk::PrimitiveTopology::PATCH_LIST.as_raw(),
+}
+
+impl PrimitiveTopology {
+    /// Returns whether this topology is a list, whose primitives cannot be restarted.
+    pub fn is_list(self) -> bool {
+        matches!(
+            self,
+            Self::PointList
+                | Self::LineList
+                | Self::TriangleList
+                | Self::LineListWithAdjacency
+                | Self::TriangleListWithAdjacency
+                | Self::PatchList
+        )
+    }
+}
+
+/// How vertices are assembled into primitives by a graphics pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkPipelineInputAssemblyStateCreateInfo")]
+#[doc(alias = "VkPipelineTessellationStateCreateInfo")]
+pub struct InputAssemblyState {
+    /// The topology of the primitives.
+    pub topology: PrimitiveTopology,
+    /// Whether a special index value restarts the assembly of strips and fans.
+    pub primitive_restart: bool,
+    /// The number of control points per patch, used with [`PrimitiveTopology::PatchList`].
+    pub patch_control_points: u32,
+}
+
+impl Default for InputAssemblyState {
+    fn default() -> Self {
+        Self {
+            topology: PrimitiveTopology::TriangleList,
+            primitive_restart: false,
+            patch_control_points: 3,
+        }
+    }
+}
+
+/// The viewports and scissor rectangles of a graphics pipeline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[doc(alias = "VkPipelineViewportStateCreateInfo")]
+pub struct ViewportState<'a> {
+    /// The number of viewports and scissor rectangles.
+    ///
+    /// This is ignored for viewports when [`DynamicState::ViewportWithCount`] is used, and
+    /// for scissors when [`DynamicState::ScissorWithCount`] is used.
+    pub count: u32,
+    /// The viewports, which must contain `count` elements unless they are dynamic.
+    pub viewports: &'a [Viewport],
+    /// The scissor rectangles, which must contain `count` elements unless they are dynamic.
+    pub scissors: &'a [Rect],
+}
+
+impl Default for ViewportState<'_> {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            viewports: &[],
+            scissors: &[],
+        }
+    }
+}
+
+/// How polygons are rasterized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkPolygonMode")]
+pub enum PolygonMode {
+    /// Polygons are filled.
+    Fill = vk::PolygonMode::FILL.as_raw(),
+    /// The edges of polygons are drawn as lines.
+    ///
+    /// This requires the [`DeviceFeatures::FILL_MODE_NON_SOLID`] feature.
+    Line = vk::PolygonMode::LINE.as_raw(),
+    /// The vertices of polygons are drawn as points.
+    ///
+    /// This requires the [`DeviceFeatures::FILL_MODE_NON_SOLID`] feature.
+    Point = vk::PolygonMode::POINT.as_raw(),
+}
+
+/// The triangles that are discarded depending on the way they are facing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+#[doc(alias = "VkCullModeFlags")]
+pub enum CullMode {
+    None = vk::CullModeFlags::NONE.as_raw(),
+    Front = vk::CullModeFlags::FRONT.as_raw(),
+    Back = vk::CullModeFlags::BACK.as_raw(),
+    FrontAndBack = vk::CullModeFlags::FRONT_AND_BACK.as_raw(),
+}
+
+/// The winding order of the vertices of front-facing triangles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkFrontFace")]
+pub enum FrontFace {
+    CounterClockwise = vk::FrontFace::COUNTER_CLOCKWISE.as_raw(),
+    Clockwise = vk::FrontFace::CLOCKWISE.as_raw(),
+}
+
+/// The factors of the bias added to the depth of fragments.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DepthBias {
+    /// A constant depth value added to each fragment.
+    pub constant_factor: f32,
+    /// The maximum or minimum bias of a fragment, or zero to leave it unclamped.
+    pub clamp: f32,
+    /// A factor applied to the slope of the fragment's depth.
+    pub slope_factor: f32,
+}
+
+/// How primitives are rasterized by a graphics pipeline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[doc(alias = "VkPipelineRasterizationStateCreateInfo")]
+pub struct RasterizationState {
+    /// Whether the depth of fragments is clamped instead of clipping primitives.
+    ///
+    /// This requires the [`DeviceFeatures::DEPTH_CLAMP`] feature.
+    pub depth_clamp: bool,
+    /// Whether primitives are discarded before rasterization.
+    pub rasterizer_discard: bool,
+    /// How polygons are rasterized.
+    pub polygon_mode: PolygonMode,
+    /// The triangles that are discarded depending on the way they are facing.
+    pub cull_mode: CullMode,
+    /// The winding order of front-facing triangles.
+    pub front_face: FrontFace,
+    /// The bias added to the depth of fragments, if any.
+    pub depth_bias: Option<DepthBias>,
+    /// The width of rasterized lines.
+    ///
+    /// Widths other than `1.0` require the [`DeviceFeatures::WIDE_LINES`] feature.
+    pub line_width: f32,
+}
+
+impl Default for RasterizationState {
+    fn default() -> Self {
+        Self {
+            depth_clamp: false,
+            rasterizer_discard: false,
+            polygon_mode: PolygonMode::Fill,
+            cull_mode: CullMode::None,
+            front_face: FrontFace::CounterClockwise,
+            depth_bias: None,
+            line_width: 1.0,
+        }
+    }
+}
+
+/// How the samples of fragments are generated by a graphics pipeline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[doc(alias = "VkPipelineMultisampleStateCreateInfo")]
+pub struct MultisampleState {
+    /// The number of samples per pixel, which must match the attachments rendered to.
+    pub samples: SampleCount,
+    /// The minimum fraction of samples that are shaded individually, if sample shading is
+    /// enabled.
+    ///
+    /// This requires the [`DeviceFeatures::SAMPLE_RATE_SHADING`] feature.
+    pub min_sample_shading: Option<f32>,
+    /// Whether the coverage of fragments is derived from the alpha of the first color
+    /// output.
+    pub alpha_to_coverage: bool,
+}
+
+impl Default for MultisampleState {
+    fn default() -> Self {
+        Self {
+            samples: SampleCount::X1,
+            min_sample_shading: None,
+            alpha_to_coverage: false,
+        }
+    }
+}
+
+/// An operation applied to the stencil value of a fragment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkStencilOp")]
+pub enum StencilOp {
+    Keep = vk::StencilOp::KEEP.as_raw(),
+    Zero = vk::StencilOp::ZERO.as_raw(),
+    Replace = vk::StencilOp::REPLACE.as_raw(),
+    IncrementAndClamp = vk::StencilOp::INCREMENT_AND_CLAMP.as_raw(),
+    DecrementAndClamp = vk::StencilOp::DECREMENT_AND_CLAMP.as_raw(),
+    Invert = vk::StencilOp::INVERT.as_raw(),
+    IncrementAndWrap = vk::StencilOp::INCREMENT_AND_WRAP.as_raw(),
+    DecrementAndWrap = vk::StencilOp::DECREMENT_AND_WRAP.as_raw(),
+}
+
+/// The stencil test and operations applied to the fragments of one face of primitives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkStencilOpState")]
+pub struct StencilOpState {
+    /// The operation applied when the stencil test fails.
+    pub fail_op: StencilOp,
+    /// The operation applied when both the stencil and depth tests pass.
+    pub pass_op: StencilOp,
+    /// The operation applied when the stencil test passes but the depth test fails.
+    pub depth_fail_op: StencilOp,
+    /// The comparison performed by the stencil test.
+    pub compare_op: CompareOp,
+    /// The bits of the stencil values that are compared.
+    pub compare_mask: u32,
+    /// The bits of the stencil values that are written.
+    pub write_mask: u32,
+    /// The reference value that stencil values are compared against.
+    pub reference: u32,
+}
+
+impl Default for StencilOpState {
+    fn default() -> Self {
+        Self {
+            fail_op: StencilOp::Keep,
+            pass_op: StencilOp::Keep,
+            depth_fail_op: StencilOp::Keep,
+            compare_op: CompareOp::Always,
+            compare_mask: !0,
+            write_mask: !0,
+            reference: 0,
+        }
+    }
+}
+
+impl StencilOpState {
+    /// Converts this state into its raw Vulkan representation.
+    fn to_raw(self) -> vk::StencilOpState {
+        vk::StencilOpState {
+            fail_op: vk::StencilOp::from_raw(self.fail_op as i32),
+            pass_op: vk::StencilOp::from_raw(self.pass_op as i32),
+            depth_fail_op: vk::StencilOp::from_raw(self.depth_fail_op as i32),
+            compare_op: vk::CompareOp::from_raw(self.compare_op as i32),
+            compare_mask: self.compare_mask,
+            write_mask: self.write_mask,
+            reference: self.reference,
+        }
+    }
+}
+
+/// The depth and stencil tests of a graphics pipeline.
+///
+/// These are ignored when the pipeline does not render to a depth or stencil attachment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[doc(alias = "VkPipelineDepthStencilStateCreateInfo")]
+pub struct DepthStencilState {
+    /// Whether the depth test is enabled.
+    pub depth_test: bool,
+    /// Whether the depth of fragments which pass the depth test is written.
+    pub depth_write: bool,
+    /// The comparison performed by the depth test.
+    pub depth_compare_op: CompareOp,
+    /// Whether fragments are discarded when the stored depth is outside of `depth_bounds`.
+    ///
+    /// This requires the [`DeviceFeatures::DEPTH_BOUNDS`] feature.
+    pub depth_bounds_test: bool,
+    /// The minimum and maximum depth of the depth bounds test.
+    pub depth_bounds: [f32; 2],
+    /// Whether the stencil test is enabled.
+    pub stencil_test: bool,
+    /// The stencil state of front-facing primitives.
+    pub front: StencilOpState,
+    /// The stencil state of back-facing primitives.
+    pub back: StencilOpState,
+}
+
+impl Default for DepthStencilState {
+    fn default() -> Self {
+        Self {
+            depth_test: false,
+            depth_write: false,
+            depth_compare_op: CompareOp::Less,
+            depth_bounds_test: false,
+            depth_bounds: [0.0, 1.0],
+            stencil_test: false,
+            front: StencilOpState::default(),
+            back: StencilOpState::default(),
+        }
+    }
+}
+
+impl DepthStencilState {
+    /// Returns a state which tests and writes depth with the provided comparison.
+    pub fn depth(compare_op: CompareOp) -> Self {
+        Self {
+            depth_test: true,
+            depth_write: true,
+            depth_compare_op: compare_op,
+            ..Self::default()
+        }
+    }
+}
+
+/// A factor by which the source or destination color is multiplied when blending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkBlendFactor")]
+pub enum BlendFactor {
+    Zero = vk::BlendFactor::ZERO.as_raw(),
+    One = vk::BlendFactor::ONE.as_raw(),
+    SrcColor = vk::BlendFactor::SRC_COLOR.as_raw(),
+    OneMinusSrcColor = vk::BlendFactor::ONE_MINUS_SRC_COLOR.as_raw(),
+    DstColor = vk::BlendFactor::DST_COLOR.as_raw(),
+    OneMinusDstColor = vk::BlendFactor::ONE_MINUS_DST_COLOR.as_raw(),
+    SrcAlpha = vk::BlendFactor::SRC_ALPHA.as_raw(),
+    OneMinusSrcAlpha = vk::BlendFactor::ONE_MINUS_SRC_ALPHA.as_raw(),
+    DstAlpha = vk::BlendFactor::DST_ALPHA.as_raw(),
+    OneMinusDstAlpha = vk::BlendFactor::ONE_MINUS_DST_ALPHA.as_raw(),
+    ConstantColor = vk::BlendFactor::CONSTANT_COLOR.as_raw(),
+    OneMinusConstantColor = vk::BlendFactor::ONE_MINUS_CONSTANT_COLOR.as_raw(),
+    ConstantAlpha = vk::BlendFactor::CONSTANT_ALPHA.as_raw(),
+    OneMinusConstantAlpha = vk::BlendFactor::ONE_MINUS_CONSTANT_ALPHA.as_raw(),
+    SrcAlphaSaturate = vk::BlendFactor::SRC_ALPHA_SATURATE.as_raw(),
+}
+
+/// The operation that combines the source and destination colors when blending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkBlendOp")]
+pub enum BlendOp {
+    Add = vk::BlendOp::ADD.as_raw(),
+    Subtract = vk::BlendOp::SUBTRACT.as_raw(),
+    ReverseSubtract = vk::BlendOp::REVERSE_SUBTRACT.as_raw(),
+    Min = vk::BlendOp::MIN.as_raw(),
+    Max = vk::BlendOp::MAX.as_raw(),
+}
+
+/// A bitwise operation that combines the source and destination colors instead of blending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkLogicOp")]
+pub enum LogicOp {
+    Clear = vk::LogicOp::CLEAR.as_raw(),
+    And = vk::LogicOp::AND.as_raw(),
+    AndReverse = vk::LogicOp::AND_REVERSE.as_raw(),
+    Copy = vk::LogicOp::COPY.as_raw(),
+    AndInverted = vk::LogicOp::AND_INVERTED.as_raw(),
+    NoOp = vk::LogicOp::NO_OP.as_raw(),
+    Xor = vk::LogicOp::XOR.as_raw(),
+    Or = vk::LogicOp::OR.as_raw(),
+    Nor = vk::LogicOp::NOR.as_raw(),
+    Equivalent = vk::LogicOp::EQUIVALENT.as_raw(),
+    Invert = vk::LogicOp::INVERT.as_raw(),
+    OrReverse = vk::LogicOp::OR_REVERSE.as_raw(),
+    CopyInverted = vk::LogicOp::COPY_INVERTED.as_raw(),
+    OrInverted = vk::LogicOp::OR_INVERTED.as_raw(),
+    Nand = vk::LogicOp::NAND.as_raw(),
+    Set = vk::LogicOp::SET.as_raw(),
+}
+
+bitflags! {
+    /// The components of a color attachment that are written.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[doc(alias = "VkColorComponentFlags")]
+    pub struct ColorComponents: u32 {
+        const R = vk::ColorComponentFlags::R.as_raw();
+        const G = vk::ColorComponentFlags::G.as_raw();
+        const B = vk::ColorComponentFlags::B.as_raw();
+        const A = vk::ColorComponentFlags::A.as_raw();
+    }
+}
+
+/// The blend equations applied to the colors written to an attachment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Blend {
+    /// The factor applied to the color of fragments.
+    pub src_color_factor: BlendFactor,
+    /// The factor applied to the color of the attachment.
+    pub dst_color_factor: BlendFactor,
+    /// The operation that combines the colors.
+    pub color_op: BlendOp,
+    /// The factor applied to the alpha of fragments.
+    pub src_alpha_factor: BlendFactor,
+    /// The factor applied to the alpha of the attachment.
+    pub dst_alpha_factor: BlendFactor,
+    /// The operation that combines the alphas.
+    pub alpha_op: BlendOp,
+}
+
+impl Blend {
+    /// Blends fragments over the attachment according to their alpha.
+    pub const ALPHA: Self = Self {
+        src_color_factor: BlendFactor::SrcAlpha,
+        dst_color_factor: BlendFactor::OneMinusSrcAlpha,
+        color_op: BlendOp::Add,
+        src_alpha_factor: BlendFactor::One,
+        dst_alpha_factor: BlendFactor::OneMinusSrcAlpha,
+        alpha_op: BlendOp::Add,
+    };
+
+    /// Blends fragments whose color is already multiplied by their alpha over the attachment.
+    pub const PREMULTIPLIED_ALPHA: Self = Self {
+        src_color_factor: BlendFactor::One,
+        dst_color_factor: BlendFactor::OneMinusSrcAlpha,
+        color_op: BlendOp::Add,
+        src_alpha_factor: BlendFactor::One,
+        dst_alpha_factor: BlendFactor::OneMinusSrcAlpha,
+        alpha_op: BlendOp::Add,
+    };
+
+    /// Converts these equations into their raw Vulkan representation.
+    fn to_raw(self) -> vk_ext::ColorBlendEquationEXT {
+        vk_ext::ColorBlendEquationEXT {
+            src_color_blend_factor: vk::BlendFactor::from_raw(self.src_color_factor as i32),
+            dst_color_blend_factor: vk::BlendFactor::from_raw(self.dst_color_factor as i32),
+            color_blend_op: vk::BlendOp::from_raw(self.color_op as i32),
+            src_alpha_blend_factor: vk::BlendFactor::from_raw(self.src_alpha_factor as i32),
+            dst_alpha_blend_factor: vk::BlendFactor::from_raw(self.dst_alpha_factor as i32),
+            alpha_blend_op: vk::BlendOp::from_raw(self.alpha_op as i32),
+        }
+    }
+}
+
+/// How the colors written to a color attachment are blended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkPipelineColorBlendAttachmentState")]
+pub struct ColorBlendAttachment {
+    /// The blend equations, or `None` to overwrite the attachment.
+    pub blend: Option<Blend>,
+    /// The components of the attachment that are written.
+    pub write_mask: ColorComponents,
+}
+
+impl Default for ColorBlendAttachment {
+    fn default() -> Self {
+        Self {
+            blend: None,
+            write_mask: ColorComponents::all(),
+        }
+    }
+}
+
+impl ColorBlendAttachment {
+    /// Converts this state into its raw Vulkan representation.
+    fn to_raw(self) -> vk::PipelineColorBlendAttachmentState {
+        let blend = self.blend.unwrap_or(Blend {
+            src_color_factor: BlendFactor::One,
+            dst_color_factor: BlendFactor::Zero,
+            color_op: BlendOp::Add,
+            src_alpha_factor: BlendFactor::One,
+            dst_alpha_factor: BlendFactor::Zero,
+            alpha_op: BlendOp::Add,
+        });
+
+        vk::PipelineColorBlendAttachmentState {
+            blend_enable: self.blend.is_some() as vk::Bool32,
+            src_color_blend_factor: vk::BlendFactor::from_raw(blend.src_color_factor as i32),
+            dst_color_blend_factor: vk::BlendFactor::from_raw(blend.dst_color_factor as i32),
+            color_blend_op: vk::BlendOp::from_raw(blend.color_op as i32),
+            src_alpha_blend_factor: vk::BlendFactor::from_raw(blend.src_alpha_factor as i32),
+            dst_alpha_blend_factor: vk::BlendFactor::from_raw(blend.dst_alpha_factor as i32),
+            alpha_blend_op: vk::BlendOp::from_raw(blend.alpha_op as i32),
+            color_write_mask: vk::ColorComponentFlags::from_raw(self.write_mask.bits()),
+        }
+    }
+}
+
+/// How the colors written by a graphics pipeline are combined with its color attachments.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[doc(alias = "VkPipelineColorBlendStateCreateInfo")]
+pub struct ColorBlendState<'a> {
+    /// The logical operation applied instead of blending, if any.
+    ///
+    /// This requires the [`DeviceFeatures::LOGIC_OP`] feature.
+    pub logic_op: Option<LogicOp>,
+    /// The blend state of each color attachment.
+    ///
+    /// When this is empty, every color attachment is overwritten. Otherwise, it must have one
+    /// element per color attachment, and the elements may only differ with the
+    /// [`DeviceFeatures::INDEPENDENT_BLEND`] feature.
+    pub attachments: &'a [ColorBlendAttachment],
+    /// The constant color used by the `Constant*` blend factors.
+    pub blend_constants: [f32; 4],
+}
+
+/// A piece of the state of a graphics pipeline which is set while recording command buffers
+/// instead of when the pipeline is created.
+///
+/// Only the states of `VK_EXT_extended_dynamic_state3` which correspond to a piece of
+/// [`GraphicsPipelineDesc`] are available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkDynamicState")]
+pub enum DynamicState {
+    /// Set with [`CommandBuffer::set_viewports`].
+    Viewport = vk::DynamicState::VIEWPORT.as_raw(),
+    /// Set with [`CommandBuffer::set_scissors`].
+    Scissor = vk::DynamicState::SCISSOR.as_raw(),
+    /// Set with [`CommandBuffer::set_line_width`].
+    LineWidth = vk::DynamicState::LINE_WIDTH.as_raw(),
+    /// Set with [`CommandBuffer::set_depth_bias`].
+    DepthBias = vk::DynamicState::DEPTH_BIAS.as_raw(),
+    /// Set with [`CommandBuffer::set_blend_constants`].
+    BlendConstants = vk::DynamicState::BLEND_CONSTANTS.as_raw(),
+    /// Set with [`CommandBuffer::set_depth_bounds`].
+    DepthBounds = vk::DynamicState::DEPTH_BOUNDS.as_raw(),
+    /// Set with [`CommandBuffer::set_stencil_compare_mask`].
+    StencilCompareMask = vk::DynamicState::STENCIL_COMPARE_MASK.as_raw(),
+    /// Set with [`CommandBuffer::set_stencil_write_mask`].
+    StencilWriteMask = vk::DynamicState::STENCIL_WRITE_MASK.as_raw(),
+    /// Set with [`CommandBuffer::set_stencil_reference`].
+    StencilReference = vk::DynamicState::STENCIL_REFERENCE.as_raw(),
+    /// Set with [`CommandBuffer::set_cull_mode`].
+    CullMode = vk::DynamicState::CULL_MODE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_front_face`].
+    FrontFace = vk::DynamicState::FRONT_FACE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_primitive_topology`].
+    PrimitiveTopology = vk::DynamicState::PRIMITIVE_TOPOLOGY_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_viewports_with_count`].
+    ViewportWithCount = vk::DynamicState::VIEWPORT_WITH_COUNT_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_scissors_with_count`].
+    ScissorWithCount = vk::DynamicState::SCISSOR_WITH_COUNT_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_depth_test_enable`].
+    DepthTestEnable = vk::DynamicState::DEPTH_TEST_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_depth_write_enable`].
+    DepthWriteEnable = vk::DynamicState::DEPTH_WRITE_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_depth_compare_op`].
+    DepthCompareOp = vk::DynamicState::DEPTH_COMPARE_OP_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_depth_bounds_test_enable`].
+    DepthBoundsTestEnable = vk::DynamicState::DEPTH_BOUNDS_TEST_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_stencil_test_enable`].
+    StencilTestEnable = vk::DynamicState::STENCIL_TEST_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_stencil_op`].
+    StencilOp = vk::DynamicState::STENCIL_OP_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_rasterizer_discard_enable`].
+    RasterizerDiscardEnable = vk::DynamicState::RASTERIZER_DISCARD_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_depth_bias_enable`].
+    DepthBiasEnable = vk::DynamicState::DEPTH_BIAS_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_primitive_restart_enable`].
+    PrimitiveRestartEnable = vk::DynamicState::PRIMITIVE_RESTART_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_depth_clamp_enable`].
+    DepthClampEnable = vk_ext::DYNAMIC_STATE_DEPTH_CLAMP_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_polygon_mode`].
+    PolygonMode = vk_ext::DYNAMIC_STATE_POLYGON_MODE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_rasterization_samples`].
+    RasterizationSamples = vk_ext::DYNAMIC_STATE_RASTERIZATION_SAMPLES_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_alpha_to_coverage_enable`].
+    AlphaToCoverageEnable = vk_ext::DYNAMIC_STATE_ALPHA_TO_COVERAGE_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_logic_op_enable`].
+    LogicOpEnable = vk_ext::DYNAMIC_STATE_LOGIC_OP_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_color_blend_enables`].
+    ColorBlendEnable = vk_ext::DYNAMIC_STATE_COLOR_BLEND_ENABLE_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_color_blend_equations`].
+    ColorBlendEquation = vk_ext::DYNAMIC_STATE_COLOR_BLEND_EQUATION_EXT.as_raw(),
+    /// Set with [`CommandBuffer::set_color_write_masks`].
+    ColorWriteMask = vk_ext::DYNAMIC_STATE_COLOR_WRITE_MASK_EXT.as_raw(),
+}
+
+impl DynamicState {
+    /// Returns the device feature which must be enabled for this state to be dynamic, if
+    /// any.
+    pub fn required_feature(self) -> Option<DeviceFeatures> {
+        match self {
+            Self::Viewport
+            | Self::Scissor
+            | Self::LineWidth
+            | Self::DepthBias
+            | Self::BlendConstants
+            | Self::DepthBounds
+            | Self::StencilCompareMask
+            | Self::StencilWriteMask
+            | Self::StencilReference => None,
+            Self::CullMode
+            | Self::FrontFace
+            | Self::PrimitiveTopology
+            | Self::ViewportWithCount
+            | Self::ScissorWithCount
+            | Self::DepthTestEnable
+            | Self::DepthWriteEnable
+            | Self::DepthCompareOp
+            | Self::DepthBoundsTestEnable
+            | Self::StencilTestEnable
+            | Self::StencilOp => Some(DeviceFeatures::EXTENDED_DYNAMIC_STATE),
+            Self::RasterizerDiscardEnable
+            | Self::DepthBiasEnable
+            | Self::PrimitiveRestartEnable => Some(DeviceFeatures::EXTENDED_DYNAMIC_STATE_2),
+            Self::DepthClampEnable => {
+                Some(DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_DEPTH_CLAMP_ENABLE)
+            }
+            Self::PolygonMode => Some(DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_POLYGON_MODE),
+            Self::RasterizationSamples => {
+                Some(DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_RASTERIZATION_SAMPLES)
+            }
+            Self::AlphaToCoverageEnable => {
+                Some(DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_ALPHA_TO_COVERAGE_ENABLE)
+            }
+            Self::LogicOpEnable => Some(DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_LOGIC_OP_ENABLE),
+            Self::ColorBlendEnable => {
+                Some(DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_ENABLE)
+            }
+            Self::ColorBlendEquation => {
+                Some(DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_EQUATION)
+            }
+            Self::ColorWriteMask => Some(DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_WRITE_MASK),
+        }
+    }
+}
+
+/// The attachments that a graphics pipeline renders to.
+#[derive(Clone, Copy)]
+pub enum GraphicsPipelineTarget<'a> {
+    /// The pipeline is used in a subpass of render passes compatible with `render_pass`.
+    RenderPass {
+        /// The render pass that the pipeline is used with.
+        render_pass: &'a RenderPass,
+        /// The index of the subpass that the pipeline is used in.
+        subpass: u32,
+    },
+    /// The pipeline is used in dynamic render pass instances with the provided attachment
+    /// formats.
+    ///
+    /// This requires the [`DeviceFeatures::DYNAMIC_RENDERING`] feature.
+    Rendering(PipelineRenderingDesc<'a>),
+}
+
+/// The parameters passed to the [`GraphicsPipeline::new`] function.
+///
+/// [`GraphicsPipelineDesc::new`] fills every piece of fixed-function state with defaults,
+/// which can then be overridden field by field.
+#[derive(Clone, Copy)]
+#[doc(alias = "VkGraphicsPipelineCreateInfo")]
+pub struct GraphicsPipelineDesc<'a> {
+    /// The shader stages, which must include exactly one vertex shader.
+    pub stages: &'a [ShaderStageDesc<'a>],
+    /// The layout of the vertex buffers.
+    pub vertex_input: VertexInputState<'a>,
+    /// How vertices are assembled into primitives.
+    pub input_assembly: InputAssemblyState,
+    /// The viewports and scissor rectangles.
+    pub viewport: ViewportState<'a>,
+    /// How primitives are rasterized.
+    pub rasterization: RasterizationState,
+    /// How the samples of fragments are generated.
+    pub multisample: MultisampleState,
+    /// The depth and stencil tests.
+    pub depth_stencil: DepthStencilState,
+    /// How the colors written are combined with the color attachments.
+    pub color_blend: ColorBlendState<'a>,
+    /// The states which are set while recording command buffers, and whose value in this
+    /// description is ignored.
+    pub dynamic_states: &'a [DynamicState],
+    /// The layout of the resources accessed by the shaders.
+    pub layout: &'a PipelineLayout,
+    /// The attachments that the pipeline renders to.
+    pub target: GraphicsPipelineTarget<'a>,
+}
+
+impl<'a> GraphicsPipelineDesc<'a> {
+    /// Creates a new [`GraphicsPipelineDesc`] with default fixed-function state.
+    ///
+    /// The defaults draw filled triangle lists without vertex buffers, culling, depth or
+    /// stencil tests and blending, to a single sample per pixel. The viewport and scissor
+    /// rectangle are dynamic.
+    pub fn new(
+        stages: &'a [ShaderStageDesc<'a>],
+        layout: &'a PipelineLayout,
+        target: GraphicsPipelineTarget<'a>,
+    ) -> Self {
+        Self {
+            stages,
+            vertex_input: VertexInputState::default(),
+            input_assembly: InputAssemblyState::default(),
+            viewport: ViewportState::default(),
+            rasterization: RasterizationState::default(),
+            multisample: MultisampleState::default(),
+            depth_stencil: DepthStencilState::default(),
+            color_blend: ColorBlendState::default(),
+            dynamic_states: &[DynamicState::Viewport, DynamicState::Scissor],
+            layout,
+            target,
+        }
+    }
+}
+
+/// The fixed-function state of a graphics pipeline, which is checked independently of its
+/// shaders and attachments.
+struct FixedFunctionState<'a> {
+    vertex_input: VertexInputState<'a>,
+    input_assembly: InputAssemblyState,
+    viewport: ViewportState<'a>,
+    rasterization: RasterizationState,
+    multisample: MultisampleState,
+    depth_stencil: DepthStencilState,
+    color_blend: ColorBlendState<'a>,
+    dynamic_states: &'a [DynamicState],
+}
+
+impl<'a> FixedFunctionState<'a> {
+    /// Returns the fixed-function state of a graphics pipeline description.
+    fn from_desc(desc: &GraphicsPipelineDesc<'a>) -> Self {
+        Self {
+            vertex_input: desc.vertex_input,
+            input_assembly: desc.input_assembly,
+            viewport: desc.viewport,
+            rasterization: desc.rasterization,
+            multisample: desc.multisample,
+            depth_stencil: desc.depth_stencil,
+            color_blend: desc.color_blend,
+            dynamic_states: desc.dynamic_states,
+        }
+    }
+
+    /// Checks this state against the limits of the device, and returns the features that it
+    /// requires.
+    ///
+    /// `tessellation` tells whether the pipeline has tessellation shaders, and
+    /// `color_attachment_count` the number of color attachments it renders to.
+    ///
+    /// # Panics
+    ///
+    /// This function panics on the invalid states documented by [`GraphicsPipeline::new`].
+    fn validate(
+        &self,
+        tessellation: bool,
+        color_attachment_count: u32,
+        limits: &PhysicalDeviceLimits,
+    ) -> Result<DeviceFeatures> {
+        let mut required_features = DeviceFeatures::empty();
+        let has_dynamic_state = |state| self.dynamic_states.contains(&state);
+
+        // Vertex input.
+        let vertex_input = self.vertex_input;
+        if vertex_input.bindings.len() as u32 > limits.max_vertex_input_bindings {
+            return Err(Error::LimitExceeded(DeviceLimit::MaxVertexInputBindings));
+        }
+        if vertex_input.attributes.len() as u32 > limits.max_vertex_input_attributes {
+            return Err(Error::LimitExceeded(DeviceLimit::MaxVertexInputAttributes));
+        }
+        for (i, binding) in vertex_input.bindings.iter().enumerate() {
+            assert!(
+                vertex_input.bindings[..i]
+                    .iter()
+                    .all(|other| other.binding != binding.binding),
+                "vertex binding {} is declared twice",
+                binding.binding
+            );
+            if binding.binding >= limits.max_vertex_input_bindings {
+                return Err(Error::LimitExceeded(DeviceLimit::MaxVertexInputBindings));
+            }
+            if binding.stride > limits.max_vertex_input_binding_stride {
+                return Err(Error::LimitExceeded(
+                    DeviceLimit::MaxVertexInputBindingStride,
+                ));
+            }
+        }
+        for (i, attribute) in vertex_input.attributes.iter().enumerate() {
+            assert!(
+                vertex_input.attributes[..i]
+                    .iter()
+                    .all(|other| other.location != attribute.location),
+                "vertex attribute location {} is declared twice",
+                attribute.location
+            );
+            assert!(
+                vertex_input
+                    .bindings
+                    .iter()
+                    .any(|binding| binding.binding == attribute.binding),
+                "vertex attribute {} reads from an undeclared binding",
+                attribute.location
+            );
+            if attribute.offset > limits.max_vertex_input_attribute_offset {
+                return Err(Error::LimitExceeded(
+                    DeviceLimit::MaxVertexInputAttributeOffset,
+                ));
+            }
+        }
+
+        // Input assembly and tessellation.
+        let input_assembly = self.input_assembly;
+        assert!(
+            has_dynamic_state(DynamicState::PrimitiveTopology)
+                || tessellation == (input_assembly.topology == PrimitiveTopology::PatchList),
+            "patch lists must be used together with tessellation shaders"
+        );
+        assert!(
+            !input_assembly.primitive_restart
+                || has_dynamic_state(DynamicState::PrimitiveTopology)
+                || !input_assembly.topology.is_list(),
+            "primitive restart cannot be used with list topologies"
+        );
+        if tessellation {
+            assert!(
+                input_assembly.patch_control_points > 0,
+                "patches must have at least one control point"
+            );
+            if input_assembly.patch_control_points > limits.max_tessellation_patch_size {
+                return Err(Error::LimitExceeded(DeviceLimit::MaxTessellationPatchSize));
+            }
+        }
+
+        // Viewports.
+        let viewport = self.viewport;
+        let dynamic_viewport_count = has_dynamic_state(DynamicState::ViewportWithCount);
+        let dynamic_scissor_count = has_dynamic_state(DynamicState::ScissorWithCount);
+        assert!(
+            !(dynamic_viewport_count && has_dynamic_state(DynamicState::Viewport)),
+            "viewports cannot be dynamic both with and without count"
+        );
+        assert!(
+            !(dynamic_scissor_count && has_dynamic_state(DynamicState::Scissor)),
+            "scissors cannot be dynamic both with and without count"
+        );
+        if viewport.count > limits.max_viewports {
+            return Err(Error::LimitExceeded(DeviceLimit::MaxViewports));
+        }
+        if viewport.count > 1 {
+            required_features |= DeviceFeatures::MULTI_VIEWPORT;
+        }
+
+        let static_viewports =
+            !dynamic_viewport_count && !has_dynamic_state(DynamicState::Viewport);
+        let static_scissors = !dynamic_scissor_count && !has_dynamic_state(DynamicState::Scissor);
+        if static_viewports {
+            assert_eq!(
+                viewport.viewports.len() as u32,
+                viewport.count,
+                "the number of static viewports must match the viewport count"
+            );
+        }
+        if static_scissors {
+            assert_eq!(
+                viewport.scissors.len() as u32,
+                viewport.count,
+                "the number of static scissors must match the viewport count"
+            );
+        }
+
+        // Rasterization.
+        let rasterization = self.rasterization;
+        if !has_dynamic_state(DynamicState::PolygonMode)
+            && rasterization.polygon_mode != PolygonMode::Fill
+        {
+            required_features |= DeviceFeatures::FILL_MODE_NON_SOLID;
+        }
+        if !has_dynamic_state(DynamicState::DepthClampEnable) && rasterization.depth_clamp {
+            required_features |= DeviceFeatures::DEPTH_CLAMP;
+        }
+        if !has_dynamic_state(DynamicState::LineWidth) && rasterization.line_width != 1.0 {
+            required_features |= DeviceFeatures::WIDE_LINES;
+            if rasterization.line_width < limits.line_width_range[0]
+                || rasterization.line_width > limits.line_width_range[1]
+            {
+                return Err(Error::LimitExceeded(DeviceLimit::LineWidthRange));
+            }
+        }
+
+        // Multisampling.
+        let multisample = self.multisample;
+        if let Some(min_sample_shading) = multisample.min_sample_shading {
+            assert!(
+                (0.0..=1.0).contains(&min_sample_shading),
+                "the minimum fraction of sample shading must be between 0 and 1"
+            );
+            required_features |= DeviceFeatures::SAMPLE_RATE_SHADING;
+        }
+
+        // Depth and stencil.
+        let depth_stencil = self.depth_stencil;
+        if depth_stencil.depth_bounds_test {
+            required_features |= DeviceFeatures::DEPTH_BOUNDS;
+        }
+
+        if color_attachment_count > limits.max_color_attachments {
+            return Err(Error::LimitExceeded(DeviceLimit::MaxColorAttachments));
+        }
+
+        // Color blending.
+        let color_blend = self.color_blend;
+        assert!(
+            color_blend.attachments.is_empty()
+                || color_blend.attachments.len() as u32 == color_attachment_count,
+            "there must be one color blend attachment per color attachment"
+        );
+        if color_blend
+            .attachments
+            .windows(2)
+            .any(|pair| pair[0] != pair[1])
+        {
+            required_features |= DeviceFeatures::INDEPENDENT_BLEND;
+        }
+        if color_blend.logic_op.is_some() {
+            required_features |= DeviceFeatures::LOGIC_OP;
+        }
+
+        // Dynamic states.
+        for (i, &state) in self.dynamic_states.iter().enumerate() {
+            assert!(
+                !self.dynamic_states[..i].contains(&state),
+                "dynamic state {state:?} is listed twice"
+            );
+            if let Some(feature) = state.required_feature() {
+                required_features |= feature;
+            }
+        }
+
+        Ok(required_features)
+    }
+}
+
+/// A pipeline which draws primitives.
+pub struct GraphicsPipeline {
+    /// The device that owns this pipeline.
+    device: Arc<Device>,
+
+    /// The handle to the pipeline.
+    handle: vk::Pipeline,
+    /// The handle to the layout of the pipeline.
+    layout: vk::PipelineLayout,
+    /// The states which must be set while recording command buffers.
+    dynamic_states: Vec<DynamicState>,
+}
+
+impl GraphicsPipeline {
+    /// Creates a new [`GraphicsPipeline`].
+    ///
+    /// The description is checked against the features and limits of the device before the
+    /// driver is called.
+    ///
+    /// # Errors
+    ///
+    /// This function returns:
+    ///
+    /// - [`Error::InvalidSpirv`] if a module does not declare the entry point of its stage.
+    /// - [`Error::MissingDeviceFeature`] with every missing feature if the description uses
+    ///   device features that were not enabled.
+    /// - [`Error::LimitExceeded`] if the vertex input layout, the number of viewports or color
+    ///   attachments, the line width or the number of control points per patch exceeds the
+    ///   limits of the device.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if:
+    ///
+    /// - the stages do not include exactly one vertex shader, include a compute shader or
+    ///   include a stage twice, or only one of the tessellation stages is present.
+    /// - the topology is [`PrimitiveTopology::PatchList`] without tessellation shaders, or
+    ///   the other way around.
+    /// - a vertex shader input is not fed by an attribute, a vertex binding or attribute
+    ///   location is declared twice, or an attribute reads from an undeclared binding.
+    /// - a dynamic state is listed twice, or a state is dynamic both with and without count.
+    /// - patches have no control points.
+    /// - `color_blend.attachments` is neither empty nor as long as the color attachments.
+    /// - the subpass does not exist, or its attachments have a different number of samples
+    ///   than `multisample.samples` while [`DynamicState::RasterizationSamples`] is not used.
+    /// - a specialization constant is given a value of a different size than its type.
+    ///
+    /// [`Error::InvalidSpirv`]: crate::Error::InvalidSpirv
+    #[doc(alias = "vkCreateGraphicsPipelines")]
+    pub fn new(desc: GraphicsPipelineDesc) -> Result<Self> {
+        let device = desc.layout.device();
+        let limits = device.limits();
+        let mut required_features = DeviceFeatures::empty();
+
+        let has_dynamic_state = |state| desc.dynamic_states.contains(&state);
+
+        // Shader stages.
+        let has_stage = |stage| desc.stages.iter().any(|s| s.stage == stage);
+        assert!(
+            desc.stages
+                .iter()
+                .enumerate()
+                .all(|(i, a)| desc.stages[..i].iter().all(|b| a.stage != b.stage)),
+            "graphics pipeline stages must be distinct"
+        );
+        assert!(
+            has_stage(ShaderStage::Vertex),
+            "graphics pipelines must have a vertex shader"
+        );
+        assert!(
+            !has_stage(ShaderStage::Compute),
+            "graphics pipelines cannot have a compute shader"
+        );
+
+        let tessellation = has_stage(ShaderStage::TessellationControl);
+        assert_eq!(
+            tessellation,
+            has_stage(ShaderStage::TessellationEvaluation),
+            "tessellation control and evaluation shaders must be used together"
+        );
+        if tessellation {
+            required_features |= DeviceFeatures::TESSELLATION_SHADER;
+        }
+        if has_stage(ShaderStage::Geometry) {
+            required_features |= DeviceFeatures::GEOMETRY_SHADER;
+        }
+
+        let mut reflections = SmallVec::<[_; 5]>::new();
+        for stage in desc.stages {
+            reflections.push(stage.module.reflect(stage.entry_point, stage.stage)?);
+        }
+
+        let vertex_input = desc.vertex_input;
+        let vertex_reflection = reflections
+            .iter()
+            .find(|reflection| reflection.stage() == ShaderStage::Vertex)
+            .unwrap();
+        for input in vertex_reflection.inputs() {
+            assert!(
+                vertex_input
+                    .attributes
+                    .iter()
+                    .any(|attribute| attribute.location == input.location),
+                "vertex shader input {} is not fed by a vertex attribute",
+                input.location
+            );
+        }
+
+        // Attachments.
+        let multisample = desc.multisample;
+        let mut rendering_formats = SmallVec::<[_; 8]>::new();
+        let (render_pass, subpass, color_attachment_count) = match desc.target {
+            GraphicsPipelineTarget::RenderPass {
+                render_pass,
+                subpass,
+            } => {
+                let subpass_desc = render_pass
+                    .subpasses()
+                    .get(subpass as usize)
+                    .expect("the subpass does not exist in the render pass");
+                let attachments = render_pass.attachments();
+                assert!(
+                    has_dynamic_state(DynamicState::RasterizationSamples)
+                        || subpass_desc
+                            .color_attachments
+                            .iter()
+                            .chain(std::iter::once(&subpass_desc.depth_stencil_attachment))
+                            .flatten()
+                            .all(
+                                |reference| attachments[reference.attachment as usize].samples
+                                    == multisample.samples
+                            ),
+                    "the attachments of the subpass must have {:?} samples",
+                    multisample.samples
+                );
+                (
+                    render_pass.handle(),
+                    subpass,
+                    subpass_desc.color_attachments.len() as u32,
+                )
+            }
+            GraphicsPipelineTarget::Rendering(rendering) => {
+                required_features |= DeviceFeatures::DYNAMIC_RENDERING;
+                rendering_formats.extend(
+                    rendering
+                        .color_formats
+                        .iter()
+                        .map(|&format| vk::Format::from_raw(format as i32)),
+                );
+                (
+                    vk::RenderPass::null(),
+                    0,
+                    rendering.color_formats.len() as u32,
+                )
+            }
+        };
+
+        // Fixed-function state.
+        required_features |= FixedFunctionState::from_desc(&desc).validate(
+            tessellation,
+            color_attachment_count,
+            limits,
+        )?;
+
+        let missing_features = required_features - device.features();
+        if !missing_features.is_empty() {
+            return Err(Error::MissingDeviceFeature(missing_features));
+        }
+
+        let input_assembly = desc.input_assembly;
+        let viewport = desc.viewport;
+        let dynamic_viewport_count = has_dynamic_state(DynamicState::ViewportWithCount);
+        let dynamic_scissor_count = has_dynamic_state(DynamicState::ScissorWithCount);
+        let viewport_count = if dynamic_viewport_count {
+            0
+        } else {
+            viewport.count
+        };
+        let scissor_count = if dynamic_scissor_count {
+            0
+        } else {
+            viewport.count
+        };
+        let static_viewports =
+            !dynamic_viewport_count && !has_dynamic_state(DynamicState::Viewport);
+        let static_scissors = !dynamic_scissor_count && !has_dynamic_state(DynamicState::Scissor);
+        let rasterization = desc.rasterization;
+        let depth_stencil = desc.depth_stencil;
+        let color_blend = desc.color_blend;
+        let blend_attachments: SmallVec<[_; 8]> = if color_blend.attachments.is_empty() {
+            (0..color_attachment_count)
+                .map(|_| ColorBlendAttachment::default().to_raw())
+                .collect()
+        } else {
+            color_blend
+                .attachments
+                .iter()
+                .map(|attachment| attachment.to_raw())
+                .collect()
+        };
+
+        // Raw state.
+        let names: SmallVec<[_; 5]> = desc
+            .stages
+            .iter()
+            // The entry points were found in their modules, so their names cannot contain a
+            // nul byte.
+            .map(|stage| CString::new(stage.entry_point).unwrap())
+            .collect();
+        let specializations: SmallVec<[_; 5]> = desc
+            .stages
+            .iter()
+            .zip(&reflections)
+            .map(|(stage, reflection)| Specialization::new(stage.specialization, reflection))
+            .collect();
+        let specialization_infos: SmallVec<[_; 5]> = specializations
+            .iter()
+            .map(|specialization| specialization.to_raw())
+            .collect();
+        let stages: SmallVec<[_; 5]> = desc
+            .stages
+            .iter()
+            .zip(&names)
+            .zip(&specialization_infos)
+            .map(
+                |((stage, name), specialization_info)| vk::PipelineShaderStageCreateInfo {
+                    flags: vk::PipelineShaderStageCreateFlags::empty(),
+                    stage: vk::ShaderStageFlags::from_raw(stage.stage as u32),
+                    module: stage.module.handle(),
+                    p_name: name.as_ptr(),
+                    p_specialization_info: specialization_info,
+
+                    p_next: std::ptr::null(),
+                    s_type: vk::StructureType::PIPELINE_SHADER_STAGE_CREATE_INFO,
+                },
+            )
+            .collect();
+
+        let vertex_bindings: SmallVec<[_; 4]> = vertex_input
+            .bindings
+            .iter()
+            .map(|binding| vk::VertexInputBindingDescription {
+                binding: binding.binding,
+                stride: binding.stride,
+                input_rate: vk::VertexInputRate::from_raw(binding.input_rate as i32),
+            })
+            .collect();
+        let vertex_attributes: SmallVec<[_; 8]> = vertex_input
+            .attributes
+            .iter()
+            .map(|attribute| vk::VertexInputAttributeDescription {
+                location: attribute.location,
+                binding: attribute.binding,
+                format: vk::Format::from_raw(attribute.format as i32),
+                offset: attribute.offset,
+            })
+            .collect();
+        let vertex_input_state = vk::PipelineVertexInputStateCreateInfo {
+            flags: vk::PipelineVertexInputStateCreateFlags::empty(),
+            vertex_binding_description_count: vertex_bindings.len() as u32,
+            p_vertex_binding_descriptions: vertex_bindings.as_ptr(),
+            vertex_attribute_description_count: vertex_attributes.len() as u32,
+            p_vertex_attribute_descriptions: vertex_attributes.as_ptr(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PIPELINE_VERTEX_INPUT_STATE_CREATE_INFO,
+        };
+
+        let input_assembly_state = vk::PipelineInputAssemblyStateCreateInfo {
+            flags: vk::PipelineInputAssemblyStateCreateFlags::empty(),
+            topology: vk::PrimitiveTopology::from_raw(input_assembly.topology as i32),
+            primitive_restart_enable: input_assembly.primitive_restart as vk::Bool32,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PIPELINE_INPUT_ASSEMBLY_STATE_CREATE_INFO,
+        };
+
+        let tessellation_state = vk::PipelineTessellationStateCreateInfo {
+            flags: vk::PipelineTessellationStateCreateFlags::empty(),
+            patch_control_points: input_assembly.patch_control_points,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PIPELINE_TESSELLATION_STATE_CREATE_INFO,
+        };
+
+        let viewports: SmallVec<[_; 1]> = viewport
+            .viewports
+            .iter()
+            .map(|viewport| viewport.to_raw())
+            .collect();
+        let scissors: SmallVec<[_; 1]> = viewport
+            .scissors
+            .iter()
+            .map(|scissor| scissor.to_raw())
+            .collect();
+        let viewport_state = vk::PipelineViewportStateCreateInfo {
+            flags: vk::PipelineViewportStateCreateFlags::empty(),
+            viewport_count,
+            p_viewports: if static_viewports {
+                viewports.as_ptr()
+            } else {
+                std::ptr::null()
+            },
+            scissor_count,
+            p_scissors: if static_scissors {
+                scissors.as_ptr()
+            } else {
+                std::ptr::null()
+            },
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PIPELINE_VIEWPORT_STATE_CREATE_INFO,
+        };
+
+        let depth_bias = rasterization.depth_bias.unwrap_or_default();
+        let rasterization_state = vk::PipelineRasterizationStateCreateInfo {
+            flags: vk::PipelineRasterizationStateCreateFlags::empty(),
+            depth_clamp_enable: rasterization.depth_clamp as vk::Bool32,
+            rasterizer_discard_enable: rasterization.rasterizer_discard as vk::Bool32,
+            polygon_mode: vk::PolygonMode::from_raw(rasterization.polygon_mode as i32),
+            cull_mode: vk::CullModeFlags::from_raw(rasterization.cull_mode as u32),
+            front_face: vk::FrontFace::from_raw(rasterization.front_face as i32),
+            depth_bias_enable: rasterization.depth_bias.is_some() as vk::Bool32,
+            depth_bias_constant_factor: depth_bias.constant_factor,
+            depth_bias_clamp: depth_bias.clamp,
+            depth_bias_slope_factor: depth_bias.slope_factor,
+            line_width: rasterization.line_width,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PIPELINE_RASTERIZATION_STATE_CREATE_INFO,
+        };
+
+        let multisample_state = vk::PipelineMultisampleStateCreateInfo {
+            flags: vk::PipelineMultisampleStateCreateFlags::empty(),
+            rasterization_samples: vk::SampleCountFlags::from_raw(multisample.samples as u32),
+            sample_shading_enable: multisample.min_sample_shading.is_some() as vk::Bool32,
+            min_sample_shading: multisample.min_sample_shading.unwrap_or(0.0),
+            p_sample_mask: std::ptr::null(),
+            alpha_to_coverage_enable: multisample.alpha_to_coverage as vk::Bool32,
+            alpha_to_one_enable: vk::FALSE,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PIPELINE_MULTISAMPLE_STATE_CREATE_INFO,
+        };
+
+        let depth_stencil_state = vk::PipelineDepthStencilStateCreateInfo {
+            flags: vk::PipelineDepthStencilStateCreateFlags::empty(),
+            depth_test_enable: depth_stencil.depth_test as vk::Bool32,
+            depth_write_enable: depth_stencil.depth_write as vk::Bool32,
+            depth_compare_op: vk::CompareOp::from_raw(depth_stencil.depth_compare_op as i32),
+            depth_bounds_test_enable: depth_stencil.depth_bounds_test as vk::Bool32,
+            stencil_test_enable: depth_stencil.stencil_test as vk::Bool32,
+            front: depth_stencil.front.to_raw(),
+            back: depth_stencil.back.to_raw(),
+            min_depth_bounds: depth_stencil.depth_bounds[0],
+            max_depth_bounds: depth_stencil.depth_bounds[1],
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PIPELINE_DEPTH_STENCIL_STATE_CREATE_INFO,
+        };
+
+        let color_blend_state = vk::PipelineColorBlendStateCreateInfo {
+            flags: vk::PipelineColorBlendStateCreateFlags::empty(),
+            logic_op_enable: color_blend.logic_op.is_some() as vk::Bool32,
+            logic_op: vk::LogicOp::from_raw(color_blend.logic_op.map_or(0, |op| op as i32)),
+            attachment_count: blend_attachments.len() as u32,
+            p_attachments: blend_attachments.as_ptr(),
+            blend_constants: color_blend.blend_constants,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PIPELINE_COLOR_BLEND_STATE_CREATE_INFO,
+        };
+
+        let dynamic_states: SmallVec<[_; 8]> = desc
+            .dynamic_states
+            .iter()
+            .map(|&state| vk::DynamicState::from_raw(state as i32))
+            .collect();
+        let dynamic_state = vk::PipelineDynamicStateCreateInfo {
+            flags: vk::PipelineDynamicStateCreateFlags::empty(),
+            dynamic_state_count: dynamic_states.len() as u32,
+            p_dynamic_states: dynamic_states.as_ptr(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PIPELINE_DYNAMIC_STATE_CREATE_INFO,
+        };
+
+        let rendering_info = match desc.target {
+            GraphicsPipelineTarget::Rendering(rendering) => {
+                Some(vk::PipelineRenderingCreateInfoKHR {
+                    view_mask: rendering.view_mask,
+                    color_attachment_count: rendering_formats.len() as u32,
+                    p_color_attachment_formats: rendering_formats.as_ptr(),
+                    depth_attachment_format: rendering
+                        .depth_format
+                        .map_or(vk::Format::UNDEFINED, |format| {
+                            vk::Format::from_raw(format as i32)
+                        }),
+                    stencil_attachment_format: rendering
+                        .stencil_format
+                        .map_or(vk::Format::UNDEFINED, |format| {
+                            vk::Format::from_raw(format as i32)
+                        }),
+
+                    p_next: std::ptr::null(),
+                    s_type: vk::StructureType::PIPELINE_RENDERING_CREATE_INFO_KHR,
+                })
+            }
+            GraphicsPipelineTarget::RenderPass { .. } => None,
+        };
+
+        let create_info = vk::GraphicsPipelineCreateInfo {
+            flags: vk::PipelineCreateFlags::empty(),
+            stage_count: stages.len() as u32,
+            p_stages: stages.as_ptr(),
+            p_vertex_input_state: &vertex_input_state,
+            p_input_assembly_state: &input_assembly_state,
+            p_tessellation_state: if tessellation {
+                &tessellation_state
+            } else {
+                std::ptr::null()
+            },
+            p_viewport_state: &viewport_state,
+            p_rasterization_state: &rasterization_state,
+            p_multisample_state: &multisample_state,
+            p_depth_stencil_state: &depth_stencil_state,
+            p_color_blend_state: &color_blend_state,
+            p_dynamic_state: &dynamic_state,
+            layout: desc.layout.handle(),
+            render_pass,
+            subpass,
+            base_pipeline_handle: vk::Pipeline::null(),
+            base_pipeline_index: -1,
+
+            p_next: rendering_info
+                .as_ref()
+                .map_or(std::ptr::null(), |info| info as *const _ as *const _),
+            s_type: vk::StructureType::GRAPHICS_PIPELINE_CREATE_INFO,
+        };
+
+        let mut handle = vk::Pipeline::null();
+
+        let ret = unsafe {
+            (device.fns().create_graphics_pipelines)(
+                device.handle(),
+                vk::PipelineCache::null(),
+                1,
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self {
+            device: device.clone(),
+            handle,
+            layout: desc.layout.handle(),
+            dynamic_states: desc.dynamic_states.to_vec(),
+        })
+    }
+
+    /// Returns the states of this pipeline which must be set while recording command
+    /// buffers.
+    #[inline(always)]
+    pub fn dynamic_states(&self) -> &[DynamicState] {
+        &self.dynamic_states
+    }
+
+    /// Returns the handle to the layout of this pipeline.
+    ///
+    /// The pipeline does not keep its layout alive.
+    #[inline(always)]
+    pub fn layout(&self) -> vk::PipelineLayout {
+        self.layout
+    }
+
+    /// Returns the device that owns this pipeline.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this pipeline.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::Pipeline {
+        self.handle
+    }
+}
+
+impl Drop for GraphicsPipeline {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_pipeline)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
+
+bitflags! {
+    /// The faces of primitives whose stencil state is set.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[doc(alias = "VkStencilFaceFlags")]
+    pub struct StencilFaces: u32 {
+        const FRONT = vk::StencilFaceFlags::FRONT.as_raw();
+        const BACK = vk::StencilFaceFlags::BACK.as_raw();
+        const FRONT_AND_BACK = vk::StencilFaceFlags::FRONT_AND_BACK.as_raw();
+    }
+}
+
+/// Returns a function of an extended dynamic state extension.
+///
+/// # Panics
+///
+/// This function panics if `feature` is not enabled on the device.
+fn dynamic_state_fn<F>(device: &Device, f: Option<F>, feature: DeviceFeatures) -> F {
+    assert!(
+        device.features().contains(feature),
+        "the {feature:?} feature is not enabled"
+    );
+    // `Device::new` refuses the feature without its extension, whose functions are loaded.
+    f.expect("the functions of the feature were not loaded by the device")
+}
+
+impl<L> CommandBuffer<Recording<'_>, L> {
+    /// Binds a graphics pipeline to the command buffer.
+    ///
+    /// # Safety
+    ///
+    /// `pipeline` must outlive the execution of the command buffer.
+    #[doc(alias = "vkCmdBindPipeline")]
+    pub unsafe fn bind_graphics_pipeline(&mut self, pipeline: &GraphicsPipeline) {
+        self.bind_pipeline(PipelineBindPoint::Graphics, pipeline.handle());
+    }
+
+    /// Sets the width of rasterized lines.
+    #[doc(alias = "vkCmdSetLineWidth")]
+    pub fn set_line_width(&mut self, width: f32) {
+        unsafe { (self.device().fns().cmd_set_line_width)(self.handle(), width) };
+    }
+
+    /// Sets the bias added to the depth of fragments.
+    #[doc(alias = "vkCmdSetDepthBias")]
+    pub fn set_depth_bias(&mut self, depth_bias: DepthBias) {
+        unsafe {
+            (self.device().fns().cmd_set_depth_bias)(
+                self.handle(),
+                depth_bias.constant_factor,
+                depth_bias.clamp,
+                depth_bias.slope_factor,
+            );
+        }
+    }
+
+    /// Sets the constant color used by the `Constant*` blend factors.
+    #[doc(alias = "vkCmdSetBlendConstants")]
+    pub fn set_blend_constants(&mut self, constants: [f32; 4]) {
+        unsafe { (self.device().fns().cmd_set_blend_constants)(self.handle(), &constants) };
+    }
+
+    /// Sets the minimum and maximum depth of the depth bounds test.
+    #[doc(alias = "vkCmdSetDepthBounds")]
+    pub fn set_depth_bounds(&mut self, bounds: [f32; 2]) {
+        unsafe { (self.device().fns().cmd_set_depth_bounds)(self.handle(), bounds[0], bounds[1]) };
+    }
+
+    /// Sets the bits of the stencil values that are compared.
+    #[doc(alias = "vkCmdSetStencilCompareMask")]
+    pub fn set_stencil_compare_mask(&mut self, faces: StencilFaces, mask: u32) {
+        unsafe {
+            (self.device().fns().cmd_set_stencil_compare_mask)(
+                self.handle(),
+                vk::StencilFaceFlags::from_raw(faces.bits()),
+                mask,
+            );
+        }
+    }
+
+    /// Sets the bits of the stencil values that are written.
+    #[doc(alias = "vkCmdSetStencilWriteMask")]
+    pub fn set_stencil_write_mask(&mut self, faces: StencilFaces, mask: u32) {
+        unsafe {
+            (self.device().fns().cmd_set_stencil_write_mask)(
+                self.handle(),
+                vk::StencilFaceFlags::from_raw(faces.bits()),
+                mask,
+            );
+        }
+    }
+
+    /// Sets the reference value that stencil values are compared against.
+    #[doc(alias = "vkCmdSetStencilReference")]
+    pub fn set_stencil_reference(&mut self, faces: StencilFaces, reference: u32) {
+        unsafe {
+            (self.device().fns().cmd_set_stencil_reference)(
+                self.handle(),
+                vk::StencilFaceFlags::from_raw(faces.bits()),
+                reference,
+            );
+        }
+    }
+
+    /// Sets the triangles that are discarded depending on the way they are facing.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetCullMode")]
+    pub fn set_cull_mode(&mut self, cull_mode: CullMode) {
+        let set_cull_mode = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_cull_mode,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        unsafe {
+            set_cull_mode(self.handle(), vk::CullModeFlags::from_raw(cull_mode as u32));
+        }
+    }
+
+    /// Sets the winding order of front-facing triangles.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetFrontFace")]
+    pub fn set_front_face(&mut self, front_face: FrontFace) {
+        let set_front_face = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_front_face,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        unsafe { set_front_face(self.handle(), vk::FrontFace::from_raw(front_face as i32)) };
+    }
+
+    /// Sets the topology of the primitives drawn.
+    ///
+    /// The topology must belong to the same class (points, lines, triangles or patches) as
+    /// the one the bound pipeline was created with.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetPrimitiveTopology")]
+    pub fn set_primitive_topology(&mut self, topology: PrimitiveTopology) {
+        let set_primitive_topology = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_primitive_topology,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        unsafe {
+            set_primitive_topology(
+                self.handle(),
+                vk::PrimitiveTopology::from_raw(topology as i32),
+            );
+        }
+    }
+
+    /// Sets both the number and the values of the viewports.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetViewportWithCount")]
+    pub fn set_viewports_with_count(&mut self, viewports: &[Viewport]) {
+        let set_viewports = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_viewport_with_count,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        let viewports: SmallVec<[_; 1]> =
+            viewports.iter().map(|viewport| viewport.to_raw()).collect();
+
+        unsafe { set_viewports(self.handle(), viewports.len() as u32, viewports.as_ptr()) };
+    }
+
+    /// Sets both the number and the values of the scissor rectangles.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetScissorWithCount")]
+    pub fn set_scissors_with_count(&mut self, scissors: &[Rect]) {
+        let set_scissors = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_scissor_with_count,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        let scissors: SmallVec<[_; 1]> = scissors.iter().map(|scissor| scissor.to_raw()).collect();
+
+        unsafe { set_scissors(self.handle(), scissors.len() as u32, scissors.as_ptr()) };
+    }
+
+    /// Sets whether the depth test is enabled.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetDepthTestEnable")]
+    pub fn set_depth_test_enable(&mut self, enable: bool) {
+        let set_depth_test_enable = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_depth_test_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        unsafe { set_depth_test_enable(self.handle(), enable as vk::Bool32) };
+    }
+
+    /// Sets whether the depth of fragments which pass the depth test is written.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetDepthWriteEnable")]
+    pub fn set_depth_write_enable(&mut self, enable: bool) {
+        let set_depth_write_enable = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_depth_write_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        unsafe { set_depth_write_enable(self.handle(), enable as vk::Bool32) };
+    }
+
+    /// Sets the comparison performed by the depth test.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetDepthCompareOp")]
+    pub fn set_depth_compare_op(&mut self, compare_op: CompareOp) {
+        let set_depth_compare_op = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_depth_compare_op,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        unsafe {
+            set_depth_compare_op(self.handle(), vk::CompareOp::from_raw(compare_op as i32));
+        }
+    }
+
+    /// Sets whether the depth bounds test is enabled.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetDepthBoundsTestEnable")]
+    pub fn set_depth_bounds_test_enable(&mut self, enable: bool) {
+        let set_depth_bounds_test_enable = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_depth_bounds_test_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        unsafe { set_depth_bounds_test_enable(self.handle(), enable as vk::Bool32) };
+    }
+
+    /// Sets whether the stencil test is enabled.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetStencilTestEnable")]
+    pub fn set_stencil_test_enable(&mut self, enable: bool) {
+        let set_stencil_test_enable = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_stencil_test_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        unsafe { set_stencil_test_enable(self.handle(), enable as vk::Bool32) };
+    }
+
+    /// Sets the stencil operations and comparison of the provided faces.
+    ///
+    /// The masks and reference value of `state` are ignored.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetStencilOp")]
+    pub fn set_stencil_op(&mut self, faces: StencilFaces, state: StencilOpState) {
+        let set_stencil_op = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_stencil_op,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE,
+        );
+
+        let state = state.to_raw();
+
+        unsafe {
+            set_stencil_op(
+                self.handle(),
+                vk::StencilFaceFlags::from_raw(faces.bits()),
+                state.fail_op,
+                state.pass_op,
+                state.depth_fail_op,
+                state.compare_op,
+            );
+        }
+    }
+
+    /// Sets whether primitives are discarded before rasterization.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_2`] feature is
+    /// not enabled.
+    #[doc(alias = "vkCmdSetRasterizerDiscardEnable")]
+    pub fn set_rasterizer_discard_enable(&mut self, enable: bool) {
+        let set_rasterizer_discard_enable = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_rasterizer_discard_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_2,
+        );
+
+        unsafe { set_rasterizer_discard_enable(self.handle(), enable as vk::Bool32) };
+    }
+
+    /// Sets whether the depth of fragments is biased.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_2`] feature is
+    /// not enabled.
+    #[doc(alias = "vkCmdSetDepthBiasEnable")]
+    pub fn set_depth_bias_enable(&mut self, enable: bool) {
+        let set_depth_bias_enable = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_depth_bias_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_2,
+        );
+
+        unsafe { set_depth_bias_enable(self.handle(), enable as vk::Bool32) };
+    }
+
+    /// Sets whether a special index value restarts the assembly of strips and fans.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_2`] feature is
+    /// not enabled.
+    #[doc(alias = "vkCmdSetPrimitiveRestartEnable")]
+    pub fn set_primitive_restart_enable(&mut self, enable: bool) {
+        let set_primitive_restart_enable = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_primitive_restart_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_2,
+        );
+
+        unsafe { set_primitive_restart_enable(self.handle(), enable as vk::Bool32) };
+    }
+
+    /// Sets whether the depth of fragments is clamped instead of clipping primitives.
+    ///
+    /// Enabling it requires the [`DeviceFeatures::DEPTH_CLAMP`] feature.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_DEPTH_CLAMP_ENABLE`]
+    /// feature is not enabled.
+    #[doc(alias = "vkCmdSetDepthClampEnableEXT")]
+    pub fn set_depth_clamp_enable(&mut self, enable: bool) {
+        let set_depth_clamp_enable = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_depth_clamp_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_DEPTH_CLAMP_ENABLE,
+        );
+
+        unsafe { set_depth_clamp_enable(self.handle(), enable as vk::Bool32) };
+    }
+
+    /// Sets how polygons are rasterized.
+    ///
+    /// Modes other than [`PolygonMode::Fill`] require the [`DeviceFeatures::FILL_MODE_NON_SOLID`]
+    /// feature.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_POLYGON_MODE`]
+    /// feature is not enabled.
+    #[doc(alias = "vkCmdSetPolygonModeEXT")]
+    pub fn set_polygon_mode(&mut self, polygon_mode: PolygonMode) {
+        let set_polygon_mode = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_polygon_mode,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_POLYGON_MODE,
+        );
+
+        unsafe {
+            set_polygon_mode(
+                self.handle(),
+                vk::PolygonMode::from_raw(polygon_mode as i32),
+            );
+        }
+    }
+
+    /// Sets the number of samples per pixel, which must match the attachments rendered to.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the
+    /// [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_RASTERIZATION_SAMPLES`] feature is not enabled.
+    #[doc(alias = "vkCmdSetRasterizationSamplesEXT")]
+    pub fn set_rasterization_samples(&mut self, samples: SampleCount) {
+        let set_rasterization_samples = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_rasterization_samples,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_RASTERIZATION_SAMPLES,
+        );
+
+        unsafe {
+            set_rasterization_samples(
+                self.handle(),
+                vk::SampleCountFlags::from_raw(samples as u32),
+            );
+        }
+    }
+
+    /// Sets whether the coverage of fragments is derived from the alpha of the first color
+    /// output.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the
+    /// [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_ALPHA_TO_COVERAGE_ENABLE`] feature is not
+    /// enabled.
+    #[doc(alias = "vkCmdSetAlphaToCoverageEnableEXT")]
+    pub fn set_alpha_to_coverage_enable(&mut self, enable: bool) {
+        let set_alpha_to_coverage_enable = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_alpha_to_coverage_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_ALPHA_TO_COVERAGE_ENABLE,
+        );
+
+        unsafe { set_alpha_to_coverage_enable(self.handle(), enable as vk::Bool32) };
+    }
+
+    /// Sets whether colors are combined with the logical operation of the bound pipeline
+    /// instead of being blended.
+    ///
+    /// Enabling it requires the [`DeviceFeatures::LOGIC_OP`] feature.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_LOGIC_OP_ENABLE`]
+    /// feature is not enabled.
+    #[doc(alias = "vkCmdSetLogicOpEnableEXT")]
+    pub fn set_logic_op_enable(&mut self, enable: bool) {
+        let set_logic_op_enable = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_logic_op_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_LOGIC_OP_ENABLE,
+        );
+
+        unsafe { set_logic_op_enable(self.handle(), enable as vk::Bool32) };
+    }
+
+    /// Sets whether blending is enabled for the color attachments, starting at attachment
+    /// `first`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_ENABLE`]
+    /// feature is not enabled.
+    #[doc(alias = "vkCmdSetColorBlendEnableEXT")]
+    pub fn set_color_blend_enables(&mut self, first: u32, enables: &[bool]) {
+        let set_color_blend_enables = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_color_blend_enable,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_ENABLE,
+        );
+
+        if enables.is_empty() {
+            return;
+        }
+
+        let enables: SmallVec<[_; 8]> =
+            enables.iter().map(|&enable| enable as vk::Bool32).collect();
+
+        unsafe {
+            set_color_blend_enables(self.handle(), first, enables.len() as u32, enables.as_ptr());
+        }
+    }
+
+    /// Sets the blend equations of the color attachments, starting at attachment `first`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the
+    /// [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_EQUATION`] feature is not enabled.
+    #[doc(alias = "vkCmdSetColorBlendEquationEXT")]
+    pub fn set_color_blend_equations(&mut self, first: u32, blends: &[Blend]) {
+        let set_color_blend_equations = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_color_blend_equation,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_EQUATION,
+        );
+
+        if blends.is_empty() {
+            return;
+        }
+
+        let equations: SmallVec<[_; 8]> = blends.iter().map(|blend| blend.to_raw()).collect();
+
+        unsafe {
+            set_color_blend_equations(
+                self.handle(),
+                first,
+                equations.len() as u32,
+                equations.as_ptr(),
+            );
+        }
+    }
+
+    /// Sets the components of the color attachments that are written, starting at attachment
+    /// `first`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the [`DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_WRITE_MASK`]
+    /// feature is not enabled.
+    #[doc(alias = "vkCmdSetColorWriteMaskEXT")]
+    pub fn set_color_write_masks(&mut self, first: u32, masks: &[ColorComponents]) {
+        let set_color_write_masks = dynamic_state_fn(
+            self.device(),
+            self.device().fns().cmd_set_color_write_mask,
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_WRITE_MASK,
+        );
+
+        if masks.is_empty() {
+            return;
+        }
+
+        let masks: SmallVec<[_; 8]> = masks
+            .iter()
+            .map(|mask| vk::ColorComponentFlags::from_raw(mask.bits()))
+            .collect();
+
+        unsafe {
+            set_color_write_masks(self.handle(), first, masks.len() as u32, masks.as_ptr());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the limits of a device without optional capabilities.
+    fn limits() -> PhysicalDeviceLimits {
+        PhysicalDeviceLimits {
+            max_memory_allocation_count: 4096,
+            buffer_image_granularity: 1,
+            non_coherent_atom_size: 64,
+            max_sampler_anisotropy: 1.0,
+            max_vertex_input_bindings: 2,
+            max_vertex_input_attributes: 2,
+            max_vertex_input_attribute_offset: 64,
+            max_vertex_input_binding_stride: 128,
+            max_tessellation_patch_size: 4,
+            max_viewports: 2,
+            max_color_attachments: 2,
+            line_width_range: [1.0, 2.0],
+        }
+    }
+
+    const VIEWPORT: Viewport = Viewport {
+        position: [0.0, 0.0],
+        size: [64.0, 64.0],
+        depth_range: [0.0, 1.0],
+    };
+
+    const SCISSOR: Rect = Rect {
+        offset: [0, 0],
+        extent: [64, 64],
+    };
+
+    /// Returns a fixed-function state with one static viewport and no dynamic states.
+    fn state() -> FixedFunctionState<'static> {
+        FixedFunctionState {
+            vertex_input: VertexInputState::default(),
+            input_assembly: InputAssemblyState::default(),
+            viewport: ViewportState {
+                count: 1,
+                viewports: &[VIEWPORT],
+                scissors: &[SCISSOR],
+            },
+            rasterization: RasterizationState::default(),
+            multisample: MultisampleState::default(),
+            depth_stencil: DepthStencilState::default(),
+            color_blend: ColorBlendState::default(),
+            dynamic_states: &[],
+        }
+    }
+
+    const BINDING: VertexBinding = VertexBinding {
+        binding: 0,
+        stride: 8,
+        input_rate: VertexInputRate::Vertex,
+    };
+
+    const ATTRIBUTE: VertexAttribute = VertexAttribute {
+        location: 0,
+        binding: 0,
+        format: Format::Rg32Float,
+        offset: 0,
+    };
+
+    const OPAQUE: ColorBlendAttachment = ColorBlendAttachment {
+        blend: None,
+        write_mask: ColorComponents::all(),
+    };
+
+    const MASKED: ColorBlendAttachment = ColorBlendAttachment {
+        blend: None,
+        write_mask: ColorComponents::empty(),
+    };
+
+    #[test]
+    fn default_state_requires_nothing() {
+        let features = state().validate(false, 1, &limits()).unwrap();
+        assert_eq!(features, DeviceFeatures::empty());
+    }
+
+    #[test]
+    fn vertex_input_limits() {
+        let check = |bindings: &[VertexBinding], attributes: &[VertexAttribute]| {
+            let mut state = state();
+            state.vertex_input = VertexInputState {
+                bindings,
+                attributes,
+            };
+            state.validate(false, 1, &limits())
+        };
+        let limit = |limit| Err(Error::LimitExceeded(limit));
+
+        assert!(check(&[BINDING], &[ATTRIBUTE]).is_ok());
+        let bindings = [0, 1, 2].map(|binding| VertexBinding { binding, ..BINDING });
+        assert_eq!(
+            check(&bindings, &[]),
+            limit(DeviceLimit::MaxVertexInputBindings)
+        );
+        let binding = VertexBinding {
+            binding: 2,
+            ..BINDING
+        };
+        assert_eq!(
+            check(&[binding], &[]),
+            limit(DeviceLimit::MaxVertexInputBindings)
+        );
+        let binding = VertexBinding {
+            stride: 256,
+            ..BINDING
+        };
+        assert_eq!(
+            check(&[binding], &[]),
+            limit(DeviceLimit::MaxVertexInputBindingStride)
+        );
+        let attributes = [0, 1, 2].map(|location| VertexAttribute {
+            location,
+            ..ATTRIBUTE
+        });
+        assert_eq!(
+            check(&[BINDING], &attributes),
+            limit(DeviceLimit::MaxVertexInputAttributes)
+        );
+        let attribute = VertexAttribute {
+            offset: 128,
+            ..ATTRIBUTE
+        };
+        assert_eq!(
+            check(&[BINDING], &[attribute]),
+            limit(DeviceLimit::MaxVertexInputAttributeOffset)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "vertex binding 0 is declared twice")]
+    fn duplicate_vertex_binding() {
+        let mut state = state();
+        state.vertex_input.bindings = &[BINDING, BINDING];
+        let _ = state.validate(false, 1, &limits());
+    }
+
+    #[test]
+    #[should_panic(expected = "vertex attribute location 0 is declared twice")]
+    fn duplicate_vertex_attribute() {
+        let mut state = state();
+        state.vertex_input = VertexInputState {
+            bindings: &[BINDING],
+            attributes: &[ATTRIBUTE, ATTRIBUTE],
+        };
+        let _ = state.validate(false, 1, &limits());
+    }
+
+    #[test]
+    #[should_panic(expected = "reads from an undeclared binding")]
+    fn undeclared_vertex_binding() {
+        let mut state = state();
+        state.vertex_input.attributes = &[ATTRIBUTE];
+        let _ = state.validate(false, 1, &limits());
+    }
+
+    #[test]
+    fn patch_limits() {
+        let mut state = state();
+        state.input_assembly.topology = PrimitiveTopology::PatchList;
+        state.input_assembly.patch_control_points = 4;
+        assert!(state.validate(true, 1, &limits()).is_ok());
+        state.input_assembly.patch_control_points = 5;
+        assert_eq!(
+            state.validate(true, 1, &limits()),
+            Err(Error::LimitExceeded(DeviceLimit::MaxTessellationPatchSize))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "patch lists must be used together with tessellation shaders")]
+    fn patch_list_without_tessellation() {
+        let mut state = state();
+        state.input_assembly.topology = PrimitiveTopology::PatchList;
+        let _ = state.validate(false, 1, &limits());
+    }
+
+    #[test]
+    fn viewport_and_attachment_limits() {
+        let mut state = state();
+        state.dynamic_states = &[DynamicState::Viewport, DynamicState::Scissor];
+        state.viewport.count = 2;
+        assert_eq!(
+            state.validate(false, 1, &limits()),
+            Ok(DeviceFeatures::MULTI_VIEWPORT)
+        );
+        state.viewport.count = 3;
+        assert_eq!(
+            state.validate(false, 1, &limits()),
+            Err(Error::LimitExceeded(DeviceLimit::MaxViewports))
+        );
+
+        assert_eq!(
+            self::state().validate(false, 3, &limits()),
+            Err(Error::LimitExceeded(DeviceLimit::MaxColorAttachments))
+        );
+    }
+
+    #[test]
+    fn line_width() {
+        let mut state = state();
+        state.rasterization.line_width = 2.0;
+        assert_eq!(
+            state.validate(false, 1, &limits()),
+            Ok(DeviceFeatures::WIDE_LINES)
+        );
+        state.rasterization.line_width = 4.0;
+        assert_eq!(
+            state.validate(false, 1, &limits()),
+            Err(Error::LimitExceeded(DeviceLimit::LineWidthRange))
+        );
+        // A dynamic line width is neither checked nor requires the feature up front.
+        state.dynamic_states = &[DynamicState::LineWidth];
+        assert_eq!(
+            state.validate(false, 1, &limits()),
+            Ok(DeviceFeatures::empty())
+        );
+    }
+
+    #[test]
+    fn required_features() {
+        let check = |f: &dyn Fn(&mut FixedFunctionState)| {
+            let mut state = state();
+            f(&mut state);
+            state.validate(false, 2, &limits()).unwrap()
+        };
+
+        assert_eq!(
+            check(&|s| s.rasterization.polygon_mode = PolygonMode::Line),
+            DeviceFeatures::FILL_MODE_NON_SOLID
+        );
+        assert_eq!(
+            check(&|s| s.rasterization.depth_clamp = true),
+            DeviceFeatures::DEPTH_CLAMP
+        );
+        assert_eq!(
+            check(&|s| s.multisample.min_sample_shading = Some(0.5)),
+            DeviceFeatures::SAMPLE_RATE_SHADING
+        );
+        assert_eq!(
+            check(&|s| s.depth_stencil.depth_bounds_test = true),
+            DeviceFeatures::DEPTH_BOUNDS
+        );
+        assert_eq!(
+            check(&|s| s.color_blend.logic_op = Some(LogicOp::Copy)),
+            DeviceFeatures::LOGIC_OP
+        );
+        assert_eq!(
+            check(&|s| { s.color_blend.attachments = &[OPAQUE, MASKED] }),
+            DeviceFeatures::INDEPENDENT_BLEND
+        );
+        // Dynamic polygon modes require their own feature instead of the static one.
+        assert_eq!(
+            check(&|s| {
+                s.rasterization.polygon_mode = PolygonMode::Line;
+                s.dynamic_states = &[DynamicState::PolygonMode];
+            }),
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_POLYGON_MODE
+        );
+        assert_eq!(
+            check(&|s| s.dynamic_states = &[DynamicState::CullMode]),
+            DeviceFeatures::EXTENDED_DYNAMIC_STATE
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is listed twice")]
+    fn duplicate_dynamic_state() {
+        let mut state = state();
+        state.dynamic_states = &[DynamicState::LineWidth, DynamicState::LineWidth];
+        let _ = state.validate(false, 1, &limits());
+    }
+
+    #[test]
+    #[should_panic(expected = "viewports cannot be dynamic both with and without count")]
+    fn dynamic_viewports_with_and_without_count() {
+        let mut state = state();
+        state.dynamic_states = &[DynamicState::Viewport, DynamicState::ViewportWithCount];
+        let _ = state.validate(false, 1, &limits());
+    }
+
+    #[test]
+    #[should_panic(expected = "one color blend attachment per color attachment")]
+    fn color_blend_attachment_count() {
+        let mut state = state();
+        state.color_blend.attachments = &[OPAQUE];
+        let _ = state.validate(false, 2, &limits());
+    }
+}
@@ -1,6 +1,15 @@
+use std::mem::MaybeUninit;
+use std::sync::Arc;
+
 use ash::vk;
 use bitflags::bitflags;
 
+use crate::memory::BoundMemory;
+use crate::{
+    Allocation, AllocationTiling, Device, DeviceMemory, Format, MemoryRequirements, Result,
+    SharingMode,
+};
+
 bitflags! {
     /// Flags specifying a collection of [`ImageUsage`]s.
     #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -44,3 +53,641 @@ pub enum ImageUsage {
     TransientAttachment = vk::ImageUsageFlags::TRANSIENT_ATTACHMENT.as_raw(),
     InputAttachment = vk::ImageUsageFlags::INPUT_ATTACHMENT.as_raw(),
 }
+
+bitflags! {
+    /// Flags specifying additional properties of an [`Image`].
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct ImageFlags: u32 {
+        /// Views of the image may use a different format than the image itself.
+        const MUTABLE_FORMAT = vk::ImageCreateFlags::MUTABLE_FORMAT.as_raw();
+        /// Cube views may be created from the image.
+        const CUBE_COMPATIBLE = vk::ImageCreateFlags::CUBE_COMPATIBLE.as_raw();
+        /// 2D array views may be created from the (3D) image.
+        const ARRAY_2D_COMPATIBLE = vk::ImageCreateFlags::TYPE_2D_ARRAY_COMPATIBLE.as_raw();
+    }
+}
+
+bitflags! {
+    /// The aspects of an image, such as its color or depth component.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct ImageAspects: u32 {
+        const COLOR = vk::ImageAspectFlags::COLOR.as_raw();
+        const DEPTH = vk::ImageAspectFlags::DEPTH.as_raw();
+        const STENCIL = vk::ImageAspectFlags::STENCIL.as_raw();
+        const PLANE_0 = vk::ImageAspectFlags::PLANE_0.as_raw();
+        const PLANE_1 = vk::ImageAspectFlags::PLANE_1.as_raw();
+        const PLANE_2 = vk::ImageAspectFlags::PLANE_2.as_raw();
+    }
+}
+
+/// The number of dimensions of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum ImageType {
+    D1 = vk::ImageType::TYPE_1D.as_raw(),
+    D2 = vk::ImageType::TYPE_2D.as_raw(),
+    D3 = vk::ImageType::TYPE_3D.as_raw(),
+}
+
+/// The number of samples per texel of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum SampleCount {
+    X1 = vk::SampleCountFlags::TYPE_1.as_raw(),
+    X2 = vk::SampleCountFlags::TYPE_2.as_raw(),
+    X4 = vk::SampleCountFlags::TYPE_4.as_raw(),
+    X8 = vk::SampleCountFlags::TYPE_8.as_raw(),
+    X16 = vk::SampleCountFlags::TYPE_16.as_raw(),
+    X32 = vk::SampleCountFlags::TYPE_32.as_raw(),
+    X64 = vk::SampleCountFlags::TYPE_64.as_raw(),
+}
+
+/// How the texels of an image are laid out in memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum ImageTiling {
+    /// The texels are laid out in an implementation-dependent way, which is the most
+    /// efficient for the device.
+    Optimal = vk::ImageTiling::OPTIMAL.as_raw(),
+    /// The texels are laid out in row-major order, which allows the host to access them.
+    Linear = vk::ImageTiling::LINEAR.as_raw(),
+}
+
+impl From<ImageTiling> for AllocationTiling {
+    #[inline]
+    fn from(value: ImageTiling) -> Self {
+        match value {
+            ImageTiling::Optimal => Self::Optimal,
+            ImageTiling::Linear => Self::Linear,
+        }
+    }
+}
+
+/// The layout of the texels of an image, which determines the operations it supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum ImageLayout {
+    /// The content of the image is unknown, and may be discarded.
+    Undefined = vk::ImageLayout::UNDEFINED.as_raw(),
+    /// The image supports all operations, potentially with reduced performance.
+    General = vk::ImageLayout::GENERAL.as_raw(),
+    ColorAttachmentOptimal = vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL.as_raw(),
+    DepthStencilAttachmentOptimal = vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL.as_raw(),
+    DepthStencilReadOnlyOptimal = vk::ImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL.as_raw(),
+    ShaderReadOnlyOptimal = vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL.as_raw(),
+    TransferSrcOptimal = vk::ImageLayout::TRANSFER_SRC_OPTIMAL.as_raw(),
+    TransferDstOptimal = vk::ImageLayout::TRANSFER_DST_OPTIMAL.as_raw(),
+    /// The image has been initialized by the host, which only makes sense for linear images.
+    Preinitialized = vk::ImageLayout::PREINITIALIZED.as_raw(),
+    /// The image can be presented to a surface.
+    PresentSrc = vk::ImageLayout::PRESENT_SRC_KHR.as_raw(),
+}
+
+/// The parameters passed to the [`Image::new`] function.
+#[derive(Debug, Clone)]
+#[doc(alias = "VkImageCreateInfo")]
+pub struct ImageDesc<'a> {
+    /// Additional properties of the image.
+    pub flags: ImageFlags,
+    /// The number of dimensions of the image.
+    pub image_type: ImageType,
+    /// The format of the texels of the image.
+    pub format: Format,
+    /// The size of the image, in texels.
+    ///
+    /// Unused dimensions must be set to `1`.
+    pub extent: [u32; 3],
+    /// The number of mipmap levels of the image.
+    pub mip_levels: u32,
+    /// The number of layers of the image.
+    pub array_layers: u32,
+    /// The number of samples per texel.
+    pub samples: SampleCount,
+    /// How the texels of the image are laid out in memory.
+    pub tiling: ImageTiling,
+    /// The ways the image will be used.
+    pub usage: ImageUsages,
+    /// The sharing mode of the image.
+    pub sharing_mode: SharingMode<&'a [u32]>,
+    /// The layout of the image when it is created.
+    ///
+    /// This must be either [`ImageLayout::Undefined`] or [`ImageLayout::Preinitialized`].
+    pub initial_layout: ImageLayout,
+}
+
+/// A range of mipmap levels and array layers of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkImageSubresourceRange")]
+pub struct ImageSubresourceRange {
+    /// The aspects of the image included in the range.
+    pub aspects: ImageAspects,
+    /// The first mipmap level included in the range.
+    pub base_mip_level: u32,
+    /// The number of mipmap levels included in the range.
+    ///
+    /// [`ImageSubresourceRange::REMAINING`] includes all the levels after `base_mip_level`.
+    pub level_count: u32,
+    /// The first array layer included in the range.
+    pub base_array_layer: u32,
+    /// The number of array layers included in the range.
+    ///
+    /// [`ImageSubresourceRange::REMAINING`] includes all the layers after `base_array_layer`.
+    pub layer_count: u32,
+}
+
+impl ImageSubresourceRange {
+    /// A special level or layer count which refers to all the remaining levels or layers.
+    pub const REMAINING: u32 = vk::REMAINING_MIP_LEVELS;
+
+    /// Returns a range that includes all the levels and layers of the provided aspects.
+    #[inline]
+    pub fn whole(aspects: ImageAspects) -> Self {
+        Self {
+            aspects,
+            base_mip_level: 0,
+            level_count: Self::REMAINING,
+            base_array_layer: 0,
+            layer_count: Self::REMAINING,
+        }
+    }
+
+    /// Converts this range into its raw Vulkan representation.
+    pub(crate) fn to_raw(self) -> vk::ImageSubresourceRange {
+        vk::ImageSubresourceRange {
+            aspect_mask: vk::ImageAspectFlags::from_raw(self.aspects.bits()),
+            base_mip_level: self.base_mip_level,
+            level_count: self.level_count,
+            base_array_layer: self.base_array_layer,
+            layer_count: self.layer_count,
+        }
+    }
+}
+
+/// A set of array layers of a single mipmap level of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkImageSubresourceLayers")]
+pub struct ImageSubresourceLayers {
+    /// The aspects of the image included in the set.
+    pub aspects: ImageAspects,
+    /// The mipmap level included in the set.
+    pub mip_level: u32,
+    /// The first array layer included in the set.
+    pub base_array_layer: u32,
+    /// The number of array layers included in the set.
+    pub layer_count: u32,
+}
+
+impl ImageSubresourceLayers {
+    /// Converts this set into its raw Vulkan representation.
+    pub(crate) fn to_raw(self) -> vk::ImageSubresourceLayers {
+        vk::ImageSubresourceLayers {
+            aspect_mask: vk::ImageAspectFlags::from_raw(self.aspects.bits()),
+            mip_level: self.mip_level,
+            base_array_layer: self.base_array_layer,
+            layer_count: self.layer_count,
+        }
+    }
+}
+
+/// The layout of a subresource of a linear image within its memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkSubresourceLayout")]
+pub struct SubresourceLayout {
+    /// The offset of the subresource from the start of the memory of the image, in bytes.
+    pub offset: u64,
+    /// The size of the subresource, in bytes.
+    pub size: u64,
+    /// The number of bytes between two rows of texels.
+    pub row_pitch: u64,
+    /// The number of bytes between two array layers.
+    pub array_pitch: u64,
+    /// The number of bytes between two slices of a 3D image.
+    pub depth_pitch: u64,
+}
+
+/// Where the memory of an [`Image`] comes from.
+enum ImageMemory {
+    /// The image is not bound to memory yet.
+    Unbound,
+    /// The image is bound to memory.
+    Bound(BoundMemory),
+    /// The image belongs to a swapchain, which owns it.
+    Swapchain,
+}
+
+/// A multidimensional array of texels that can be used by the device.
+///
+/// Owned images are destroyed when this object is dropped, and the memory they are bound to is
+/// kept alive until then. Images borrowed from a [`Swapchain`](crate::Swapchain) are left
+/// untouched.
+pub struct Image {
+    /// The device that owns this image.
+    device: Arc<Device>,
+
+    /// The handle to the image.
+    handle: vk::Image,
+    /// Additional properties of the image.
+    flags: ImageFlags,
+    /// The number of dimensions of the image.
+    image_type: ImageType,
+    /// The format of the texels of the image.
+    format: Format,
+    /// The size of the image, in texels.
+    extent: [u32; 3],
+    /// The number of mipmap levels of the image.
+    mip_levels: u32,
+    /// The number of layers of the image.
+    array_layers: u32,
+    /// The number of samples per texel.
+    samples: SampleCount,
+    /// How the texels of the image are laid out in memory.
+    tiling: ImageTiling,
+    /// The ways the image may be used.
+    usage: ImageUsages,
+    /// Where the memory of the image comes from.
+    memory: ImageMemory,
+}
+
+impl Image {
+    /// Creates a new [`Image`] instance from the provided handle.
+    ///
+    /// # Safety
+    ///
+    /// The provided handle must be valid and belong to the provided device. `desc` must match
+    /// the parameters used to create it. The created [`Image`] will take care of destroying it
+    /// when it is dropped.
+    pub unsafe fn from_handle(device: Arc<Device>, handle: vk::Image, desc: &ImageDesc) -> Self {
+        Self {
+            device,
+            handle,
+            flags: desc.flags,
+            image_type: desc.image_type,
+            format: desc.format,
+            extent: desc.extent,
+            mip_levels: desc.mip_levels,
+            array_layers: desc.array_layers,
+            samples: desc.samples,
+            tiling: desc.tiling,
+            usage: desc.usage,
+            memory: ImageMemory::Unbound,
+        }
+    }
+
+    /// Creates a new [`Image`] instance for an image owned by a swapchain.
+    ///
+    /// # Safety
+    ///
+    /// The provided handle must be an image of a swapchain created with the provided
+    /// parameters, and must not be used once the swapchain has been destroyed.
+    pub(crate) unsafe fn from_swapchain(
+        device: Arc<Device>,
+        handle: vk::Image,
+        desc: &ImageDesc,
+    ) -> Self {
+        let mut image = Self::from_handle(device, handle, desc);
+        image.memory = ImageMemory::Swapchain;
+        image
+    }
+
+    /// Creates a new [`Image`].
+    ///
+    /// The image is not bound to any memory yet.
+    #[doc(alias = "vkCreateImage")]
+    pub fn new(device: Arc<Device>, desc: ImageDesc) -> Result<Self> {
+        assert!(
+            matches!(
+                desc.initial_layout,
+                ImageLayout::Undefined | ImageLayout::Preinitialized
+            ),
+            "the initial layout of an image must be `Undefined` or `Preinitialized`"
+        );
+
+        let (sharing_mode, queue_families) = desc.sharing_mode.to_raw();
+
+        let create_info = vk::ImageCreateInfo {
+            flags: vk::ImageCreateFlags::from_raw(desc.flags.bits()),
+            image_type: vk::ImageType::from_raw(desc.image_type as i32),
+            format: vk::Format::from_raw(desc.format as i32),
+            extent: vk::Extent3D {
+                width: desc.extent[0],
+                height: desc.extent[1],
+                depth: desc.extent[2],
+            },
+            mip_levels: desc.mip_levels,
+            array_layers: desc.array_layers,
+            samples: vk::SampleCountFlags::from_raw(desc.samples as u32),
+            tiling: vk::ImageTiling::from_raw(desc.tiling as i32),
+            usage: vk::ImageUsageFlags::from_raw(desc.usage.bits()),
+            sharing_mode,
+            queue_family_index_count: queue_families.len() as u32,
+            p_queue_family_indices: queue_families.as_ptr(),
+            initial_layout: vk::ImageLayout::from_raw(desc.initial_layout as i32),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::IMAGE_CREATE_INFO,
+        };
+
+        let mut handle = vk::Image::null();
+
+        let ret = unsafe {
+            (device.fns().create_image)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(unsafe { Self::from_handle(device, handle, &desc) })
+    }
+
+    /// Returns the memory requirements of this image.
+    #[doc(alias = "vkGetImageMemoryRequirements")]
+    pub fn memory_requirements(&self) -> MemoryRequirements {
+        let mut requirements = MaybeUninit::<vk::MemoryRequirements>::uninit();
+
+        let requirements = unsafe {
+            (self.device.fns().get_image_memory_requirements)(
+                self.device.handle(),
+                self.handle,
+                requirements.as_mut_ptr(),
+            );
+            requirements.assume_init()
+        };
+
+        MemoryRequirements {
+            size: requirements.size,
+            alignment: requirements.alignment,
+            memory_type_bits: requirements.memory_type_bits,
+        }
+    }
+
+    /// Binds this image to the provided memory, starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the image is already bound to memory, if the memory belongs to
+    /// another device, or if it does not satisfy the requirements of the image.
+    #[doc(alias = "vkBindImageMemory")]
+    pub fn bind_memory(&mut self, memory: Arc<DeviceMemory>, offset: u64) -> Result<()> {
+        self.check_binding(memory.device(), memory.memory_type(), offset, memory.size());
+        unsafe { self.bind_raw(memory.handle(), offset)? };
+        self.memory = ImageMemory::Bound(BoundMemory::Device(memory));
+        Ok(())
+    }
+
+    /// Binds this image to the provided allocation.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the image is already bound to memory, if the allocation was
+    /// made on another device or for another tiling, or if it does not satisfy the
+    /// requirements of the image.
+    #[doc(alias = "vkBindImageMemory")]
+    pub fn bind_allocation(&mut self, allocation: Allocation) -> Result<()> {
+        self.check_allocation_tiling(&allocation);
+        self.check_binding(
+            allocation.allocator().backend(),
+            allocation.memory_type(),
+            allocation.offset(),
+            allocation.offset() + allocation.size(),
+        );
+        unsafe { self.bind_raw(allocation.memory(), allocation.offset())? };
+        self.memory = ImageMemory::Bound(BoundMemory::Allocation(allocation));
+        Ok(())
+    }
+
+    /// Binds this image to the range of a shared allocation starting at `offset`, relative to
+    /// the start of the allocation.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the image is already bound to memory, if the allocation was
+    /// made on another device or for another tiling, or if the range does not satisfy the
+    /// requirements of the image.
+    pub(crate) fn bind_shared_allocation(
+        &mut self,
+        allocation: Arc<Allocation>,
+        offset: u64,
+    ) -> Result<()> {
+        self.check_allocation_tiling(&allocation);
+        self.check_binding(
+            allocation.allocator().backend(),
+            allocation.memory_type(),
+            allocation.offset() + offset,
+            allocation.offset() + allocation.size(),
+        );
+        unsafe { self.bind_raw(allocation.memory(), allocation.offset() + offset)? };
+        self.memory = ImageMemory::Bound(BoundMemory::Shared(allocation));
+        Ok(())
+    }
+
+    /// Checks that an allocation was made for the tiling of this image, so that it is kept
+    /// apart from resources of the other tiling as required by `bufferImageGranularity`.
+    fn check_allocation_tiling(&self, allocation: &Allocation) {
+        assert_eq!(
+            allocation.tiling(),
+            AllocationTiling::from(self.tiling),
+            "the allocation was not made for the tiling of the image"
+        );
+    }
+
+    /// Returns the layout of a subresource of this image within its memory.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the image does not have linear tiling.
+    #[doc(alias = "vkGetImageSubresourceLayout")]
+    pub fn subresource_layout(
+        &self,
+        aspect: ImageAspects,
+        mip_level: u32,
+        array_layer: u32,
+    ) -> SubresourceLayout {
+        assert!(
+            self.tiling == ImageTiling::Linear,
+            "subresource layouts can only be queried for linear images"
+        );
+
+        let subresource = vk::ImageSubresource {
+            aspect_mask: vk::ImageAspectFlags::from_raw(aspect.bits()),
+            mip_level,
+            array_layer,
+        };
+
+        let mut layout = MaybeUninit::<vk::SubresourceLayout>::uninit();
+
+        let layout = unsafe {
+            (self.device.fns().get_image_subresource_layout)(
+                self.device.handle(),
+                self.handle,
+                &subresource,
+                layout.as_mut_ptr(),
+            );
+            layout.assume_init()
+        };
+
+        SubresourceLayout {
+            offset: layout.offset,
+            size: layout.size,
+            row_pitch: layout.row_pitch,
+            array_pitch: layout.array_pitch,
+            depth_pitch: layout.depth_pitch,
+        }
+    }
+
+    /// Returns the allocation that this image is bound to, if it was bound with
+    /// [`bind_allocation`](Self::bind_allocation).
+    #[inline]
+    pub fn allocation(&self) -> Option<&Allocation> {
+        match &self.memory {
+            ImageMemory::Bound(BoundMemory::Allocation(allocation)) => Some(allocation),
+            _ => None,
+        }
+    }
+
+    /// Returns the allocation that this image is bound to, if it was bound with
+    /// [`bind_allocation`](Self::bind_allocation).
+    #[inline]
+    pub fn allocation_mut(&mut self) -> Option<&mut Allocation> {
+        match &mut self.memory {
+            ImageMemory::Bound(BoundMemory::Allocation(allocation)) => Some(allocation),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this image belongs to a swapchain.
+    #[inline]
+    pub fn is_swapchain_image(&self) -> bool {
+        matches!(self.memory, ImageMemory::Swapchain)
+    }
+
+    /// Returns the additional properties of this image.
+    #[inline(always)]
+    pub fn flags(&self) -> ImageFlags {
+        self.flags
+    }
+
+    /// Returns the number of dimensions of this image.
+    #[inline(always)]
+    pub fn image_type(&self) -> ImageType {
+        self.image_type
+    }
+
+    /// Returns the format of the texels of this image.
+    #[inline(always)]
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the size of this image, in texels.
+    #[inline(always)]
+    pub fn extent(&self) -> [u32; 3] {
+        self.extent
+    }
+
+    /// Returns the number of mipmap levels of this image.
+    #[inline(always)]
+    pub fn mip_levels(&self) -> u32 {
+        self.mip_levels
+    }
+
+    /// Returns the number of layers of this image.
+    #[inline(always)]
+    pub fn array_layers(&self) -> u32 {
+        self.array_layers
+    }
+
+    /// Returns the number of samples per texel of this image.
+    #[inline(always)]
+    pub fn samples(&self) -> SampleCount {
+        self.samples
+    }
+
+    /// Returns how the texels of this image are laid out in memory.
+    #[inline(always)]
+    pub fn tiling(&self) -> ImageTiling {
+        self.tiling
+    }
+
+    /// Returns the ways this image may be used.
+    #[inline(always)]
+    pub fn usage(&self) -> ImageUsages {
+        self.usage
+    }
+
+    /// Returns the device that owns this image.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this image.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::Image {
+        self.handle
+    }
+
+    /// Checks that memory of the provided device and type, available up to `end`, can be bound
+    /// at `offset`.
+    fn check_binding(&self, device: &Arc<Device>, memory_type: u32, offset: u64, end: u64) {
+        assert!(
+            Arc::ptr_eq(device, &self.device),
+            "the memory does not belong to the same device as the image"
+        );
+        assert!(
+            matches!(self.memory, ImageMemory::Unbound),
+            "image is already bound to memory"
+        );
+
+        let requirements = self.memory_requirements();
+        assert!(
+            requirements.memory_type_bits & (1 << memory_type) != 0,
+            "memory type not allowed for this image"
+        );
+        assert!(
+            offset.is_multiple_of(requirements.alignment.max(1)),
+            "memory offset is not properly aligned"
+        );
+        assert!(
+            offset + requirements.size <= end,
+            "memory is too small for this image"
+        );
+    }
+
+    /// Binds the image to the provided memory.
+    ///
+    /// # Safety
+    ///
+    /// The memory must satisfy the requirements of the image.
+    unsafe fn bind_raw(&self, memory: vk::DeviceMemory, offset: u64) -> Result<()> {
+        let ret = (self.device.fns().bind_image_memory)(
+            self.device.handle(),
+            self.handle,
+            memory,
+            offset,
+        );
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Image {
+    fn drop(&mut self) {
+        if self.is_swapchain_image() {
+            return;
+        }
+
+        unsafe {
+            (self.device.fns().destroy_image)(self.device.handle(), self.handle, std::ptr::null());
+        }
+    }
+}
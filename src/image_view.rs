@@ -0,0 +1,256 @@
+use std::ffi::c_void;
+use std::sync::Arc;
+
+use ash::vk;
+
+use crate::{
+    Device, Format, Image, ImageFlags, ImageSubresourceRange, ImageUsages, Result,
+    SamplerYcbcrConversion,
+};
+
+/// The type of an [`ImageView`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum ImageViewType {
+    D1 = vk::ImageViewType::TYPE_1D.as_raw(),
+    D2 = vk::ImageViewType::TYPE_2D.as_raw(),
+    D3 = vk::ImageViewType::TYPE_3D.as_raw(),
+    Cube = vk::ImageViewType::CUBE.as_raw(),
+    D1Array = vk::ImageViewType::TYPE_1D_ARRAY.as_raw(),
+    D2Array = vk::ImageViewType::TYPE_2D_ARRAY.as_raw(),
+    CubeArray = vk::ImageViewType::CUBE_ARRAY.as_raw(),
+}
+
+/// Where a component of a texel is read from.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum ComponentSwizzle {
+    /// The component is read from itself.
+    #[default]
+    Identity = vk::ComponentSwizzle::IDENTITY.as_raw(),
+    /// The component is always zero.
+    Zero = vk::ComponentSwizzle::ZERO.as_raw(),
+    /// The component is always one.
+    One = vk::ComponentSwizzle::ONE.as_raw(),
+    /// The component is read from the red component.
+    R = vk::ComponentSwizzle::R.as_raw(),
+    /// The component is read from the green component.
+    G = vk::ComponentSwizzle::G.as_raw(),
+    /// The component is read from the blue component.
+    B = vk::ComponentSwizzle::B.as_raw(),
+    /// The component is read from the alpha component.
+    A = vk::ComponentSwizzle::A.as_raw(),
+}
+
+/// Describes how the components of texels are remapped when they are read.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkComponentMapping")]
+pub struct ComponentMapping {
+    pub r: ComponentSwizzle,
+    pub g: ComponentSwizzle,
+    pub b: ComponentSwizzle,
+    pub a: ComponentSwizzle,
+}
+
+impl ComponentMapping {
+    /// Converts this mapping into its raw Vulkan representation.
+    pub(crate) fn to_raw(self) -> vk::ComponentMapping {
+        vk::ComponentMapping {
+            r: vk::ComponentSwizzle::from_raw(self.r as i32),
+            g: vk::ComponentSwizzle::from_raw(self.g as i32),
+            b: vk::ComponentSwizzle::from_raw(self.b as i32),
+            a: vk::ComponentSwizzle::from_raw(self.a as i32),
+        }
+    }
+}
+
+/// The parameters passed to the [`ImageView::new`] function.
+#[derive(Debug, Clone)]
+#[doc(alias = "VkImageViewCreateInfo")]
+pub struct ImageViewDesc {
+    /// The type of the view.
+    pub view_type: ImageViewType,
+    /// The format used to interpret the texels of the image.
+    ///
+    /// `None` uses the format of the image. A different format requires the image to have been
+    /// created with [`ImageFlags::MUTABLE_FORMAT`].
+    pub format: Option<Format>,
+    /// How the components of the texels are remapped.
+    pub components: ComponentMapping,
+    /// The part of the image that the view refers to.
+    pub subresource_range: ImageSubresourceRange,
+    /// The YCbCr conversion applied when the view is sampled.
+    ///
+    /// Views of multi-planar images must have a conversion in order to be sampled as a whole.
+    pub ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
+}
+
+impl ImageViewDesc {
+    /// Returns a description of a view covering the whole image, with its own format.
+    pub fn whole(view_type: ImageViewType, image: &Image) -> Self {
+        Self {
+            view_type,
+            format: None,
+            components: ComponentMapping::default(),
+            subresource_range: ImageSubresourceRange::whole(image.format().aspects()),
+            ycbcr_conversion: None,
+        }
+    }
+}
+
+/// A view into an [`Image`], which describes how it is accessed by the device.
+///
+/// The view borrows its image, which therefore cannot be destroyed while the view exists.
+pub struct ImageView<'a> {
+    /// The device that owns this view.
+    device: Arc<Device>,
+
+    /// The handle to the view.
+    handle: vk::ImageView,
+    /// The image that the view refers to.
+    image: &'a Image,
+    /// The type of the view.
+    view_type: ImageViewType,
+    /// The format used to interpret the texels of the image.
+    format: Format,
+    /// The part of the image that the view refers to.
+    subresource_range: ImageSubresourceRange,
+    /// The YCbCr conversion applied when the view is sampled, if any.
+    ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
+}
+
+impl<'a> ImageView<'a> {
+    /// Creates a new [`ImageView`] of the provided image.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the view uses a different format than the image but the image
+    /// was not created with [`ImageFlags::MUTABLE_FORMAT`], or if the view is a cube view but
+    /// the image was not created with [`ImageFlags::CUBE_COMPATIBLE`].
+    #[doc(alias = "vkCreateImageView")]
+    pub fn new(image: &'a Image, desc: ImageViewDesc) -> Result<Self> {
+        let format = desc.format.unwrap_or(image.format());
+
+        assert!(
+            format == image.format() || image.flags().contains(ImageFlags::MUTABLE_FORMAT),
+            "the image was not created with the MUTABLE_FORMAT flag"
+        );
+        assert!(
+            !matches!(
+                desc.view_type,
+                ImageViewType::Cube | ImageViewType::CubeArray
+            ) || image.flags().contains(ImageFlags::CUBE_COMPATIBLE),
+            "the image was not created with the CUBE_COMPATIBLE flag"
+        );
+
+        let conversion_info =
+            desc.ycbcr_conversion
+                .as_ref()
+                .map(|conversion| vk::SamplerYcbcrConversionInfo {
+                    conversion: conversion.handle(),
+
+                    p_next: std::ptr::null(),
+                    s_type: vk::StructureType::SAMPLER_YCBCR_CONVERSION_INFO,
+                });
+
+        let create_info = vk::ImageViewCreateInfo {
+            flags: vk::ImageViewCreateFlags::empty(),
+            image: image.handle(),
+            view_type: vk::ImageViewType::from_raw(desc.view_type as i32),
+            format: vk::Format::from_raw(format as i32),
+            components: desc.components.to_raw(),
+            subresource_range: desc.subresource_range.to_raw(),
+
+            p_next: conversion_info
+                .as_ref()
+                .map_or(std::ptr::null(), |info| info as *const _ as *const c_void),
+            s_type: vk::StructureType::IMAGE_VIEW_CREATE_INFO,
+        };
+
+        let device = image.device();
+        let mut handle = vk::ImageView::null();
+
+        let ret = unsafe {
+            (device.fns().create_image_view)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self {
+            device: device.clone(),
+            handle,
+            image,
+            view_type: desc.view_type,
+            format,
+            subresource_range: desc.subresource_range,
+            ycbcr_conversion: desc.ycbcr_conversion,
+        })
+    }
+
+    /// Returns the image that this view refers to.
+    #[inline(always)]
+    pub fn image(&self) -> &'a Image {
+        self.image
+    }
+
+    /// Returns the type of this view.
+    #[inline(always)]
+    pub fn view_type(&self) -> ImageViewType {
+        self.view_type
+    }
+
+    /// Returns the format used to interpret the texels of the image.
+    #[inline(always)]
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the part of the image that this view refers to.
+    #[inline(always)]
+    pub fn subresource_range(&self) -> &ImageSubresourceRange {
+        &self.subresource_range
+    }
+
+    /// Returns the usage of the image that this view refers to.
+    #[inline(always)]
+    pub fn usage(&self) -> ImageUsages {
+        self.image.usage()
+    }
+
+    /// Returns the YCbCr conversion applied when this view is sampled, if any.
+    #[inline(always)]
+    pub fn ycbcr_conversion(&self) -> Option<&Arc<SamplerYcbcrConversion>> {
+        self.ycbcr_conversion.as_ref()
+    }
+
+    /// Returns the device that owns this view.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this view.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::ImageView {
+        self.handle
+    }
+}
+
+impl Drop for ImageView<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_image_view)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
@@ -44,6 +44,22 @@ bitflags! {
         const WAYLAND_SURFACE = 1 << 3;
         /// The `VK_KHR_win32_surface` extension.
         const WIN32_SURFACE = 1 << 4;
+        /// The `VK_EXT_headless_surface` extension.
+        const HEADLESS_SURFACE = 1 << 5;
+        /// The `VK_KHR_display` extension.
+        const DISPLAY = 1 << 6;
+        /// The `VK_KHR_android_surface` extension.
+        const ANDROID_SURFACE = 1 << 7;
+        /// The `VK_EXT_metal_surface` extension.
+        const METAL_SURFACE = 1 << 8;
+        /// The `VK_EXT_direct_mode_display` extension.
+        const DIRECT_MODE_DISPLAY = 1 << 9;
+        /// The `VK_EXT_acquire_drm_display` extension.
+        const ACQUIRE_DRM_DISPLAY = 1 << 10;
+        /// The `VK_KHR_get_physical_device_properties2` extension.
+        ///
+        /// This extension is part of the core API since Vulkan 1.1.
+        const GET_PHYSICAL_DEVICE_PROPERTIES_2 = 1 << 11;
     }
 }
 
@@ -60,12 +76,19 @@ impl InstanceExtensions {
             Self::XLIB_SURFACE => ash::extensions::khr::XlibSurface::name(),
             Self::WAYLAND_SURFACE => ash::extensions::khr::WaylandSurface::name(),
             Self::WIN32_SURFACE => ash::extensions::khr::Win32Surface::name(),
+            Self::HEADLESS_SURFACE => vk::ExtHeadlessSurfaceFn::name(),
+            Self::DISPLAY => ash::extensions::khr::Display::name(),
+            Self::ANDROID_SURFACE => ash::extensions::khr::AndroidSurface::name(),
+            Self::METAL_SURFACE => ash::extensions::ext::MetalSurface::name(),
+            Self::DIRECT_MODE_DISPLAY => vk::ExtDirectModeDisplayFn::name(),
+            Self::ACQUIRE_DRM_DISPLAY => vk::ExtAcquireDrmDisplayFn::name(),
+            Self::GET_PHYSICAL_DEVICE_PROPERTIES_2 => vk::KhrGetPhysicalDeviceProperties2Fn::name(),
             _ => panic!("multiple extension bits are set"),
         }
     }
 
     /// Returns an instance of [`InstanceExtensions`] with the extensions required for the
-    /// provided [`raw_window_handle::RawWindowHandle`].
+    /// provided [`raw_window_handle::RawDisplayHandle`].
     #[cfg(feature = "raw-window-handle")]
     pub fn required_for_raw_display_handle(rdh: raw_window_handle::RawDisplayHandle) -> Self {
         use raw_window_handle::RawDisplayHandle as Rdh;
@@ -75,38 +98,74 @@ impl InstanceExtensions {
             Rdh::Xlib(_) => Self::SURFACE | Self::XLIB_SURFACE,
             Rdh::Xcb(_) => Self::SURFACE | Self::XCB_SURFACE,
             Rdh::Wayland(_) => Self::SURFACE | Self::WAYLAND_SURFACE,
+            Rdh::Android(_) => Self::SURFACE | Self::ANDROID_SURFACE,
+            Rdh::AppKit(_) | Rdh::UiKit(_) => Self::SURFACE | Self::METAL_SURFACE,
+            Rdh::Drm(_) => {
+                Self::SURFACE
+                    | Self::DISPLAY
+                    | Self::DIRECT_MODE_DISPLAY
+                    | Self::ACQUIRE_DRM_DISPLAY
+            }
             _ => Self::empty(),
         }
     }
 
-    /// Returns an instance of [`InstanceExtensions`] with the extensions required for the
-    /// provided [`raw_window_handle::RawWindowHandle`].
+    /// Returns an instance of [`InstanceExtensions`] with the extensions required to create a
+    /// [`Surface`](crate::Surface) for the provided window.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// This function panics if the provided window does not have a display handle.
-    pub fn required_for_window<W>(window: &W) -> Self
+    /// This function fails with [`Error::WindowHandleUnavailable`] if the display handle of the
+    /// window cannot currently be retrieved.
+    #[cfg(feature = "raw-window-handle")]
+    pub fn required_for_window<W>(window: &W) -> Result<Self>
     where
         W: ?Sized + raw_window_handle::HasDisplayHandle,
     {
-        Self::required_for_raw_display_handle(window.display_handle().unwrap().as_raw())
+        let rdh = window.display_handle().map_err(Error::from)?.as_raw();
+        Ok(Self::required_for_raw_display_handle(rdh))
     }
 }
 
 /// A collection of Vulkan functions that have been loaded for a specific [`Instance`].
+///
+/// Functions that are provided by instance extensions are optional, and will be `None` when the
+/// extension that provides them was not enabled.
 #[derive(Debug)]
 #[rustfmt::skip]
 pub struct InstanceFns {
     pub destroy_instance: vk::PFN_vkDestroyInstance,
     pub enumerate_physical_devices: vk::PFN_vkEnumeratePhysicalDevices,
     pub get_physical_device_properties: vk::PFN_vkGetPhysicalDeviceProperties,
-    pub destroy_surface: vk::PFN_vkDestroySurfaceKHR,
-    pub get_physical_device_surface_support: vk::PFN_vkGetPhysicalDeviceSurfaceSupportKHR,
-    pub get_physical_device_surface_capabilities: vk::PFN_vkGetPhysicalDeviceSurfaceCapabilitiesKHR,
-    pub get_physical_device_surface_formats: vk::PFN_vkGetPhysicalDeviceSurfaceFormatsKHR,
-    pub get_physical_device_surface_present_modes: vk::PFN_vkGetPhysicalDeviceSurfacePresentModesKHR,
+    pub get_physical_device_memory_properties: vk::PFN_vkGetPhysicalDeviceMemoryProperties,
+    pub get_physical_device_features: vk::PFN_vkGetPhysicalDeviceFeatures,
+    pub get_physical_device_features2: Option<vk::PFN_vkGetPhysicalDeviceFeatures2>,
+    pub get_physical_device_memory_properties2: Option<vk::PFN_vkGetPhysicalDeviceMemoryProperties2>,
+    pub enumerate_device_extension_properties: vk::PFN_vkEnumerateDeviceExtensionProperties,
+    pub destroy_surface: Option<vk::PFN_vkDestroySurfaceKHR>,
+    pub get_physical_device_surface_support: Option<vk::PFN_vkGetPhysicalDeviceSurfaceSupportKHR>,
+    pub get_physical_device_surface_capabilities: Option<vk::PFN_vkGetPhysicalDeviceSurfaceCapabilitiesKHR>,
+    pub get_physical_device_surface_formats: Option<vk::PFN_vkGetPhysicalDeviceSurfaceFormatsKHR>,
+    pub get_physical_device_surface_present_modes: Option<vk::PFN_vkGetPhysicalDeviceSurfacePresentModesKHR>,
+    pub create_xlib_surface: Option<vk::PFN_vkCreateXlibSurfaceKHR>,
+    pub create_xcb_surface: Option<vk::PFN_vkCreateXcbSurfaceKHR>,
+    pub create_wayland_surface: Option<vk::PFN_vkCreateWaylandSurfaceKHR>,
+    pub create_win32_surface: Option<vk::PFN_vkCreateWin32SurfaceKHR>,
+    pub create_headless_surface: Option<vk::PFN_vkCreateHeadlessSurfaceEXT>,
+    pub create_android_surface: Option<vk::PFN_vkCreateAndroidSurfaceKHR>,
+    pub create_metal_surface: Option<vk::PFN_vkCreateMetalSurfaceEXT>,
     pub create_device: vk::PFN_vkCreateDevice,
     pub get_device_proc_addr: vk::PFN_vkGetDeviceProcAddr,
+    pub get_physical_device_display_properties: Option<vk::PFN_vkGetPhysicalDeviceDisplayPropertiesKHR>,
+    pub get_physical_device_display_plane_properties: Option<vk::PFN_vkGetPhysicalDeviceDisplayPlanePropertiesKHR>,
+    pub get_display_plane_supported_displays: Option<vk::PFN_vkGetDisplayPlaneSupportedDisplaysKHR>,
+    pub get_display_mode_properties: Option<vk::PFN_vkGetDisplayModePropertiesKHR>,
+    pub create_display_mode: Option<vk::PFN_vkCreateDisplayModeKHR>,
+    pub get_display_plane_capabilities: Option<vk::PFN_vkGetDisplayPlaneCapabilitiesKHR>,
+    pub create_display_plane_surface: Option<vk::PFN_vkCreateDisplayPlaneSurfaceKHR>,
+    pub release_display: Option<vk::PFN_vkReleaseDisplayEXT>,
+    pub acquire_drm_display: Option<vk::PFN_vkAcquireDrmDisplayEXT>,
+    pub get_drm_display: Option<vk::PFN_vkGetDrmDisplayEXT>,
 }
 
 impl InstanceFns {
@@ -117,6 +176,7 @@ impl InstanceFns {
     /// The provided instance and entry point must come from the same Vulkan implementation. The
     /// handle must be valid.
     #[rustfmt::skip]
+    #[allow(clippy::missing_transmute_annotations)]
     unsafe fn load(handle: vk::Instance, ep: vk::PFN_vkGetInstanceProcAddr) -> Self {
         macro_rules! load {
             ($name:ident) => {
@@ -127,17 +187,46 @@ impl InstanceFns {
             };
         }
 
+        // Functions that were promoted to the core API might only be available through their
+        // extension name on older implementations.
+        let get_physical_device_memory_properties2: Option<vk::PFN_vkGetPhysicalDeviceMemoryProperties2> = load!(vkGetPhysicalDeviceMemoryProperties2);
+        let get_physical_device_memory_properties2_khr: Option<vk::PFN_vkGetPhysicalDeviceMemoryProperties2> = load!(vkGetPhysicalDeviceMemoryProperties2KHR);
+        let get_physical_device_features2: Option<vk::PFN_vkGetPhysicalDeviceFeatures2> = load!(vkGetPhysicalDeviceFeatures2);
+        let get_physical_device_features2_khr: Option<vk::PFN_vkGetPhysicalDeviceFeatures2> = load!(vkGetPhysicalDeviceFeatures2KHR);
+
         Self {
             destroy_instance: load!(vkDestroyInstance),
             enumerate_physical_devices: load!(vkEnumeratePhysicalDevices),
             get_physical_device_properties: load!(vkGetPhysicalDeviceProperties),
+            get_physical_device_memory_properties: load!(vkGetPhysicalDeviceMemoryProperties),
+            get_physical_device_features: load!(vkGetPhysicalDeviceFeatures),
+            get_physical_device_features2: get_physical_device_features2.or(get_physical_device_features2_khr),
+            get_physical_device_memory_properties2: get_physical_device_memory_properties2.or(get_physical_device_memory_properties2_khr),
+            enumerate_device_extension_properties: load!(vkEnumerateDeviceExtensionProperties),
             destroy_surface: load!(vkDestroySurfaceKHR),
             get_physical_device_surface_support: load!(vkGetPhysicalDeviceSurfaceSupportKHR),
             get_physical_device_surface_capabilities: load!(vkGetPhysicalDeviceSurfaceCapabilitiesKHR),
             get_physical_device_surface_formats: load!(vkGetPhysicalDeviceSurfaceFormatsKHR),
             get_physical_device_surface_present_modes: load!(vkGetPhysicalDeviceSurfacePresentModesKHR),
+            create_xlib_surface: load!(vkCreateXlibSurfaceKHR),
+            create_xcb_surface: load!(vkCreateXcbSurfaceKHR),
+            create_wayland_surface: load!(vkCreateWaylandSurfaceKHR),
+            create_win32_surface: load!(vkCreateWin32SurfaceKHR),
+            create_headless_surface: load!(vkCreateHeadlessSurfaceEXT),
+            create_android_surface: load!(vkCreateAndroidSurfaceKHR),
+            create_metal_surface: load!(vkCreateMetalSurfaceEXT),
             create_device: load!(vkCreateDevice),
             get_device_proc_addr: load!(vkGetDeviceProcAddr),
+            get_physical_device_display_properties: load!(vkGetPhysicalDeviceDisplayPropertiesKHR),
+            get_physical_device_display_plane_properties: load!(vkGetPhysicalDeviceDisplayPlanePropertiesKHR),
+            get_display_plane_supported_displays: load!(vkGetDisplayPlaneSupportedDisplaysKHR),
+            get_display_mode_properties: load!(vkGetDisplayModePropertiesKHR),
+            create_display_mode: load!(vkCreateDisplayModeKHR),
+            get_display_plane_capabilities: load!(vkGetDisplayPlaneCapabilitiesKHR),
+            create_display_plane_surface: load!(vkCreateDisplayPlaneSurfaceKHR),
+            release_display: load!(vkReleaseDisplayEXT),
+            acquire_drm_display: load!(vkAcquireDrmDisplayEXT),
+            get_drm_display: load!(vkGetDrmDisplayEXT),
         }
     }
 }
@@ -296,3 +385,74 @@ impl Debug for Instance {
         f.debug_tuple("Instance").field(&self.handle).finish()
     }
 }
+
+#[cfg(all(test, feature = "raw-window-handle"))]
+mod tests {
+    use super::*;
+    use raw_window_handle::*;
+    use std::ptr::NonNull;
+
+    #[test]
+    fn raw_display_handle_extensions() {
+        let ptr = NonNull::dangling();
+        let ext = InstanceExtensions::required_for_raw_display_handle;
+
+        let cases = [
+            (
+                RawDisplayHandle::Windows(WindowsDisplayHandle::new()),
+                InstanceExtensions::SURFACE | InstanceExtensions::WIN32_SURFACE,
+            ),
+            (
+                RawDisplayHandle::Xlib(XlibDisplayHandle::new(None, 0)),
+                InstanceExtensions::SURFACE | InstanceExtensions::XLIB_SURFACE,
+            ),
+            (
+                RawDisplayHandle::Xcb(XcbDisplayHandle::new(None, 0)),
+                InstanceExtensions::SURFACE | InstanceExtensions::XCB_SURFACE,
+            ),
+            (
+                RawDisplayHandle::Wayland(WaylandDisplayHandle::new(ptr)),
+                InstanceExtensions::SURFACE | InstanceExtensions::WAYLAND_SURFACE,
+            ),
+            (
+                RawDisplayHandle::Android(AndroidDisplayHandle::new()),
+                InstanceExtensions::SURFACE | InstanceExtensions::ANDROID_SURFACE,
+            ),
+            (
+                RawDisplayHandle::AppKit(AppKitDisplayHandle::new()),
+                InstanceExtensions::SURFACE | InstanceExtensions::METAL_SURFACE,
+            ),
+            (
+                RawDisplayHandle::UiKit(UiKitDisplayHandle::new()),
+                InstanceExtensions::SURFACE | InstanceExtensions::METAL_SURFACE,
+            ),
+            (
+                RawDisplayHandle::Drm(DrmDisplayHandle::new(0)),
+                InstanceExtensions::SURFACE
+                    | InstanceExtensions::DISPLAY
+                    | InstanceExtensions::DIRECT_MODE_DISPLAY
+                    | InstanceExtensions::ACQUIRE_DRM_DISPLAY,
+            ),
+            (
+                RawDisplayHandle::Gbm(GbmDisplayHandle::new(ptr)),
+                InstanceExtensions::empty(),
+            ),
+            (
+                RawDisplayHandle::Web(WebDisplayHandle::new()),
+                InstanceExtensions::empty(),
+            ),
+            (
+                RawDisplayHandle::Haiku(HaikuDisplayHandle::new()),
+                InstanceExtensions::empty(),
+            ),
+            (
+                RawDisplayHandle::Orbital(OrbitalDisplayHandle::new()),
+                InstanceExtensions::empty(),
+            ),
+        ];
+
+        for (rdh, expected) in cases {
+            assert_eq!(ext(rdh), expected, "{rdh:?}");
+        }
+    }
+}
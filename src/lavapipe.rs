@@ -0,0 +1,72 @@
+//! Access to lavapipe, the software implementation of Mesa, used by tests that need an actual
+//! driver.
+//!
+//! Tests using it are marked `#[ignore]`, and run with `cargo test -- --ignored`. They are
+//! still skipped at runtime when the Vulkan loader or the lavapipe driver is not installed.
+
+use std::sync::Arc;
+
+use crate::{
+    Device, DeviceDesc, DeviceExtensions, DeviceFeatures, Instance, InstanceDesc,
+    InstanceExtensions, Library, Queue, QueueFamilyDesc,
+};
+
+/// The queue family used by the tests.
+///
+/// lavapipe exposes a single queue family, which supports graphics, compute and transfer
+/// operations.
+pub const QUEUE_FAMILY: u32 = 0;
+
+/// Creates a device on lavapipe with the provided features enabled, along with its first
+/// queue.
+///
+/// Returns `None` if lavapipe is not available or does not support the features.
+pub fn device(features: DeviceFeatures) -> Option<(Arc<Device>, Queue)> {
+    let Ok(library) = Library::new() else {
+        eprintln!("skipping: the Vulkan loader is not installed");
+        return None;
+    };
+
+    let instance = Instance::new(
+        library,
+        InstanceDesc {
+            application_name: None,
+            application_version: 0,
+            engine_name: None,
+            engine_version: 0,
+            extensions: InstanceExtensions::empty(),
+        },
+    )
+    .ok()?;
+    let physical_device = instance
+        .enumerate_physical_devices()
+        .ok()?
+        .find(|physical_device| physical_device.properties().name.starts_with("llvmpipe"));
+
+    let Some(physical_device) = physical_device else {
+        eprintln!("skipping: lavapipe is not installed");
+        return None;
+    };
+
+    if !physical_device.supported_features().contains(features) {
+        eprintln!("skipping: lavapipe does not support {features:?}");
+        return None;
+    }
+
+    let device = Device::new(
+        physical_device,
+        DeviceDesc {
+            extensions: DeviceExtensions::empty(),
+            features,
+            queue_families: &[QueueFamilyDesc {
+                index: QUEUE_FAMILY,
+                priorities: &[1.0],
+            }],
+        },
+    )
+    .expect("failed to create a device on lavapipe");
+
+    let queue = Queue::new(device.clone(), QUEUE_FAMILY, 0);
+
+    Some((device, queue))
+}
@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use ash::vk;
+use smallvec::SmallVec;
+
+use crate::{Device, Result, ShaderStages};
+
+/// The type of a descriptor, which determines the kind of resource it refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkDescriptorType")]
+pub enum DescriptorType {
+    Sampler = vk::DescriptorType::SAMPLER.as_raw(),
+    CombinedImageSampler = vk::DescriptorType::COMBINED_IMAGE_SAMPLER.as_raw(),
+    SampledImage = vk::DescriptorType::SAMPLED_IMAGE.as_raw(),
+    StorageImage = vk::DescriptorType::STORAGE_IMAGE.as_raw(),
+    UniformTexelBuffer = vk::DescriptorType::UNIFORM_TEXEL_BUFFER.as_raw(),
+    StorageTexelBuffer = vk::DescriptorType::STORAGE_TEXEL_BUFFER.as_raw(),
+    UniformBuffer = vk::DescriptorType::UNIFORM_BUFFER.as_raw(),
+    StorageBuffer = vk::DescriptorType::STORAGE_BUFFER.as_raw(),
+    UniformBufferDynamic = vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC.as_raw(),
+    StorageBufferDynamic = vk::DescriptorType::STORAGE_BUFFER_DYNAMIC.as_raw(),
+    InputAttachment = vk::DescriptorType::INPUT_ATTACHMENT.as_raw(),
+}
+
+/// A binding of a [`DescriptorSetLayout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkDescriptorSetLayoutBinding")]
+pub struct DescriptorSetLayoutBinding {
+    /// The binding number, which matches the `binding` decoration in shaders.
+    pub binding: u32,
+    /// The type of the descriptors of the binding.
+    pub descriptor_type: DescriptorType,
+    /// The number of descriptors of the binding, which are accessed as an array in shaders.
+    pub count: u32,
+    /// The shader stages which may access the binding.
+    pub stages: ShaderStages,
+}
+
+/// Describes the bindings of the descriptor sets that may be bound at a given set number.
+pub struct DescriptorSetLayout {
+    /// The device that owns this layout.
+    device: Arc<Device>,
+
+    /// The handle to the layout.
+    handle: vk::DescriptorSetLayout,
+    /// The bindings of the layout.
+    bindings: Vec<DescriptorSetLayoutBinding>,
+}
+
+impl DescriptorSetLayout {
+    /// Creates a new [`DescriptorSetLayout`] with the provided bindings.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if two bindings have the same binding number.
+    #[doc(alias = "vkCreateDescriptorSetLayout")]
+    pub fn new(device: Arc<Device>, bindings: &[DescriptorSetLayoutBinding]) -> Result<Self> {
+        assert!(
+            bindings
+                .iter()
+                .enumerate()
+                .all(|(i, a)| bindings[..i].iter().all(|b| a.binding != b.binding)),
+            "descriptor set layout bindings must have distinct binding numbers"
+        );
+
+        let raw_bindings: SmallVec<[_; 8]> = bindings
+            .iter()
+            .map(|binding| vk::DescriptorSetLayoutBinding {
+                binding: binding.binding,
+                descriptor_type: vk::DescriptorType::from_raw(binding.descriptor_type as i32),
+                descriptor_count: binding.count,
+                stage_flags: vk::ShaderStageFlags::from_raw(binding.stages.bits()),
+                p_immutable_samplers: std::ptr::null(),
+            })
+            .collect();
+
+        let create_info = vk::DescriptorSetLayoutCreateInfo {
+            flags: vk::DescriptorSetLayoutCreateFlags::empty(),
+            binding_count: raw_bindings.len() as u32,
+            p_bindings: raw_bindings.as_ptr(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::DESCRIPTOR_SET_LAYOUT_CREATE_INFO,
+        };
+
+        let mut handle = vk::DescriptorSetLayout::null();
+
+        let ret = unsafe {
+            (device.fns().create_descriptor_set_layout)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self {
+            device,
+            handle,
+            bindings: bindings.to_vec(),
+        })
+    }
+
+    /// Returns the bindings of this layout.
+    #[inline(always)]
+    pub fn bindings(&self) -> &[DescriptorSetLayoutBinding] {
+        &self.bindings
+    }
+
+    /// Returns the device that owns this layout.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this layout.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::DescriptorSetLayout {
+        self.handle
+    }
+}
+
+impl Drop for DescriptorSetLayout {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_descriptor_set_layout)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
+
+/// A range of push constants which is accessible to some shader stages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkPushConstantRange")]
+pub struct PushConstantRange {
+    /// The shader stages which may access the range.
+    pub stages: ShaderStages,
+    /// The offset of the range, in bytes.
+    pub offset: u32,
+    /// The size of the range, in bytes.
+    pub size: u32,
+}
+
+/// Describes the descriptor sets and push constants that the shaders of a pipeline access.
+///
+/// The layout keeps its descriptor set layouts alive.
+pub struct PipelineLayout {
+    /// The device that owns this layout.
+    device: Arc<Device>,
+
+    /// The handle to the layout.
+    handle: vk::PipelineLayout,
+    /// The layouts of the descriptor sets, indexed by set number.
+    set_layouts: Vec<Arc<DescriptorSetLayout>>,
+    /// The push constant ranges of the layout.
+    push_constant_ranges: Vec<PushConstantRange>,
+}
+
+impl PipelineLayout {
+    /// Creates a new [`PipelineLayout`] from the layouts of its descriptor sets, indexed by
+    /// set number, and its push constant ranges.
+    #[doc(alias = "vkCreatePipelineLayout")]
+    pub fn new(
+        device: Arc<Device>,
+        set_layouts: &[Arc<DescriptorSetLayout>],
+        push_constant_ranges: &[PushConstantRange],
+    ) -> Result<Self> {
+        let raw_set_layouts: SmallVec<[_; 4]> =
+            set_layouts.iter().map(|layout| layout.handle()).collect();
+        let raw_ranges: SmallVec<[_; 4]> = push_constant_ranges
+            .iter()
+            .map(|range| vk::PushConstantRange {
+                stage_flags: vk::ShaderStageFlags::from_raw(range.stages.bits()),
+                offset: range.offset,
+                size: range.size,
+            })
+            .collect();
+
+        let create_info = vk::PipelineLayoutCreateInfo {
+            flags: vk::PipelineLayoutCreateFlags::empty(),
+            set_layout_count: raw_set_layouts.len() as u32,
+            p_set_layouts: raw_set_layouts.as_ptr(),
+            push_constant_range_count: raw_ranges.len() as u32,
+            p_push_constant_ranges: raw_ranges.as_ptr(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PIPELINE_LAYOUT_CREATE_INFO,
+        };
+
+        let mut handle = vk::PipelineLayout::null();
+
+        let ret = unsafe {
+            (device.fns().create_pipeline_layout)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self {
+            device,
+            handle,
+            set_layouts: set_layouts.to_vec(),
+            push_constant_ranges: push_constant_ranges.to_vec(),
+        })
+    }
+
+    /// Returns the layouts of the descriptor sets, indexed by set number.
+    #[inline(always)]
+    pub fn set_layouts(&self) -> &[Arc<DescriptorSetLayout>] {
+        &self.set_layouts
+    }
+
+    /// Returns the push constant ranges of this layout.
+    #[inline(always)]
+    pub fn push_constant_ranges(&self) -> &[PushConstantRange] {
+        &self.push_constant_ranges
+    }
+
+    /// Returns the device that owns this layout.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this layout.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::PipelineLayout {
+        self.handle
+    }
+}
+
+impl Drop for PipelineLayout {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_pipeline_layout)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
@@ -15,6 +15,9 @@ pub use physical_device::*;
 mod surface;
 pub use surface::*;
 
+mod display;
+pub use display::*;
+
 mod swapchain;
 pub use swapchain::*;
 
@@ -24,10 +27,90 @@ pub use format::*;
 mod image;
 pub use image::*;
 
+mod image_view;
+pub use image_view::*;
+
+mod sampler;
+pub use sampler::*;
+
+mod memory;
+pub use memory::*;
+
+mod allocator;
+pub use allocator::*;
+
+mod buffer;
+pub use buffer::*;
+
 mod sync;
 pub use sync::*;
 
+mod sync2;
+pub use sync2::*;
+
+mod pipeline;
+pub use pipeline::*;
+
+mod spirv;
+pub use spirv::*;
+
+mod shader;
+pub use shader::*;
+
+mod layout;
+pub use layout::*;
+
+mod reflect;
+pub use reflect::*;
+
+mod compute;
+pub use compute::*;
+
+mod graphics;
+pub use graphics::*;
+
+mod command;
+pub use command::*;
+
+mod rendering;
+pub use rendering::*;
+
+mod render_pass;
+pub use render_pass::*;
+
+mod frame_pools;
+pub use frame_pools::*;
+
+mod tracker;
+pub use tracker::*;
+
+mod render_graph;
+pub use render_graph::*;
+
+mod timeline;
+pub use timeline::*;
+
+mod queue;
+pub use queue::*;
+
+#[cfg(feature = "async")]
+mod waiter;
+#[cfg(feature = "async")]
+pub use waiter::*;
+
 mod device;
 pub use device::*;
 
 mod utility;
+mod vk_ext;
+
+#[cfg(all(
+    feature = "raw-window-handle",
+    any(target_os = "macos", target_os = "ios")
+))]
+mod metal;
+
+#[cfg(test)]
+mod lavapipe;
+#[cfg(test)]
+mod mock;
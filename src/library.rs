@@ -1,4 +1,3 @@
-use std::ffi::c_char;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -49,6 +48,7 @@ impl LibraryFns {
     /// # Safety
     ///
     /// The provided entry point must comply with the Vulkan specification.
+    #[allow(clippy::missing_transmute_annotations)]
     unsafe fn load(ep: vk::PFN_vkGetInstanceProcAddr) -> Self {
         macro_rules! load {
             ($name:ident) => {
@@ -74,10 +74,10 @@ impl LibraryFns {
 /// This struct is meant to be used through an [`Arc<T>`] to ensure that it is not dropped while
 /// still in use.
 pub struct Library {
-    /// The inner library object.
+    /// The inner library object, if the entry point was loaded from a dynamic library.
     ///
     /// Dropping this field will unload the library from the current program's memory.
-    _inner: libloading::Library,
+    _inner: Option<libloading::Library>,
 
     /// The list of functions loaded from the library.
     fns: LibraryFns,
@@ -97,11 +97,26 @@ impl Library {
             .get(b"vkGetInstanceProcAddr\0")
             .map_err(|_| LibraryError::MissingEntryPoint)?;
         Ok(Arc::new(Self {
-            _inner: inner,
+            _inner: Some(inner),
             fns: LibraryFns::load(ep),
         }))
     }
 
+    /// Creates a new [`Library`] instance from a Vulkan entry point, such as one exported by a
+    /// statically linked implementation.
+    ///
+    /// # Safety
+    ///
+    /// The provided entry point must comply with the Vulkan specification, and the
+    /// implementation it belongs to must remain loaded for as long as the returned library is
+    /// alive.
+    pub unsafe fn from_entry_point(ep: vk::PFN_vkGetInstanceProcAddr) -> Arc<Self> {
+        Arc::new(Self {
+            _inner: None,
+            fns: LibraryFns::load(ep),
+        })
+    }
+
     /// Creates a new [`Library`] instance by loading the Vulkan library located at the provided
     /// path.
     ///
@@ -147,7 +162,7 @@ impl Library {
         let func = unsafe {
             (self.fns.get_instance_proc_addr)(
                 vk::Instance::null(),
-                b"vkEnumerateInstanceVersion\0".as_ptr() as *const c_char,
+                c"vkEnumerateInstanceVersion".as_ptr(),
             )
         };
 
@@ -0,0 +1,557 @@
+use std::ffi::c_void;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+use ash::vk;
+use bitflags::bitflags;
+
+use crate::{Allocation, Device, DeviceExtensions, DeviceFeatures, Result};
+
+bitflags! {
+    /// The properties of a [`MemoryType`].
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct MemoryProperties: u32 {
+        /// The memory is the most efficient for device access.
+        const DEVICE_LOCAL = vk::MemoryPropertyFlags::DEVICE_LOCAL.as_raw();
+        /// The memory can be mapped for host access.
+        const HOST_VISIBLE = vk::MemoryPropertyFlags::HOST_VISIBLE.as_raw();
+        /// Host writes and device writes are visible to each other without explicit flushes and
+        /// invalidations.
+        const HOST_COHERENT = vk::MemoryPropertyFlags::HOST_COHERENT.as_raw();
+        /// The memory is cached on the host, making host reads faster.
+        const HOST_CACHED = vk::MemoryPropertyFlags::HOST_CACHED.as_raw();
+        /// The memory is only allocated by the implementation when it is actually needed.
+        const LAZILY_ALLOCATED = vk::MemoryPropertyFlags::LAZILY_ALLOCATED.as_raw();
+        /// The memory can only be accessed by the device, and by protected queue operations.
+        const PROTECTED = vk::MemoryPropertyFlags::PROTECTED.as_raw();
+    }
+}
+
+bitflags! {
+    /// The properties of a [`MemoryHeap`].
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct MemoryHeapFlags: u32 {
+        /// The heap corresponds to device-local memory.
+        const DEVICE_LOCAL = vk::MemoryHeapFlags::DEVICE_LOCAL.as_raw();
+        /// In a device group, the heap is replicated to each physical device.
+        const MULTI_INSTANCE = vk::MemoryHeapFlags::MULTI_INSTANCE.as_raw();
+    }
+}
+
+/// A type of memory that can be allocated from a [`MemoryHeap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoryType {
+    /// The properties of this memory type.
+    pub properties: MemoryProperties,
+    /// The index of the heap from which this memory type is allocated.
+    pub heap_index: u32,
+}
+
+/// A heap of memory, from which one or more [`MemoryType`]s can be allocated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoryHeap {
+    /// The total size of the heap, in bytes.
+    pub size: u64,
+    /// The properties of the heap.
+    pub flags: MemoryHeapFlags,
+}
+
+/// Describes the memory of a physical device.
+#[derive(Debug, Clone)]
+#[doc(alias = "VkPhysicalDeviceMemoryProperties")]
+pub struct MemoryInfo {
+    /// The memory types exposed by the physical device.
+    ///
+    /// The index of a memory type in this list is the index to use when allocating memory of
+    /// that type.
+    pub types: Box<[MemoryType]>,
+    /// The memory heaps exposed by the physical device.
+    pub heaps: Box<[MemoryHeap]>,
+}
+
+impl MemoryInfo {
+    /// Converts the provided raw Vulkan memory properties into a [`MemoryInfo`].
+    pub(crate) fn from_raw(raw: &vk::PhysicalDeviceMemoryProperties) -> Self {
+        let types = raw.memory_types[..raw.memory_type_count as usize]
+            .iter()
+            .map(|ty| MemoryType {
+                properties: MemoryProperties::from_bits_retain(ty.property_flags.as_raw()),
+                heap_index: ty.heap_index,
+            })
+            .collect();
+
+        let heaps = raw.memory_heaps[..raw.memory_heap_count as usize]
+            .iter()
+            .map(|heap| MemoryHeap {
+                size: heap.size,
+                flags: MemoryHeapFlags::from_bits_retain(heap.flags.as_raw()),
+            })
+            .collect();
+
+        Self { types, heaps }
+    }
+
+    /// Finds a memory type suitable for a resource.
+    ///
+    /// `type_bits` is the set of memory types allowed for the resource (as reported by its
+    /// memory requirements), where bit `i` corresponds to the memory type at index `i`. Among the
+    /// allowed types which have all the `required` properties, the first one that has the
+    /// most `preferred` properties is selected.
+    ///
+    /// Returns the index of the selected memory type, or `None` if no memory type is suitable.
+    pub fn find_memory_type(
+        &self,
+        type_bits: u32,
+        required: MemoryProperties,
+        preferred: MemoryProperties,
+    ) -> Option<u32> {
+        self.types
+            .iter()
+            .enumerate()
+            .filter(|&(index, ty)| {
+                type_bits & (1 << index) != 0 && ty.properties.contains(required)
+            })
+            .min_by_key(|(_, ty)| {
+                std::cmp::Reverse((ty.properties & preferred).bits().count_ones())
+            })
+            .map(|(index, _)| index as u32)
+    }
+}
+
+/// The budget and usage of a [`MemoryHeap`], as reported by `VK_EXT_memory_budget`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HeapBudget {
+    /// An estimation of how much memory the process can allocate from the heap before
+    /// allocations may fail or cause performance degradation, in bytes.
+    pub budget: u64,
+    /// An estimation of how much memory the process is currently using in the heap, in bytes.
+    pub usage: u64,
+}
+
+/// The memory requirements of a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkMemoryRequirements")]
+pub struct MemoryRequirements {
+    /// The size of the memory required by the resource, in bytes.
+    pub size: u64,
+    /// The required alignment of the resource within its memory, in bytes.
+    pub alignment: u64,
+    /// The set of memory types that the resource can be bound to. Bit `i` corresponds to the
+    /// memory type at index `i`.
+    pub memory_type_bits: u32,
+}
+
+/// A resource that an allocation of memory is dedicated to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkMemoryDedicatedAllocateInfo")]
+pub enum DedicatedResource {
+    /// The memory is dedicated to a buffer.
+    Buffer(vk::Buffer),
+    /// The memory is dedicated to an image.
+    Image(vk::Image),
+}
+
+/// The parameters passed to the [`DeviceMemory::new`] function.
+#[derive(Debug, Clone)]
+#[doc(alias = "VkMemoryAllocateInfo")]
+pub struct MemoryAllocDesc {
+    /// The size of the allocation, in bytes.
+    pub size: u64,
+    /// The index of the memory type to allocate from.
+    ///
+    /// See [`MemoryInfo::find_memory_type`].
+    pub memory_type: u32,
+}
+
+/// A block of memory allocated on a [`Device`].
+///
+/// The memory is freed when this object is dropped.
+pub struct DeviceMemory {
+    /// The device that owns this memory.
+    device: Arc<Device>,
+
+    /// The handle to the memory.
+    handle: vk::DeviceMemory,
+    /// The size of the allocation, in bytes.
+    size: u64,
+    /// The index of the memory type that the memory was allocated from.
+    memory_type: u32,
+}
+
+impl DeviceMemory {
+    /// Creates a new [`DeviceMemory`] instance from the provided handle.
+    ///
+    /// # Safety
+    ///
+    /// The provided handle must be valid and belong to the provided device. `size` and
+    /// `memory_type` must match the parameters used to allocate it. The created
+    /// [`DeviceMemory`] will take care of freeing it when it is dropped.
+    pub unsafe fn from_handle(
+        device: Arc<Device>,
+        handle: vk::DeviceMemory,
+        size: u64,
+        memory_type: u32,
+    ) -> Self {
+        Self {
+            device,
+            handle,
+            size,
+            memory_type,
+        }
+    }
+
+    /// Allocates a new block of memory on the provided device.
+    #[doc(alias = "vkAllocateMemory")]
+    pub fn new(device: Arc<Device>, desc: MemoryAllocDesc) -> Result<Self> {
+        assert!(
+            (desc.memory_type as usize) < device.memory_properties().types.len(),
+            "invalid memory type index"
+        );
+
+        let handle = allocate_memory(&device, desc.memory_type, desc.size, None)?;
+        Ok(unsafe { Self::from_handle(device, handle, desc.size, desc.memory_type) })
+    }
+
+    /// Maps `size` bytes of this memory, starting at `offset`, into the address space of the
+    /// current process.
+    ///
+    /// The memory is unmapped when the returned [`MappedMemory`] is dropped. When the memory is
+    /// not host-coherent, the range that is actually mapped is expanded to respect the
+    /// `nonCoherentAtomSize` limit of the device.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the memory type of this memory is not host-visible, or if the
+    /// requested range is empty or out of bounds.
+    #[doc(alias = "vkMapMemory")]
+    pub fn map(&mut self, offset: u64, size: u64) -> Result<MappedMemory<'_>> {
+        assert!(
+            self.properties().contains(MemoryProperties::HOST_VISIBLE),
+            "memory is not host-visible"
+        );
+        assert!(size != 0, "cannot map an empty range");
+        assert!(
+            offset.checked_add(size).is_some_and(|end| end <= self.size),
+            "mapped range out of bounds"
+        );
+
+        // Ranges of non-coherent memory are flushed and invalidated in multiples of the
+        // `nonCoherentAtomSize` limit, and those ranges must lie within the mapped range. The
+        // mapping is therefore expanded to the atom size as well.
+        let (map_start, map_end) = if self.properties().contains(MemoryProperties::HOST_COHERENT) {
+            (offset, offset + size)
+        } else {
+            let atom = self.device.limits().non_coherent_atom_size.max(1);
+            let start = offset / atom * atom;
+            let end = (offset + size)
+                .div_ceil(atom)
+                .saturating_mul(atom)
+                .min(self.size);
+            (start, end)
+        };
+
+        let mut ptr = std::ptr::null_mut();
+
+        let ret = unsafe {
+            (self.device.fns().map_memory)(
+                self.device.handle(),
+                self.handle,
+                map_start,
+                map_end - map_start,
+                vk::MemoryMapFlags::empty(),
+                &mut ptr,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        let ptr = NonNull::new(ptr as *mut u8).expect("vkMapMemory returned a null pointer");
+
+        Ok(MappedMemory {
+            // SAFETY: `offset` lies within the mapped range.
+            ptr: unsafe { ptr.add((offset - map_start) as usize) },
+            len: usize::try_from(size).expect("mapped range does not fit in memory"),
+            offset,
+            memory: self,
+        })
+    }
+
+    /// Returns the properties of the memory type that this memory was allocated from.
+    #[inline]
+    pub fn properties(&self) -> MemoryProperties {
+        self.device.memory_properties().types[self.memory_type as usize].properties
+    }
+
+    /// Returns the size of the allocation, in bytes.
+    #[inline(always)]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the index of the memory type that this memory was allocated from.
+    #[inline(always)]
+    pub fn memory_type(&self) -> u32 {
+        self.memory_type
+    }
+
+    /// Returns the device that owns this memory.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this memory.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::DeviceMemory {
+        self.handle
+    }
+}
+
+impl Drop for DeviceMemory {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().free_memory)(self.device.handle(), self.handle, std::ptr::null());
+        }
+    }
+}
+
+/// The memory that a buffer or an image is bound to.
+///
+/// This keeps the memory alive for as long as the resource exists.
+pub(crate) enum BoundMemory {
+    /// The resource is bound to a [`DeviceMemory`] object, which is only held to keep it alive.
+    Device(#[allow(dead_code)] Arc<DeviceMemory>),
+    /// The resource is bound to an [`Allocation`].
+    Allocation(Allocation),
+    /// The resource is bound to a range of an [`Allocation`] shared with other resources,
+    /// which is only held to keep it alive.
+    Shared(#[allow(dead_code)] Arc<Allocation>),
+}
+
+/// Allocates `size` bytes of memory from the provided memory type.
+///
+/// When the `bufferDeviceAddress` feature is enabled, the memory is allocated with the
+/// `DEVICE_ADDRESS` flag, so that any buffer can be bound to it. When `dedicated` is set, the
+/// memory is allocated for that resource only, provided that the device supports Vulkan 1.1 or
+/// has the `VK_KHR_dedicated_allocation` extension enabled.
+pub(crate) fn allocate_memory(
+    device: &Device,
+    memory_type: u32,
+    size: u64,
+    dedicated: Option<DedicatedResource>,
+) -> Result<vk::DeviceMemory> {
+    // Without support for dedicated allocations, the memory is still only used by the resource,
+    // but the implementation is not told about it.
+    let dedicated = dedicated.filter(|_| {
+        device.api_version() >= vk::API_VERSION_1_1
+            || device
+                .extensions()
+                .contains(DeviceExtensions::DEDICATED_ALLOCATION)
+    });
+
+    let dedicated_info = dedicated.map(|resource| vk::MemoryDedicatedAllocateInfo {
+        image: match resource {
+            DedicatedResource::Image(image) => image,
+            DedicatedResource::Buffer(_) => vk::Image::null(),
+        },
+        buffer: match resource {
+            DedicatedResource::Buffer(buffer) => buffer,
+            DedicatedResource::Image(_) => vk::Buffer::null(),
+        },
+
+        p_next: std::ptr::null(),
+        s_type: vk::StructureType::MEMORY_DEDICATED_ALLOCATE_INFO,
+    });
+
+    let mut p_next = match &dedicated_info {
+        Some(info) => info as *const _ as *const c_void,
+        None => std::ptr::null(),
+    };
+
+    let flags_info = vk::MemoryAllocateFlagsInfo {
+        flags: vk::MemoryAllocateFlags::DEVICE_ADDRESS,
+        device_mask: 0,
+
+        p_next,
+        s_type: vk::StructureType::MEMORY_ALLOCATE_FLAGS_INFO,
+    };
+
+    if device
+        .features()
+        .contains(DeviceFeatures::BUFFER_DEVICE_ADDRESS)
+    {
+        p_next = &flags_info as *const _ as *const c_void;
+    }
+
+    let allocate_info = vk::MemoryAllocateInfo {
+        allocation_size: size,
+        memory_type_index: memory_type,
+
+        p_next,
+        s_type: vk::StructureType::MEMORY_ALLOCATE_INFO,
+    };
+
+    let mut handle = vk::DeviceMemory::null();
+
+    let ret = unsafe {
+        (device.fns().allocate_memory)(
+            device.handle(),
+            &allocate_info,
+            std::ptr::null(),
+            &mut handle,
+        )
+    };
+
+    if ret != vk::Result::SUCCESS {
+        return Err(ret.into());
+    }
+
+    Ok(handle)
+}
+
+/// A range of [`DeviceMemory`] mapped into the address space of the current process.
+///
+/// The memory is unmapped when this guard is dropped.
+///
+/// When the memory is not [`HOST_COHERENT`](MemoryProperties::HOST_COHERENT), host writes must
+/// be made visible to the device with [`flush`](Self::flush), and device writes must be made
+/// visible to the host with [`invalidate`](Self::invalidate).
+pub struct MappedMemory<'a> {
+    /// The memory that has been mapped.
+    memory: &'a mut DeviceMemory,
+
+    /// A pointer to the first mapped byte.
+    ptr: NonNull<u8>,
+    /// The number of mapped bytes.
+    len: usize,
+    /// The offset of the mapped range within the memory.
+    offset: u64,
+}
+
+// SAFETY: the guard behaves like a `&mut [u8]`.
+unsafe impl Send for MappedMemory<'_> {}
+unsafe impl Sync for MappedMemory<'_> {}
+
+impl MappedMemory<'_> {
+    /// Makes host writes to the provided range visible to the device.
+    ///
+    /// `offset` and `size` are relative to the start of the mapped range, and are expanded to
+    /// respect the `nonCoherentAtomSize` limit of the device. This is a no-op for host-coherent
+    /// memory.
+    #[doc(alias = "vkFlushMappedMemoryRanges")]
+    pub fn flush(&self, offset: u64, size: u64) -> Result<()> {
+        let Some(range) = self.non_coherent_range(offset, size) else {
+            return Ok(());
+        };
+
+        let device = self.memory.device();
+        let ret = unsafe { (device.fns().flush_mapped_memory_ranges)(device.handle(), 1, &range) };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+
+    /// Makes device writes to the provided range visible to the host.
+    ///
+    /// `offset` and `size` are relative to the start of the mapped range, and are expanded to
+    /// respect the `nonCoherentAtomSize` limit of the device. This is a no-op for host-coherent
+    /// memory.
+    #[doc(alias = "vkInvalidateMappedMemoryRanges")]
+    pub fn invalidate(&mut self, offset: u64, size: u64) -> Result<()> {
+        let Some(range) = self.non_coherent_range(offset, size) else {
+            return Ok(());
+        };
+
+        let device = self.memory.device();
+        let ret =
+            unsafe { (device.fns().invalidate_mapped_memory_ranges)(device.handle(), 1, &range) };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the offset of the mapped range within the [`DeviceMemory`].
+    #[inline(always)]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the [`DeviceMemory`] that has been mapped.
+    #[inline(always)]
+    pub fn memory(&self) -> &DeviceMemory {
+        self.memory
+    }
+
+    /// Computes the memory range to flush or invalidate for the provided range, relative to the
+    /// start of the mapping.
+    ///
+    /// Returns `None` if the memory is host-coherent.
+    fn non_coherent_range(&self, offset: u64, size: u64) -> Option<vk::MappedMemoryRange> {
+        assert!(
+            offset
+                .checked_add(size)
+                .is_some_and(|end| end <= self.len as u64),
+            "range out of bounds"
+        );
+
+        if self
+            .memory
+            .properties()
+            .contains(MemoryProperties::HOST_COHERENT)
+        {
+            return None;
+        }
+
+        let atom = self.memory.device().limits().non_coherent_atom_size.max(1);
+
+        // The range must be a multiple of the atom size, unless it ends at the end of the
+        // allocation. `map` aligned the mapped range the same way, so the expanded range never
+        // extends past it.
+        let start = (self.offset + offset) / atom * atom;
+        let end = (self.offset + offset + size)
+            .div_ceil(atom)
+            .saturating_mul(atom)
+            .min(self.memory.size());
+
+        Some(vk::MappedMemoryRange {
+            memory: self.memory.handle(),
+            offset: start,
+            size: end - start,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::MAPPED_MEMORY_RANGE,
+        })
+    }
+}
+
+impl Deref for MappedMemory<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for MappedMemory<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for MappedMemory<'_> {
+    fn drop(&mut self) {
+        let device = self.memory.device();
+        unsafe { (device.fns().unmap_memory)(device.handle(), self.memory.handle()) };
+    }
+}
@@ -0,0 +1,105 @@
+//! A minimal amount of Objective-C glue used to retrieve a `CAMetalLayer` from the views
+//! exposed by `raw-window-handle` on Apple platforms.
+
+use std::ffi::{c_char, c_void};
+
+use ash::vk;
+
+/// An Objective-C object pointer.
+type Id = *mut c_void;
+/// An Objective-C selector.
+type Sel = *const c_void;
+
+#[link(name = "objc", kind = "dylib")]
+extern "C" {
+    fn objc_getClass(name: *const c_char) -> Id;
+    fn sel_registerName(name: *const c_char) -> Sel;
+    fn objc_msgSend();
+}
+
+// Make sure that the `CAMetalLayer` class is available at runtime.
+#[link(name = "QuartzCore", kind = "framework")]
+extern "C" {}
+
+/// Sends a message that takes no argument and returns an object.
+unsafe fn send_id(obj: Id, sel: &std::ffi::CStr) -> Id {
+    let f: unsafe extern "C" fn(Id, Sel) -> Id = std::mem::transmute(objc_msgSend as *const ());
+    f(obj, sel_registerName(sel.as_ptr()))
+}
+
+/// Sends a message that takes no argument and returns a boolean.
+unsafe fn send_bool(obj: Id, sel: &std::ffi::CStr) -> bool {
+    let f: unsafe extern "C" fn(Id, Sel) -> i8 = std::mem::transmute(objc_msgSend as *const ());
+    f(obj, sel_registerName(sel.as_ptr())) != 0
+}
+
+/// Sends a message that takes an object and returns a boolean.
+unsafe fn send_id_bool(obj: Id, sel: &std::ffi::CStr, arg: Id) -> bool {
+    let f: unsafe extern "C" fn(Id, Sel, Id) -> i8 = std::mem::transmute(objc_msgSend as *const ());
+    f(obj, sel_registerName(sel.as_ptr()), arg) != 0
+}
+
+/// Sends a message that takes an object and returns nothing.
+unsafe fn send_set_id(obj: Id, sel: &std::ffi::CStr, arg: Id) {
+    let f: unsafe extern "C" fn(Id, Sel, Id) = std::mem::transmute(objc_msgSend as *const ());
+    f(obj, sel_registerName(sel.as_ptr()), arg)
+}
+
+/// Sends a message that takes a boolean and returns nothing.
+unsafe fn send_set_bool(obj: Id, sel: &std::ffi::CStr, arg: bool) {
+    let f: unsafe extern "C" fn(Id, Sel, i8) = std::mem::transmute(objc_msgSend as *const ());
+    f(obj, sel_registerName(sel.as_ptr()), arg as i8)
+}
+
+/// Returns the layer of the provided view if it is a `CAMetalLayer`.
+unsafe fn metal_layer_of(view: Id) -> Option<Id> {
+    let class = objc_getClass(c"CAMetalLayer".as_ptr());
+    let layer = send_id(view, c"layer");
+
+    if !layer.is_null() && send_id_bool(layer, c"isKindOfClass:", class) {
+        Some(layer)
+    } else {
+        None
+    }
+}
+
+/// Returns whether the calling thread is the main thread of the application.
+pub fn is_main_thread() -> bool {
+    unsafe { send_bool(objc_getClass(c"NSThread".as_ptr()), c"isMainThread") }
+}
+
+/// Returns a `CAMetalLayer` backing the provided `NSView`.
+///
+/// If the view is not already backed by a `CAMetalLayer`, a new one is created and assigned to
+/// the view.
+///
+/// # Safety
+///
+/// `ns_view` must be a valid `NSView` pointer. This function must be called on the main thread.
+pub unsafe fn layer_from_ns_view(ns_view: *mut c_void) -> *const vk::CAMetalLayer {
+    if let Some(layer) = metal_layer_of(ns_view) {
+        return layer;
+    }
+
+    let class = objc_getClass(c"CAMetalLayer".as_ptr());
+    let layer = send_id(class, c"layer");
+
+    // The view retains the layer, so the autoreleased object returned by `+layer` remains alive
+    // for as long as the view itself.
+    send_set_bool(ns_view, c"setWantsLayer:", true);
+    send_set_id(ns_view, c"setLayer:", layer);
+
+    layer
+}
+
+/// Returns the `CAMetalLayer` backing the provided `UIView`, if any.
+///
+/// Unlike `NSView`s, the layer of a `UIView` is determined by its class and cannot be replaced.
+/// Views that are not backed by a `CAMetalLayer` are not supported.
+///
+/// # Safety
+///
+/// `ui_view` must be a valid `UIView` pointer.
+pub unsafe fn layer_from_ui_view(ui_view: *mut c_void) -> Option<*const vk::CAMetalLayer> {
+    metal_layer_of(ui_view).map(|layer| layer as *const vk::CAMetalLayer)
+}
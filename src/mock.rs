@@ -0,0 +1,217 @@
+//! A fake Vulkan implementation, used by tests that do not need an actual driver.
+//!
+//! The fake implementation only provides the functions that the tests exercise. Functions that
+//! must be loaded unconditionally but are never called are backed by a stub that aborts the
+//! program.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr};
+use std::sync::Arc;
+
+use ash::vk;
+
+use crate::{Instance, Library};
+
+thread_local! {
+    /// The names of the functions that the fake implementation pretends not to expose.
+    static HIDDEN: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+
+    /// The structure types received by the object creation functions, along with the
+    /// structure type that the function expected.
+    static RECEIVED: RefCell<Vec<(vk::StructureType, vk::StructureType)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Creates an [`Instance`] backed by the fake implementation.
+///
+/// The functions named in `hidden` are reported as unavailable, as if the extension that
+/// provides them was not enabled.
+pub fn instance(hidden: &[&'static str]) -> Arc<Instance> {
+    HIDDEN.with(|h| *h.borrow_mut() = hidden.to_vec());
+    RECEIVED.with(|r| r.borrow_mut().clear());
+
+    unsafe {
+        let library = Library::from_entry_point(get_instance_proc_addr);
+        Instance::from_handle(library, vk::Handle::from_raw(0x1000))
+    }
+}
+
+/// Returns the structure types received by the creation functions since the last call to
+/// [`instance`], as `(received, expected)` pairs.
+pub fn received_s_types() -> Vec<(vk::StructureType, vk::StructureType)> {
+    RECEIVED.with(|r| r.borrow().clone())
+}
+
+unsafe extern "system" fn get_instance_proc_addr(
+    _instance: vk::Instance,
+    name: *const c_char,
+) -> vk::PFN_vkVoidFunction {
+    let name = CStr::from_ptr(name).to_str().unwrap();
+
+    if HIDDEN.with(|h| h.borrow().contains(&name)) {
+        return None;
+    }
+
+    macro_rules! cast {
+        ($f:expr) => {
+            Some(std::mem::transmute::<*const (), unsafe extern "system" fn()>($f as *const ()))
+        };
+    }
+
+    match name {
+        "vkDestroyInstance" => cast!(destroy_instance),
+        "vkDestroySurfaceKHR" => cast!(destroy_surface),
+        "vkGetPhysicalDeviceSurfacePresentModesKHR" => cast!(get_surface_present_modes),
+        "vkGetPhysicalDeviceSurfaceFormatsKHR" => cast!(get_surface_formats),
+        "vkGetPhysicalDeviceSurfaceCapabilitiesKHR" => cast!(get_surface_capabilities),
+        "vkCreateXlibSurfaceKHR" => cast!(create_surface::<vk::XlibSurfaceCreateInfoKHR>),
+        "vkCreateXcbSurfaceKHR" => cast!(create_surface::<vk::XcbSurfaceCreateInfoKHR>),
+        "vkCreateWaylandSurfaceKHR" => cast!(create_surface::<vk::WaylandSurfaceCreateInfoKHR>),
+        "vkCreateWin32SurfaceKHR" => cast!(create_surface::<vk::Win32SurfaceCreateInfoKHR>),
+        "vkCreateAndroidSurfaceKHR" => cast!(create_surface::<vk::AndroidSurfaceCreateInfoKHR>),
+        "vkCreateMetalSurfaceEXT" => cast!(create_surface::<vk::MetalSurfaceCreateInfoEXT>),
+        "vkCreateHeadlessSurfaceEXT" => {
+            cast!(create_surface::<vk::HeadlessSurfaceCreateInfoEXT>)
+        }
+        "vkCreateDisplayPlaneSurfaceKHR" => {
+            cast!(create_surface::<vk::DisplaySurfaceCreateInfoKHR>)
+        }
+        // Core functions are loaded unconditionally and must therefore not be null.
+        "vkCreateInstance"
+        | "vkEnumeratePhysicalDevices"
+        | "vkGetPhysicalDeviceProperties"
+        | "vkGetPhysicalDeviceMemoryProperties"
+        | "vkGetPhysicalDeviceFeatures"
+        | "vkEnumerateDeviceExtensionProperties"
+        | "vkCreateDevice"
+        | "vkGetDeviceProcAddr" => cast!(unimplemented),
+        _ => None,
+    }
+}
+
+unsafe extern "system" fn unimplemented() {
+    std::process::abort();
+}
+
+unsafe extern "system" fn destroy_instance(
+    _instance: vk::Instance,
+    _allocator: *const vk::AllocationCallbacks,
+) {
+}
+
+unsafe extern "system" fn destroy_surface(
+    _instance: vk::Instance,
+    _surface: vk::SurfaceKHR,
+    _allocator: *const vk::AllocationCallbacks,
+) {
+}
+
+/// The present modes reported for every surface, including one unknown to the crate.
+pub const PRESENT_MODES: [vk::PresentModeKHR; 3] = [
+    vk::PresentModeKHR::FIFO,
+    vk::PresentModeKHR::from_raw(0x7FFF_0000),
+    vk::PresentModeKHR::MAILBOX,
+];
+
+/// The formats reported for every surface, including an unknown format and an unknown color
+/// space.
+pub const SURFACE_FORMATS: [vk::SurfaceFormatKHR; 3] = [
+    vk::SurfaceFormatKHR {
+        format: vk::Format::from_raw(0x7FFF_0000),
+        color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+    },
+    vk::SurfaceFormatKHR {
+        format: vk::Format::B8G8R8A8_SRGB,
+        color_space: vk::ColorSpaceKHR::SRGB_NONLINEAR,
+    },
+    vk::SurfaceFormatKHR {
+        format: vk::Format::B8G8R8A8_SRGB,
+        color_space: vk::ColorSpaceKHR::from_raw(0x7FFF_0000),
+    },
+];
+
+/// The transform reported as the current transform of every surface, unknown to the crate.
+pub const CURRENT_TRANSFORM: vk::SurfaceTransformFlagsKHR =
+    vk::SurfaceTransformFlagsKHR::from_raw(1 << 30);
+
+/// Copies `items` into the array enumerated by a `vkGetPhysicalDevice*` function.
+unsafe fn enumerate<T: Copy>(items: &[T], count: *mut u32, data: *mut T) -> vk::Result {
+    if data.is_null() {
+        *count = items.len() as u32;
+        return vk::Result::SUCCESS;
+    }
+
+    let written = items.len().min(*count as usize);
+    std::ptr::copy_nonoverlapping(items.as_ptr(), data, written);
+    *count = written as u32;
+
+    if written < items.len() {
+        vk::Result::INCOMPLETE
+    } else {
+        vk::Result::SUCCESS
+    }
+}
+
+unsafe extern "system" fn get_surface_present_modes(
+    _physical_device: vk::PhysicalDevice,
+    _surface: vk::SurfaceKHR,
+    count: *mut u32,
+    modes: *mut vk::PresentModeKHR,
+) -> vk::Result {
+    enumerate(&PRESENT_MODES, count, modes)
+}
+
+unsafe extern "system" fn get_surface_formats(
+    _physical_device: vk::PhysicalDevice,
+    _surface: vk::SurfaceKHR,
+    count: *mut u32,
+    formats: *mut vk::SurfaceFormatKHR,
+) -> vk::Result {
+    enumerate(&SURFACE_FORMATS, count, formats)
+}
+
+unsafe extern "system" fn get_surface_capabilities(
+    _physical_device: vk::PhysicalDevice,
+    _surface: vk::SurfaceKHR,
+    caps: *mut vk::SurfaceCapabilitiesKHR,
+) -> vk::Result {
+    *caps = vk::SurfaceCapabilitiesKHR {
+        min_image_count: 2,
+        max_image_count: 0,
+        current_extent: vk::Extent2D {
+            width: 640,
+            height: 480,
+        },
+        min_image_extent: vk::Extent2D {
+            width: 1,
+            height: 1,
+        },
+        max_image_extent: vk::Extent2D {
+            width: 4096,
+            height: 4096,
+        },
+        max_image_array_layers: 1,
+        supported_transforms: vk::SurfaceTransformFlagsKHR::IDENTITY | CURRENT_TRANSFORM,
+        current_transform: CURRENT_TRANSFORM,
+        supported_composite_alpha: vk::CompositeAlphaFlagsKHR::OPAQUE,
+        supported_usage_flags: vk::ImageUsageFlags::COLOR_ATTACHMENT,
+    };
+    vk::Result::SUCCESS
+}
+
+/// Records the structure type of `info`, which is expected to be a `T`.
+unsafe extern "system" fn create_surface<T: Default>(
+    _instance: vk::Instance,
+    info: *const T,
+    _allocator: *const vk::AllocationCallbacks,
+    surface: *mut vk::SurfaceKHR,
+) -> vk::Result {
+    // Every create info structure starts with its structure type, and the `Default`
+    // implementations provided by `ash` initialize it to the right value.
+    let received = *info.cast::<vk::StructureType>();
+    let expected = *(&T::default() as *const T).cast::<vk::StructureType>();
+    RECEIVED.with(|r| r.borrow_mut().push((received, expected)));
+
+    *surface = vk::Handle::from_raw(0x2000);
+    vk::Result::SUCCESS
+}
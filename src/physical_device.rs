@@ -1,4 +1,4 @@
-use std::ffi::CStr;
+use std::ffi::{c_void, CStr};
 use std::mem::MaybeUninit;
 use std::sync::Arc;
 
@@ -6,8 +6,10 @@ use ash::vk;
 use smallvec::SmallVec;
 
 use crate::{
-    ColorSpace, CompositeAlphas, Format, ImageUsages, Instance, PresentModes, Result, Surface,
-    SurfaceCaps, SurfaceTransform, SurfaceTransforms,
+    vk_ext, ColorSpace, CompositeAlphas, DeviceExtensions, DeviceFeatures, Display,
+    DisplayPlaneProperties, DisplayProperties, Error, Format, HeapBudget, ImageUsages, Instance,
+    InstanceExtensions, MemoryInfo, PresentModes, Result, Surface, SurfaceCaps, SurfaceTransform,
+    SurfaceTransforms,
 };
 
 /// A physical device.
@@ -62,40 +64,312 @@ impl PhysicalDevice {
                 unknown => unreachable!("unknown device type: {}", unknown.as_raw()),
             },
             name,
+            limits: PhysicalDeviceLimits::from_raw(&properties.limits),
         }
     }
 
-    /// Returns the list of present modes that the provided surface supports with this physical
-    /// device.
-    pub fn surface_present_modes(&self, surface: &Surface) -> Result<PresentModes> {
-        let mut list = SmallVec::<[vk::PresentModeKHR; 8]>::default();
+    /// Returns the set of optional features that are supported by this physical device.
+    #[doc(alias = "vkGetPhysicalDeviceFeatures")]
+    #[doc(alias = "vkGetPhysicalDeviceFeatures2")]
+    pub fn supported_features(&self) -> DeviceFeatures {
+        let mut result = DeviceFeatures::empty();
+
+        let mut features = MaybeUninit::<vk::PhysicalDeviceFeatures>::uninit();
+        let features = unsafe {
+            (self.instance.fns().get_physical_device_features)(self.handle, features.as_mut_ptr());
+            features.assume_init()
+        };
+
+        let core_features = [
+            (
+                features.sampler_anisotropy,
+                DeviceFeatures::SAMPLER_ANISOTROPY,
+            ),
+            (features.geometry_shader, DeviceFeatures::GEOMETRY_SHADER),
+            (
+                features.tessellation_shader,
+                DeviceFeatures::TESSELLATION_SHADER,
+            ),
+            (
+                features.independent_blend,
+                DeviceFeatures::INDEPENDENT_BLEND,
+            ),
+            (features.logic_op, DeviceFeatures::LOGIC_OP),
+            (
+                features.fill_mode_non_solid,
+                DeviceFeatures::FILL_MODE_NON_SOLID,
+            ),
+            (features.depth_clamp, DeviceFeatures::DEPTH_CLAMP),
+            (features.depth_bounds, DeviceFeatures::DEPTH_BOUNDS),
+            (features.wide_lines, DeviceFeatures::WIDE_LINES),
+            (
+                features.sample_rate_shading,
+                DeviceFeatures::SAMPLE_RATE_SHADING,
+            ),
+            (features.multi_viewport, DeviceFeatures::MULTI_VIEWPORT),
+        ];
+        for (supported, feature) in core_features {
+            if supported != vk::FALSE {
+                result |= feature;
+            }
+        }
+
+        // Features introduced after Vulkan 1.0 can only be queried through the
+        // `vkGetPhysicalDeviceFeatures2` function.
+        let Some(get_features2) = self.instance.fns().get_physical_device_features2 else {
+            return result;
+        };
+
+        let mut extended_dynamic_state_3 =
+            vk_ext::PhysicalDeviceExtendedDynamicState3FeaturesEXT::default();
+        let mut extended_dynamic_state_2 = vk::PhysicalDeviceExtendedDynamicState2FeaturesEXT {
+            p_next: &mut extended_dynamic_state_3 as *mut _ as *mut c_void,
+            ..Default::default()
+        };
+        let mut extended_dynamic_state = vk::PhysicalDeviceExtendedDynamicStateFeaturesEXT {
+            p_next: &mut extended_dynamic_state_2 as *mut _ as *mut c_void,
+            ..Default::default()
+        };
+        let mut imageless_framebuffer = vk::PhysicalDeviceImagelessFramebufferFeatures {
+            p_next: &mut extended_dynamic_state as *mut _ as *mut c_void,
+            ..Default::default()
+        };
+        let mut synchronization_2 = vk::PhysicalDeviceSynchronization2FeaturesKHR {
+            p_next: &mut imageless_framebuffer as *mut _ as *mut c_void,
+            ..Default::default()
+        };
+        let mut timeline_semaphore = vk::PhysicalDeviceTimelineSemaphoreFeatures {
+            p_next: &mut synchronization_2 as *mut _ as *mut c_void,
+            ..Default::default()
+        };
+        let mut dynamic_rendering = vk::PhysicalDeviceDynamicRenderingFeaturesKHR {
+            p_next: &mut timeline_semaphore as *mut _ as *mut c_void,
+            ..Default::default()
+        };
+        let mut sampler_ycbcr_conversion = vk::PhysicalDeviceSamplerYcbcrConversionFeatures {
+            p_next: &mut dynamic_rendering as *mut _ as *mut c_void,
+            ..Default::default()
+        };
+        let mut buffer_device_address = vk::PhysicalDeviceBufferDeviceAddressFeatures {
+            p_next: &mut sampler_ycbcr_conversion as *mut _ as *mut c_void,
+            ..Default::default()
+        };
+        let mut features2 = vk::PhysicalDeviceFeatures2 {
+            p_next: &mut buffer_device_address as *mut _ as *mut c_void,
+            ..Default::default()
+        };
+
+        unsafe { get_features2(self.handle, &mut features2) };
+
+        if buffer_device_address.buffer_device_address != vk::FALSE {
+            result |= DeviceFeatures::BUFFER_DEVICE_ADDRESS;
+        }
+        if sampler_ycbcr_conversion.sampler_ycbcr_conversion != vk::FALSE {
+            result |= DeviceFeatures::SAMPLER_YCBCR_CONVERSION;
+        }
+        if dynamic_rendering.dynamic_rendering != vk::FALSE {
+            result |= DeviceFeatures::DYNAMIC_RENDERING;
+        }
+        if timeline_semaphore.timeline_semaphore != vk::FALSE {
+            result |= DeviceFeatures::TIMELINE_SEMAPHORE;
+        }
+        if synchronization_2.synchronization2 != vk::FALSE {
+            result |= DeviceFeatures::SYNCHRONIZATION_2;
+        }
+        if imageless_framebuffer.imageless_framebuffer != vk::FALSE {
+            result |= DeviceFeatures::IMAGELESS_FRAMEBUFFER;
+        }
+        if extended_dynamic_state.extended_dynamic_state != vk::FALSE {
+            result |= DeviceFeatures::EXTENDED_DYNAMIC_STATE;
+        }
+        if extended_dynamic_state_2.extended_dynamic_state2 != vk::FALSE {
+            result |= DeviceFeatures::EXTENDED_DYNAMIC_STATE_2;
+        }
+
+        let extended_dynamic_state_3_features = [
+            (
+                extended_dynamic_state_3.extended_dynamic_state3_depth_clamp_enable,
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_DEPTH_CLAMP_ENABLE,
+            ),
+            (
+                extended_dynamic_state_3.extended_dynamic_state3_polygon_mode,
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_POLYGON_MODE,
+            ),
+            (
+                extended_dynamic_state_3.extended_dynamic_state3_rasterization_samples,
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_RASTERIZATION_SAMPLES,
+            ),
+            (
+                extended_dynamic_state_3.extended_dynamic_state3_alpha_to_coverage_enable,
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_ALPHA_TO_COVERAGE_ENABLE,
+            ),
+            (
+                extended_dynamic_state_3.extended_dynamic_state3_logic_op_enable,
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_LOGIC_OP_ENABLE,
+            ),
+            (
+                extended_dynamic_state_3.extended_dynamic_state3_color_blend_enable,
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_ENABLE,
+            ),
+            (
+                extended_dynamic_state_3.extended_dynamic_state3_color_blend_equation,
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_BLEND_EQUATION,
+            ),
+            (
+                extended_dynamic_state_3.extended_dynamic_state3_color_write_mask,
+                DeviceFeatures::EXTENDED_DYNAMIC_STATE_3_COLOR_WRITE_MASK,
+            ),
+        ];
+        for (supported, feature) in extended_dynamic_state_3_features {
+            if supported != vk::FALSE {
+                result |= feature;
+            }
+        }
+
+        result
+    }
+
+    /// Returns the set of known device extensions that are supported by this physical device.
+    ///
+    /// Extensions that this crate does not know about are ignored.
+    #[doc(alias = "vkEnumerateDeviceExtensionProperties")]
+    pub fn supported_extensions(&self) -> Result<DeviceExtensions> {
+        let mut list = Vec::new();
 
         let ret = unsafe {
             crate::utility::read_into_vector(&mut list, |count, data| {
-                (self
-                    .instance
-                    .fns()
-                    .get_physical_device_surface_present_modes)(
+                (self.instance.fns().enumerate_device_extension_properties)(
                     self.handle,
-                    surface.handle(),
+                    std::ptr::null(),
                     count,
                     data,
                 )
             })
         };
 
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        let supported = list
+            .iter()
+            .map(|props| unsafe { CStr::from_ptr(props.extension_name.as_ptr()) })
+            .flat_map(|name| DeviceExtensions::all().iter().find(|e| e.name() == name))
+            .collect();
+
+        Ok(supported)
+    }
+
+    /// Returns the memory types and heaps exposed by this physical device.
+    #[doc(alias = "vkGetPhysicalDeviceMemoryProperties2")]
+    pub fn memory_properties(&self) -> MemoryInfo {
+        let mut properties = vk::PhysicalDeviceMemoryProperties2 {
+            memory_properties: vk::PhysicalDeviceMemoryProperties::default(),
+
+            p_next: std::ptr::null_mut(),
+            s_type: vk::StructureType::PHYSICAL_DEVICE_MEMORY_PROPERTIES_2,
+        };
+
+        match self.instance.fns().get_physical_device_memory_properties2 {
+            Some(get_fn) => unsafe { get_fn(self.handle, &mut properties) },
+            None => unsafe {
+                (self.instance.fns().get_physical_device_memory_properties)(
+                    self.handle,
+                    &mut properties.memory_properties,
+                )
+            },
+        }
+
+        MemoryInfo::from_raw(&properties.memory_properties)
+    }
+
+    /// Returns the current budget and usage of each memory heap of this physical device.
+    ///
+    /// The returned list is indexed like [`MemoryInfo::heaps`].
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingDeviceExtension`] if the physical device does
+    /// not support the `VK_EXT_memory_budget` extension, and with
+    /// [`Error::MissingInstanceExtension`] if neither Vulkan 1.1 nor the
+    /// `VK_KHR_get_physical_device_properties2` extension are available.
+    #[doc(alias = "VkPhysicalDeviceMemoryBudgetPropertiesEXT")]
+    pub fn memory_budget(&self) -> Result<Box<[HeapBudget]>> {
+        let get_fn = self
+            .instance
+            .fns()
+            .get_physical_device_memory_properties2
+            .ok_or(Error::MissingInstanceExtension(
+                InstanceExtensions::GET_PHYSICAL_DEVICE_PROPERTIES_2,
+            ))?;
+
+        if !self
+            .supported_extensions()?
+            .contains(DeviceExtensions::MEMORY_BUDGET)
+        {
+            return Err(Error::MissingDeviceExtension(
+                DeviceExtensions::MEMORY_BUDGET,
+            ));
+        }
+
+        let mut budget = vk::PhysicalDeviceMemoryBudgetPropertiesEXT {
+            heap_budget: [0; vk::MAX_MEMORY_HEAPS],
+            heap_usage: [0; vk::MAX_MEMORY_HEAPS],
+
+            p_next: std::ptr::null_mut(),
+            s_type: vk::StructureType::PHYSICAL_DEVICE_MEMORY_BUDGET_PROPERTIES_EXT,
+        };
+
+        let mut properties = vk::PhysicalDeviceMemoryProperties2 {
+            memory_properties: vk::PhysicalDeviceMemoryProperties::default(),
+
+            p_next: &mut budget as *mut _ as *mut std::ffi::c_void,
+            s_type: vk::StructureType::PHYSICAL_DEVICE_MEMORY_PROPERTIES_2,
+        };
+
+        unsafe { get_fn(self.handle, &mut properties) };
+
+        let heap_count = properties.memory_properties.memory_heap_count as usize;
+        let budgets = budget.heap_budget[..heap_count]
+            .iter()
+            .zip(&budget.heap_usage[..heap_count])
+            .map(|(&budget, &usage)| HeapBudget { budget, usage })
+            .collect();
+
+        Ok(budgets)
+    }
+
+    /// Returns the list of present modes that the provided surface supports with this physical
+    /// device.
+    ///
+    /// Present modes that this crate does not know about are skipped.
+    pub fn surface_present_modes(&self, surface: &Surface) -> Result<PresentModes> {
+        let get_fn = self
+            .instance
+            .fns()
+            .get_physical_device_surface_present_modes
+            .ok_or(Error::MissingInstanceExtension(InstanceExtensions::SURFACE))?;
+
+        let mut list = SmallVec::<[vk::PresentModeKHR; 8]>::default();
+
+        let ret = unsafe {
+            crate::utility::read_into_vector(&mut list, |count, data| {
+                get_fn(self.handle, surface.handle(), count, data)
+            })
+        };
+
         if ret != vk::Result::SUCCESS {
             Err(ret.into())
         } else {
             let modes = list
                 .iter()
                 .copied()
-                .map(|x| match x {
-                    vk::PresentModeKHR::IMMEDIATE => PresentModes::IMMEDIATE,
-                    vk::PresentModeKHR::MAILBOX => PresentModes::MAILBOX,
-                    vk::PresentModeKHR::FIFO => PresentModes::FIFO,
-                    vk::PresentModeKHR::FIFO_RELAXED => PresentModes::FIFO_RELAXED,
-                    unknown => unreachable!("unknown present mode: {}", unknown.as_raw()),
+                .filter_map(|x| match x {
+                    vk::PresentModeKHR::IMMEDIATE => Some(PresentModes::IMMEDIATE),
+                    vk::PresentModeKHR::MAILBOX => Some(PresentModes::MAILBOX),
+                    vk::PresentModeKHR::FIFO => Some(PresentModes::FIFO),
+                    vk::PresentModeKHR::FIFO_RELAXED => Some(PresentModes::FIFO_RELAXED),
+                    _ => None,
                 })
                 .collect();
 
@@ -104,6 +378,8 @@ impl PhysicalDevice {
     }
 
     /// Returns an iterator over the list of supported surface formats for the provided surface.
+    ///
+    /// Formats and color spaces that this crate does not know about are skipped.
     #[doc(alias = "vkGetPhysicalDeviceSurfaceFormats")]
     pub fn surface_supported_formats(
         &self,
@@ -111,27 +387,28 @@ impl PhysicalDevice {
     ) -> Result<impl Iterator<Item = (Format, ColorSpace)>> {
         assert!(Arc::ptr_eq(self.instance(), surface.instance()));
 
+        let get_fn = self
+            .instance
+            .fns()
+            .get_physical_device_surface_formats
+            .ok_or(Error::MissingInstanceExtension(InstanceExtensions::SURFACE))?;
+
         let mut vec = Vec::new();
 
         let ret = unsafe {
             crate::utility::read_into_vector(&mut vec, |count, data| {
-                (self.instance.fns().get_physical_device_surface_formats)(
-                    self.handle,
-                    surface.handle(),
-                    count,
-                    data,
-                )
+                get_fn(self.handle, surface.handle(), count, data)
             })
         };
 
         if ret != vk::Result::SUCCESS {
             Err(ret.into())
         } else {
-            let iter = vec.into_iter().map(|surface_format| {
-                (
-                    Format::from_raw(surface_format.format),
-                    ColorSpace::from_raw(surface_format.color_space),
-                )
+            let iter = vec.into_iter().filter_map(|surface_format| {
+                Some((
+                    Format::from_raw(surface_format.format)?,
+                    ColorSpace::from_raw(surface_format.color_space)?,
+                ))
             });
 
             Ok(iter)
@@ -142,15 +419,15 @@ impl PhysicalDevice {
     pub fn surface_capabilities(&self, surface: &Surface) -> Result<SurfaceCaps> {
         assert!(Arc::ptr_eq(self.instance(), surface.instance()));
 
+        let get_fn = self
+            .instance
+            .fns()
+            .get_physical_device_surface_capabilities
+            .ok_or(Error::MissingInstanceExtension(InstanceExtensions::SURFACE))?;
+
         let mut caps = MaybeUninit::<vk::SurfaceCapabilitiesKHR>::uninit();
 
-        let ret = unsafe {
-            (self.instance.fns().get_physical_device_surface_capabilities)(
-                self.handle,
-                surface.handle(),
-                caps.as_mut_ptr(),
-            )
-        };
+        let ret = unsafe { get_fn(self.handle, surface.handle(), caps.as_mut_ptr()) };
 
         if ret != vk::Result::SUCCESS {
             Err(ret.into())
@@ -170,28 +447,28 @@ impl PhysicalDevice {
                 min_image_extent: [caps.min_image_extent.width, caps.min_image_extent.height],
                 max_image_extent: [caps.max_image_extent.width, caps.max_image_extent.height],
                 max_image_array_layers: caps.max_image_array_layers,
-                supported_transforms: SurfaceTransforms::from_bits_retain(
+                supported_transforms: SurfaceTransforms::from_bits_truncate(
                     caps.supported_transforms.as_raw(),
                 ),
                 current_transform: match caps.current_transform {
-                    vk::SurfaceTransformFlagsKHR::IDENTITY => SurfaceTransform::Identity,
-                    vk::SurfaceTransformFlagsKHR::ROTATE_90 => SurfaceTransform::Rotate90,
-                    vk::SurfaceTransformFlagsKHR::ROTATE_180 => SurfaceTransform::Rotate180,
-                    vk::SurfaceTransformFlagsKHR::ROTATE_270 => SurfaceTransform::Rotate270,
+                    vk::SurfaceTransformFlagsKHR::IDENTITY => Some(SurfaceTransform::Identity),
+                    vk::SurfaceTransformFlagsKHR::ROTATE_90 => Some(SurfaceTransform::Rotate90),
+                    vk::SurfaceTransformFlagsKHR::ROTATE_180 => Some(SurfaceTransform::Rotate180),
+                    vk::SurfaceTransformFlagsKHR::ROTATE_270 => Some(SurfaceTransform::Rotate270),
                     vk::SurfaceTransformFlagsKHR::HORIZONTAL_MIRROR => {
-                        SurfaceTransform::HorizontalMirror
+                        Some(SurfaceTransform::HorizontalMirror)
                     }
                     vk::SurfaceTransformFlagsKHR::HORIZONTAL_MIRROR_ROTATE_90 => {
-                        SurfaceTransform::HorizontalMirrorRotate90
+                        Some(SurfaceTransform::HorizontalMirrorRotate90)
                     }
                     vk::SurfaceTransformFlagsKHR::HORIZONTAL_MIRROR_ROTATE_180 => {
-                        SurfaceTransform::HorizontalMirrorRotate180
+                        Some(SurfaceTransform::HorizontalMirrorRotate180)
                     }
                     vk::SurfaceTransformFlagsKHR::HORIZONTAL_MIRROR_ROTATE_270 => {
-                        SurfaceTransform::HorizontalMirrorRotate270
+                        Some(SurfaceTransform::HorizontalMirrorRotate270)
                     }
-                    vk::SurfaceTransformFlagsKHR::INHERIT => SurfaceTransform::Inherit,
-                    unknown => unreachable!("unknown surface transform: {}", unknown.as_raw()),
+                    vk::SurfaceTransformFlagsKHR::INHERIT => Some(SurfaceTransform::Inherit),
+                    _ => None,
                 },
                 supported_composite_alpha: CompositeAlphas::from_bits_retain(
                     caps.supported_composite_alpha.as_raw(),
@@ -201,6 +478,152 @@ impl PhysicalDevice {
         }
     }
 
+    /// Returns the list of displays that are attached to this physical device.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the `VK_KHR_display`
+    /// extension is not enabled.
+    #[doc(alias = "vkGetPhysicalDeviceDisplayPropertiesKHR")]
+    pub fn display_properties(&self) -> Result<impl Iterator<Item = DisplayProperties>> {
+        let get_fn = self
+            .instance
+            .fns()
+            .get_physical_device_display_properties
+            .ok_or(Error::MissingInstanceExtension(InstanceExtensions::DISPLAY))?;
+
+        let mut list = SmallVec::<[vk::DisplayPropertiesKHR; 4]>::new();
+
+        let ret = unsafe {
+            crate::utility::read_into_vector(&mut list, |count, data| {
+                get_fn(self.handle, count, data)
+            })
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        // The display names are owned by the implementation and remain valid for as long as the
+        // instance is alive, which the returned iterator ensures.
+        let this = self.clone();
+        let iter = list
+            .into_iter()
+            .map(move |props| unsafe { DisplayProperties::from_raw(this.clone(), &props) });
+
+        Ok(iter)
+    }
+
+    /// Returns the list of display planes supported by this physical device.
+    ///
+    /// The index of a plane in the returned list is the index to use when referring to that
+    /// plane in other functions.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the `VK_KHR_display`
+    /// extension is not enabled.
+    #[doc(alias = "vkGetPhysicalDeviceDisplayPlanePropertiesKHR")]
+    pub fn display_plane_properties(&self) -> Result<impl Iterator<Item = DisplayPlaneProperties>> {
+        let get_fn = self
+            .instance
+            .fns()
+            .get_physical_device_display_plane_properties
+            .ok_or(Error::MissingInstanceExtension(InstanceExtensions::DISPLAY))?;
+
+        let mut list = SmallVec::<[vk::DisplayPlanePropertiesKHR; 4]>::new();
+
+        let ret = unsafe {
+            crate::utility::read_into_vector(&mut list, |count, data| {
+                get_fn(self.handle, count, data)
+            })
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        let this = self.clone();
+        let iter = list.into_iter().map(move |props| DisplayPlaneProperties {
+            current_display: if props.current_display == vk::DisplayKHR::null() {
+                None
+            } else {
+                Some(unsafe { Display::from_handle(this.clone(), props.current_display) })
+            },
+            current_stack_index: props.current_stack_index,
+        });
+
+        Ok(iter)
+    }
+
+    /// Returns the list of displays that the plane at index `plane_index` can be used with.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the `VK_KHR_display`
+    /// extension is not enabled.
+    #[doc(alias = "vkGetDisplayPlaneSupportedDisplaysKHR")]
+    pub fn display_plane_supported_displays(
+        &self,
+        plane_index: u32,
+    ) -> Result<impl Iterator<Item = Display>> {
+        let get_fn = self
+            .instance
+            .fns()
+            .get_display_plane_supported_displays
+            .ok_or(Error::MissingInstanceExtension(InstanceExtensions::DISPLAY))?;
+
+        let mut list = SmallVec::<[vk::DisplayKHR; 4]>::new();
+
+        let ret = unsafe {
+            crate::utility::read_into_vector(&mut list, |count, data| {
+                get_fn(self.handle, plane_index, count, data)
+            })
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        let this = self.clone();
+        let iter = list
+            .into_iter()
+            .map(move |handle| unsafe { Display::from_handle(this.clone(), handle) });
+
+        Ok(iter)
+    }
+
+    /// Returns the display that corresponds to the DRM connector `connector_id` of the provided
+    /// DRM file descriptor.
+    ///
+    /// The returned display must be acquired with [`Display::acquire_drm`] before it can be
+    /// presented to.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the
+    /// `VK_EXT_acquire_drm_display` extension is not enabled.
+    #[doc(alias = "vkGetDrmDisplayEXT")]
+    pub fn drm_display(&self, drm_fd: i32, connector_id: u32) -> Result<Display> {
+        let get_fn = self
+            .instance
+            .fns()
+            .get_drm_display
+            .ok_or(Error::MissingInstanceExtension(
+                InstanceExtensions::ACQUIRE_DRM_DISPLAY,
+            ))?;
+
+        let mut handle = vk::DisplayKHR::null();
+
+        let ret = unsafe { get_fn(self.handle, drm_fd, connector_id, &mut handle) };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(unsafe { Display::from_handle(self.clone(), handle) })
+    }
+
     /// Returns the instance that owns this physical device.
     #[inline(always)]
     pub fn instance(&self) -> &Arc<Instance> {
@@ -229,6 +652,97 @@ pub struct PhysicalDeviceInfo {
     pub device_id: u32,
     /// The type of the device.
     pub device_type: DeviceType,
+    /// The limits of the device.
+    pub limits: PhysicalDeviceLimits,
+}
+
+/// The implementation-dependent limits of a physical device.
+#[derive(Debug, Clone)]
+#[doc(alias = "VkPhysicalDeviceLimits")]
+pub struct PhysicalDeviceLimits {
+    /// The maximum number of device memory allocations that can exist simultaneously.
+    pub max_memory_allocation_count: u32,
+    /// The granularity, in bytes, at which buffers and optimal images bound to the same memory
+    /// allocation must be placed to avoid aliasing.
+    pub buffer_image_granularity: u64,
+    /// The alignment, in bytes, of the ranges passed when flushing or invalidating
+    /// non-coherent mapped memory.
+    pub non_coherent_atom_size: u64,
+    /// The maximum degree of anisotropy that samplers can use.
+    pub max_sampler_anisotropy: f32,
+    /// The maximum number of vertex buffers that a graphics pipeline can read from.
+    pub max_vertex_input_bindings: u32,
+    /// The maximum number of vertex attributes that a graphics pipeline can declare.
+    pub max_vertex_input_attributes: u32,
+    /// The maximum offset, in bytes, of a vertex attribute within its vertex.
+    pub max_vertex_input_attribute_offset: u32,
+    /// The maximum stride, in bytes, between the vertices of a vertex buffer.
+    pub max_vertex_input_binding_stride: u32,
+    /// The maximum number of control points in a tessellation patch.
+    pub max_tessellation_patch_size: u32,
+    /// The maximum number of viewports and scissor rectangles of a graphics pipeline.
+    pub max_viewports: u32,
+    /// The maximum number of color attachments that a subpass can render to.
+    pub max_color_attachments: u32,
+    /// The minimum and maximum supported widths of lines.
+    pub line_width_range: [f32; 2],
+}
+
+impl PhysicalDeviceLimits {
+    /// Converts the provided raw Vulkan limits into a [`PhysicalDeviceLimits`].
+    fn from_raw(raw: &vk::PhysicalDeviceLimits) -> Self {
+        Self {
+            max_memory_allocation_count: raw.max_memory_allocation_count,
+            buffer_image_granularity: raw.buffer_image_granularity,
+            non_coherent_atom_size: raw.non_coherent_atom_size,
+            max_sampler_anisotropy: raw.max_sampler_anisotropy,
+            max_vertex_input_bindings: raw.max_vertex_input_bindings,
+            max_vertex_input_attributes: raw.max_vertex_input_attributes,
+            max_vertex_input_attribute_offset: raw.max_vertex_input_attribute_offset,
+            max_vertex_input_binding_stride: raw.max_vertex_input_binding_stride,
+            max_tessellation_patch_size: raw.max_tessellation_patch_size,
+            max_viewports: raw.max_viewports,
+            max_color_attachments: raw.max_color_attachments,
+            line_width_range: raw.line_width_range,
+        }
+    }
+}
+
+/// One of the [`PhysicalDeviceLimits`] that a request can exceed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceLimit {
+    /// [`PhysicalDeviceLimits::max_vertex_input_bindings`].
+    MaxVertexInputBindings,
+    /// [`PhysicalDeviceLimits::max_vertex_input_attributes`].
+    MaxVertexInputAttributes,
+    /// [`PhysicalDeviceLimits::max_vertex_input_attribute_offset`].
+    MaxVertexInputAttributeOffset,
+    /// [`PhysicalDeviceLimits::max_vertex_input_binding_stride`].
+    MaxVertexInputBindingStride,
+    /// [`PhysicalDeviceLimits::max_tessellation_patch_size`].
+    MaxTessellationPatchSize,
+    /// [`PhysicalDeviceLimits::max_viewports`].
+    MaxViewports,
+    /// [`PhysicalDeviceLimits::max_color_attachments`].
+    MaxColorAttachments,
+    /// [`PhysicalDeviceLimits::line_width_range`].
+    LineWidthRange,
+}
+
+impl DeviceLimit {
+    /// Returns the name of the limit in the Vulkan specification.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MaxVertexInputBindings => "maxVertexInputBindings",
+            Self::MaxVertexInputAttributes => "maxVertexInputAttributes",
+            Self::MaxVertexInputAttributeOffset => "maxVertexInputAttributeOffset",
+            Self::MaxVertexInputBindingStride => "maxVertexInputBindingStride",
+            Self::MaxTessellationPatchSize => "maxTessellationPatchSize",
+            Self::MaxViewports => "maxViewports",
+            Self::MaxColorAttachments => "maxColorAttachments",
+            Self::LineWidthRange => "lineWidthRange",
+        }
+    }
 }
 
 /// The type of the device.
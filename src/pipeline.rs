@@ -0,0 +1,213 @@
+use ash::vk;
+use bitflags::bitflags;
+use smallvec::SmallVec;
+
+use crate::ShaderReflection;
+
+/// The kind of pipeline that a command applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum PipelineBindPoint {
+    Graphics = vk::PipelineBindPoint::GRAPHICS.as_raw(),
+    Compute = vk::PipelineBindPoint::COMPUTE.as_raw(),
+}
+
+bitflags! {
+    /// Flags specifying a collection of [`ShaderStage`]s.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct ShaderStages: u32 {
+        const VERTEX = vk::ShaderStageFlags::VERTEX.as_raw();
+        const TESSELLATION_CONTROL = vk::ShaderStageFlags::TESSELLATION_CONTROL.as_raw();
+        const TESSELLATION_EVALUATION = vk::ShaderStageFlags::TESSELLATION_EVALUATION.as_raw();
+        const GEOMETRY = vk::ShaderStageFlags::GEOMETRY.as_raw();
+        const FRAGMENT = vk::ShaderStageFlags::FRAGMENT.as_raw();
+        const COMPUTE = vk::ShaderStageFlags::COMPUTE.as_raw();
+        const ALL_GRAPHICS = vk::ShaderStageFlags::ALL_GRAPHICS.as_raw();
+    }
+}
+
+impl From<ShaderStage> for ShaderStages {
+    fn from(value: ShaderStage) -> Self {
+        match value {
+            ShaderStage::Vertex => Self::VERTEX,
+            ShaderStage::TessellationControl => Self::TESSELLATION_CONTROL,
+            ShaderStage::TessellationEvaluation => Self::TESSELLATION_EVALUATION,
+            ShaderStage::Geometry => Self::GEOMETRY,
+            ShaderStage::Fragment => Self::FRAGMENT,
+            ShaderStage::Compute => Self::COMPUTE,
+        }
+    }
+}
+
+/// A programmable stage of a pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ShaderStage {
+    Vertex = vk::ShaderStageFlags::VERTEX.as_raw(),
+    TessellationControl = vk::ShaderStageFlags::TESSELLATION_CONTROL.as_raw(),
+    TessellationEvaluation = vk::ShaderStageFlags::TESSELLATION_EVALUATION.as_raw(),
+    Geometry = vk::ShaderStageFlags::GEOMETRY.as_raw(),
+    Fragment = vk::ShaderStageFlags::FRAGMENT.as_raw(),
+    Compute = vk::ShaderStageFlags::COMPUTE.as_raw(),
+}
+
+/// The type of the indices stored in an index buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum IndexType {
+    U16 = vk::IndexType::UINT16.as_raw(),
+    U32 = vk::IndexType::UINT32.as_raw(),
+}
+
+/// A viewport, which maps normalized device coordinates to framebuffer coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[doc(alias = "VkViewport")]
+pub struct Viewport {
+    /// The position of the upper-left corner of the viewport.
+    pub position: [f32; 2],
+    /// The size of the viewport.
+    pub size: [f32; 2],
+    /// The depth range of the viewport.
+    pub depth_range: [f32; 2],
+}
+
+impl Viewport {
+    /// Converts this viewport into its raw Vulkan representation.
+    pub(crate) fn to_raw(self) -> vk::Viewport {
+        vk::Viewport {
+            x: self.position[0],
+            y: self.position[1],
+            width: self.size[0],
+            height: self.size[1],
+            min_depth: self.depth_range[0],
+            max_depth: self.depth_range[1],
+        }
+    }
+}
+
+/// A two-dimensional rectangle, in framebuffer coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkRect2D")]
+pub struct Rect {
+    /// The position of the upper-left corner of the rectangle.
+    pub offset: [i32; 2],
+    /// The size of the rectangle.
+    pub extent: [u32; 2],
+}
+
+impl Rect {
+    /// Converts this rectangle into its raw Vulkan representation.
+    pub(crate) fn to_raw(self) -> vk::Rect2D {
+        vk::Rect2D {
+            offset: vk::Offset2D {
+                x: self.offset[0],
+                y: self.offset[1],
+            },
+            extent: vk::Extent2D {
+                width: self.extent[0],
+                height: self.extent[1],
+            },
+        }
+    }
+}
+
+/// The value given to a specialization constant when a pipeline is created.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpecializationValue {
+    Bool(bool),
+    I32(i32),
+    U32(u32),
+    F32(f32),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+}
+
+impl SpecializationValue {
+    /// Returns the size of this value, in bytes.
+    pub fn size(self) -> u32 {
+        match self {
+            Self::Bool(_) | Self::I32(_) | Self::U32(_) | Self::F32(_) => 4,
+            Self::I64(_) | Self::U64(_) | Self::F64(_) => 8,
+        }
+    }
+
+    /// Returns this value as an unsigned integer, if it is an integer or a boolean.
+    pub(crate) fn as_u64(self) -> Option<u64> {
+        match self {
+            Self::Bool(value) => Some(value as u64),
+            Self::I32(value) => Some(value as u64),
+            Self::U32(value) => Some(value as u64),
+            Self::I64(value) => Some(value as u64),
+            Self::U64(value) => Some(value),
+            Self::F32(_) | Self::F64(_) => None,
+        }
+    }
+
+    /// Appends the bytes of this value to `data`.
+    fn write(self, data: &mut SmallVec<[u8; 64]>) {
+        match self {
+            Self::Bool(value) => data.extend_from_slice(&vk::Bool32::from(value).to_ne_bytes()),
+            Self::I32(value) => data.extend_from_slice(&value.to_ne_bytes()),
+            Self::U32(value) => data.extend_from_slice(&value.to_ne_bytes()),
+            Self::F32(value) => data.extend_from_slice(&value.to_ne_bytes()),
+            Self::I64(value) => data.extend_from_slice(&value.to_ne_bytes()),
+            Self::U64(value) => data.extend_from_slice(&value.to_ne_bytes()),
+            Self::F64(value) => data.extend_from_slice(&value.to_ne_bytes()),
+        }
+    }
+}
+
+/// The values of the specialization constants of a shader stage, laid out for the driver.
+pub(crate) struct Specialization {
+    /// The id, offset and size of each constant.
+    entries: SmallVec<[vk::SpecializationMapEntry; 8]>,
+    /// The values of the constants.
+    data: SmallVec<[u8; 64]>,
+}
+
+impl Specialization {
+    /// Lays out the provided `(constant_id, value)` pairs.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if a constant declared by the shader is given a value of a
+    /// different size than its type.
+    pub fn new(constants: &[(u32, SpecializationValue)], reflection: &ShaderReflection) -> Self {
+        let mut entries = SmallVec::new();
+        let mut data = SmallVec::new();
+
+        for &(constant_id, value) in constants {
+            if let Some(declared) = reflection
+                .spec_constants()
+                .iter()
+                .find(|constant| constant.id == constant_id)
+            {
+                assert_eq!(
+                    declared.scalar_type.size(),
+                    value.size(),
+                    "specialization constant {constant_id} is given a value of the wrong size"
+                );
+            }
+
+            entries.push(vk::SpecializationMapEntry {
+                constant_id,
+                offset: data.len() as u32,
+                size: value.size() as usize,
+            });
+            value.write(&mut data);
+        }
+
+        Self { entries, data }
+    }
+
+    /// Returns the raw Vulkan representation of the constants, which borrows from `self`.
+    pub fn to_raw(&self) -> vk::SpecializationInfo {
+        vk::SpecializationInfo {
+            map_entry_count: self.entries.len() as u32,
+            p_map_entries: self.entries.as_ptr(),
+            data_size: self.data.len(),
+            p_data: self.data.as_ptr().cast(),
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use std::ffi::c_void;
+use std::sync::Arc;
+
+use ash::vk;
+use smallvec::SmallVec;
+
+use crate::{
+    CommandBuffer, Device, DeviceExtensions, Error, Executable, Fence, PipelineStages, Result,
+    Semaphore, Swapchain, TimelineSemaphore,
+};
+
+/// A batch of command buffers submitted to a [`Queue`].
+#[derive(Clone, Copy, Default)]
+#[doc(alias = "VkSubmitInfo")]
+pub struct SubmitDesc<'a> {
+    /// The semaphores to wait on before executing the command buffers, along with the stages
+    /// at which each wait occurs.
+    pub wait_semaphores: &'a [(&'a Semaphore, PipelineStages)],
+    /// The timeline semaphores to wait on before executing the command buffers, along with
+    /// the value to wait for and the stages at which each wait occurs.
+    pub wait_timelines: &'a [(&'a TimelineSemaphore, u64, PipelineStages)],
+    /// The command buffers to execute.
+    pub command_buffers: &'a [&'a CommandBuffer<Executable>],
+    /// The semaphores to signal once the command buffers have finished executing.
+    pub signal_semaphores: &'a [&'a Semaphore],
+    /// The timeline semaphores to signal once the command buffers have finished executing,
+    /// along with the value to signal.
+    pub signal_timelines: &'a [(&'a TimelineSemaphore, u64)],
+}
+
+impl SubmitDesc<'_> {
+    /// Returns the number of semaphores waited on by this batch.
+    fn wait_count(&self) -> usize {
+        self.wait_semaphores.len() + self.wait_timelines.len()
+    }
+
+    /// Returns the number of semaphores signaled by this batch.
+    fn signal_count(&self) -> usize {
+        self.signal_semaphores.len() + self.signal_timelines.len()
+    }
+}
+
+/// A queue of a [`Device`], to which work is submitted.
+///
+/// Only one [`Queue`] instance may exist at a time for a given queue of the device, which
+/// guarantees the external synchronization that Vulkan requires for it.
+pub struct Queue {
+    /// The device that owns this queue.
+    device: Arc<Device>,
+
+    /// The handle to the queue.
+    handle: vk::Queue,
+    /// The family of the queue.
+    family: u32,
+    /// The index of the queue within its family.
+    index: u32,
+}
+
+impl Queue {
+    /// Retrieves the queue at `index` within queue family `family`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the device was not created with this queue, or if a [`Queue`]
+    /// instance already exists for it.
+    #[doc(alias = "vkGetDeviceQueue")]
+    pub fn new(device: Arc<Device>, family: u32, index: u32) -> Self {
+        assert!(
+            index < device.queue_count(family),
+            "the device was not created with this queue"
+        );
+
+        {
+            let mut taken = device.taken_queues.lock().unwrap();
+            assert!(
+                !taken.contains(&(family, index)),
+                "the queue has already been retrieved"
+            );
+            taken.push((family, index));
+        }
+
+        let mut handle = vk::Queue::null();
+        unsafe { (device.fns().get_device_queue)(device.handle(), family, index, &mut handle) };
+
+        Self {
+            device,
+            handle,
+            family,
+            index,
+        }
+    }
+
+    /// Submits batches of command buffers to this queue.
+    ///
+    /// The provided fence, which must be unsignaled, is signaled once all the batches have
+    /// finished executing.
+    ///
+    /// # Safety
+    ///
+    /// The command buffers must have been allocated for the family of this queue and must not
+    /// be pending execution unless they were recorded with
+    /// [`CommandBufferUsages::SIMULTANEOUS_USE`](crate::CommandBufferUsages::SIMULTANEOUS_USE).
+    /// Every binary semaphore waited on must have a pending signal operation, every timeline
+    /// value signaled must be greater than the values previously signaled, and the command
+    /// buffers and semaphores must outlive the execution of the batches. The fence, if
+    /// provided, must be unsignaled, and must outlive the execution of the batches without
+    /// being reset until it is signaled.
+    #[doc(alias = "vkQueueSubmit")]
+    pub unsafe fn submit(
+        &mut self,
+        submits: &[SubmitDesc],
+        fence: Option<&mut Fence>,
+    ) -> Result<()> {
+        let mut wait_semaphores = SmallVec::<[_; 4]>::new();
+        let mut wait_values = SmallVec::<[_; 4]>::new();
+        let mut wait_stages = SmallVec::<[_; 4]>::new();
+        let mut command_buffers = SmallVec::<[_; 8]>::new();
+        let mut signal_semaphores = SmallVec::<[_; 4]>::new();
+        let mut signal_values = SmallVec::<[_; 4]>::new();
+
+        // Binary and timeline semaphores share the same arrays. The values of the binary
+        // semaphores are ignored.
+        for submit in submits {
+            for (semaphore, stages) in submit.wait_semaphores {
+                wait_semaphores.push(semaphore.handle());
+                wait_values.push(0);
+                wait_stages.push(vk::PipelineStageFlags::from_raw(stages.bits()));
+            }
+            for (semaphore, value, stages) in submit.wait_timelines {
+                wait_semaphores.push(semaphore.handle());
+                wait_values.push(*value);
+                wait_stages.push(vk::PipelineStageFlags::from_raw(stages.bits()));
+            }
+            command_buffers.extend(submit.command_buffers.iter().map(|cb| cb.handle()));
+            for semaphore in submit.signal_semaphores {
+                signal_semaphores.push(semaphore.handle());
+                signal_values.push(0);
+            }
+            for (semaphore, value) in submit.signal_timelines {
+                signal_semaphores.push(semaphore.handle());
+                signal_values.push(*value);
+            }
+        }
+
+        // The arrays of all the batches are stored contiguously, so each batch points into
+        // them at the offsets accumulated so far.
+        let mut offsets = [0usize; 3];
+        let timeline_infos: SmallVec<[_; 2]> = submits
+            .iter()
+            .map(|submit| {
+                let info = vk::TimelineSemaphoreSubmitInfo {
+                    wait_semaphore_value_count: submit.wait_count() as u32,
+                    p_wait_semaphore_values: wait_values.as_ptr().add(offsets[0]),
+                    signal_semaphore_value_count: submit.signal_count() as u32,
+                    p_signal_semaphore_values: signal_values.as_ptr().add(offsets[2]),
+
+                    p_next: std::ptr::null(),
+                    s_type: vk::StructureType::TIMELINE_SEMAPHORE_SUBMIT_INFO,
+                };
+
+                offsets[0] += submit.wait_count();
+                offsets[2] += submit.signal_count();
+
+                info
+            })
+            .collect();
+
+        let mut offsets = [0usize; 3];
+        let submit_infos: SmallVec<[_; 2]> = submits
+            .iter()
+            .zip(&timeline_infos)
+            .map(|(submit, timeline_info)| {
+                let uses_timelines =
+                    !submit.wait_timelines.is_empty() || !submit.signal_timelines.is_empty();
+
+                let info = vk::SubmitInfo {
+                    wait_semaphore_count: submit.wait_count() as u32,
+                    p_wait_semaphores: wait_semaphores.as_ptr().add(offsets[0]),
+                    p_wait_dst_stage_mask: wait_stages.as_ptr().add(offsets[0]),
+                    command_buffer_count: submit.command_buffers.len() as u32,
+                    p_command_buffers: command_buffers.as_ptr().add(offsets[1]),
+                    signal_semaphore_count: submit.signal_count() as u32,
+                    p_signal_semaphores: signal_semaphores.as_ptr().add(offsets[2]),
+
+                    p_next: if uses_timelines {
+                        timeline_info as *const _ as *const c_void
+                    } else {
+                        std::ptr::null()
+                    },
+                    s_type: vk::StructureType::SUBMIT_INFO,
+                };
+
+                offsets[0] += submit.wait_count();
+                offsets[1] += submit.command_buffers.len();
+                offsets[2] += submit.signal_count();
+
+                info
+            })
+            .collect();
+
+        let ret = (self.device.fns().queue_submit)(
+            self.handle,
+            submit_infos.len() as u32,
+            submit_infos.as_ptr(),
+            fence.map_or(vk::Fence::null(), |fence| fence.handle()),
+        );
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+
+    /// Queues an image of a swapchain for presentation, once the provided semaphores are
+    /// signaled.
+    ///
+    /// Returns whether the swapchain no longer matches the surface exactly, in which case it
+    /// should be re-created.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingDeviceExtension`] if the device does not have
+    /// the `VK_KHR_swapchain` extension enabled.
+    ///
+    /// # Safety
+    ///
+    /// The image must have been acquired and be in the
+    /// [`ImageLayout::PresentSrc`](crate::ImageLayout::PresentSrc) layout once the semaphores
+    /// are signaled, and every semaphore must have a pending signal operation.
+    #[doc(alias = "vkQueuePresentKHR")]
+    pub unsafe fn present(
+        &mut self,
+        swapchain: &mut Swapchain,
+        image_index: u32,
+        wait_semaphores: &[&Semaphore],
+    ) -> Result<bool> {
+        let present_fn = self
+            .device
+            .fns()
+            .queue_present
+            .ok_or(Error::MissingDeviceExtension(DeviceExtensions::SWAPCHAIN))?;
+
+        let wait_semaphores: SmallVec<[_; 4]> =
+            wait_semaphores.iter().map(|s| s.handle()).collect();
+        let swapchain = swapchain.handle();
+
+        let present_info = vk::PresentInfoKHR {
+            wait_semaphore_count: wait_semaphores.len() as u32,
+            p_wait_semaphores: wait_semaphores.as_ptr(),
+            swapchain_count: 1,
+            p_swapchains: &swapchain,
+            p_image_indices: &image_index,
+            p_results: std::ptr::null_mut(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::PRESENT_INFO_KHR,
+        };
+
+        let ret = present_fn(self.handle, &present_info);
+
+        match ret {
+            vk::Result::SUCCESS => Ok(false),
+            vk::Result::SUBOPTIMAL_KHR => Ok(true),
+            _ => Err(ret.into()),
+        }
+    }
+
+    /// Waits for all the work submitted to this queue to complete.
+    #[doc(alias = "vkQueueWaitIdle")]
+    pub fn wait_idle(&mut self) -> Result<()> {
+        let ret = unsafe { (self.device.fns().queue_wait_idle)(self.handle) };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the family of this queue.
+    #[inline(always)]
+    pub fn family(&self) -> u32 {
+        self.family
+    }
+
+    /// Returns the index of this queue within its family.
+    #[inline(always)]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the device that owns this queue.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this queue.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::Queue {
+        self.handle
+    }
+}
+
+impl Drop for Queue {
+    fn drop(&mut self) {
+        let mut taken = self.device.taken_queues.lock().unwrap();
+        taken.retain(|&queue| queue != (self.family, self.index));
+    }
+}
@@ -0,0 +1,1254 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::spirv::{instructions, native_words, OP_ENTRY_POINT};
+use crate::{
+    DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorType, Device, ExecutionModel,
+    Format, PipelineLayout, PushConstantRange, Result, ShaderStage, ShaderStages, SpirvError,
+};
+
+const OP_NAME: u16 = 5;
+const OP_EXECUTION_MODE: u16 = 16;
+const OP_TYPE_BOOL: u16 = 20;
+const OP_TYPE_INT: u16 = 21;
+const OP_TYPE_FLOAT: u16 = 22;
+const OP_TYPE_VECTOR: u16 = 23;
+const OP_TYPE_MATRIX: u16 = 24;
+const OP_TYPE_IMAGE: u16 = 25;
+const OP_TYPE_SAMPLER: u16 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u16 = 27;
+const OP_TYPE_ARRAY: u16 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u16 = 29;
+const OP_TYPE_STRUCT: u16 = 30;
+const OP_TYPE_POINTER: u16 = 32;
+const OP_CONSTANT_TRUE: u16 = 41;
+const OP_CONSTANT_FALSE: u16 = 42;
+const OP_CONSTANT: u16 = 43;
+const OP_CONSTANT_COMPOSITE: u16 = 44;
+const OP_SPEC_CONSTANT_TRUE: u16 = 48;
+const OP_SPEC_CONSTANT_FALSE: u16 = 49;
+const OP_SPEC_CONSTANT: u16 = 50;
+const OP_SPEC_CONSTANT_COMPOSITE: u16 = 51;
+const OP_VARIABLE: u16 = 59;
+const OP_DECORATE: u16 = 71;
+const OP_MEMBER_DECORATE: u16 = 72;
+
+const DECORATION_SPEC_ID: u32 = 1;
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_ARRAY_STRIDE: u32 = 6;
+const DECORATION_MATRIX_STRIDE: u32 = 7;
+const DECORATION_BUILT_IN: u32 = 11;
+const DECORATION_LOCATION: u32 = 30;
+const DECORATION_COMPONENT: u32 = 31;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+const DECORATION_OFFSET: u32 = 35;
+
+const BUILT_IN_WORKGROUP_SIZE: u32 = 25;
+
+const EXECUTION_MODE_LOCAL_SIZE: u32 = 17;
+const EXECUTION_MODE_LOCAL_SIZE_ID: u32 = 38;
+
+const STORAGE_CLASS_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_CLASS_INPUT: u32 = 1;
+const STORAGE_CLASS_UNIFORM: u32 = 2;
+const STORAGE_CLASS_OUTPUT: u32 = 3;
+const STORAGE_CLASS_PUSH_CONSTANT: u32 = 9;
+const STORAGE_CLASS_STORAGE_BUFFER: u32 = 12;
+
+const DIM_BUFFER: u32 = 5;
+const DIM_SUBPASS_DATA: u32 = 6;
+
+/// The maximum number of nested types that reflection follows, which keeps malformed modules
+/// from recursing without bound.
+const MAX_TYPE_DEPTH: u32 = 64;
+
+/// The first SPIR-V version whose entry points list all the global variables they use, and
+/// not only their inputs and outputs.
+const VERSION_1_4: u32 = 0x0001_0400;
+
+/// The type of a scalar value in a shader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScalarType {
+    /// A boolean, which is represented as a 32-bit `VkBool32` outside of shaders.
+    Bool,
+    /// An integer of the provided width, in bits.
+    Int { width: u32, signed: bool },
+    /// A floating-point number of the provided width, in bits.
+    Float { width: u32 },
+}
+
+impl ScalarType {
+    /// Returns the size of values of this type outside of shaders, in bytes.
+    pub fn size(self) -> u32 {
+        match self {
+            Self::Bool => 4,
+            Self::Int { width, .. } | Self::Float { width } => width / 8,
+        }
+    }
+}
+
+/// A descriptor binding accessed by a shader.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DescriptorBinding {
+    /// The descriptor set of the binding.
+    pub set: u32,
+    /// The binding number.
+    pub binding: u32,
+    /// The type of the descriptors of the binding.
+    pub descriptor_type: DescriptorType,
+    /// The number of descriptors of the binding, or zero for runtime-sized arrays.
+    pub count: u32,
+    /// The stage of the shader.
+    pub stages: ShaderStages,
+    /// The name of the variable, if the module contains debug information.
+    pub name: Option<String>,
+}
+
+/// An input or output variable of a shader, such as a vertex input or a fragment output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InterfaceVariable {
+    /// The location of the variable.
+    pub location: u32,
+    /// The first component of the location used by the variable.
+    pub component: u32,
+    /// The type of the components of the variable, or `None` if it is not a scalar or a
+    /// vector.
+    pub scalar_type: Option<ScalarType>,
+    /// The number of components of the variable, or zero if it is not a scalar or a vector.
+    pub components: u32,
+    /// The name of the variable, if the module contains debug information.
+    pub name: Option<String>,
+}
+
+impl InterfaceVariable {
+    /// Returns the format matching the type of the variable, if it is a 32-bit scalar or
+    /// vector.
+    pub fn format(&self) -> Option<Format> {
+        let formats = match self.scalar_type? {
+            ScalarType::Float { width: 32 } => [
+                Format::R32Float,
+                Format::Rg32Float,
+                Format::Rgb32Float,
+                Format::Rgba32Float,
+            ],
+            ScalarType::Int {
+                width: 32,
+                signed: false,
+            } => [
+                Format::R32Uint,
+                Format::Rg32Uint,
+                Format::Rgb32Uint,
+                Format::Rgba32Uint,
+            ],
+            ScalarType::Int {
+                width: 32,
+                signed: true,
+            } => [
+                Format::R32Sint,
+                Format::Rg32Sint,
+                Format::Rgb32Sint,
+                Format::Rgba32Sint,
+            ],
+            _ => return None,
+        };
+
+        formats
+            .get(self.components.checked_sub(1)? as usize)
+            .copied()
+    }
+}
+
+/// A specialization constant declared by a shader.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpecConstant {
+    /// The constant id, which matches the `constant_id` layout qualifier.
+    pub id: u32,
+    /// The type of the constant.
+    pub scalar_type: ScalarType,
+    /// The bits of the default value of the constant.
+    pub default: u64,
+    /// The name of the constant, if the module contains debug information.
+    pub name: Option<String>,
+}
+
+/// The size of the workgroups of a compute shader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WorkgroupSize {
+    /// The default size of the workgroups.
+    pub size: [u32; 3],
+    /// The ids of the specialization constants which override each dimension, if any.
+    pub spec_ids: [Option<u32>; 3],
+}
+
+/// The resources and interface of an entry point of a SPIR-V module, extracted by a
+/// lightweight parser.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShaderReflection {
+    /// The stage of the entry point.
+    stage: ShaderStage,
+    /// The descriptor bindings accessed by the entry point.
+    descriptor_bindings: Vec<DescriptorBinding>,
+    /// The range of push constants accessed by the entry point.
+    push_constants: Option<PushConstantRange>,
+    /// The input variables of the entry point.
+    inputs: Vec<InterfaceVariable>,
+    /// The output variables of the entry point.
+    outputs: Vec<InterfaceVariable>,
+    /// The specialization constants of the module.
+    spec_constants: Vec<SpecConstant>,
+    /// The size of the workgroups of the entry point.
+    workgroup_size: Option<WorkgroupSize>,
+}
+
+impl ShaderReflection {
+    /// Extracts the resources and interface of an entry point of a SPIR-V module, in either
+    /// byte order.
+    ///
+    /// Modules older than SPIR-V 1.4 do not record which resources an entry point uses, in
+    /// which case all the descriptor bindings and push constants of the module are reported.
+    /// Built-in variables are not reported as inputs or outputs.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`SpirvError::MissingEntryPoint`] if the module does not declare
+    /// the entry point for the provided stage.
+    pub fn new(
+        words: &[u32],
+        entry_point: &str,
+        stage: ShaderStage,
+    ) -> std::result::Result<Self, SpirvError> {
+        let words = native_words(words)?;
+        let module = Module::parse(&words, entry_point, stage)?;
+        let (function, interface) = module
+            .entry_point
+            .clone()
+            .ok_or(SpirvError::MissingEntryPoint)?;
+
+        let uses = |variable: u32| words[1] < VERSION_1_4 || interface.contains(&variable);
+
+        let mut descriptor_bindings = Vec::new();
+        let mut push_constants: Option<PushConstantRange> = None;
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+
+        for &(variable, ty, storage_class) in &module.variables {
+            let Some(&Type::Pointer { pointee, .. }) = module.types.get(&ty) else {
+                continue;
+            };
+
+            match storage_class {
+                STORAGE_CLASS_UNIFORM_CONSTANT
+                | STORAGE_CLASS_UNIFORM
+                | STORAGE_CLASS_STORAGE_BUFFER
+                    if uses(variable) =>
+                {
+                    let decorations = module.decorations(variable);
+                    let (Some(set), Some(binding)) = (decorations.set, decorations.binding) else {
+                        continue;
+                    };
+                    let (element, count) = module.descriptor_count(pointee)?;
+                    let Some(descriptor_type) = module.descriptor_type(element, storage_class)
+                    else {
+                        continue;
+                    };
+
+                    descriptor_bindings.push(DescriptorBinding {
+                        set,
+                        binding,
+                        descriptor_type,
+                        count,
+                        stages: stage.into(),
+                        name: module.names.get(&variable).cloned(),
+                    });
+                }
+                STORAGE_CLASS_PUSH_CONSTANT if uses(variable) => {
+                    let Some(Type::Struct { members }) = module.types.get(&pointee) else {
+                        continue;
+                    };
+                    let offset = (0..members.len() as u32)
+                        .filter_map(|member| module.member_decorations(pointee, member).offset)
+                        .min()
+                        .unwrap_or(0);
+                    let end = module
+                        .size_of(pointee, None)?
+                        .checked_next_multiple_of(4)
+                        .ok_or(SpirvError::InvalidType(pointee))?;
+
+                    let range = push_constants.get_or_insert(PushConstantRange {
+                        stages: stage.into(),
+                        offset,
+                        size: 0,
+                    });
+                    let end = end.max(range.offset + range.size);
+                    range.offset = range.offset.min(offset);
+                    range.size = end - range.offset;
+                }
+                STORAGE_CLASS_INPUT | STORAGE_CLASS_OUTPUT if interface.contains(&variable) => {
+                    let decorations = module.decorations(variable);
+                    let (Some(location), None) = (decorations.location, decorations.builtin) else {
+                        continue;
+                    };
+
+                    // The inputs of tessellation and geometry shaders, and the outputs of
+                    // tessellation control shaders, are arrays with one element per vertex.
+                    let per_vertex = match stage {
+                        ShaderStage::TessellationControl => true,
+                        ShaderStage::TessellationEvaluation | ShaderStage::Geometry => {
+                            storage_class == STORAGE_CLASS_INPUT
+                        }
+                        _ => false,
+                    };
+                    let ty = match module.types.get(&pointee) {
+                        Some(&Type::Array { element, .. }) if per_vertex => element,
+                        _ => pointee,
+                    };
+                    let (scalar_type, components) = module.scalar_and_components(ty);
+
+                    let variable = InterfaceVariable {
+                        location,
+                        component: decorations.component.unwrap_or(0),
+                        scalar_type,
+                        components,
+                        name: module.names.get(&variable).cloned(),
+                    };
+
+                    if storage_class == STORAGE_CLASS_INPUT {
+                        inputs.push(variable);
+                    } else {
+                        outputs.push(variable);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut spec_constants: Vec<_> = module
+            .spec_constants
+            .iter()
+            .filter_map(|&(constant, ty, default)| {
+                Some(SpecConstant {
+                    id: module.decorations(constant).spec_id?,
+                    scalar_type: module.scalar_and_components(ty).0?,
+                    default,
+                    name: module.names.get(&constant).cloned(),
+                })
+            })
+            .collect();
+
+        descriptor_bindings.sort_by_key(|binding| (binding.set, binding.binding));
+        inputs.sort_by_key(|variable| (variable.location, variable.component));
+        outputs.sort_by_key(|variable| (variable.location, variable.component));
+        spec_constants.sort_by_key(|constant| constant.id);
+
+        Ok(Self {
+            stage,
+            descriptor_bindings,
+            push_constants,
+            inputs,
+            outputs,
+            spec_constants,
+            workgroup_size: module.workgroup_size(function),
+        })
+    }
+
+    /// Returns the stage of the entry point.
+    #[inline(always)]
+    pub fn stage(&self) -> ShaderStage {
+        self.stage
+    }
+
+    /// Returns the descriptor bindings accessed by the entry point, sorted by set and binding.
+    #[inline(always)]
+    pub fn descriptor_bindings(&self) -> &[DescriptorBinding] {
+        &self.descriptor_bindings
+    }
+
+    /// Returns the range of push constants accessed by the entry point, if any.
+    #[inline(always)]
+    pub fn push_constants(&self) -> Option<PushConstantRange> {
+        self.push_constants
+    }
+
+    /// Returns the input variables of the entry point, such as the vertex inputs of a vertex
+    /// shader, sorted by location.
+    #[inline(always)]
+    pub fn inputs(&self) -> &[InterfaceVariable] {
+        &self.inputs
+    }
+
+    /// Returns the output variables of the entry point, such as the color outputs of a
+    /// fragment shader, sorted by location.
+    #[inline(always)]
+    pub fn outputs(&self) -> &[InterfaceVariable] {
+        &self.outputs
+    }
+
+    /// Returns the specialization constants of the module, sorted by id.
+    #[inline(always)]
+    pub fn spec_constants(&self) -> &[SpecConstant] {
+        &self.spec_constants
+    }
+
+    /// Returns the size of the workgroups of the entry point, if it is a compute shader.
+    #[inline(always)]
+    pub fn workgroup_size(&self) -> Option<WorkgroupSize> {
+        self.workgroup_size
+    }
+}
+
+/// The resources that the stages of a pipeline access, merged into the description of a
+/// pipeline layout.
+///
+/// The fields can be adjusted before the layout is created, for example to give a count to
+/// runtime-sized arrays or to make buffers dynamic.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PipelineReflection {
+    /// The bindings of each descriptor set, indexed by set number and sorted by binding.
+    pub sets: Vec<Vec<DescriptorSetLayoutBinding>>,
+    /// The push constant ranges, where stages accessing the same range share an entry.
+    pub push_constant_ranges: Vec<PushConstantRange>,
+}
+
+impl PipelineReflection {
+    /// Merges the resources accessed by the stages of a pipeline.
+    ///
+    /// Bindings declared by several stages are accessible to all of them, and use the largest
+    /// of their counts.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidSpirv`] if two stages declare the same binding
+    /// with different descriptor types.
+    ///
+    /// [`Error::InvalidSpirv`]: crate::Error::InvalidSpirv
+    pub fn merge<'a>(stages: impl IntoIterator<Item = &'a ShaderReflection>) -> Result<Self> {
+        let mut result = Self::default();
+
+        for stage in stages {
+            for binding in &stage.descriptor_bindings {
+                let set = binding.set as usize;
+                if result.sets.len() <= set {
+                    result.sets.resize_with(set + 1, Vec::new);
+                }
+
+                match result.sets[set]
+                    .iter_mut()
+                    .find(|existing| existing.binding == binding.binding)
+                {
+                    Some(existing) if existing.descriptor_type != binding.descriptor_type => {
+                        return Err(SpirvError::ConflictingBinding {
+                            set: binding.set,
+                            binding: binding.binding,
+                        }
+                        .into());
+                    }
+                    Some(existing) => {
+                        existing.count = existing.count.max(binding.count);
+                        existing.stages |= binding.stages;
+                    }
+                    None => result.sets[set].push(DescriptorSetLayoutBinding {
+                        binding: binding.binding,
+                        descriptor_type: binding.descriptor_type,
+                        count: binding.count,
+                        stages: binding.stages,
+                    }),
+                }
+            }
+
+            if let Some(range) = stage.push_constants {
+                match result
+                    .push_constant_ranges
+                    .iter_mut()
+                    .find(|existing| existing.offset == range.offset && existing.size == range.size)
+                {
+                    Some(existing) => existing.stages |= range.stages,
+                    None => result.push_constant_ranges.push(range),
+                }
+            }
+        }
+
+        for set in &mut result.sets {
+            set.sort_by_key(|binding| binding.binding);
+        }
+
+        Ok(result)
+    }
+
+    /// Creates the layouts of the descriptor sets, indexed by set number.
+    ///
+    /// Sets that no stage accesses get an empty layout.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if a binding is a runtime-sized array whose count was not set.
+    pub fn create_set_layouts(
+        &self,
+        device: &Arc<Device>,
+    ) -> Result<Vec<Arc<DescriptorSetLayout>>> {
+        self.sets
+            .iter()
+            .enumerate()
+            .map(|(set, bindings)| {
+                if let Some(binding) = bindings.iter().find(|binding| binding.count == 0) {
+                    panic!(
+                        "binding {} of set {set} is a runtime-sized array, whose count must be \
+                         set before creating the layout",
+                        binding.binding
+                    );
+                }
+
+                DescriptorSetLayout::new(device.clone(), bindings).map(Arc::new)
+            })
+            .collect()
+    }
+
+    /// Creates a pipeline layout, along with the layouts of its descriptor sets.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if a binding is a runtime-sized array whose count was not set.
+    pub fn create_pipeline_layout(&self, device: Arc<Device>) -> Result<PipelineLayout> {
+        let set_layouts = self.create_set_layouts(&device)?;
+        PipelineLayout::new(device, &set_layouts, &self.push_constant_ranges)
+    }
+}
+
+/// A type declared by a SPIR-V module.
+#[derive(Debug, Clone)]
+enum Type {
+    Bool,
+    Int { width: u32, signed: bool },
+    Float { width: u32 },
+    Vector { component: u32, count: u32 },
+    Matrix { column: u32, count: u32 },
+    Image { dim: u32, sampled: u32 },
+    Sampler,
+    SampledImage { image: u32 },
+    Array { element: u32, length: u32 },
+    RuntimeArray { element: u32 },
+    Struct { members: Vec<u32> },
+    Pointer { pointee: u32 },
+}
+
+/// The decorations applied to an id.
+#[derive(Debug, Clone, Copy, Default)]
+struct Decorations {
+    spec_id: Option<u32>,
+    buffer_block: bool,
+    array_stride: Option<u32>,
+    builtin: Option<u32>,
+    location: Option<u32>,
+    component: Option<u32>,
+    binding: Option<u32>,
+    set: Option<u32>,
+}
+
+/// The decorations applied to a member of a structure.
+#[derive(Debug, Clone, Copy, Default)]
+struct MemberDecorations {
+    offset: Option<u32>,
+    matrix_stride: Option<u32>,
+}
+
+/// The declarations of a SPIR-V module that reflection relies on.
+#[derive(Debug, Default)]
+struct Module {
+    names: HashMap<u32, String>,
+    decorations: HashMap<u32, Decorations>,
+    member_decorations: HashMap<(u32, u32), MemberDecorations>,
+    types: HashMap<u32, Type>,
+    /// The values of scalar constants and the default values of scalar specialization
+    /// constants.
+    constants: HashMap<u32, u64>,
+    /// The constituents of composite constants.
+    composites: HashMap<u32, Vec<u32>>,
+    /// The id, type and default value of scalar specialization constants.
+    spec_constants: Vec<(u32, u32, u64)>,
+    /// The id, pointer type and storage class of global variables.
+    variables: Vec<(u32, u32, u32)>,
+    /// The function and interface of the requested entry point.
+    entry_point: Option<(u32, Vec<u32>)>,
+    /// The local size execution modes, indexed by function, along with whether they are
+    /// expressed as ids.
+    local_sizes: HashMap<u32, ([u32; 3], bool)>,
+}
+
+impl Module {
+    /// Collects the declarations of a module in the native byte order.
+    fn parse(
+        words: &[u32],
+        entry_point: &str,
+        stage: ShaderStage,
+    ) -> std::result::Result<Self, SpirvError> {
+        let mut module = Self::default();
+
+        for instruction in instructions(words) {
+            let instruction = instruction?;
+            let truncated = SpirvError::TruncatedInstruction(instruction.offset);
+            let operands = instruction.operands;
+            let operand = |index: usize| operands.get(index).copied().ok_or(truncated);
+
+            match instruction.opcode {
+                OP_NAME => {
+                    let (name, _) = instruction.string(1)?;
+                    module.names.insert(operand(0)?, name);
+                }
+                OP_ENTRY_POINT => {
+                    let (name, len) = instruction.string(2)?;
+                    let model = ExecutionModel::from_raw(operand(0)?);
+                    if name == entry_point && model.and_then(ExecutionModel::stage) == Some(stage) {
+                        let interface = operands.get(2 + len..).unwrap_or_default().to_vec();
+                        module.entry_point = Some((operand(1)?, interface));
+                    }
+                }
+                OP_EXECUTION_MODE => {
+                    let mode = operand(1)?;
+                    if mode == EXECUTION_MODE_LOCAL_SIZE || mode == EXECUTION_MODE_LOCAL_SIZE_ID {
+                        let size = [operand(2)?, operand(3)?, operand(4)?];
+                        let is_id = mode == EXECUTION_MODE_LOCAL_SIZE_ID;
+                        module.local_sizes.insert(operand(0)?, (size, is_id));
+                    }
+                }
+                OP_TYPE_BOOL => {
+                    module.types.insert(operand(0)?, Type::Bool);
+                }
+                OP_TYPE_INT => {
+                    let ty = Type::Int {
+                        width: operand(1)?,
+                        signed: operand(2)? != 0,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                OP_TYPE_FLOAT => {
+                    let ty = Type::Float { width: operand(1)? };
+                    module.types.insert(operand(0)?, ty);
+                }
+                OP_TYPE_VECTOR => {
+                    let ty = Type::Vector {
+                        component: operand(1)?,
+                        count: operand(2)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                OP_TYPE_MATRIX => {
+                    let ty = Type::Matrix {
+                        column: operand(1)?,
+                        count: operand(2)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                OP_TYPE_IMAGE => {
+                    let ty = Type::Image {
+                        dim: operand(2)?,
+                        sampled: operand(6)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                OP_TYPE_SAMPLER => {
+                    module.types.insert(operand(0)?, Type::Sampler);
+                }
+                OP_TYPE_SAMPLED_IMAGE => {
+                    let ty = Type::SampledImage { image: operand(1)? };
+                    module.types.insert(operand(0)?, ty);
+                }
+                OP_TYPE_ARRAY => {
+                    let ty = Type::Array {
+                        element: operand(1)?,
+                        length: operand(2)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                OP_TYPE_RUNTIME_ARRAY => {
+                    let ty = Type::RuntimeArray {
+                        element: operand(1)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                OP_TYPE_STRUCT => {
+                    let ty = Type::Struct {
+                        members: operands.get(1..).unwrap_or_default().to_vec(),
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                OP_TYPE_POINTER => {
+                    let ty = Type::Pointer {
+                        pointee: operand(2)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                OP_CONSTANT | OP_SPEC_CONSTANT => {
+                    let low = operand(2)? as u64;
+                    let high = operands.get(3).map_or(0, |&high| (high as u64) << 32);
+                    module.constants.insert(operand(1)?, low | high);
+
+                    if instruction.opcode == OP_SPEC_CONSTANT {
+                        module
+                            .spec_constants
+                            .push((operand(1)?, operand(0)?, low | high));
+                    }
+                }
+                OP_CONSTANT_TRUE | OP_CONSTANT_FALSE => {
+                    let value = (instruction.opcode == OP_CONSTANT_TRUE) as u64;
+                    module.constants.insert(operand(1)?, value);
+                }
+                OP_SPEC_CONSTANT_TRUE | OP_SPEC_CONSTANT_FALSE => {
+                    let value = (instruction.opcode == OP_SPEC_CONSTANT_TRUE) as u64;
+                    module.constants.insert(operand(1)?, value);
+                    module
+                        .spec_constants
+                        .push((operand(1)?, operand(0)?, value));
+                }
+                OP_CONSTANT_COMPOSITE | OP_SPEC_CONSTANT_COMPOSITE => {
+                    let constituents = operands.get(2..).unwrap_or_default().to_vec();
+                    module.composites.insert(operand(1)?, constituents);
+                }
+                OP_VARIABLE => {
+                    module
+                        .variables
+                        .push((operand(1)?, operand(0)?, operand(2)?));
+                }
+                OP_DECORATE => {
+                    let decorations = module.decorations.entry(operand(0)?).or_default();
+                    match operand(1)? {
+                        DECORATION_SPEC_ID => decorations.spec_id = Some(operand(2)?),
+                        DECORATION_BUFFER_BLOCK => decorations.buffer_block = true,
+                        DECORATION_ARRAY_STRIDE => decorations.array_stride = Some(operand(2)?),
+                        DECORATION_BUILT_IN => decorations.builtin = Some(operand(2)?),
+                        DECORATION_LOCATION => decorations.location = Some(operand(2)?),
+                        DECORATION_COMPONENT => decorations.component = Some(operand(2)?),
+                        DECORATION_BINDING => decorations.binding = Some(operand(2)?),
+                        DECORATION_DESCRIPTOR_SET => decorations.set = Some(operand(2)?),
+                        _ => (),
+                    }
+                }
+                OP_MEMBER_DECORATE => {
+                    let key = (operand(0)?, operand(1)?);
+                    let decorations = module.member_decorations.entry(key).or_default();
+                    match operand(2)? {
+                        DECORATION_OFFSET => decorations.offset = Some(operand(3)?),
+                        DECORATION_MATRIX_STRIDE => decorations.matrix_stride = Some(operand(3)?),
+                        _ => (),
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        Ok(module)
+    }
+
+    /// Returns the decorations applied to an id.
+    fn decorations(&self, id: u32) -> Decorations {
+        self.decorations.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Returns the decorations applied to a member of a structure.
+    fn member_decorations(&self, ty: u32, member: u32) -> MemberDecorations {
+        self.member_decorations
+            .get(&(ty, member))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the element type and the number of descriptors of a possibly arrayed
+    /// descriptor type, where runtime-sized arrays have a count of zero.
+    fn descriptor_count(&self, mut ty: u32) -> std::result::Result<(u32, u32), SpirvError> {
+        let invalid = SpirvError::InvalidType(ty);
+        let mut count = 1u32;
+
+        for _ in 0..MAX_TYPE_DEPTH {
+            match self.types.get(&ty) {
+                Some(&Type::Array { element, length }) => {
+                    let length = self.constants.get(&length).copied().unwrap_or(1);
+                    count = u32::try_from(length)
+                        .ok()
+                        .and_then(|length| count.checked_mul(length))
+                        .ok_or(invalid)?;
+                    ty = element;
+                }
+                Some(&Type::RuntimeArray { element }) => {
+                    count = 0;
+                    ty = element;
+                }
+                _ => return Ok((ty, count)),
+            }
+        }
+
+        Err(invalid)
+    }
+
+    /// Returns the type of the descriptors of a variable of the provided type and storage
+    /// class, if it is a descriptor.
+    fn descriptor_type(&self, ty: u32, storage_class: u32) -> Option<DescriptorType> {
+        let image_type = |dim: u32, sampled: u32| match (dim, sampled) {
+            (DIM_SUBPASS_DATA, _) => DescriptorType::InputAttachment,
+            (DIM_BUFFER, 2) => DescriptorType::StorageTexelBuffer,
+            (DIM_BUFFER, _) => DescriptorType::UniformTexelBuffer,
+            (_, 2) => DescriptorType::StorageImage,
+            _ => DescriptorType::SampledImage,
+        };
+
+        match self.types.get(&ty)? {
+            Type::Sampler => Some(DescriptorType::Sampler),
+            Type::Image { dim, sampled } => Some(image_type(*dim, *sampled)),
+            Type::SampledImage { image } => match self.types.get(image) {
+                Some(Type::Image {
+                    dim: DIM_BUFFER, ..
+                }) => Some(DescriptorType::UniformTexelBuffer),
+                _ => Some(DescriptorType::CombinedImageSampler),
+            },
+            Type::Struct { .. } => match storage_class {
+                STORAGE_CLASS_STORAGE_BUFFER => Some(DescriptorType::StorageBuffer),
+                STORAGE_CLASS_UNIFORM if self.decorations(ty).buffer_block => {
+                    Some(DescriptorType::StorageBuffer)
+                }
+                STORAGE_CLASS_UNIFORM => Some(DescriptorType::UniformBuffer),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the type and number of the components of a scalar or vector type.
+    fn scalar_and_components(&self, ty: u32) -> (Option<ScalarType>, u32) {
+        let scalar = |ty| match self.types.get(&ty) {
+            Some(Type::Bool) => Some(ScalarType::Bool),
+            Some(&Type::Int { width, signed }) => Some(ScalarType::Int { width, signed }),
+            Some(&Type::Float { width }) => Some(ScalarType::Float { width }),
+            _ => None,
+        };
+
+        match self.types.get(&ty) {
+            // The components of vectors are scalars, which keeps this from recursing.
+            Some(&Type::Vector { component, count }) => (scalar(component), count),
+            _ => match scalar(ty) {
+                Some(scalar_type) => (Some(scalar_type), 1),
+                None => (None, 0),
+            },
+        }
+    }
+
+    /// Returns the size of a type laid out with explicit offsets and strides, in bytes.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`SpirvError::InvalidType`] if the type contains itself, is
+    /// nested more than [`MAX_TYPE_DEPTH`] times or is larger than `u32::MAX` bytes.
+    fn size_of(&self, ty: u32, matrix_stride: Option<u32>) -> std::result::Result<u32, SpirvError> {
+        self.size_of_nested(ty, matrix_stride, 0, &mut HashMap::new())
+    }
+
+    /// Returns the size of a type nested `depth` times in the type passed to
+    /// [`Module::size_of`], memoizing the sizes of the types it contains in `sizes` so that
+    /// types referenced many times are only visited once.
+    fn size_of_nested(
+        &self,
+        ty: u32,
+        matrix_stride: Option<u32>,
+        depth: u32,
+        sizes: &mut HashMap<(u32, Option<u32>), u32>,
+    ) -> std::result::Result<u32, SpirvError> {
+        if let Some(&size) = sizes.get(&(ty, matrix_stride)) {
+            return Ok(size);
+        }
+        let invalid = SpirvError::InvalidType(ty);
+        if depth >= MAX_TYPE_DEPTH {
+            return Err(invalid);
+        }
+        let mut size_of =
+            |ty, matrix_stride| self.size_of_nested(ty, matrix_stride, depth + 1, sizes);
+
+        let size = match self.types.get(&ty) {
+            Some(Type::Bool) => Some(4),
+            Some(&Type::Int { width, .. } | &Type::Float { width }) => Some(width / 8),
+            Some(&Type::Vector { component, count }) => {
+                count.checked_mul(size_of(component, None)?)
+            }
+            Some(&Type::Matrix { column, count }) => {
+                let stride = match matrix_stride {
+                    Some(stride) => stride,
+                    None => size_of(column, None)?,
+                };
+                count.checked_mul(stride)
+            }
+            Some(&Type::Array { element, length }) => {
+                let length = self.constants.get(&length).copied().unwrap_or(0);
+                let stride = match self.decorations(ty).array_stride {
+                    Some(stride) => stride,
+                    None => size_of(element, matrix_stride)?,
+                };
+                u32::try_from(length)
+                    .ok()
+                    .and_then(|length| length.checked_mul(stride))
+            }
+            Some(Type::Struct { members }) => {
+                let mut end = 0;
+                for (index, &member) in members.iter().enumerate() {
+                    let decorations = self.member_decorations(ty, index as u32);
+                    let member_end = decorations
+                        .offset
+                        .unwrap_or(0)
+                        .checked_add(size_of(member, decorations.matrix_stride)?)
+                        .ok_or(invalid)?;
+                    end = end.max(member_end);
+                }
+                Some(end)
+            }
+            _ => Some(0),
+        }
+        .ok_or(invalid)?;
+
+        sizes.insert((ty, matrix_stride), size);
+        Ok(size)
+    }
+
+    /// Returns the size of the workgroups of the provided entry point function.
+    ///
+    /// A constant decorated with the `WorkgroupSize` built-in takes precedence over the
+    /// execution modes of the entry point.
+    fn workgroup_size(&self, function: u32) -> Option<WorkgroupSize> {
+        let from_ids = |ids: &[u32]| {
+            let mut result = WorkgroupSize {
+                size: [1; 3],
+                spec_ids: [None; 3],
+            };
+            for (dim, &id) in ids.iter().take(3).enumerate() {
+                result.size[dim] = self.constants.get(&id).copied().unwrap_or(1) as u32;
+                result.spec_ids[dim] = self.decorations(id).spec_id;
+            }
+            result
+        };
+
+        let builtin = self
+            .composites
+            .iter()
+            .find(|(&id, _)| self.decorations(id).builtin == Some(BUILT_IN_WORKGROUP_SIZE));
+        if let Some((_, constituents)) = builtin {
+            return Some(from_ids(constituents));
+        }
+
+        match *self.local_sizes.get(&function)? {
+            (size, false) => Some(WorkgroupSize {
+                size,
+                spec_ids: [None; 3],
+            }),
+            (ids, true) => Some(from_ids(&ids)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spirv::tests::{entry_point, module, op, string};
+    use crate::Error;
+
+    const MODEL_VERTEX: u32 = 0;
+    const MODEL_FRAGMENT: u32 = 4;
+    const MODEL_GL_COMPUTE: u32 = 5;
+
+    /// The ids of the scalar and vector types declared by [`types`].
+    const FLOAT: u32 = 2;
+    const VEC4: u32 = 3;
+    const UINT: u32 = 4;
+    /// The id of an unsigned constant of value 3 declared by [`types`].
+    const THREE: u32 = 5;
+
+    /// Declares the types shared by the modules of the tests.
+    fn types() -> Vec<Vec<u32>> {
+        vec![
+            op(OP_TYPE_FLOAT, &[FLOAT, 32]),
+            op(OP_TYPE_VECTOR, &[VEC4, FLOAT, 4]),
+            op(OP_TYPE_INT, &[UINT, 32, 0]),
+            op(OP_CONSTANT, &[UINT, THREE, 3]),
+        ]
+    }
+
+    /// Declares a variable bound to a descriptor, whose pointer type is declared as `id - 1`.
+    fn descriptor(
+        id: u32,
+        pointee: u32,
+        storage_class: u32,
+        set: u32,
+        binding: u32,
+    ) -> Vec<Vec<u32>> {
+        vec![
+            op(OP_TYPE_POINTER, &[id - 1, storage_class, pointee]),
+            op(OP_VARIABLE, &[id - 1, id, storage_class]),
+            op(OP_DECORATE, &[id, DECORATION_DESCRIPTOR_SET, set]),
+            op(OP_DECORATE, &[id, DECORATION_BINDING, binding]),
+        ]
+    }
+
+    /// Declares a push constant block whose only member is a `vec4` at the provided offset,
+    /// using the ids from `id - 2` to `id`.
+    fn push_constants(id: u32, offset: u32) -> Vec<Vec<u32>> {
+        vec![
+            op(OP_TYPE_STRUCT, &[id - 2, VEC4]),
+            op(OP_MEMBER_DECORATE, &[id - 2, 0, DECORATION_OFFSET, offset]),
+            op(
+                OP_TYPE_POINTER,
+                &[id - 1, STORAGE_CLASS_PUSH_CONSTANT, id - 2],
+            ),
+            op(OP_VARIABLE, &[id - 1, id, STORAGE_CLASS_PUSH_CONSTANT]),
+        ]
+    }
+
+    /// Assembles a module with a single entry point named `main`, whose function is `%1`.
+    fn shader(model: u32, declarations: Vec<Vec<u32>>) -> Vec<u32> {
+        let mut instructions = vec![entry_point(model, 1, "main")];
+        instructions.extend(types());
+        instructions.extend(declarations);
+        module(100, &instructions)
+    }
+
+    #[test]
+    fn descriptor_bindings() {
+        let mut declarations = Vec::new();
+        // layout(set = 0, binding = 1) uniform Params { vec4 color; } params;
+        declarations.push(op(OP_TYPE_STRUCT, &[10, VEC4]));
+        declarations.extend(descriptor(12, 10, STORAGE_CLASS_UNIFORM, 0, 1));
+        let mut name = vec![12];
+        name.extend(string("params"));
+        declarations.push(op(OP_NAME, &name));
+        // layout(set = 1, binding = 0) uniform sampler2D textures[3];
+        declarations.push(op(OP_TYPE_IMAGE, &[20, FLOAT, 1, 0, 0, 0, 1, 0]));
+        declarations.push(op(OP_TYPE_SAMPLED_IMAGE, &[21, 20]));
+        declarations.push(op(OP_TYPE_ARRAY, &[22, 21, THREE]));
+        declarations.extend(descriptor(24, 22, STORAGE_CLASS_UNIFORM_CONSTANT, 1, 0));
+        // layout(set = 0, binding = 0) buffer Data { float values[]; };
+        declarations.push(op(OP_TYPE_RUNTIME_ARRAY, &[30, FLOAT]));
+        declarations.push(op(OP_TYPE_STRUCT, &[31, 30]));
+        declarations.extend(descriptor(33, 31, STORAGE_CLASS_STORAGE_BUFFER, 0, 0));
+
+        let words = shader(MODEL_GL_COMPUTE, declarations);
+        let reflection = ShaderReflection::new(&words, "main", ShaderStage::Compute).unwrap();
+
+        let binding =
+            |set, binding, descriptor_type, count, name: Option<&str>| DescriptorBinding {
+                set,
+                binding,
+                descriptor_type,
+                count,
+                stages: ShaderStages::COMPUTE,
+                name: name.map(String::from),
+            };
+        assert_eq!(
+            reflection.descriptor_bindings(),
+            &[
+                binding(0, 0, DescriptorType::StorageBuffer, 1, None),
+                binding(0, 1, DescriptorType::UniformBuffer, 1, Some("params")),
+                binding(1, 0, DescriptorType::CombinedImageSampler, 3, None),
+            ]
+        );
+
+        assert_eq!(
+            ShaderReflection::new(&words, "main", ShaderStage::Vertex),
+            Err(SpirvError::MissingEntryPoint)
+        );
+    }
+
+    #[test]
+    fn push_constant_ranges() {
+        let reflect = |model, stage, offset| {
+            let words = shader(model, push_constants(12, offset));
+            ShaderReflection::new(&words, "main", stage).unwrap()
+        };
+        let vertex = reflect(MODEL_VERTEX, ShaderStage::Vertex, 0);
+        let fragment = reflect(MODEL_FRAGMENT, ShaderStage::Fragment, 0);
+        let fragment_offset = reflect(MODEL_FRAGMENT, ShaderStage::Fragment, 16);
+
+        assert_eq!(
+            fragment_offset.push_constants(),
+            Some(PushConstantRange {
+                stages: ShaderStages::FRAGMENT,
+                offset: 16,
+                size: 16,
+            })
+        );
+
+        // Stages accessing the same range share an entry.
+        let merged = PipelineReflection::merge([&vertex, &fragment]).unwrap();
+        assert_eq!(
+            merged.push_constant_ranges,
+            [PushConstantRange {
+                stages: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                offset: 0,
+                size: 16,
+            }]
+        );
+        let merged = PipelineReflection::merge([&vertex, &fragment_offset]).unwrap();
+        assert_eq!(
+            merged.push_constant_ranges,
+            [
+                vertex.push_constants().unwrap(),
+                fragment_offset.push_constants().unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_bindings() {
+        let reflect = |model, stage, storage_class, array| {
+            let mut declarations = vec![op(OP_TYPE_STRUCT, &[10, VEC4])];
+            if storage_class == STORAGE_CLASS_UNIFORM && array {
+                declarations.push(op(OP_TYPE_ARRAY, &[11, 10, THREE]));
+                declarations.extend(descriptor(13, 11, storage_class, 0, 0));
+            } else {
+                declarations.extend(descriptor(13, 10, storage_class, 0, 0));
+            }
+            let words = shader(model, declarations);
+            ShaderReflection::new(&words, "main", stage).unwrap()
+        };
+        let vertex = reflect(
+            MODEL_VERTEX,
+            ShaderStage::Vertex,
+            STORAGE_CLASS_UNIFORM,
+            false,
+        );
+        let fragment = reflect(
+            MODEL_FRAGMENT,
+            ShaderStage::Fragment,
+            STORAGE_CLASS_UNIFORM,
+            true,
+        );
+
+        // Bindings shared by several stages are visible to all of them with the largest count.
+        let merged = PipelineReflection::merge([&vertex, &fragment]).unwrap();
+        assert_eq!(
+            merged.sets,
+            [[DescriptorSetLayoutBinding {
+                binding: 0,
+                descriptor_type: DescriptorType::UniformBuffer,
+                count: 3,
+                stages: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+            }]]
+        );
+
+        let storage = reflect(
+            MODEL_FRAGMENT,
+            ShaderStage::Fragment,
+            STORAGE_CLASS_STORAGE_BUFFER,
+            false,
+        );
+        assert_eq!(
+            PipelineReflection::merge([&vertex, &storage]),
+            Err(Error::InvalidSpirv(SpirvError::ConflictingBinding {
+                set: 0,
+                binding: 0,
+            }))
+        );
+    }
+
+    #[test]
+    fn workgroup_size() {
+        let execution_mode = op(OP_EXECUTION_MODE, &[1, EXECUTION_MODE_LOCAL_SIZE, 8, 4, 1]);
+        let words = shader(MODEL_GL_COMPUTE, vec![execution_mode.clone()]);
+        let reflection = ShaderReflection::new(&words, "main", ShaderStage::Compute).unwrap();
+        assert_eq!(
+            reflection.workgroup_size(),
+            Some(WorkgroupSize {
+                size: [8, 4, 1],
+                spec_ids: [None; 3],
+            })
+        );
+
+        // A constant decorated with the `WorkgroupSize` built-in overrides the execution mode.
+        let declarations = vec![
+            execution_mode,
+            op(OP_TYPE_VECTOR, &[10, UINT, 3]),
+            op(OP_SPEC_CONSTANT, &[UINT, 11, 16]),
+            op(OP_DECORATE, &[11, DECORATION_SPEC_ID, 7]),
+            op(OP_SPEC_CONSTANT_COMPOSITE, &[10, 12, 11, THREE, THREE]),
+            op(
+                OP_DECORATE,
+                &[12, DECORATION_BUILT_IN, BUILT_IN_WORKGROUP_SIZE],
+            ),
+        ];
+        let words = shader(MODEL_GL_COMPUTE, declarations);
+        let reflection = ShaderReflection::new(&words, "main", ShaderStage::Compute).unwrap();
+        assert_eq!(
+            reflection.workgroup_size(),
+            Some(WorkgroupSize {
+                size: [16, 3, 3],
+                spec_ids: [Some(7), None, None],
+            })
+        );
+        assert_eq!(
+            reflection.spec_constants(),
+            &[SpecConstant {
+                id: 7,
+                scalar_type: ScalarType::Int {
+                    width: 32,
+                    signed: false,
+                },
+                default: 16,
+                name: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_types() {
+        let reflect = |declarations| {
+            let words = shader(MODEL_GL_COMPUTE, declarations);
+            ShaderReflection::new(&words, "main", ShaderStage::Compute)
+        };
+
+        // An array of itself.
+        let mut declarations = vec![op(OP_TYPE_ARRAY, &[10, 10, THREE])];
+        declarations.extend(descriptor(12, 10, STORAGE_CLASS_UNIFORM_CONSTANT, 0, 0));
+        assert_eq!(reflect(declarations), Err(SpirvError::InvalidType(10)));
+
+        // An array of 2^32 samplers.
+        let mut declarations = vec![
+            op(OP_CONSTANT, &[UINT, 10, 0x1_0000]),
+            op(OP_TYPE_SAMPLER, &[11]),
+            op(OP_TYPE_ARRAY, &[12, 11, 10]),
+            op(OP_TYPE_ARRAY, &[13, 12, 10]),
+        ];
+        declarations.extend(descriptor(15, 13, STORAGE_CLASS_UNIFORM_CONSTANT, 0, 0));
+        assert_eq!(reflect(declarations), Err(SpirvError::InvalidType(13)));
+
+        // A push constant block which contains itself.
+        let mut declarations = vec![op(OP_TYPE_STRUCT, &[10, VEC4, 10])];
+        declarations.push(op(OP_TYPE_POINTER, &[11, STORAGE_CLASS_PUSH_CONSTANT, 10]));
+        declarations.push(op(OP_VARIABLE, &[11, 12, STORAGE_CLASS_PUSH_CONSTANT]));
+        assert_eq!(reflect(declarations), Err(SpirvError::InvalidType(10)));
+
+        // A push constant block with an array of 2^32 bytes.
+        let mut declarations = vec![
+            op(OP_CONSTANT, &[UINT, 20, 0x1000_0000]),
+            op(OP_TYPE_ARRAY, &[21, VEC4, 20]),
+        ];
+        declarations.extend(push_constants(12, 0));
+        declarations[2] = op(OP_TYPE_STRUCT, &[10, 21]);
+        assert_eq!(reflect(declarations), Err(SpirvError::InvalidType(21)));
+
+        // A vector of itself has no scalar type.
+        let declarations = vec![
+            op(OP_TYPE_VECTOR, &[10, 10, 2]),
+            op(OP_TYPE_POINTER, &[11, STORAGE_CLASS_INPUT, 10]),
+            op(OP_VARIABLE, &[11, 12, STORAGE_CLASS_INPUT]),
+            op(OP_DECORATE, &[12, DECORATION_LOCATION, 0]),
+        ];
+        assert!(reflect(declarations).is_ok());
+    }
+
+    #[test]
+    fn nested_types_are_measured_once() {
+        // Each structure contains the previous one twice, so that visiting every member would
+        // take 2^60 steps.
+        let mut declarations = vec![op(OP_TYPE_STRUCT, &[10, FLOAT])];
+        for id in 11..70 {
+            declarations.push(op(OP_TYPE_STRUCT, &[id, id - 1, id - 1]));
+        }
+        declarations.push(op(OP_TYPE_POINTER, &[70, STORAGE_CLASS_PUSH_CONSTANT, 69]));
+        declarations.push(op(OP_VARIABLE, &[70, 71, STORAGE_CLASS_PUSH_CONSTANT]));
+        let words = shader(MODEL_GL_COMPUTE, declarations);
+
+        let reflection = ShaderReflection::new(&words, "main", ShaderStage::Compute).unwrap();
+        assert_eq!(reflection.push_constants().unwrap().size, 4);
+    }
+}
@@ -0,0 +1,1541 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use smallvec::SmallVec;
+
+use crate::{
+    Accesses2, Allocation, AllocationDesc, AllocationTiling, Allocator, Buffer, BufferUse,
+    CommandBuffer, CommandBufferUsages, CommandPool, Device, Error, Executable, Fence, Format,
+    Image, ImageDesc, ImageFlags, ImageLayout, ImageSubresourceRange, ImageTiling, ImageType,
+    ImageUsages, ImageUse, MemoryBarrier2, MemoryProperties, MemoryRequirements, PipelineStages,
+    PipelineStages2, Queue, Recording, Result, SampleCount, Semaphore, SharingMode, StateTracker,
+    SubmitDesc, Swapchain, TrackedCommandBuffer, TrackedImageDesc,
+};
+
+/// An image declared in a [`RenderGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GraphImage(usize);
+
+/// A buffer declared in a [`RenderGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GraphBuffer(usize);
+
+/// The kind of queue that a pass of a [`RenderGraph`] runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PassQueue {
+    /// The pass runs on the graphics queue.
+    Graphics,
+    /// The pass only records compute and transfer commands, and runs on the compute queue
+    /// when the graph has one. Otherwise, it runs on the graphics queue.
+    AsyncCompute,
+}
+
+/// The parameters of a transient image, created and owned by a [`RenderGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransientImageDesc {
+    /// The number of dimensions of the image.
+    pub image_type: ImageType,
+    /// The format of the texels of the image.
+    pub format: Format,
+    /// The size of the image, in texels.
+    pub extent: [u32; 3],
+    /// The number of mipmap levels of the image.
+    pub mip_levels: u32,
+    /// The number of layers of the image.
+    pub array_layers: u32,
+    /// The number of samples per texel.
+    pub samples: SampleCount,
+    /// The ways the image will be used by the passes of the graph.
+    pub usage: ImageUsages,
+}
+
+/// The queue families that a [`RenderGraph`] is compiled for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GraphQueueFamilies {
+    /// The queue family of the graphics queue, which must support graphics and compute
+    /// operations.
+    pub graphics: u32,
+    /// The queue family of the compute queue, if async compute should be used.
+    pub compute: Option<u32>,
+}
+
+/// A resource accessed by a pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum GraphResource {
+    Image(usize),
+    Buffer(usize),
+}
+
+/// An access of a pass to a resource.
+#[derive(Debug, Clone, Copy)]
+struct PassAccess {
+    /// The resource accessed.
+    resource: GraphResource,
+    /// The stages accessing the resource.
+    stages: PipelineStages2,
+    /// The accesses performed on the resource.
+    access: Accesses2,
+    /// The layout of the image, or [`ImageLayout::Undefined`] for buffers.
+    layout: ImageLayout,
+    /// Whether the pass writes the resource.
+    write: bool,
+}
+
+/// The function recording the commands of a pass.
+type RecordFn<'a> = Box<dyn FnOnce(&mut PassContext<'_, '_>) + 'a>;
+
+/// A pass declared in a [`RenderGraph`].
+struct Pass<'a> {
+    /// The name of the pass.
+    name: String,
+    /// The kind of queue that the pass runs on.
+    queue: PassQueue,
+    /// The resources accessed by the pass.
+    accesses: Vec<PassAccess>,
+    /// The function recording the commands of the pass, or `None` for the internal
+    /// presentation pass.
+    record: Option<RecordFn<'a>>,
+}
+
+/// Where an image of a [`RenderGraph`] comes from.
+enum ImageSource<'a> {
+    /// The image was created outside of the graph.
+    Imported(&'a Image),
+    /// The image belongs to a swapchain, and is presented once the graph has executed.
+    Swapchain(&'a Image),
+    /// The image is created by the graph, and only lives while it executes.
+    Transient(TransientImageDesc),
+}
+
+/// An image declared in a [`RenderGraph`].
+struct ImageEntry<'a> {
+    /// The name of the image.
+    name: String,
+    /// Where the image comes from.
+    source: ImageSource<'a>,
+    /// Whether the image is an output of the graph.
+    output: bool,
+}
+
+/// A buffer declared in a [`RenderGraph`].
+struct BufferEntry<'a> {
+    /// The name of the buffer.
+    name: String,
+    /// The buffer.
+    buffer: &'a Buffer,
+    /// Whether the buffer is an output of the graph.
+    output: bool,
+}
+
+/// A frame graph, made of passes declaring the resources they read and write.
+///
+/// The graph is built once per frame, and compiled into an ordered list of submissions whose
+/// barriers are inserted by a [`StateTracker`]:
+///
+/// - Passes that do not contribute to an output of the graph are culled.
+/// - Transient images are created by the graph, and the images whose lifetimes do not overlap
+///   share the same memory.
+/// - [`PassQueue::AsyncCompute`] passes run on the compute queue when there is one, with the
+///   semaphores and queue family ownership transfers that this requires.
+///
+/// Passes execute in the order in which they are declared.
+#[derive(Default)]
+pub struct RenderGraph<'a> {
+    /// The declared passes.
+    passes: Vec<Pass<'a>>,
+    /// The declared images.
+    images: Vec<ImageEntry<'a>>,
+    /// The declared buffers.
+    buffers: Vec<BufferEntry<'a>>,
+}
+
+impl<'a> RenderGraph<'a> {
+    /// Creates a new, empty [`RenderGraph`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares an image created outside of the graph.
+    ///
+    /// The state of the image is taken from the [`StateTracker`] that the graph executes with.
+    pub fn import_image(&mut self, name: impl Into<String>, image: &'a Image) -> GraphImage {
+        self.add_image(name.into(), ImageSource::Imported(image))
+    }
+
+    /// Declares an image of a swapchain, as acquired for this frame.
+    ///
+    /// The image is an output of the graph, and is transitioned to
+    /// [`ImageLayout::PresentSrc`] on the graphics queue once all the passes have executed.
+    /// Its previous contents are discarded.
+    pub fn import_swapchain_image(
+        &mut self,
+        name: impl Into<String>,
+        swapchain: &'a Swapchain,
+        image_index: u32,
+    ) -> GraphImage {
+        let image = &swapchain.images()[image_index as usize];
+        let handle = self.add_image(name.into(), ImageSource::Swapchain(image));
+        self.images[handle.0].output = true;
+        handle
+    }
+
+    /// Declares an image created by the graph, whose memory may be shared with other
+    /// transient images.
+    ///
+    /// The contents of the image are undefined before the first pass that writes it.
+    pub fn create_image(
+        &mut self,
+        name: impl Into<String>,
+        desc: TransientImageDesc,
+    ) -> GraphImage {
+        self.add_image(name.into(), ImageSource::Transient(desc))
+    }
+
+    /// Declares a buffer created outside of the graph.
+    pub fn import_buffer(&mut self, name: impl Into<String>, buffer: &'a Buffer) -> GraphBuffer {
+        self.buffers.push(BufferEntry {
+            name: name.into(),
+            buffer,
+            output: false,
+        });
+        GraphBuffer(self.buffers.len() - 1)
+    }
+
+    /// Marks an image as an output of the graph, which keeps the passes writing it from
+    /// being culled.
+    pub fn mark_image_output(&mut self, image: GraphImage) {
+        self.images[image.0].output = true;
+    }
+
+    /// Marks a buffer as an output of the graph, which keeps the passes writing it from
+    /// being culled.
+    pub fn mark_buffer_output(&mut self, buffer: GraphBuffer) {
+        self.buffers[buffer.0].output = true;
+    }
+
+    /// Starts declaring a pass, which is added to the graph once its recording function is
+    /// provided with [`PassBuilder::record`].
+    pub fn add_pass(&mut self, name: impl Into<String>, queue: PassQueue) -> PassBuilder<'_, 'a> {
+        PassBuilder {
+            graph: self,
+            name: name.into(),
+            queue,
+            accesses: Vec::new(),
+        }
+    }
+
+    /// Adds an image to the graph.
+    fn add_image(&mut self, name: String, source: ImageSource<'a>) -> GraphImage {
+        self.images.push(ImageEntry {
+            name,
+            source,
+            output: false,
+        });
+        GraphImage(self.images.len() - 1)
+    }
+
+    /// Compiles this graph into submissions for the provided queue families, and creates its
+    /// transient images.
+    ///
+    /// The transient images share a single allocation made from `allocator`, whose memory
+    /// blocks are reused from one frame to the next.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::NoSuitableMemoryType`] if the transient images have no
+    /// device-local memory type in common.
+    pub fn compile(
+        mut self,
+        allocator: &Arc<Allocator>,
+        families: GraphQueueFamilies,
+    ) -> Result<CompiledGraph<'a>> {
+        // The swapchain images are presented by a final pass on the graphics queue.
+        let swapchain_images: SmallVec<[_; 1]> = self
+            .images
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| matches!(entry.source, ImageSource::Swapchain(_)))
+            .map(|(index, _)| index)
+            .collect();
+
+        if !swapchain_images.is_empty() {
+            self.passes.push(Pass {
+                name: "present".into(),
+                queue: PassQueue::Graphics,
+                accesses: swapchain_images
+                    .iter()
+                    .map(|&index| PassAccess {
+                        resource: GraphResource::Image(index),
+                        stages: ImageUse::PRESENT.stages,
+                        access: ImageUse::PRESENT.access,
+                        layout: ImageUse::PRESENT.layout,
+                        write: false,
+                    })
+                    .collect(),
+                record: None,
+            });
+        }
+
+        let (passes, culled) = self.cull();
+
+        let compute_family = families
+            .compute
+            .filter(|&compute| compute != families.graphics);
+        let pass_families: Vec<u32> = passes
+            .iter()
+            .map(|pass| match (pass.queue, compute_family) {
+                (PassQueue::AsyncCompute, Some(compute)) => compute,
+                _ => families.graphics,
+            })
+            .collect();
+
+        let submissions = schedule(&passes, &pass_families);
+
+        let mut graph = CompiledGraph {
+            device: allocator.backend().clone(),
+            families,
+            passes: passes
+                .into_iter()
+                .zip(pass_families)
+                .map(|(pass, family)| CompiledPass {
+                    pass,
+                    family,
+                    alias_barrier: false,
+                })
+                .collect(),
+            culled,
+            images: Vec::new(),
+            buffers: self.buffers,
+            submissions,
+            memory: None,
+        };
+
+        graph.create_transient_images(allocator, self.images)?;
+
+        Ok(graph)
+    }
+
+    /// Removes the passes which do not contribute to an output of the graph, and returns the
+    /// remaining passes along with the removed ones.
+    fn cull(&mut self) -> (Vec<Pass<'a>>, Vec<CulledPass>) {
+        let mut needed: Vec<GraphResource> = self
+            .images
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.output)
+            .map(|(index, _)| GraphResource::Image(index))
+            .chain(
+                self.buffers
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| entry.output)
+                    .map(|(index, _)| GraphResource::Buffer(index)),
+            )
+            .collect();
+
+        let mut kept = Vec::new();
+        let mut culled = Vec::new();
+
+        // A pass is needed if it writes a resource needed by an output or by a later pass
+        // that is needed itself.
+        for pass in std::mem::take(&mut self.passes).into_iter().rev() {
+            let is_needed = pass.record.is_none()
+                || pass
+                    .accesses
+                    .iter()
+                    .any(|access| access.write && needed.contains(&access.resource));
+
+            if is_needed {
+                for access in &pass.accesses {
+                    if !needed.contains(&access.resource) {
+                        needed.push(access.resource);
+                    }
+                }
+                kept.push(pass);
+            } else {
+                culled.push(CulledPass {
+                    name: pass.name,
+                    accesses: pass.accesses,
+                });
+            }
+        }
+
+        kept.reverse();
+        culled.reverse();
+        (kept, culled)
+    }
+}
+
+/// A pass being declared in a [`RenderGraph`].
+#[must_use = "the pass is only added to the graph by `PassBuilder::record`"]
+pub struct PassBuilder<'g, 'a> {
+    /// The graph that the pass is added to.
+    graph: &'g mut RenderGraph<'a>,
+    /// The name of the pass.
+    name: String,
+    /// The kind of queue that the pass runs on.
+    queue: PassQueue,
+    /// The resources accessed by the pass.
+    accesses: Vec<PassAccess>,
+}
+
+impl<'a> PassBuilder<'_, 'a> {
+    /// Declares that the pass reads an image.
+    pub fn read_image(self, image: GraphImage, usage: ImageUse) -> Self {
+        self.image_access(image, usage, false)
+    }
+
+    /// Declares that the pass writes an image, possibly reading it as well.
+    pub fn write_image(self, image: GraphImage, usage: ImageUse) -> Self {
+        self.image_access(image, usage, true)
+    }
+
+    /// Declares that the pass reads a buffer.
+    pub fn read_buffer(self, buffer: GraphBuffer, usage: BufferUse) -> Self {
+        self.buffer_access(buffer, usage, false)
+    }
+
+    /// Declares that the pass writes a buffer, possibly reading it as well.
+    pub fn write_buffer(self, buffer: GraphBuffer, usage: BufferUse) -> Self {
+        self.buffer_access(buffer, usage, true)
+    }
+
+    /// Adds the pass to the graph, with the function recording its commands.
+    ///
+    /// The barriers required by the declared accesses are recorded before the function is
+    /// called.
+    pub fn record(self, record: impl FnOnce(&mut PassContext<'_, '_>) + 'a) {
+        self.graph.passes.push(Pass {
+            name: self.name,
+            queue: self.queue,
+            accesses: self.accesses,
+            record: Some(Box::new(record)),
+        });
+    }
+
+    /// Adds an access to an image.
+    fn image_access(mut self, image: GraphImage, usage: ImageUse, write: bool) -> Self {
+        self.accesses.push(PassAccess {
+            resource: GraphResource::Image(image.0),
+            stages: usage.stages,
+            access: usage.access,
+            layout: usage.layout,
+            write,
+        });
+        self
+    }
+
+    /// Adds an access to a buffer.
+    fn buffer_access(mut self, buffer: GraphBuffer, usage: BufferUse, write: bool) -> Self {
+        self.accesses.push(PassAccess {
+            resource: GraphResource::Buffer(buffer.0),
+            stages: usage.stages,
+            access: usage.access,
+            layout: ImageLayout::Undefined,
+            write,
+        });
+        self
+    }
+}
+
+/// The state passed to the recording function of a pass.
+pub struct PassContext<'c, 'p> {
+    /// The command buffer recording the pass.
+    commands: &'c mut CommandBuffer<Recording<'p>>,
+    /// The images of the graph, or `None` for the transient images that were culled.
+    images: &'c [Option<&'c Image>],
+    /// The buffers of the graph.
+    buffers: &'c [BufferEntry<'c>],
+}
+
+impl<'p> PassContext<'_, 'p> {
+    /// Returns the command buffer recording the pass.
+    #[inline(always)]
+    pub fn commands(&mut self) -> &mut CommandBuffer<Recording<'p>> {
+        self.commands
+    }
+
+    /// Returns an image of the graph.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the image is not accessed by any pass that was kept.
+    #[inline]
+    pub fn image(&self, image: GraphImage) -> &Image {
+        self.images[image.0].expect("the image is not used by the graph")
+    }
+
+    /// Returns a buffer of the graph.
+    #[inline]
+    pub fn buffer(&self, buffer: GraphBuffer) -> &Buffer {
+        self.buffers[buffer.0].buffer
+    }
+}
+
+/// A pass removed from a [`RenderGraph`] because it does not contribute to any output.
+struct CulledPass {
+    /// The name of the pass.
+    name: String,
+    /// The resources accessed by the pass.
+    accesses: Vec<PassAccess>,
+}
+
+/// A pass of a [`CompiledGraph`].
+struct CompiledPass<'a> {
+    /// The pass.
+    pass: Pass<'a>,
+    /// The queue family that the pass runs on.
+    family: u32,
+    /// Whether the pass is the first user of a transient image sharing its memory with an
+    /// image used earlier, in which case all the previous accesses must complete before it.
+    alias_barrier: bool,
+}
+
+/// A group of consecutive passes running on the same queue, submitted as a single command
+/// buffer.
+#[derive(Debug, Clone, Default)]
+struct Submission {
+    /// The queue family of the submission.
+    family: u32,
+    /// The indices of the passes of the submission.
+    passes: Vec<usize>,
+    /// The indices of the earlier submissions that this submission waits on.
+    waits: Vec<usize>,
+    /// The resources released to another queue family at the end of the submission, along
+    /// with that queue family and the layout that images are transitioned to.
+    releases: Vec<(GraphResource, u32, ImageLayout)>,
+}
+
+/// Groups the passes into submissions, and computes the dependencies between submissions on
+/// different queues.
+fn schedule(passes: &[Pass], families: &[u32]) -> Vec<Submission> {
+    let mut submissions: Vec<Submission> = Vec::new();
+    let mut last_use: HashMap<GraphResource, (usize, u32)> = HashMap::new();
+
+    for (index, (pass, &family)) in passes.iter().zip(families).enumerate() {
+        if submissions.last().is_none_or(|last| last.family != family) {
+            submissions.push(Submission {
+                family,
+                ..Default::default()
+            });
+        }
+
+        let current = submissions.len() - 1;
+        submissions[current].passes.push(index);
+
+        for access in &pass.accesses {
+            if let Some(&(submission, used_by)) = last_use.get(&access.resource) {
+                if used_by != family {
+                    add_wait(&mut submissions[current], submission);
+
+                    let releases = &mut submissions[submission].releases;
+                    if !releases.iter().any(|release| release.0 == access.resource) {
+                        releases.push((access.resource, family, access.layout));
+                    }
+                }
+            }
+            last_use.insert(access.resource, (current, family));
+        }
+    }
+
+    // The last submission waits for the last submission of the other queue, so that
+    // completing it means that the whole graph has executed.
+    if let Some(last) = submissions.last().map(|last| last.family) {
+        let other = submissions.iter().rposition(|s| s.family != last);
+        if let Some(other) = other {
+            let final_index = submissions.len() - 1;
+            add_wait(&mut submissions[final_index], other);
+        }
+    }
+
+    submissions
+}
+
+/// Makes a submission wait on another one.
+fn add_wait(submission: &mut Submission, wait: usize) {
+    if !submission.waits.contains(&wait) {
+        submission.waits.push(wait);
+    }
+}
+
+/// The first and last pass using an image, along with the queue family of these passes if it
+/// is always the same.
+type Lifetime = (usize, usize, Option<u32>);
+
+/// Computes the lifetime of each image of a graph, or `None` for the images that no pass uses.
+fn image_lifetimes(passes: &[CompiledPass], image_count: usize) -> Vec<Option<Lifetime>> {
+    let mut lifetimes: Vec<Option<Lifetime>> = vec![None; image_count];
+    for (index, pass) in passes.iter().enumerate() {
+        for access in &pass.pass.accesses {
+            if let GraphResource::Image(image) = access.resource {
+                let lifetime = lifetimes[image].get_or_insert((index, index, Some(pass.family)));
+                lifetime.1 = index;
+                if lifetime.2 != Some(pass.family) {
+                    lifetime.2 = None;
+                }
+            }
+        }
+    }
+    lifetimes
+}
+
+/// Places transient images within a shared range of memory, and returns the index, offset and
+/// size of each of them.
+///
+/// Larger images are placed first, at the lowest offset which does not overlap with the images
+/// already placed whose lifetimes overlap with theirs. Images used on multiple queues, or on
+/// different queues, never share memory.
+fn place_transients(
+    transients: &[(usize, MemoryRequirements)],
+    lifetimes: &[Option<Lifetime>],
+) -> Vec<(usize, u64, u64)> {
+    let mut sorted: Vec<_> = transients.to_vec();
+    sorted.sort_by_key(|(_, req)| std::cmp::Reverse(req.size));
+
+    let conflicts = |a: usize, b: usize| {
+        let (a_first, a_last, a_family) = lifetimes[a].unwrap();
+        let (b_first, b_last, b_family) = lifetimes[b].unwrap();
+        let overlap = a_first <= b_last && b_first <= a_last;
+        overlap || a_family.is_none() || a_family != b_family
+    };
+
+    let mut placed: Vec<(usize, u64, u64)> = Vec::new();
+
+    for (index, req) in sorted {
+        let blockers: SmallVec<[_; 8]> = placed
+            .iter()
+            .filter(|(other, ..)| conflicts(index, *other))
+            .map(|&(_, offset, size)| (offset, offset + size))
+            .collect();
+
+        let mut offset = 0;
+        while let Some(&(_, end)) = blockers
+            .iter()
+            .find(|&&(start, end)| offset < end && start < offset + req.size)
+        {
+            offset = end.next_multiple_of(req.alignment.max(1));
+        }
+
+        placed.push((index, offset, req.size));
+    }
+
+    placed
+}
+
+/// Returns whether a placed image shares memory with an image whose last use precedes its
+/// first use.
+fn aliases_earlier_image(
+    placed: &[(usize, u64, u64)],
+    lifetimes: &[Option<Lifetime>],
+    index: usize,
+) -> bool {
+    let &(_, offset, size) = placed.iter().find(|(other, ..)| *other == index).unwrap();
+    let (first, ..) = lifetimes[index].unwrap();
+    placed.iter().any(|&(other, other_offset, other_size)| {
+        let (.., other_last, _) = lifetimes[other].unwrap();
+        other != index
+            && other_last < first
+            && other_offset < offset + size
+            && offset < other_offset + other_size
+    })
+}
+
+/// An image of a [`CompiledGraph`].
+enum CompiledImage<'a> {
+    /// The image was created outside of the graph.
+    Imported(&'a Image),
+    /// The image belongs to a swapchain.
+    Swapchain(&'a Image),
+    /// The image was created by the graph and bound at `offset` within its memory.
+    Transient {
+        image: Image,
+        offset: u64,
+        size: u64,
+    },
+    /// The image was created by the graph, but no pass uses it.
+    Unused,
+}
+
+impl CompiledImage<'_> {
+    /// Returns the image, if it exists.
+    fn get(&self) -> Option<&Image> {
+        match self {
+            Self::Imported(image) | Self::Swapchain(image) => Some(image),
+            Self::Transient { image, .. } => Some(image),
+            Self::Unused => None,
+        }
+    }
+}
+
+/// A [`RenderGraph`] compiled into submissions, ready to be executed.
+pub struct CompiledGraph<'a> {
+    /// The device that owns the transient images.
+    device: Arc<Device>,
+    /// The queue families that the graph was compiled for.
+    families: GraphQueueFamilies,
+    /// The passes that were kept, in execution order.
+    passes: Vec<CompiledPass<'a>>,
+    /// The passes that were culled.
+    culled: Vec<CulledPass>,
+    /// The images of the graph, along with their name.
+    images: Vec<(String, CompiledImage<'a>)>,
+    /// The buffers of the graph.
+    buffers: Vec<BufferEntry<'a>>,
+    /// The submissions of the graph, in submission order.
+    submissions: Vec<Submission>,
+    /// The allocation shared by the transient images.
+    memory: Option<Arc<Allocation>>,
+}
+
+/// The parameters passed to [`CompiledGraph::execute`].
+pub struct GraphExecuteDesc<'e> {
+    /// The queue of the graphics queue family.
+    pub graphics_queue: &'e mut Queue,
+    /// The queue of the compute queue family, if the graph was compiled with one.
+    pub compute_queue: Option<&'e mut Queue>,
+    /// The pool that the command buffers of the graphics queue are allocated from.
+    pub graphics_pool: &'e CommandPool,
+    /// The pool that the command buffers of the compute queue are allocated from.
+    pub compute_pool: Option<&'e CommandPool>,
+    /// The tracker which holds the state of the imported resources.
+    pub tracker: &'e mut StateTracker,
+    /// The semaphores waited on by the first submission to the graphics queue, such as the
+    /// semaphore signaled when the swapchain image is acquired.
+    pub wait_semaphores: &'e [(&'e Semaphore, PipelineStages)],
+    /// The semaphores signaled once the graph has executed, such as the semaphore waited on
+    /// by the presentation.
+    pub signal_semaphores: &'e [&'e Semaphore],
+    /// The fence signaled once the graph has executed.
+    pub fence: Option<&'e mut Fence>,
+}
+
+/// The objects that must be kept alive while an executed graph is pending execution.
+pub struct GraphExecution<'a> {
+    /// The images of the graph, including the transient images.
+    _images: Vec<(String, CompiledImage<'a>)>,
+    /// The allocation shared by the transient images.
+    _memory: Option<Arc<Allocation>>,
+    /// The command buffers of the submissions.
+    _command_buffers: Vec<CommandBuffer<Executable>>,
+    /// The semaphores between submissions.
+    _semaphores: Vec<Semaphore>,
+}
+
+impl<'a> CompiledGraph<'a> {
+    /// Creates the transient images of the graph, sharing memory between the images whose
+    /// lifetimes do not overlap.
+    fn create_transient_images(
+        &mut self,
+        allocator: &Arc<Allocator>,
+        entries: Vec<ImageEntry<'a>>,
+    ) -> Result<()> {
+        let lifetimes = image_lifetimes(&self.passes, entries.len());
+
+        let mut transients = Vec::new();
+        for (index, entry) in entries.into_iter().enumerate() {
+            let image = match entry.source {
+                ImageSource::Imported(image) => CompiledImage::Imported(image),
+                ImageSource::Swapchain(image) => CompiledImage::Swapchain(image),
+                ImageSource::Transient(_) if lifetimes[index].is_none() => CompiledImage::Unused,
+                ImageSource::Transient(desc) => {
+                    let image = Image::new(
+                        self.device.clone(),
+                        ImageDesc {
+                            flags: ImageFlags::empty(),
+                            image_type: desc.image_type,
+                            format: desc.format,
+                            extent: desc.extent,
+                            mip_levels: desc.mip_levels,
+                            array_layers: desc.array_layers,
+                            samples: desc.samples,
+                            tiling: ImageTiling::Optimal,
+                            usage: desc.usage,
+                            sharing_mode: SharingMode::Exclusive,
+                            initial_layout: ImageLayout::Undefined,
+                        },
+                    )?;
+                    transients.push((index, image.memory_requirements()));
+                    CompiledImage::Transient {
+                        image,
+                        offset: 0,
+                        size: 0,
+                    }
+                }
+            };
+            self.images.push((entry.name, image));
+        }
+
+        if transients.is_empty() {
+            return Ok(());
+        }
+
+        let placed = place_transients(&transients, &lifetimes);
+
+        let requirements = MemoryRequirements {
+            size: placed
+                .iter()
+                .map(|&(_, offset, size)| offset + size)
+                .max()
+                .unwrap(),
+            alignment: transients
+                .iter()
+                .map(|(_, req)| req.alignment)
+                .max()
+                .unwrap(),
+            memory_type_bits: transients
+                .iter()
+                .fold(u32::MAX, |bits, (_, req)| bits & req.memory_type_bits),
+        };
+        let memory = Arc::new(allocator.allocate(&AllocationDesc {
+            requirements,
+            required: MemoryProperties::DEVICE_LOCAL,
+            preferred: MemoryProperties::empty(),
+            tiling: AllocationTiling::Optimal,
+            dedicated: None,
+        })?);
+
+        for &(index, bound_offset, bound_size) in &placed {
+            if let CompiledImage::Transient {
+                image,
+                offset,
+                size,
+            } = &mut self.images[index].1
+            {
+                image.bind_shared_allocation(memory.clone(), bound_offset)?;
+                *offset = bound_offset;
+                *size = bound_size;
+            }
+
+            // An image reusing the memory of an earlier image must wait for its accesses.
+            if aliases_earlier_image(&placed, &lifetimes, index) {
+                let (first, ..) = lifetimes[index].unwrap();
+                self.passes[first].alias_barrier = true;
+            }
+        }
+
+        self.memory = Some(memory);
+        Ok(())
+    }
+
+    /// Records and submits the passes of the graph.
+    ///
+    /// The returned object must be kept alive until the graph has finished executing, which
+    /// is signaled by the fence and the semaphores of `desc`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::MissingQueue`] if the graph uses the compute queue but
+    /// no compute queue or pool is provided.
+    ///
+    /// Every command buffer is recorded before the first submission. If recording fails,
+    /// nothing is submitted, but the state of the imported resources in the tracker already
+    /// reflects the commands recorded so far. If a submission fails after others succeeded,
+    /// the resources of the graph are leaked, since the earlier submissions may still use
+    /// them.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the queues or the pools do not belong to the queue families
+    /// that the graph was compiled for.
+    ///
+    /// # Safety
+    ///
+    /// The passes must record valid commands, and the resources imported into the graph must
+    /// outlive its execution. The state of the imported resources in the tracker must match
+    /// the work submitted so far.
+    pub unsafe fn execute(self, desc: GraphExecuteDesc) -> Result<GraphExecution<'a>> {
+        let GraphExecuteDesc {
+            graphics_queue,
+            mut compute_queue,
+            graphics_pool,
+            compute_pool,
+            tracker,
+            wait_semaphores,
+            signal_semaphores,
+            mut fence,
+        } = desc;
+
+        assert_eq!(graphics_queue.family(), self.families.graphics);
+        assert_eq!(graphics_pool.queue_family(), self.families.graphics);
+
+        let uses_compute = self
+            .submissions
+            .iter()
+            .any(|submission| submission.family != self.families.graphics);
+        if uses_compute && (compute_queue.is_none() || compute_pool.is_none()) {
+            return Err(Error::MissingQueue);
+        }
+
+        let Self {
+            device,
+            mut passes,
+            images,
+            buffers,
+            submissions,
+            memory,
+            ..
+        } = self;
+
+        // Transient and swapchain images start with undefined contents.
+        for (_, image) in &images {
+            if let CompiledImage::Swapchain(_) | CompiledImage::Transient { .. } = image {
+                let image = image.get().unwrap();
+                tracker.register_image(image.handle(), TrackedImageDesc::new(image));
+            }
+        }
+
+        let semaphores: Vec<Vec<Semaphore>> = submissions
+            .iter()
+            .map(|submission| {
+                submission
+                    .waits
+                    .iter()
+                    .map(|_| Semaphore::new(device.clone()))
+                    .collect::<Result<_>>()
+            })
+            .collect::<Result<_>>()?;
+
+        // Every command buffer is recorded before the first submission, so that an error
+        // while recording never leaves submitted work using resources that are then dropped.
+        let recorded = (|| -> Result<Vec<_>> {
+            let image_refs: Vec<Option<&Image>> =
+                images.iter().map(|(_, image)| image.get()).collect();
+            let mut command_buffers = Vec::with_capacity(submissions.len());
+
+            for submission in &submissions {
+                let pool = if submission.family == self.families.graphics {
+                    graphics_pool
+                } else {
+                    // The compute pool was checked to be provided if the graph uses it.
+                    compute_pool.unwrap()
+                };
+                assert_eq!(pool.queue_family(), submission.family);
+
+                let mut commands = pool
+                    .allocate()?
+                    .begin(pool, CommandBufferUsages::ONE_TIME_SUBMIT)?;
+
+                {
+                    let mut recorder = TrackedCommandBuffer::new(&mut commands, tracker);
+
+                    for &pass_index in &submission.passes {
+                        let CompiledPass {
+                            pass,
+                            alias_barrier,
+                            ..
+                        } = &mut passes[pass_index];
+
+                        if *alias_barrier {
+                            recorder.commands().pipeline_barrier2(
+                                &[MemoryBarrier2 {
+                                    src_stages: PipelineStages2::ALL_COMMANDS,
+                                    src_access: Accesses2::MEMORY_WRITE,
+                                    dst_stages: PipelineStages2::ALL_COMMANDS,
+                                    dst_access: Accesses2::MEMORY_READ | Accesses2::MEMORY_WRITE,
+                                }],
+                                &[],
+                                &[],
+                            );
+                        }
+
+                        for access in &pass.accesses {
+                            match access.resource {
+                                GraphResource::Image(image) => {
+                                    let image = image_refs[image].unwrap();
+                                    recorder.use_image(
+                                        image,
+                                        &ImageSubresourceRange::whole(image.format().aspects()),
+                                        ImageUse {
+                                            stages: access.stages,
+                                            access: access.access,
+                                            layout: access.layout,
+                                        },
+                                    );
+                                }
+                                GraphResource::Buffer(buffer) => {
+                                    recorder.use_buffer(
+                                        buffers[buffer].buffer,
+                                        BufferUse {
+                                            stages: access.stages,
+                                            access: access.access,
+                                        },
+                                    );
+                                }
+                            }
+                        }
+
+                        let commands = recorder.commands();
+                        if let Some(record) = pass.record.take() {
+                            record(&mut PassContext {
+                                commands,
+                                images: &image_refs,
+                                buffers: &buffers,
+                            });
+                        }
+                    }
+
+                    for &(resource, dst_family, layout) in &submission.releases {
+                        match resource {
+                            GraphResource::Image(image) => {
+                                let image = image_refs[image].unwrap();
+                                recorder.release_image(
+                                    image,
+                                    &ImageSubresourceRange::whole(image.format().aspects()),
+                                    dst_family,
+                                    layout,
+                                );
+                            }
+                            GraphResource::Buffer(buffer) => {
+                                recorder.release_buffer(buffers[buffer].buffer, dst_family);
+                            }
+                        }
+                    }
+                }
+
+                command_buffers.push(commands.end()?);
+            }
+
+            Ok(command_buffers)
+        })();
+
+        // The handles of the transient images are reused once they are destroyed.
+        for (_, image) in &images {
+            if let CompiledImage::Transient { image, .. } = image {
+                tracker.forget_image(image.handle());
+            }
+        }
+
+        let execution = GraphExecution {
+            _images: images,
+            _memory: memory,
+            _command_buffers: recorded?,
+            _semaphores: semaphores.into_iter().flatten().collect(),
+        };
+
+        let first_graphics = submissions
+            .iter()
+            .position(|submission| submission.family == self.families.graphics);
+
+        // The semaphores waited on by each submission, in the order of their `waits`.
+        let mut semaphores = execution._semaphores.iter();
+        let semaphores: Vec<Vec<&Semaphore>> = submissions
+            .iter()
+            .map(|submission| semaphores.by_ref().take(submission.waits.len()).collect())
+            .collect();
+
+        let mut failure = None;
+        for (index, (submission, commands)) in submissions
+            .iter()
+            .zip(&execution._command_buffers)
+            .enumerate()
+        {
+            // Submission `index` signals one semaphore for each later submission waiting on it.
+            let waits: SmallVec<[_; 4]> = submission
+                .waits
+                .iter()
+                .zip(&semaphores[index])
+                .map(|(_, &semaphore)| (semaphore, PipelineStages::ALL_COMMANDS))
+                .chain(
+                    (Some(index) == first_graphics)
+                        .then_some(wait_semaphores)
+                        .into_iter()
+                        .flatten()
+                        .map(|&(semaphore, stages)| (semaphore, stages)),
+                )
+                .collect();
+
+            let is_last = index == submissions.len() - 1;
+            let signals: SmallVec<[_; 4]> = submissions
+                .iter()
+                .zip(&semaphores)
+                .skip(index + 1)
+                .flat_map(|(later, later_semaphores)| {
+                    later
+                        .waits
+                        .iter()
+                        .zip(later_semaphores)
+                        .filter(|&(&wait, _)| wait == index)
+                        .map(|(_, &semaphore)| semaphore)
+                })
+                .chain(
+                    is_last
+                        .then_some(signal_semaphores)
+                        .into_iter()
+                        .flatten()
+                        .copied(),
+                )
+                .collect();
+
+            let queue = if submission.family == self.families.graphics {
+                &mut *graphics_queue
+            } else {
+                compute_queue.as_deref_mut().unwrap()
+            };
+            assert_eq!(queue.family(), submission.family);
+
+            let fence = if is_last { fence.take() } else { None };
+
+            let result = queue.submit(
+                &[SubmitDesc {
+                    wait_semaphores: &waits,
+                    command_buffers: &[commands],
+                    signal_semaphores: &signals,
+                    ..Default::default()
+                }],
+                fence,
+            );
+
+            if let Err(error) = result {
+                failure = Some((index, error));
+                break;
+            }
+        }
+
+        match failure {
+            // The earlier submissions may still be using the resources of the graph, which are
+            // leaked rather than destroyed while in use.
+            Some((index, error)) => {
+                if index > 0 {
+                    std::mem::forget(execution);
+                }
+                Err(error)
+            }
+            None => Ok(execution),
+        }
+    }
+
+    /// Returns the number of submissions that the graph was compiled into.
+    #[inline(always)]
+    pub fn submission_count(&self) -> usize {
+        self.submissions.len()
+    }
+
+    /// Returns the names of the passes that were culled.
+    pub fn culled_passes(&self) -> impl Iterator<Item = &str> {
+        self.culled.iter().map(|pass| pass.name.as_str())
+    }
+
+    /// Returns the size of the memory shared by the transient images, in bytes.
+    pub fn transient_memory_size(&self) -> u64 {
+        self.memory.as_ref().map_or(0, |memory| memory.size())
+    }
+
+    /// Returns the device that the graph was compiled for.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns a description of the compiled graph in the Graphviz DOT language.
+    ///
+    /// Submissions are drawn as clusters of passes, and semaphores between submissions as
+    /// dashed edges. Culled passes are grayed out, and transient images are labeled with the
+    /// range of memory they are bound to.
+    pub fn to_dot(&self) -> String {
+        dot(
+            self.families,
+            &self.passes,
+            &self.culled,
+            &self.images,
+            &self.buffers,
+            &self.submissions,
+        )
+    }
+}
+
+/// Describes the parts of a compiled graph in the Graphviz DOT language.
+fn dot(
+    families: GraphQueueFamilies,
+    passes: &[CompiledPass],
+    culled: &[CulledPass],
+    images: &[(String, CompiledImage)],
+    buffers: &[BufferEntry],
+    submissions: &[Submission],
+) -> String {
+    let mut dot = String::new();
+
+    writeln!(dot, "digraph render_graph {{").unwrap();
+    writeln!(dot, "    rankdir=LR;").unwrap();
+    writeln!(dot, "    node [fontname=\"monospace\"];").unwrap();
+
+    for (index, submission) in submissions.iter().enumerate() {
+        let queue = if submission.family == families.graphics {
+            "graphics"
+        } else {
+            "compute"
+        };
+
+        writeln!(dot, "    subgraph cluster_{} {{", index).unwrap();
+        writeln!(
+            dot,
+            "        label=\"submission {} ({} queue, family {})\";",
+            index, queue, submission.family
+        )
+        .unwrap();
+        for &pass in &submission.passes {
+            writeln!(
+                dot,
+                "        pass_{} [label=\"{}\", shape=box];",
+                pass,
+                escape(&passes[pass].pass.name)
+            )
+            .unwrap();
+        }
+        writeln!(dot, "    }}").unwrap();
+    }
+
+    for (index, pass) in culled.iter().enumerate() {
+        writeln!(
+            dot,
+            "    culled_{} [label=\"{} (culled)\", shape=box, style=dashed, color=gray, fontcolor=gray];",
+            index,
+            escape(&pass.name)
+        )
+        .unwrap();
+    }
+
+    for (index, (name, image)) in images.iter().enumerate() {
+        let details = match image {
+            CompiledImage::Imported(_) => "imported".to_string(),
+            CompiledImage::Swapchain(_) => "swapchain".to_string(),
+            CompiledImage::Transient { offset, size, .. } => {
+                format!("transient, {} bytes at {}", size, offset)
+            }
+            CompiledImage::Unused => "transient, unused".to_string(),
+        };
+        writeln!(
+            dot,
+            "    image_{} [label=\"{}\\n{}\", shape=ellipse];",
+            index,
+            escape(name),
+            details
+        )
+        .unwrap();
+    }
+
+    for (index, buffer) in buffers.iter().enumerate() {
+        writeln!(
+            dot,
+            "    buffer_{} [label=\"{}\", shape=ellipse];",
+            index,
+            escape(&buffer.name)
+        )
+        .unwrap();
+    }
+
+    let kept = passes
+        .iter()
+        .enumerate()
+        .map(|(index, pass)| (format!("pass_{}", index), &pass.pass.accesses, ""));
+    let culled = culled
+        .iter()
+        .enumerate()
+        .map(|(index, pass)| (format!("culled_{}", index), &pass.accesses, ", color=gray"));
+
+    for (node, accesses, style) in kept.chain(culled) {
+        for access in accesses {
+            let resource = match access.resource {
+                GraphResource::Image(index) => format!("image_{}", index),
+                GraphResource::Buffer(index) => format!("buffer_{}", index),
+            };
+            if access.write {
+                writeln!(
+                    dot,
+                    "    {} -> {} [label=\"write\"{}];",
+                    node, resource, style
+                )
+                .unwrap();
+            } else {
+                writeln!(
+                    dot,
+                    "    {} -> {} [label=\"read\"{}];",
+                    resource, node, style
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    for submission in submissions {
+        for &wait in &submission.waits {
+            let src = submissions[wait].passes.last().unwrap();
+            let dst = submission.passes.first().unwrap();
+            writeln!(
+                dot,
+                "    pass_{} -> pass_{} [style=dashed, label=\"semaphore\", constraint=false];",
+                src, dst
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(dot, "}}").unwrap();
+    dot
+}
+
+/// Escapes a string for use within a quoted DOT label.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The parameters of the transient images declared by the tests.
+    const DESC: TransientImageDesc = TransientImageDesc {
+        image_type: ImageType::D2,
+        format: Format::Rgba8Unorm,
+        extent: [64, 64, 1],
+        mip_levels: 1,
+        array_layers: 1,
+        samples: SampleCount::X1,
+        usage: ImageUsages::COLOR_ATTACHMENT.union(ImageUsages::SAMPLED),
+    };
+
+    /// Returns the memory requirements of an image of `size` bytes.
+    fn requirements(size: u64, alignment: u64) -> MemoryRequirements {
+        MemoryRequirements {
+            size,
+            alignment,
+            memory_type_bits: u32::MAX,
+        }
+    }
+
+    /// Returns a graph of four passes, where the two middle ones run on the compute queue:
+    /// `draw` writes `a`, `blur` reads `a` and writes `b`, `sharpen` writes `b`, and `compose`
+    /// reads `b` and writes `c`, the output.
+    fn async_graph() -> RenderGraph<'static> {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_image("a", DESC);
+        let b = graph.create_image("b", DESC);
+        let c = graph.create_image("c", DESC);
+        graph.mark_image_output(c);
+
+        graph
+            .add_pass("draw", PassQueue::Graphics)
+            .write_image(a, ImageUse::COLOR_ATTACHMENT)
+            .record(|_| {});
+        graph
+            .add_pass("blur", PassQueue::AsyncCompute)
+            .read_image(a, ImageUse::SAMPLED)
+            .write_image(b, ImageUse::TRANSFER_DST)
+            .record(|_| {});
+        graph
+            .add_pass("sharpen", PassQueue::AsyncCompute)
+            .write_image(b, ImageUse::TRANSFER_DST)
+            .record(|_| {});
+        graph
+            .add_pass("compose", PassQueue::Graphics)
+            .read_image(b, ImageUse::SAMPLED)
+            .write_image(c, ImageUse::COLOR_ATTACHMENT)
+            .record(|_| {});
+        graph
+    }
+
+    #[test]
+    fn culls_passes_without_outputs() {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_image("a", DESC);
+        let b = graph.create_image("b", DESC);
+        let c = graph.create_image("c", DESC);
+        graph.mark_image_output(c);
+
+        graph
+            .add_pass("write a", PassQueue::Graphics)
+            .write_image(a, ImageUse::COLOR_ATTACHMENT)
+            .record(|_| {});
+        graph
+            .add_pass("write b", PassQueue::Graphics)
+            .read_image(a, ImageUse::SAMPLED)
+            .write_image(b, ImageUse::COLOR_ATTACHMENT)
+            .record(|_| {});
+        graph
+            .add_pass("read b", PassQueue::Graphics)
+            .read_image(b, ImageUse::SAMPLED)
+            .record(|_| {});
+        graph
+            .add_pass("write c", PassQueue::Graphics)
+            .read_image(a, ImageUse::SAMPLED)
+            .write_image(c, ImageUse::COLOR_ATTACHMENT)
+            .record(|_| {});
+
+        let (kept, culled) = graph.cull();
+
+        let kept: Vec<_> = kept.iter().map(|pass| pass.name.as_str()).collect();
+        let culled: Vec<_> = culled.iter().map(|pass| pass.name.as_str()).collect();
+        assert_eq!(kept, ["write a", "write c"]);
+        assert_eq!(culled, ["write b", "read b"]);
+    }
+
+    #[test]
+    fn schedules_single_queue_as_one_submission() {
+        let graph = async_graph();
+
+        let submissions = schedule(&graph.passes, &[0, 0, 0, 0]);
+
+        assert_eq!(submissions.len(), 1);
+        assert_eq!(submissions[0].passes, [0, 1, 2, 3]);
+        assert!(submissions[0].waits.is_empty());
+        assert!(submissions[0].releases.is_empty());
+    }
+
+    #[test]
+    fn schedules_async_compute_with_semaphores() {
+        let graph = async_graph();
+
+        let submissions = schedule(&graph.passes, &[0, 1, 1, 0]);
+
+        assert_eq!(submissions.len(), 3);
+
+        assert_eq!(submissions[0].family, 0);
+        assert_eq!(submissions[0].passes, [0]);
+        assert!(submissions[0].waits.is_empty());
+        assert_eq!(
+            submissions[0].releases,
+            [(GraphResource::Image(0), 1, ImageUse::SAMPLED.layout)]
+        );
+
+        // Consecutive passes on the same queue are grouped.
+        assert_eq!(submissions[1].family, 1);
+        assert_eq!(submissions[1].passes, [1, 2]);
+        assert_eq!(submissions[1].waits, [0]);
+        assert_eq!(
+            submissions[1].releases,
+            [(GraphResource::Image(1), 0, ImageUse::SAMPLED.layout)]
+        );
+
+        // The final wait on the compute queue is not duplicated.
+        assert_eq!(submissions[2].family, 0);
+        assert_eq!(submissions[2].passes, [3]);
+        assert_eq!(submissions[2].waits, [1]);
+        assert!(submissions[2].releases.is_empty());
+    }
+
+    #[test]
+    fn last_submission_waits_for_other_queue() {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_image("a", DESC);
+        let b = graph.create_image("b", DESC);
+
+        graph
+            .add_pass("draw", PassQueue::Graphics)
+            .write_image(a, ImageUse::COLOR_ATTACHMENT)
+            .record(|_| {});
+        graph
+            .add_pass("simulate", PassQueue::AsyncCompute)
+            .write_image(b, ImageUse::TRANSFER_DST)
+            .record(|_| {});
+        graph
+            .add_pass("post", PassQueue::Graphics)
+            .write_image(a, ImageUse::COLOR_ATTACHMENT)
+            .record(|_| {});
+
+        let submissions = schedule(&graph.passes, &[0, 1, 0]);
+
+        // The passes share no resource, but completing the graph must still complete the
+        // compute submission.
+        assert_eq!(submissions.len(), 3);
+        assert!(submissions[1].waits.is_empty());
+        assert_eq!(submissions[2].waits, [1]);
+        assert!(submissions.iter().all(|s| s.releases.is_empty()));
+    }
+
+    #[test]
+    fn places_transients_without_overlapping_lifetimes() {
+        let lifetimes = [
+            Some((0, 1, Some(0))),
+            Some((2, 3, Some(0))),
+            Some((1, 2, Some(0))),
+            Some((0, 0, None)),
+            Some((3, 3, Some(1))),
+        ];
+        let transients = [
+            (0, requirements(256, 256)),
+            (1, requirements(128, 128)),
+            (2, requirements(64, 256)),
+            (3, requirements(32, 64)),
+            (4, requirements(16, 16)),
+        ];
+
+        let mut placed = place_transients(&transients, &lifetimes);
+        placed.sort();
+
+        assert_eq!(
+            placed,
+            [
+                // The largest image is placed first.
+                (0, 0, 256),
+                // The lifetime of this image follows the one of image 0, so it reuses its
+                // memory.
+                (1, 0, 128),
+                // This image is alive while images 0 and 1 are, and is aligned after them.
+                (2, 256, 64),
+                // Images used on several queues never share memory.
+                (3, 320, 32),
+                // Neither do images used on different queues.
+                (4, 352, 16),
+            ]
+        );
+
+        assert!(!aliases_earlier_image(&placed, &lifetimes, 0));
+        assert!(aliases_earlier_image(&placed, &lifetimes, 1));
+        assert!(!aliases_earlier_image(&placed, &lifetimes, 2));
+        assert!(!aliases_earlier_image(&placed, &lifetimes, 3));
+        assert!(!aliases_earlier_image(&placed, &lifetimes, 4));
+    }
+
+    #[test]
+    fn dot_shows_submissions_culled_passes_and_semaphores() {
+        let mut graph = async_graph();
+        let unused = graph.create_image("\"unused\"", DESC);
+        graph
+            .add_pass("debug", PassQueue::Graphics)
+            .write_image(unused, ImageUse::COLOR_ATTACHMENT)
+            .record(|_| {});
+
+        let (passes, culled) = graph.cull();
+        let families = [0, 1, 1, 0];
+        let submissions = schedule(&passes, &families);
+        let passes: Vec<_> = passes
+            .into_iter()
+            .zip(families)
+            .map(|(pass, family)| CompiledPass {
+                pass,
+                family,
+                alias_barrier: false,
+            })
+            .collect();
+        let images: Vec<_> = graph
+            .images
+            .iter()
+            .map(|entry| (entry.name.clone(), CompiledImage::Unused))
+            .collect();
+
+        let dot = dot(
+            GraphQueueFamilies {
+                graphics: 0,
+                compute: Some(1),
+            },
+            &passes,
+            &culled,
+            &images,
+            &[],
+            &submissions,
+        );
+
+        assert!(dot.starts_with("digraph render_graph {"));
+        assert!(dot.contains("label=\"submission 0 (graphics queue, family 0)\";"));
+        assert!(dot.contains("label=\"submission 1 (compute queue, family 1)\";"));
+        assert!(dot.contains("pass_2 [label=\"sharpen\", shape=box];"));
+        assert!(dot.contains("culled_0 [label=\"debug (culled)\""));
+        assert!(dot.contains("image_3 [label=\"\\\"unused\\\"\\ntransient, unused\""));
+        assert!(dot.contains("pass_0 -> image_0 [label=\"write\"];"));
+        assert!(dot.contains("image_0 -> pass_1 [label=\"read\"];"));
+        assert!(dot.contains("culled_0 -> image_3 [label=\"write\", color=gray];"));
+        assert!(dot.contains("pass_0 -> pass_1 [style=dashed"));
+        assert!(dot.contains("pass_2 -> pass_3 [style=dashed"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+}
@@ -0,0 +1,927 @@
+use std::sync::Arc;
+
+use ash::vk;
+use smallvec::SmallVec;
+
+use crate::{
+    Accesses, AttachmentLoadOp, AttachmentStoreOp, ClearColor, CommandBuffer, Device,
+    DeviceFeatures, Error, Format, ImageFlags, ImageLayout, ImageUsage, ImageUsages, ImageView,
+    PipelineStages, Recording, Rect, Result, SampleCount,
+};
+
+/// Describes an attachment of a [`RenderPass`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkAttachmentDescription")]
+pub struct AttachmentDesc {
+    /// The format of the views used for the attachment.
+    pub format: Format,
+    /// The number of samples of the views used for the attachment.
+    pub samples: SampleCount,
+    /// What happens to the color or depth contents at the beginning of the first subpass that
+    /// uses the attachment.
+    pub load_op: AttachmentLoadOp,
+    /// What happens to the color or depth contents at the end of the last subpass that uses
+    /// the attachment.
+    pub store_op: AttachmentStoreOp,
+    /// What happens to the stencil contents at the beginning of the first subpass that uses
+    /// the attachment.
+    pub stencil_load_op: AttachmentLoadOp,
+    /// What happens to the stencil contents at the end of the last subpass that uses the
+    /// attachment.
+    pub stencil_store_op: AttachmentStoreOp,
+    /// The layout of the attachment when the render pass instance begins.
+    pub initial_layout: ImageLayout,
+    /// The layout that the attachment is transitioned to when the render pass instance ends.
+    pub final_layout: ImageLayout,
+}
+
+impl AttachmentDesc {
+    /// Creates a single-sampled attachment whose stencil contents, if any, are not needed.
+    pub fn new(
+        format: Format,
+        load_op: AttachmentLoadOp,
+        store_op: AttachmentStoreOp,
+        initial_layout: ImageLayout,
+        final_layout: ImageLayout,
+    ) -> Self {
+        Self {
+            format,
+            samples: SampleCount::X1,
+            load_op,
+            store_op,
+            stencil_load_op: AttachmentLoadOp::DontCare,
+            stencil_store_op: AttachmentStoreOp::DontCare,
+            initial_layout,
+            final_layout,
+        }
+    }
+
+    /// Converts this description into its raw Vulkan representation.
+    fn to_raw(self) -> vk::AttachmentDescription {
+        vk::AttachmentDescription {
+            flags: vk::AttachmentDescriptionFlags::empty(),
+            format: vk::Format::from_raw(self.format as i32),
+            samples: vk::SampleCountFlags::from_raw(self.samples as u32),
+            load_op: vk::AttachmentLoadOp::from_raw(self.load_op as i32),
+            store_op: vk::AttachmentStoreOp::from_raw(self.store_op as i32),
+            stencil_load_op: vk::AttachmentLoadOp::from_raw(self.stencil_load_op as i32),
+            stencil_store_op: vk::AttachmentStoreOp::from_raw(self.stencil_store_op as i32),
+            initial_layout: vk::ImageLayout::from_raw(self.initial_layout as i32),
+            final_layout: vk::ImageLayout::from_raw(self.final_layout as i32),
+        }
+    }
+}
+
+/// A reference from a subpass to an attachment of its [`RenderPass`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkAttachmentReference")]
+pub struct AttachmentRef {
+    /// The index of the attachment in the render pass.
+    pub attachment: u32,
+    /// The layout of the attachment during the subpass.
+    pub layout: ImageLayout,
+}
+
+/// Converts an optional attachment reference into its raw Vulkan representation.
+fn attachment_ref_to_raw(reference: Option<AttachmentRef>) -> vk::AttachmentReference {
+    match reference {
+        Some(reference) => vk::AttachmentReference {
+            attachment: reference.attachment,
+            layout: vk::ImageLayout::from_raw(reference.layout as i32),
+        },
+        None => vk::AttachmentReference {
+            attachment: vk::ATTACHMENT_UNUSED,
+            layout: vk::ImageLayout::UNDEFINED,
+        },
+    }
+}
+
+/// Describes a subpass of a [`RenderPass`].
+///
+/// Unused entries of the attachment lists are `None`, which keeps the indices of the
+/// following entries stable.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[doc(alias = "VkSubpassDescription")]
+pub struct SubpassDesc {
+    /// The attachments read by the fragment shader, whose indices match the
+    /// `input_attachment_index` decorations.
+    ///
+    /// The images of these attachments must have the [`ImageUsage::InputAttachment`] usage.
+    pub input_attachments: Vec<Option<AttachmentRef>>,
+    /// The color attachments, whose indices match the fragment shader outputs.
+    pub color_attachments: Vec<Option<AttachmentRef>>,
+    /// The attachments that the color attachments are resolved into at the end of the
+    /// subpass, which is either empty or as long as `color_attachments`.
+    pub resolve_attachments: Vec<Option<AttachmentRef>>,
+    /// The depth/stencil attachment, if any.
+    pub depth_stencil_attachment: Option<AttachmentRef>,
+    /// The attachments which are not used by the subpass, but whose contents must be
+    /// preserved through it.
+    pub preserve_attachments: Vec<u32>,
+}
+
+impl SubpassDesc {
+    /// Returns an iterator over the attachments used by this subpass, along with the usage
+    /// that they require.
+    fn used_attachments(&self) -> impl Iterator<Item = (u32, ImageUsage)> + '_ {
+        let input = self
+            .input_attachments
+            .iter()
+            .flatten()
+            .map(|reference| (reference.attachment, ImageUsage::InputAttachment));
+        let color = self
+            .color_attachments
+            .iter()
+            .chain(&self.resolve_attachments)
+            .flatten()
+            .map(|reference| (reference.attachment, ImageUsage::ColorAttachment));
+        let depth_stencil = self
+            .depth_stencil_attachment
+            .iter()
+            .map(|reference| (reference.attachment, ImageUsage::DepthStencilAttachment));
+
+        input.chain(color).chain(depth_stencil)
+    }
+}
+
+/// A dependency between two subpasses of a [`RenderPass`], or between a subpass and the
+/// commands recorded outside of the render pass instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkSubpassDependency")]
+pub struct SubpassDependency {
+    /// The index of the first subpass, or `None` for the commands recorded before the render
+    /// pass instance.
+    pub src_subpass: Option<u32>,
+    /// The index of the second subpass, or `None` for the commands recorded after the render
+    /// pass instance.
+    pub dst_subpass: Option<u32>,
+    /// The stages of the first subpass that must complete.
+    pub src_stages: PipelineStages,
+    /// The stages of the second subpass that wait.
+    pub dst_stages: PipelineStages,
+    /// The accesses of the first subpass that must be made available.
+    pub src_access: Accesses,
+    /// The accesses of the second subpass that the memory must be made visible to.
+    pub dst_access: Accesses,
+    /// Whether the dependency only applies to the same framebuffer region in both subpasses.
+    pub by_region: bool,
+}
+
+impl SubpassDependency {
+    /// Converts this dependency into its raw Vulkan representation.
+    fn to_raw(self) -> vk::SubpassDependency {
+        vk::SubpassDependency {
+            src_subpass: self.src_subpass.unwrap_or(vk::SUBPASS_EXTERNAL),
+            dst_subpass: self.dst_subpass.unwrap_or(vk::SUBPASS_EXTERNAL),
+            src_stage_mask: vk::PipelineStageFlags::from_raw(self.src_stages.bits()),
+            dst_stage_mask: vk::PipelineStageFlags::from_raw(self.dst_stages.bits()),
+            src_access_mask: vk::AccessFlags::from_raw(self.src_access.bits()),
+            dst_access_mask: vk::AccessFlags::from_raw(self.dst_access.bits()),
+            dependency_flags: if self.by_region {
+                vk::DependencyFlags::BY_REGION
+            } else {
+                vk::DependencyFlags::empty()
+            },
+        }
+    }
+}
+
+/// Builds a [`RenderPass`] from its attachments, subpasses and dependencies.
+#[derive(Debug, Clone, Default)]
+pub struct RenderPassBuilder {
+    /// The attachments of the render pass.
+    attachments: Vec<AttachmentDesc>,
+    /// The subpasses of the render pass.
+    subpasses: Vec<SubpassDesc>,
+    /// The dependencies between the subpasses.
+    dependencies: Vec<SubpassDependency>,
+}
+
+impl RenderPassBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an attachment to the render pass, and returns its index.
+    pub fn attachment(&mut self, desc: AttachmentDesc) -> u32 {
+        self.attachments.push(desc);
+        self.attachments.len() as u32 - 1
+    }
+
+    /// Adds a subpass to the render pass, and returns its index.
+    ///
+    /// Subpasses are executed in the order in which they are added, unless dependencies
+    /// allow them to overlap.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the subpass references an attachment that was not added, or
+    /// if it has resolve attachments but not as many as color attachments.
+    pub fn subpass(&mut self, desc: SubpassDesc) -> u32 {
+        assert!(
+            desc.resolve_attachments.is_empty()
+                || desc.resolve_attachments.len() == desc.color_attachments.len(),
+            "a subpass must have as many resolve attachments as color attachments"
+        );
+        assert!(
+            desc.used_attachments()
+                .map(|(attachment, _)| attachment)
+                .chain(desc.preserve_attachments.iter().copied())
+                .all(|attachment| (attachment as usize) < self.attachments.len()),
+            "the subpass references an unknown attachment"
+        );
+
+        self.subpasses.push(desc);
+        self.subpasses.len() as u32 - 1
+    }
+
+    /// Adds a dependency between two subpasses.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the dependency references a subpass that was not added, if
+    /// both of its subpasses are external, or if it goes from a later subpass to an earlier
+    /// one.
+    pub fn dependency(&mut self, dependency: SubpassDependency) -> &mut Self {
+        let subpass_count = self.subpasses.len() as u32;
+
+        assert!(
+            dependency.src_subpass.is_some() || dependency.dst_subpass.is_some(),
+            "a dependency cannot be external on both sides"
+        );
+        assert!(
+            dependency
+                .src_subpass
+                .iter()
+                .chain(&dependency.dst_subpass)
+                .all(|&subpass| subpass < subpass_count),
+            "the dependency references an unknown subpass"
+        );
+        if let (Some(src), Some(dst)) = (dependency.src_subpass, dependency.dst_subpass) {
+            assert!(src <= dst, "a dependency cannot go to an earlier subpass");
+        }
+
+        self.dependencies.push(dependency);
+        self
+    }
+
+    /// Creates the render pass.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if no subpass was added.
+    #[doc(alias = "vkCreateRenderPass")]
+    pub fn build(self, device: Arc<Device>) -> Result<RenderPass> {
+        assert!(
+            !self.subpasses.is_empty(),
+            "a render pass must have at least one subpass"
+        );
+
+        let attachments: SmallVec<[_; 8]> = self
+            .attachments
+            .iter()
+            .map(|attachment| attachment.to_raw())
+            .collect();
+
+        // The references are stored first so that the subpass descriptions can point into
+        // them.
+        let references: SmallVec<[_; 4]> = self
+            .subpasses
+            .iter()
+            .map(|subpass| {
+                let to_raw = |references: &[Option<AttachmentRef>]| -> SmallVec<[_; 4]> {
+                    references
+                        .iter()
+                        .copied()
+                        .map(attachment_ref_to_raw)
+                        .collect()
+                };
+
+                (
+                    to_raw(&subpass.input_attachments),
+                    to_raw(&subpass.color_attachments),
+                    to_raw(&subpass.resolve_attachments),
+                    subpass
+                        .depth_stencil_attachment
+                        .map(Some)
+                        .map(attachment_ref_to_raw),
+                )
+            })
+            .collect();
+
+        let subpasses: SmallVec<[_; 4]> = self
+            .subpasses
+            .iter()
+            .zip(&references)
+            .map(
+                |(subpass, (input, color, resolve, depth_stencil))| vk::SubpassDescription {
+                    flags: vk::SubpassDescriptionFlags::empty(),
+                    pipeline_bind_point: vk::PipelineBindPoint::GRAPHICS,
+                    input_attachment_count: input.len() as u32,
+                    p_input_attachments: input.as_ptr(),
+                    color_attachment_count: color.len() as u32,
+                    p_color_attachments: color.as_ptr(),
+                    p_resolve_attachments: if resolve.is_empty() {
+                        std::ptr::null()
+                    } else {
+                        resolve.as_ptr()
+                    },
+                    p_depth_stencil_attachment: depth_stencil
+                        .as_ref()
+                        .map_or(std::ptr::null(), |reference| reference),
+                    preserve_attachment_count: subpass.preserve_attachments.len() as u32,
+                    p_preserve_attachments: subpass.preserve_attachments.as_ptr(),
+                },
+            )
+            .collect();
+
+        let dependencies: SmallVec<[_; 4]> = self
+            .dependencies
+            .iter()
+            .map(|dependency| dependency.to_raw())
+            .collect();
+
+        let create_info = vk::RenderPassCreateInfo {
+            flags: vk::RenderPassCreateFlags::empty(),
+            attachment_count: attachments.len() as u32,
+            p_attachments: attachments.as_ptr(),
+            subpass_count: subpasses.len() as u32,
+            p_subpasses: subpasses.as_ptr(),
+            dependency_count: dependencies.len() as u32,
+            p_dependencies: dependencies.as_ptr(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::RENDER_PASS_CREATE_INFO,
+        };
+
+        let mut handle = vk::RenderPass::null();
+
+        let ret = unsafe {
+            (device.fns().create_render_pass)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(RenderPass {
+            device,
+            handle,
+            attachments: self.attachments,
+            subpasses: self.subpasses,
+            dependencies: self.dependencies,
+        })
+    }
+}
+
+/// A render pass, which describes the attachments that a sequence of subpasses renders to
+/// and how they depend on each other.
+///
+/// Render passes are created through a [`RenderPassBuilder`].
+pub struct RenderPass {
+    /// The device that owns this render pass.
+    device: Arc<Device>,
+
+    /// The handle to the render pass.
+    handle: vk::RenderPass,
+    /// The attachments of the render pass.
+    attachments: Vec<AttachmentDesc>,
+    /// The subpasses of the render pass.
+    subpasses: Vec<SubpassDesc>,
+    /// The dependencies between the subpasses.
+    dependencies: Vec<SubpassDependency>,
+}
+
+impl RenderPass {
+    /// Returns the attachments of this render pass.
+    #[inline(always)]
+    pub fn attachments(&self) -> &[AttachmentDesc] {
+        &self.attachments
+    }
+
+    /// Returns the subpasses of this render pass.
+    #[inline(always)]
+    pub fn subpasses(&self) -> &[SubpassDesc] {
+        &self.subpasses
+    }
+
+    /// Returns the dependencies between the subpasses of this render pass.
+    #[inline(always)]
+    pub fn dependencies(&self) -> &[SubpassDependency] {
+        &self.dependencies
+    }
+
+    /// Returns the usages that the image of an attachment must have, given the subpasses that
+    /// use it.
+    pub fn attachment_usage(&self, attachment: u32) -> ImageUsages {
+        self.subpasses
+            .iter()
+            .flat_map(SubpassDesc::used_attachments)
+            .filter(|&(index, _)| index == attachment)
+            .fold(ImageUsages::empty(), |usage, (_, required)| {
+                usage | required.into()
+            })
+    }
+
+    /// Returns whether this render pass is compatible with `other`, in which case framebuffers
+    /// and pipelines created for one of them may be used with the other.
+    ///
+    /// Two render passes are compatible when they only differ by the layouts of their
+    /// attachments and attachment references, and by the load and store operations of their
+    /// attachments. Attachment references are compared by the format and the number of
+    /// samples of the attachment they refer to, and shorter lists of references are treated
+    /// as if they were padded with unused references. When both render passes have a single
+    /// subpass, their resolve attachments are ignored.
+    pub fn is_compatible_with(&self, other: &RenderPass) -> bool {
+        if self.handle == other.handle {
+            return true;
+        }
+
+        let attachments_match = self.attachments.len() == other.attachments.len()
+            && self
+                .attachments
+                .iter()
+                .zip(&other.attachments)
+                .all(|(a, b)| a.format == b.format && a.samples == b.samples);
+
+        if !attachments_match
+            || self.subpasses.len() != other.subpasses.len()
+            || self.dependencies != other.dependencies
+        {
+            return false;
+        }
+
+        let single_subpass = self.subpasses.len() == 1;
+        let reference_key = |render_pass: &RenderPass, reference: Option<&AttachmentRef>| {
+            reference.map(|reference| {
+                let attachment = &render_pass.attachments[reference.attachment as usize];
+                (attachment.format, attachment.samples)
+            })
+        };
+        let references_match = |a: &[Option<AttachmentRef>], b: &[Option<AttachmentRef>]| {
+            (0..a.len().max(b.len())).all(|i| {
+                reference_key(self, a.get(i).and_then(Option::as_ref))
+                    == reference_key(other, b.get(i).and_then(Option::as_ref))
+            })
+        };
+
+        self.subpasses.iter().zip(&other.subpasses).all(|(a, b)| {
+            references_match(&a.input_attachments, &b.input_attachments)
+                && references_match(&a.color_attachments, &b.color_attachments)
+                && (single_subpass
+                    || references_match(&a.resolve_attachments, &b.resolve_attachments))
+                && reference_key(self, a.depth_stencil_attachment.as_ref())
+                    == reference_key(other, b.depth_stencil_attachment.as_ref())
+                && a.preserve_attachments == b.preserve_attachments
+        })
+    }
+
+    /// Returns the device that owns this render pass.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this render pass.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::RenderPass {
+        self.handle
+    }
+}
+
+impl Drop for RenderPass {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_render_pass)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
+
+/// Describes an attachment of an imageless [`Framebuffer`], which constrains the views that
+/// may be provided when a render pass instance begins.
+#[derive(Debug, Clone, Copy)]
+#[doc(alias = "VkFramebufferAttachmentImageInfo")]
+pub struct FramebufferAttachmentDesc<'a> {
+    /// The flags that the images of the views are created with.
+    pub flags: ImageFlags,
+    /// The usages that the images of the views are created with.
+    pub usage: ImageUsages,
+    /// The width and height of the views.
+    pub extent: [u32; 2],
+    /// The number of array layers of the views.
+    pub layer_count: u32,
+    /// The formats that views of the images may use.
+    pub view_formats: &'a [Format],
+}
+
+/// The attachments of a [`Framebuffer`].
+#[derive(Clone, Copy)]
+pub enum FramebufferAttachments<'a> {
+    /// The views rendered to, one per attachment of the render pass.
+    Views(&'a [&'a ImageView<'a>]),
+    /// The properties of the views, which are only provided when a render pass instance
+    /// begins.
+    ///
+    /// This requires the [`DeviceFeatures::IMAGELESS_FRAMEBUFFER`] feature.
+    Imageless(&'a [FramebufferAttachmentDesc<'a>]),
+}
+
+/// The parameters passed to the [`Framebuffer::new`] function.
+#[derive(Clone, Copy)]
+#[doc(alias = "VkFramebufferCreateInfo")]
+pub struct FramebufferDesc<'a> {
+    /// The render pass that the framebuffer is used with, or a compatible one.
+    pub render_pass: &'a RenderPass,
+    /// The attachments of the framebuffer.
+    pub attachments: FramebufferAttachments<'a>,
+    /// The width and height of the framebuffer.
+    pub extent: [u32; 2],
+    /// The number of layers of the framebuffer.
+    pub layers: u32,
+}
+
+/// The attachments that a [`RenderPass`] renders to.
+///
+/// The framebuffer does not keep its views alive: it must not be used once one of them has
+/// been destroyed.
+pub struct Framebuffer {
+    /// The device that owns this framebuffer.
+    device: Arc<Device>,
+
+    /// The handle to the framebuffer.
+    handle: vk::Framebuffer,
+    /// The width and height of the framebuffer.
+    extent: [u32; 2],
+    /// The number of layers of the framebuffer.
+    layers: u32,
+    /// The number of attachments of the framebuffer.
+    attachment_count: u32,
+    /// Whether the views are only provided when a render pass instance begins.
+    imageless: bool,
+}
+
+impl Framebuffer {
+    /// Creates a new [`Framebuffer`] for the provided render pass.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::MissingDeviceFeature`] if the framebuffer is imageless
+    /// but the [`DeviceFeatures::IMAGELESS_FRAMEBUFFER`] feature is not enabled.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the number of attachments does not match the render pass, if
+    /// a view does not use the format of its attachment, or if the image of an attachment
+    /// lacks a usage required by the subpasses that use it.
+    #[doc(alias = "vkCreateFramebuffer")]
+    pub fn new(desc: FramebufferDesc) -> Result<Self> {
+        let render_pass = desc.render_pass;
+        let device = render_pass.device();
+
+        let attachment_count = match desc.attachments {
+            FramebufferAttachments::Views(views) => views.len(),
+            FramebufferAttachments::Imageless(attachments) => attachments.len(),
+        };
+        assert_eq!(
+            attachment_count,
+            render_pass.attachments().len(),
+            "the framebuffer must have as many attachments as the render pass"
+        );
+
+        let check_usage = |index: usize, usage: ImageUsages| {
+            let required = render_pass.attachment_usage(index as u32);
+            assert!(
+                usage.contains(required),
+                "the image of attachment {index} lacks the {:?} usage",
+                required.difference(usage)
+            );
+        };
+
+        let mut views = SmallVec::<[_; 8]>::new();
+        let mut attachment_infos = SmallVec::<[_; 8]>::new();
+        let mut formats = SmallVec::<[SmallVec<[_; 2]>; 8]>::new();
+
+        match desc.attachments {
+            FramebufferAttachments::Views(attachment_views) => {
+                for (index, (view, attachment)) in attachment_views
+                    .iter()
+                    .zip(render_pass.attachments())
+                    .enumerate()
+                {
+                    assert_eq!(
+                        view.format(),
+                        attachment.format,
+                        "the view of attachment {index} does not use the format of the attachment"
+                    );
+                    check_usage(index, view.usage());
+                    views.push(view.handle());
+                }
+            }
+            FramebufferAttachments::Imageless(attachments) => {
+                if !device
+                    .features()
+                    .contains(DeviceFeatures::IMAGELESS_FRAMEBUFFER)
+                {
+                    return Err(Error::MissingDeviceFeature(
+                        DeviceFeatures::IMAGELESS_FRAMEBUFFER,
+                    ));
+                }
+
+                for (index, attachment) in attachments.iter().enumerate() {
+                    assert!(
+                        attachment
+                            .view_formats
+                            .contains(&render_pass.attachments()[index].format),
+                        "the view formats of attachment {index} do not include its format"
+                    );
+                    check_usage(index, attachment.usage);
+                    formats.push(
+                        attachment
+                            .view_formats
+                            .iter()
+                            .map(|&format| vk::Format::from_raw(format as i32))
+                            .collect(),
+                    );
+                }
+
+                attachment_infos.extend(attachments.iter().zip(&formats).map(
+                    |(attachment, formats)| vk::FramebufferAttachmentImageInfo {
+                        flags: vk::ImageCreateFlags::from_raw(attachment.flags.bits()),
+                        usage: vk::ImageUsageFlags::from_raw(attachment.usage.bits()),
+                        width: attachment.extent[0],
+                        height: attachment.extent[1],
+                        layer_count: attachment.layer_count,
+                        view_format_count: formats.len() as u32,
+                        p_view_formats: formats.as_ptr(),
+
+                        p_next: std::ptr::null(),
+                        s_type: vk::StructureType::FRAMEBUFFER_ATTACHMENT_IMAGE_INFO,
+                    },
+                ));
+            }
+        }
+
+        let imageless = matches!(desc.attachments, FramebufferAttachments::Imageless(_));
+        let attachments_info = vk::FramebufferAttachmentsCreateInfo {
+            attachment_image_info_count: attachment_infos.len() as u32,
+            p_attachment_image_infos: attachment_infos.as_ptr(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::FRAMEBUFFER_ATTACHMENTS_CREATE_INFO,
+        };
+
+        let create_info = vk::FramebufferCreateInfo {
+            flags: if imageless {
+                vk::FramebufferCreateFlags::IMAGELESS
+            } else {
+                vk::FramebufferCreateFlags::empty()
+            },
+            render_pass: render_pass.handle(),
+            attachment_count: attachment_count as u32,
+            p_attachments: if imageless {
+                std::ptr::null()
+            } else {
+                views.as_ptr()
+            },
+            width: desc.extent[0],
+            height: desc.extent[1],
+            layers: desc.layers,
+
+            p_next: if imageless {
+                &attachments_info as *const _ as *const _
+            } else {
+                std::ptr::null()
+            },
+            s_type: vk::StructureType::FRAMEBUFFER_CREATE_INFO,
+        };
+
+        let mut handle = vk::Framebuffer::null();
+
+        let ret = unsafe {
+            (device.fns().create_framebuffer)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self {
+            device: device.clone(),
+            handle,
+            extent: desc.extent,
+            layers: desc.layers,
+            attachment_count: attachment_count as u32,
+            imageless,
+        })
+    }
+
+    /// Returns the width and height of this framebuffer.
+    #[inline(always)]
+    pub fn extent(&self) -> [u32; 2] {
+        self.extent
+    }
+
+    /// Returns the number of layers of this framebuffer.
+    #[inline(always)]
+    pub fn layers(&self) -> u32 {
+        self.layers
+    }
+
+    /// Returns whether the views of this framebuffer are only provided when a render pass
+    /// instance begins.
+    #[inline(always)]
+    pub fn is_imageless(&self) -> bool {
+        self.imageless
+    }
+
+    /// Returns the device that owns this framebuffer.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this framebuffer.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::Framebuffer {
+        self.handle
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_framebuffer)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
+
+/// A value that an attachment is cleared to when a render pass instance begins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[doc(alias = "VkClearValue")]
+pub enum ClearValue {
+    /// The value of a color attachment.
+    Color(ClearColor),
+    /// The depth and stencil values of a depth/stencil attachment.
+    DepthStencil(f32, u32),
+}
+
+impl ClearValue {
+    /// Converts this value into its raw Vulkan representation.
+    fn to_raw(self) -> vk::ClearValue {
+        match self {
+            Self::Color(color) => vk::ClearValue {
+                color: color.to_raw(),
+            },
+            Self::DepthStencil(depth, stencil) => vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue { depth, stencil },
+            },
+        }
+    }
+}
+
+/// How the commands of a subpass are provided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkSubpassContents")]
+pub enum SubpassContents {
+    /// The commands are recorded in the primary command buffer.
+    Inline = vk::SubpassContents::INLINE.as_raw(),
+    /// The commands are recorded in secondary command buffers.
+    SecondaryCommandBuffers = vk::SubpassContents::SECONDARY_COMMAND_BUFFERS.as_raw(),
+}
+
+/// The parameters passed to [`CommandBuffer::begin_render_pass`].
+#[derive(Clone, Copy)]
+#[doc(alias = "VkRenderPassBeginInfo")]
+pub struct RenderPassBeginDesc<'a> {
+    /// The render pass to begin an instance of.
+    pub render_pass: &'a RenderPass,
+    /// The framebuffer rendered to, created for a compatible render pass.
+    pub framebuffer: &'a Framebuffer,
+    /// The views rendered to when the framebuffer is imageless, and empty otherwise.
+    pub views: &'a [&'a ImageView<'a>],
+    /// The area rendered to, which must be included in the framebuffer.
+    pub render_area: Rect,
+    /// The values that the attachments are cleared to, indexed by attachment.
+    ///
+    /// Only the values of attachments whose load operation is [`AttachmentLoadOp::Clear`]
+    /// are used.
+    pub clear_values: &'a [ClearValue],
+    /// How the commands of the first subpass are provided.
+    pub contents: SubpassContents,
+}
+
+impl CommandBuffer<Recording<'_>> {
+    /// Begins a render pass instance, starting with its first subpass.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if views are provided for a framebuffer that is not imageless,
+    /// or if the framebuffer is imageless and the number of views does not match its number
+    /// of attachments.
+    ///
+    /// # Safety
+    ///
+    /// The render pass, the framebuffer and the attachment views must outlive the execution of
+    /// the command buffer, and the attachments must be in the initial layouts of the render pass
+    /// when the command executes.
+    #[doc(alias = "vkCmdBeginRenderPass")]
+    pub unsafe fn begin_render_pass(&mut self, desc: &RenderPassBeginDesc) {
+        let framebuffer = desc.framebuffer;
+
+        if framebuffer.is_imageless() {
+            assert_eq!(
+                desc.views.len(),
+                framebuffer.attachment_count as usize,
+                "a view must be provided for each attachment of an imageless framebuffer"
+            );
+        } else {
+            assert!(
+                desc.views.is_empty(),
+                "views can only be provided for imageless framebuffers"
+            );
+        }
+
+        let views: SmallVec<[_; 8]> = desc.views.iter().map(|view| view.handle()).collect();
+        let clear_values: SmallVec<[_; 8]> = desc
+            .clear_values
+            .iter()
+            .map(|value| value.to_raw())
+            .collect();
+
+        let attachment_info = vk::RenderPassAttachmentBeginInfo {
+            attachment_count: views.len() as u32,
+            p_attachments: views.as_ptr(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::RENDER_PASS_ATTACHMENT_BEGIN_INFO,
+        };
+
+        let begin_info = vk::RenderPassBeginInfo {
+            render_pass: desc.render_pass.handle(),
+            framebuffer: framebuffer.handle(),
+            render_area: desc.render_area.to_raw(),
+            clear_value_count: clear_values.len() as u32,
+            p_clear_values: clear_values.as_ptr(),
+
+            p_next: if framebuffer.is_imageless() {
+                &attachment_info as *const _ as *const _
+            } else {
+                std::ptr::null()
+            },
+            s_type: vk::StructureType::RENDER_PASS_BEGIN_INFO,
+        };
+
+        (self.device().fns().cmd_begin_render_pass)(
+            self.handle(),
+            &begin_info,
+            vk::SubpassContents::from_raw(desc.contents as i32),
+        );
+    }
+
+    /// Moves to the next subpass of the current render pass instance.
+    ///
+    /// # Safety
+    ///
+    /// The command buffer must be within a render pass instance begun with
+    /// [`CommandBuffer::begin_render_pass`], whose current subpass is not the last subpass of the
+    /// render pass.
+    #[doc(alias = "vkCmdNextSubpass")]
+    pub unsafe fn next_subpass(&mut self, contents: SubpassContents) {
+        (self.device().fns().cmd_next_subpass)(
+            self.handle(),
+            vk::SubpassContents::from_raw(contents as i32),
+        );
+    }
+
+    /// Ends the current render pass instance.
+    ///
+    /// # Safety
+    ///
+    /// The command buffer must be within a render pass instance begun with
+    /// [`CommandBuffer::begin_render_pass`], whose current subpass is the last subpass of the
+    /// render pass.
+    #[doc(alias = "vkCmdEndRenderPass")]
+    pub unsafe fn end_render_pass(&mut self) {
+        (self.device().fns().cmd_end_render_pass)(self.handle());
+    }
+}
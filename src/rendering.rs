@@ -0,0 +1,339 @@
+use ash::vk;
+use bitflags::bitflags;
+use smallvec::SmallVec;
+
+use crate::{
+    ClearColor, CommandBuffer, DeviceFeatures, Error, Format, ImageLayout, ImageView, Recording,
+    Rect, Result,
+};
+
+/// What happens to the contents of an attachment at the beginning of a render pass instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkAttachmentLoadOp")]
+pub enum AttachmentLoadOp {
+    /// The previous contents of the attachment are preserved.
+    Load = vk::AttachmentLoadOp::LOAD.as_raw(),
+    /// The attachment is cleared to its clear value.
+    Clear = vk::AttachmentLoadOp::CLEAR.as_raw(),
+    /// The previous contents of the attachment are not needed, and become undefined.
+    DontCare = vk::AttachmentLoadOp::DONT_CARE.as_raw(),
+}
+
+/// What happens to the contents of an attachment at the end of a render pass instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+#[doc(alias = "VkAttachmentStoreOp")]
+pub enum AttachmentStoreOp {
+    /// The contents written during the render pass instance are stored to memory.
+    Store = vk::AttachmentStoreOp::STORE.as_raw(),
+    /// The contents of the attachment are not needed afterwards, and become undefined.
+    DontCare = vk::AttachmentStoreOp::DONT_CARE.as_raw(),
+}
+
+/// How the samples of a multisampled attachment are combined when it is resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+#[doc(alias = "VkResolveModeFlagBits")]
+pub enum ResolveMode {
+    /// The value of the first sample is used.
+    SampleZero = vk::ResolveModeFlags::SAMPLE_ZERO.as_raw(),
+    /// The average of the samples is used.
+    Average = vk::ResolveModeFlags::AVERAGE.as_raw(),
+    /// The minimum of the samples is used.
+    Min = vk::ResolveModeFlags::MIN.as_raw(),
+    /// The maximum of the samples is used.
+    Max = vk::ResolveModeFlags::MAX.as_raw(),
+}
+
+bitflags! {
+    /// Additional properties of a dynamic render pass instance.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[doc(alias = "VkRenderingFlags")]
+    pub struct RenderingFlags: u32 {
+        /// The contents of the render pass instance are recorded in secondary command buffers.
+        const CONTENTS_SECONDARY_COMMAND_BUFFERS = vk::RenderingFlagsKHR::CONTENTS_SECONDARY_COMMAND_BUFFERS.as_raw();
+        /// The render pass instance is suspended, and resumed by a later render pass instance.
+        const SUSPENDING = vk::RenderingFlagsKHR::SUSPENDING.as_raw();
+        /// The render pass instance resumes a suspended render pass instance.
+        const RESUMING = vk::RenderingFlagsKHR::RESUMING.as_raw();
+    }
+}
+
+/// The image that a multisampled attachment is resolved into at the end of a render pass
+/// instance.
+#[derive(Clone, Copy)]
+pub struct AttachmentResolve<'a> {
+    /// How the samples are combined.
+    pub mode: ResolveMode,
+    /// The single-sampled view written by the resolve.
+    pub view: &'a ImageView<'a>,
+    /// The layout of the view during the render pass instance.
+    pub layout: ImageLayout,
+}
+
+/// A color attachment of a dynamic render pass instance.
+#[derive(Clone, Copy)]
+#[doc(alias = "VkRenderingAttachmentInfo")]
+pub struct ColorAttachment<'a> {
+    /// The view rendered to.
+    pub view: &'a ImageView<'a>,
+    /// The layout of the view during the render pass instance.
+    pub layout: ImageLayout,
+    /// What happens to the contents of the view at the beginning of the instance.
+    pub load_op: AttachmentLoadOp,
+    /// What happens to the contents of the view at the end of the instance.
+    pub store_op: AttachmentStoreOp,
+    /// The value that the view is cleared to if `load_op` is [`AttachmentLoadOp::Clear`].
+    pub clear_value: ClearColor,
+    /// The view that the attachment is resolved into, if any.
+    pub resolve: Option<AttachmentResolve<'a>>,
+}
+
+impl ColorAttachment<'_> {
+    /// Converts this attachment into its raw Vulkan representation.
+    fn to_raw(self) -> vk::RenderingAttachmentInfoKHR {
+        attachment_to_raw(
+            self.view,
+            self.layout,
+            self.load_op,
+            self.store_op,
+            vk::ClearValue {
+                color: self.clear_value.to_raw(),
+            },
+            self.resolve,
+        )
+    }
+}
+
+/// The depth attachment of a dynamic render pass instance.
+#[derive(Clone, Copy)]
+#[doc(alias = "VkRenderingAttachmentInfo")]
+pub struct DepthAttachment<'a> {
+    /// The view rendered to.
+    pub view: &'a ImageView<'a>,
+    /// The layout of the view during the render pass instance.
+    pub layout: ImageLayout,
+    /// What happens to the depth of the view at the beginning of the instance.
+    pub load_op: AttachmentLoadOp,
+    /// What happens to the depth of the view at the end of the instance.
+    pub store_op: AttachmentStoreOp,
+    /// The depth that the view is cleared to if `load_op` is [`AttachmentLoadOp::Clear`].
+    pub clear_value: f32,
+    /// The view that the attachment is resolved into, if any.
+    pub resolve: Option<AttachmentResolve<'a>>,
+}
+
+impl DepthAttachment<'_> {
+    /// Converts this attachment into its raw Vulkan representation.
+    fn to_raw(self) -> vk::RenderingAttachmentInfoKHR {
+        attachment_to_raw(
+            self.view,
+            self.layout,
+            self.load_op,
+            self.store_op,
+            vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue {
+                    depth: self.clear_value,
+                    stencil: 0,
+                },
+            },
+            self.resolve,
+        )
+    }
+}
+
+/// The stencil attachment of a dynamic render pass instance.
+#[derive(Clone, Copy)]
+#[doc(alias = "VkRenderingAttachmentInfo")]
+pub struct StencilAttachment<'a> {
+    /// The view rendered to.
+    pub view: &'a ImageView<'a>,
+    /// The layout of the view during the render pass instance.
+    pub layout: ImageLayout,
+    /// What happens to the stencil of the view at the beginning of the instance.
+    pub load_op: AttachmentLoadOp,
+    /// What happens to the stencil of the view at the end of the instance.
+    pub store_op: AttachmentStoreOp,
+    /// The stencil value that the view is cleared to if `load_op` is
+    /// [`AttachmentLoadOp::Clear`].
+    pub clear_value: u32,
+    /// The view that the attachment is resolved into, if any.
+    pub resolve: Option<AttachmentResolve<'a>>,
+}
+
+impl StencilAttachment<'_> {
+    /// Converts this attachment into its raw Vulkan representation.
+    fn to_raw(self) -> vk::RenderingAttachmentInfoKHR {
+        attachment_to_raw(
+            self.view,
+            self.layout,
+            self.load_op,
+            self.store_op,
+            vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue {
+                    depth: 0.0,
+                    stencil: self.clear_value,
+                },
+            },
+            self.resolve,
+        )
+    }
+}
+
+/// Builds a raw rendering attachment.
+fn attachment_to_raw(
+    view: &ImageView,
+    layout: ImageLayout,
+    load_op: AttachmentLoadOp,
+    store_op: AttachmentStoreOp,
+    clear_value: vk::ClearValue,
+    resolve: Option<AttachmentResolve>,
+) -> vk::RenderingAttachmentInfoKHR {
+    let (resolve_mode, resolve_image_view, resolve_image_layout) = match resolve {
+        Some(resolve) => (
+            vk::ResolveModeFlags::from_raw(resolve.mode as u32),
+            resolve.view.handle(),
+            vk::ImageLayout::from_raw(resolve.layout as i32),
+        ),
+        None => (
+            vk::ResolveModeFlags::NONE,
+            vk::ImageView::null(),
+            vk::ImageLayout::UNDEFINED,
+        ),
+    };
+
+    vk::RenderingAttachmentInfoKHR {
+        image_view: view.handle(),
+        image_layout: vk::ImageLayout::from_raw(layout as i32),
+        resolve_mode,
+        resolve_image_view,
+        resolve_image_layout,
+        load_op: vk::AttachmentLoadOp::from_raw(load_op as i32),
+        store_op: vk::AttachmentStoreOp::from_raw(store_op as i32),
+        clear_value,
+
+        p_next: std::ptr::null(),
+        s_type: vk::StructureType::RENDERING_ATTACHMENT_INFO_KHR,
+    }
+}
+
+/// The parameters passed to [`CommandBuffer::begin_rendering`].
+#[derive(Clone, Copy)]
+#[doc(alias = "VkRenderingInfo")]
+pub struct RenderingDesc<'a> {
+    /// Additional properties of the render pass instance.
+    pub flags: RenderingFlags,
+    /// The area rendered to, which must be included in all the attachments.
+    pub render_area: Rect,
+    /// The number of layers rendered to when `view_mask` is zero.
+    pub layer_count: u32,
+    /// The views rendered to when multiview is used, or zero.
+    pub view_mask: u32,
+    /// The color attachments, whose indices match the fragment shader outputs.
+    pub color_attachments: &'a [ColorAttachment<'a>],
+    /// The depth attachment, if any.
+    pub depth_attachment: Option<DepthAttachment<'a>>,
+    /// The stencil attachment, if any.
+    ///
+    /// For combined depth/stencil formats, this should be the same view as the depth
+    /// attachment.
+    pub stencil_attachment: Option<StencilAttachment<'a>>,
+}
+
+/// The formats of the attachments that a graphics pipeline renders to when it is used with
+/// dynamic rendering instead of a render pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[doc(alias = "VkPipelineRenderingCreateInfo")]
+pub struct PipelineRenderingDesc<'a> {
+    /// The views rendered to when multiview is used, or zero.
+    pub view_mask: u32,
+    /// The formats of the color attachments.
+    pub color_formats: &'a [Format],
+    /// The format of the depth attachment, if any.
+    pub depth_format: Option<Format>,
+    /// The format of the stencil attachment, if any.
+    pub stencil_format: Option<Format>,
+}
+
+impl<L> CommandBuffer<Recording<'_>, L> {
+    /// Begins a dynamic render pass instance, rendering to the provided attachments.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::MissingDeviceFeature`] if the
+    /// [`DeviceFeatures::DYNAMIC_RENDERING`] feature is not enabled.
+    ///
+    /// # Safety
+    ///
+    /// The attachment views and their images must outlive the execution of the command buffer,
+    /// and the images must be in the layouts provided for them when the command executes.
+    #[doc(alias = "vkCmdBeginRendering")]
+    pub unsafe fn begin_rendering(&mut self, desc: &RenderingDesc) -> Result<()> {
+        let begin_rendering = self
+            .device()
+            .fns()
+            .cmd_begin_rendering
+            .filter(|_| {
+                self.device()
+                    .features()
+                    .contains(DeviceFeatures::DYNAMIC_RENDERING)
+            })
+            .ok_or(Error::MissingDeviceFeature(
+                DeviceFeatures::DYNAMIC_RENDERING,
+            ))?;
+
+        let color_attachments: SmallVec<[_; 4]> = desc
+            .color_attachments
+            .iter()
+            .map(|attachment| attachment.to_raw())
+            .collect();
+        let depth_attachment = desc.depth_attachment.map(DepthAttachment::to_raw);
+        let stencil_attachment = desc.stencil_attachment.map(StencilAttachment::to_raw);
+
+        let rendering_info = vk::RenderingInfoKHR {
+            flags: vk::RenderingFlagsKHR::from_raw(desc.flags.bits()),
+            render_area: desc.render_area.to_raw(),
+            layer_count: desc.layer_count,
+            view_mask: desc.view_mask,
+            color_attachment_count: color_attachments.len() as u32,
+            p_color_attachments: color_attachments.as_ptr(),
+            p_depth_attachment: depth_attachment
+                .as_ref()
+                .map_or(std::ptr::null(), |attachment| attachment),
+            p_stencil_attachment: stencil_attachment
+                .as_ref()
+                .map_or(std::ptr::null(), |attachment| attachment),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::RENDERING_INFO_KHR,
+        };
+
+        begin_rendering(self.handle(), &rendering_info);
+
+        Ok(())
+    }
+
+    /// Ends the current dynamic render pass instance.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if dynamic rendering is not available, in which case no render
+    /// pass instance could have been begun with [`CommandBuffer::begin_rendering`].
+    ///
+    /// # Safety
+    ///
+    /// The command buffer must be within a render pass instance begun with
+    /// [`CommandBuffer::begin_rendering`], and not within one begun with
+    /// [`CommandBuffer::begin_render_pass`].
+    #[doc(alias = "vkCmdEndRendering")]
+    pub unsafe fn end_rendering(&mut self) {
+        let end_rendering = self
+            .device()
+            .fns()
+            .cmd_end_rendering
+            .expect("dynamic rendering is not available");
+
+        end_rendering(self.handle());
+    }
+}
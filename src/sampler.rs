@@ -0,0 +1,498 @@
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use ash::vk;
+
+use crate::{ComponentMapping, Device, DeviceFeatures, Error, Format, Result};
+
+/// A filter used when sampling a texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum Filter {
+    /// The nearest texel is used.
+    Nearest = vk::Filter::NEAREST.as_raw(),
+    /// The neighbouring texels are linearly interpolated.
+    Linear = vk::Filter::LINEAR.as_raw(),
+}
+
+/// A filter used when sampling between mipmap levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum SamplerMipmapMode {
+    /// The nearest mipmap level is used.
+    Nearest = vk::SamplerMipmapMode::NEAREST.as_raw(),
+    /// The two nearest mipmap levels are linearly interpolated.
+    Linear = vk::SamplerMipmapMode::LINEAR.as_raw(),
+}
+
+/// What happens when a texture is sampled outside of its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum SamplerAddressMode {
+    Repeat = vk::SamplerAddressMode::REPEAT.as_raw(),
+    MirroredRepeat = vk::SamplerAddressMode::MIRRORED_REPEAT.as_raw(),
+    ClampToEdge = vk::SamplerAddressMode::CLAMP_TO_EDGE.as_raw(),
+    ClampToBorder = vk::SamplerAddressMode::CLAMP_TO_BORDER.as_raw(),
+    MirrorClampToEdge = vk::SamplerAddressMode::MIRROR_CLAMP_TO_EDGE.as_raw(),
+}
+
+/// A comparison operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum CompareOp {
+    Never = vk::CompareOp::NEVER.as_raw(),
+    Less = vk::CompareOp::LESS.as_raw(),
+    Equal = vk::CompareOp::EQUAL.as_raw(),
+    LessOrEqual = vk::CompareOp::LESS_OR_EQUAL.as_raw(),
+    Greater = vk::CompareOp::GREATER.as_raw(),
+    NotEqual = vk::CompareOp::NOT_EQUAL.as_raw(),
+    GreaterOrEqual = vk::CompareOp::GREATER_OR_EQUAL.as_raw(),
+    Always = vk::CompareOp::ALWAYS.as_raw(),
+}
+
+/// The color returned when sampling outside of a texture with
+/// [`SamplerAddressMode::ClampToBorder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum BorderColor {
+    FloatTransparentBlack = vk::BorderColor::FLOAT_TRANSPARENT_BLACK.as_raw(),
+    IntTransparentBlack = vk::BorderColor::INT_TRANSPARENT_BLACK.as_raw(),
+    FloatOpaqueBlack = vk::BorderColor::FLOAT_OPAQUE_BLACK.as_raw(),
+    IntOpaqueBlack = vk::BorderColor::INT_OPAQUE_BLACK.as_raw(),
+    FloatOpaqueWhite = vk::BorderColor::FLOAT_OPAQUE_WHITE.as_raw(),
+    IntOpaqueWhite = vk::BorderColor::INT_OPAQUE_WHITE.as_raw(),
+}
+
+/// The color model of a YCbCr image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum YcbcrModel {
+    RgbIdentity = vk::SamplerYcbcrModelConversion::RGB_IDENTITY.as_raw(),
+    YcbcrIdentity = vk::SamplerYcbcrModelConversion::YCBCR_IDENTITY.as_raw(),
+    Ycbcr709 = vk::SamplerYcbcrModelConversion::YCBCR_709.as_raw(),
+    Ycbcr601 = vk::SamplerYcbcrModelConversion::YCBCR_601.as_raw(),
+    Ycbcr2020 = vk::SamplerYcbcrModelConversion::YCBCR_2020.as_raw(),
+}
+
+/// The range of the encoded values of a YCbCr image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum YcbcrRange {
+    /// The full range of the encoded values is used.
+    ItuFull = vk::SamplerYcbcrRange::ITU_FULL.as_raw(),
+    /// Headroom and footroom are reserved, as defined by the ITU standards.
+    ItuNarrow = vk::SamplerYcbcrRange::ITU_NARROW.as_raw(),
+}
+
+/// The location of downsampled chroma samples relative to the luma samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum ChromaLocation {
+    CositedEven = vk::ChromaLocation::COSITED_EVEN.as_raw(),
+    Midpoint = vk::ChromaLocation::MIDPOINT.as_raw(),
+}
+
+/// The parameters passed to the [`SamplerYcbcrConversion::new`] function.
+#[derive(Debug, Clone)]
+#[doc(alias = "VkSamplerYcbcrConversionCreateInfo")]
+pub struct SamplerYcbcrConversionDesc {
+    /// The format of the images to convert.
+    pub format: Format,
+    /// The color model of the images.
+    pub model: YcbcrModel,
+    /// The range of the encoded values of the images.
+    pub range: YcbcrRange,
+    /// How the components of the texels are remapped before the conversion.
+    pub components: ComponentMapping,
+    /// The horizontal location of the chroma samples.
+    pub x_chroma_offset: ChromaLocation,
+    /// The vertical location of the chroma samples.
+    pub y_chroma_offset: ChromaLocation,
+    /// The filter used to reconstruct chroma samples.
+    pub chroma_filter: Filter,
+    /// Whether chroma reconstruction must be done explicitly.
+    pub force_explicit_reconstruction: bool,
+}
+
+/// A conversion from YCbCr to RGB applied when sampling multi-planar images.
+///
+/// The same conversion must be used by the [`ImageView`](crate::ImageView) and the [`Sampler`]
+/// used to sample an image.
+pub struct SamplerYcbcrConversion {
+    /// The device that owns this conversion.
+    device: Arc<Device>,
+
+    /// The handle to the conversion.
+    handle: vk::SamplerYcbcrConversion,
+}
+
+impl SamplerYcbcrConversion {
+    /// Creates a new [`SamplerYcbcrConversion`].
+    ///
+    /// # Errors
+    ///
+    /// [`Error::MissingDeviceFeature`] is returned if the `samplerYcbcrConversion` feature was
+    /// not enabled on the device.
+    #[doc(alias = "vkCreateSamplerYcbcrConversion")]
+    pub fn new(device: Arc<Device>, desc: SamplerYcbcrConversionDesc) -> Result<Self> {
+        let create_fn = device
+            .fns()
+            .create_sampler_ycbcr_conversion
+            .filter(|_| {
+                device
+                    .features()
+                    .contains(DeviceFeatures::SAMPLER_YCBCR_CONVERSION)
+            })
+            .ok_or(Error::MissingDeviceFeature(
+                DeviceFeatures::SAMPLER_YCBCR_CONVERSION,
+            ))?;
+
+        let create_info = vk::SamplerYcbcrConversionCreateInfo {
+            format: vk::Format::from_raw(desc.format as i32),
+            ycbcr_model: vk::SamplerYcbcrModelConversion::from_raw(desc.model as i32),
+            ycbcr_range: vk::SamplerYcbcrRange::from_raw(desc.range as i32),
+            components: desc.components.to_raw(),
+            x_chroma_offset: vk::ChromaLocation::from_raw(desc.x_chroma_offset as i32),
+            y_chroma_offset: vk::ChromaLocation::from_raw(desc.y_chroma_offset as i32),
+            chroma_filter: vk::Filter::from_raw(desc.chroma_filter as i32),
+            force_explicit_reconstruction: desc.force_explicit_reconstruction as vk::Bool32,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::SAMPLER_YCBCR_CONVERSION_CREATE_INFO,
+        };
+
+        let mut handle = vk::SamplerYcbcrConversion::null();
+
+        let ret =
+            unsafe { create_fn(device.handle(), &create_info, std::ptr::null(), &mut handle) };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self { device, handle })
+    }
+
+    /// Returns the device that owns this conversion.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this conversion.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::SamplerYcbcrConversion {
+        self.handle
+    }
+}
+
+impl std::fmt::Debug for SamplerYcbcrConversion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SamplerYcbcrConversion")
+            .field(&self.handle)
+            .finish()
+    }
+}
+
+impl Drop for SamplerYcbcrConversion {
+    fn drop(&mut self) {
+        // The conversion could only be created if the function is available.
+        if let Some(destroy_fn) = self.device.fns().destroy_sampler_ycbcr_conversion {
+            unsafe { destroy_fn(self.device.handle(), self.handle, std::ptr::null()) };
+        }
+    }
+}
+
+/// The parameters passed to the [`Sampler::new`] function.
+///
+/// Descriptions are compared and hashed by the bit patterns of their floating-point fields,
+/// which allows them to be used as keys of a [`SamplerCache`].
+#[derive(Debug, Clone)]
+#[doc(alias = "VkSamplerCreateInfo")]
+pub struct SamplerDesc {
+    /// The filter used when the texture is magnified.
+    pub mag_filter: Filter,
+    /// The filter used when the texture is minified.
+    pub min_filter: Filter,
+    /// The filter used between mipmap levels.
+    pub mipmap_mode: SamplerMipmapMode,
+    /// What happens when the texture is sampled outside of its bounds, along the U, V and W
+    /// coordinates.
+    pub address_modes: [SamplerAddressMode; 3],
+    /// A bias added to the computed mipmap level.
+    pub mip_lod_bias: f32,
+    /// The maximum degree of anisotropy, if anisotropic filtering is enabled.
+    ///
+    /// Anisotropic filtering requires the `samplerAnisotropy` feature to be enabled on the
+    /// device. The value is clamped to the `maxSamplerAnisotropy` limit.
+    pub max_anisotropy: Option<f32>,
+    /// The operator used to compare sampled values against a reference value, for depth
+    /// comparison samplers.
+    pub compare_op: Option<CompareOp>,
+    /// The minimum mipmap level that can be sampled.
+    pub min_lod: f32,
+    /// The maximum mipmap level that can be sampled.
+    pub max_lod: f32,
+    /// The color returned when sampling outside of the texture with
+    /// [`SamplerAddressMode::ClampToBorder`].
+    pub border_color: BorderColor,
+    /// Whether texel coordinates are used instead of normalized coordinates.
+    pub unnormalized_coordinates: bool,
+    /// The YCbCr conversion applied when sampling multi-planar images.
+    pub ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
+}
+
+impl Default for SamplerDesc {
+    fn default() -> Self {
+        Self {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            mipmap_mode: SamplerMipmapMode::Linear,
+            address_modes: [SamplerAddressMode::Repeat; 3],
+            mip_lod_bias: 0.0,
+            max_anisotropy: None,
+            compare_op: None,
+            min_lod: 0.0,
+            max_lod: vk::LOD_CLAMP_NONE,
+            border_color: BorderColor::FloatTransparentBlack,
+            unnormalized_coordinates: false,
+            ycbcr_conversion: None,
+        }
+    }
+}
+
+impl SamplerDesc {
+    /// Returns the value used to compare and hash this description.
+    #[allow(clippy::type_complexity)]
+    fn key(
+        &self,
+    ) -> (
+        [i32; 3],
+        [SamplerAddressMode; 3],
+        [u32; 3],
+        Option<u32>,
+        Option<CompareOp>,
+        BorderColor,
+        bool,
+        Option<vk::SamplerYcbcrConversion>,
+    ) {
+        (
+            [
+                self.mag_filter as i32,
+                self.min_filter as i32,
+                self.mipmap_mode as i32,
+            ],
+            self.address_modes,
+            [
+                self.mip_lod_bias.to_bits(),
+                self.min_lod.to_bits(),
+                self.max_lod.to_bits(),
+            ],
+            self.max_anisotropy.map(f32::to_bits),
+            self.compare_op,
+            self.border_color,
+            self.unnormalized_coordinates,
+            self.ycbcr_conversion.as_ref().map(|c| c.handle()),
+        )
+    }
+}
+
+impl PartialEq for SamplerDesc {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for SamplerDesc {}
+
+impl Hash for SamplerDesc {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// Describes how textures are read by shaders.
+pub struct Sampler {
+    /// The device that owns this sampler.
+    device: Arc<Device>,
+
+    /// The handle to the sampler.
+    handle: vk::Sampler,
+    /// The YCbCr conversion used by the sampler, kept alive alongside it.
+    ycbcr_conversion: Option<Arc<SamplerYcbcrConversion>>,
+}
+
+impl Sampler {
+    /// Creates a new [`Sampler`].
+    ///
+    /// # Errors
+    ///
+    /// [`Error::MissingDeviceFeature`] is returned if anisotropic filtering is requested but the
+    /// `samplerAnisotropy` feature was not enabled on the device.
+    #[doc(alias = "vkCreateSampler")]
+    pub fn new(device: Arc<Device>, desc: &SamplerDesc) -> Result<Self> {
+        if desc.max_anisotropy.is_some()
+            && !device
+                .features()
+                .contains(DeviceFeatures::SAMPLER_ANISOTROPY)
+        {
+            return Err(Error::MissingDeviceFeature(
+                DeviceFeatures::SAMPLER_ANISOTROPY,
+            ));
+        }
+
+        let conversion_info =
+            desc.ycbcr_conversion
+                .as_ref()
+                .map(|conversion| vk::SamplerYcbcrConversionInfo {
+                    conversion: conversion.handle(),
+
+                    p_next: std::ptr::null(),
+                    s_type: vk::StructureType::SAMPLER_YCBCR_CONVERSION_INFO,
+                });
+
+        let create_info = vk::SamplerCreateInfo {
+            flags: vk::SamplerCreateFlags::empty(),
+            mag_filter: vk::Filter::from_raw(desc.mag_filter as i32),
+            min_filter: vk::Filter::from_raw(desc.min_filter as i32),
+            mipmap_mode: vk::SamplerMipmapMode::from_raw(desc.mipmap_mode as i32),
+            address_mode_u: vk::SamplerAddressMode::from_raw(desc.address_modes[0] as i32),
+            address_mode_v: vk::SamplerAddressMode::from_raw(desc.address_modes[1] as i32),
+            address_mode_w: vk::SamplerAddressMode::from_raw(desc.address_modes[2] as i32),
+            mip_lod_bias: desc.mip_lod_bias,
+            anisotropy_enable: desc.max_anisotropy.is_some() as vk::Bool32,
+            max_anisotropy: desc
+                .max_anisotropy
+                .map_or(1.0, |max| max.min(device.limits().max_sampler_anisotropy)),
+            compare_enable: desc.compare_op.is_some() as vk::Bool32,
+            compare_op: desc.compare_op.map_or(vk::CompareOp::NEVER, |op| {
+                vk::CompareOp::from_raw(op as i32)
+            }),
+            min_lod: desc.min_lod,
+            max_lod: desc.max_lod,
+            border_color: vk::BorderColor::from_raw(desc.border_color as i32),
+            unnormalized_coordinates: desc.unnormalized_coordinates as vk::Bool32,
+
+            p_next: conversion_info
+                .as_ref()
+                .map_or(std::ptr::null(), |info| info as *const _ as *const c_void),
+            s_type: vk::StructureType::SAMPLER_CREATE_INFO,
+        };
+
+        let mut handle = vk::Sampler::null();
+
+        let ret = unsafe {
+            (device.fns().create_sampler)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self {
+            device,
+            handle,
+            ycbcr_conversion: desc.ycbcr_conversion.clone(),
+        })
+    }
+
+    /// Returns the YCbCr conversion used by this sampler, if any.
+    #[inline(always)]
+    pub fn ycbcr_conversion(&self) -> Option<&Arc<SamplerYcbcrConversion>> {
+        self.ycbcr_conversion.as_ref()
+    }
+
+    /// Returns the device that owns this sampler.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this sampler.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::Sampler {
+        self.handle
+    }
+}
+
+impl Drop for Sampler {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_sampler)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
+
+/// A cache of [`Sampler`]s which creates a single sampler per distinct [`SamplerDesc`].
+///
+/// Devices support a limited number of samplers, so sharing identical ones is usually a good
+/// idea.
+pub struct SamplerCache {
+    /// The device that owns the samplers.
+    device: Arc<Device>,
+    /// The samplers that have been created so far.
+    samplers: Mutex<HashMap<SamplerDesc, Arc<Sampler>>>,
+}
+
+impl SamplerCache {
+    /// Creates a new, empty [`SamplerCache`].
+    pub fn new(device: Arc<Device>) -> Self {
+        Self {
+            device,
+            samplers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns a sampler matching the provided description, creating it if needed.
+    pub fn get(&self, desc: &SamplerDesc) -> Result<Arc<Sampler>> {
+        let mut samplers = self.samplers.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(sampler) = samplers.get(desc) {
+            return Ok(sampler.clone());
+        }
+
+        let sampler = Arc::new(Sampler::new(self.device.clone(), desc)?);
+        samplers.insert(desc.clone(), sampler.clone());
+        Ok(sampler)
+    }
+
+    /// Returns the number of distinct samplers in the cache.
+    pub fn len(&self) -> usize {
+        self.samplers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all the samplers from the cache.
+    ///
+    /// Samplers that are still referenced elsewhere remain alive until they are dropped.
+    pub fn clear(&self) {
+        self.samplers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Returns the device that owns the samplers of this cache.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
@@ -0,0 +1,160 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use ash::vk;
+
+use crate::spirv::native_words;
+use crate::{spirv_words, Device, EntryPoint, Result, ShaderReflection, ShaderStage, SpirvInfo};
+
+/// A module of SPIR-V code, from which the shader stages of pipelines are created.
+///
+/// The code is parsed when the module is created, which allows pipelines to check that the
+/// entry points they use exist before calling into the driver.
+pub struct ShaderModule {
+    /// The device that owns this module.
+    device: Arc<Device>,
+
+    /// The handle to the module.
+    handle: vk::ShaderModule,
+    /// The information extracted from the code of the module.
+    info: SpirvInfo,
+    /// The code of the module, in the native byte order.
+    code: Box<[u32]>,
+}
+
+impl ShaderModule {
+    /// Creates a new [`ShaderModule`] from SPIR-V words.
+    ///
+    /// Words in the opposite byte order are swapped before being passed to the driver.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidSpirv`] if the code does not start with a valid
+    /// SPIR-V header or if its instructions cannot be parsed.
+    ///
+    /// [`Error::InvalidSpirv`]: crate::Error::InvalidSpirv
+    #[doc(alias = "vkCreateShaderModule")]
+    pub fn from_spirv(device: Arc<Device>, code: &[u32]) -> Result<Self> {
+        let code = native_words(code)?;
+        let info = SpirvInfo::parse(&code)?;
+
+        let create_info = vk::ShaderModuleCreateInfo {
+            flags: vk::ShaderModuleCreateFlags::empty(),
+            code_size: code.len() * 4,
+            p_code: code.as_ptr(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::SHADER_MODULE_CREATE_INFO,
+        };
+
+        let mut handle = vk::ShaderModule::null();
+
+        let ret = unsafe {
+            (device.fns().create_shader_module)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self {
+            device,
+            handle,
+            info,
+            code: code.into_owned().into_boxed_slice(),
+        })
+    }
+
+    /// Creates a new [`ShaderModule`] from SPIR-V code stored as bytes, such as the contents
+    /// of a `.spv` file.
+    ///
+    /// The bytes do not need to be aligned, and may be in either byte order.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidSpirv`] if the length of the code is not a
+    /// multiple of four, or if the code is not a valid SPIR-V module.
+    ///
+    /// [`Error::InvalidSpirv`]: crate::Error::InvalidSpirv
+    pub fn from_bytes(device: Arc<Device>, bytes: &[u8]) -> Result<Self> {
+        Self::from_spirv(device, &spirv_words(bytes)?)
+    }
+
+    /// Creates a new [`ShaderModule`] from a file containing SPIR-V code.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::Io`] if the file cannot be read, and
+    /// [`Error::InvalidSpirv`] if its contents are not a valid SPIR-V module.
+    ///
+    /// [`Error::Io`]: crate::Error::Io
+    /// [`Error::InvalidSpirv`]: crate::Error::InvalidSpirv
+    pub fn from_file(device: Arc<Device>, path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_bytes(device, &std::fs::read(path)?)
+    }
+
+    /// Returns the information extracted from the code of this module.
+    #[inline(always)]
+    pub fn info(&self) -> &SpirvInfo {
+        &self.info
+    }
+
+    /// Returns the entry points declared by this module.
+    #[inline(always)]
+    pub fn entry_points(&self) -> &[EntryPoint] {
+        self.info.entry_points()
+    }
+
+    /// Returns the entry point with the provided name and stage, if this module declares it.
+    #[inline]
+    pub fn entry_point(&self, name: &str, stage: ShaderStage) -> Option<&EntryPoint> {
+        self.info.entry_point(name, stage)
+    }
+
+    /// Extracts the resources and interface of an entry point of this module.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::InvalidSpirv`] if the module does not declare the entry
+    /// point for the provided stage.
+    ///
+    /// [`Error::InvalidSpirv`]: crate::Error::InvalidSpirv
+    pub fn reflect(&self, entry_point: &str, stage: ShaderStage) -> Result<ShaderReflection> {
+        Ok(ShaderReflection::new(&self.code, entry_point, stage)?)
+    }
+
+    /// Returns the code of this module, in the native byte order.
+    #[inline(always)]
+    pub fn code(&self) -> &[u32] {
+        &self.code
+    }
+
+    /// Returns the device that owns this module.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this module.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::ShaderModule {
+        self.handle
+    }
+}
+
+impl Drop for ShaderModule {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_shader_module)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
@@ -0,0 +1,492 @@
+use std::borrow::Cow;
+
+use crate::ShaderStage;
+
+/// The magic number found in the first word of SPIR-V modules.
+const MAGIC: u32 = 0x0723_0203;
+
+/// The number of words in the header of SPIR-V modules.
+const HEADER_LEN: usize = 5;
+
+/// The opcode of the `OpEntryPoint` instruction.
+pub(crate) const OP_ENTRY_POINT: u16 = 15;
+
+/// An error that might occur when reading SPIR-V code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpirvError {
+    /// The length of the code in bytes is not a multiple of four.
+    InvalidLength,
+    /// The code is shorter than the header of a SPIR-V module.
+    TooShort,
+    /// The code does not start with the SPIR-V magic number, in either byte order.
+    InvalidMagic(u32),
+    /// The instruction at the provided word offset has a zero length or extends past the end
+    /// of the code.
+    TruncatedInstruction(usize),
+    /// The instruction at the provided word offset contains a literal string which is not
+    /// nul-terminated or not valid UTF-8.
+    InvalidString(usize),
+    /// The type with the provided id contains itself, is nested too deeply, or has a size or
+    /// descriptor count which does not fit in 32 bits.
+    InvalidType(u32),
+    /// The module does not declare the requested entry point for the requested stage.
+    MissingEntryPoint,
+    /// Two stages of a pipeline declare the same descriptor binding with different types.
+    ConflictingBinding {
+        /// The descriptor set of the binding.
+        set: u32,
+        /// The binding number.
+        binding: u32,
+    },
+}
+
+impl std::fmt::Display for SpirvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength => write!(f, "the length of the code is not a multiple of 4"),
+            Self::TooShort => write!(f, "the code is too short to be a SPIR-V module"),
+            Self::InvalidMagic(magic) => write!(f, "invalid SPIR-V magic number: {magic:#010x}"),
+            Self::TruncatedInstruction(offset) => {
+                write!(f, "truncated instruction at word {offset}")
+            }
+            Self::InvalidString(offset) => write!(f, "invalid string at word {offset}"),
+            Self::InvalidType(id) => write!(f, "invalid type %{id}"),
+            Self::MissingEntryPoint => write!(f, "the entry point is not declared by the module"),
+            Self::ConflictingBinding { set, binding } => write!(
+                f,
+                "binding {binding} of set {set} has different types in different stages"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpirvError {}
+
+/// Converts SPIR-V code stored as bytes into words in the native byte order.
+///
+/// The byte order of the code is detected from its magic number. The bytes are borrowed when
+/// they are already suitably aligned and in the native byte order, and copied otherwise.
+pub fn spirv_words(bytes: &[u8]) -> Result<Cow<'_, [u32]>, SpirvError> {
+    if !bytes.len().is_multiple_of(4) {
+        return Err(SpirvError::InvalidLength);
+    }
+    if bytes.len() < HEADER_LEN * 4 {
+        return Err(SpirvError::TooShort);
+    }
+
+    let magic: [u8; 4] = bytes[..4].try_into().unwrap();
+    let from_bytes: fn([u8; 4]) -> u32 = if u32::from_le_bytes(magic) == MAGIC {
+        u32::from_le_bytes
+    } else if u32::from_be_bytes(magic) == MAGIC {
+        u32::from_be_bytes
+    } else {
+        return Err(SpirvError::InvalidMagic(u32::from_le_bytes(magic)));
+    };
+
+    if u32::from_ne_bytes(magic) == MAGIC {
+        if let Ok(words) = bytemuck::try_cast_slice(bytes) {
+            return Ok(Cow::Borrowed(words));
+        }
+    }
+
+    Ok(Cow::Owned(
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| from_bytes(chunk.try_into().unwrap()))
+            .collect(),
+    ))
+}
+
+/// Makes sure that SPIR-V code is in the native byte order, swapping the bytes of its words if
+/// needed.
+pub(crate) fn native_words(words: &[u32]) -> Result<Cow<'_, [u32]>, SpirvError> {
+    if words.len() < HEADER_LEN {
+        return Err(SpirvError::TooShort);
+    }
+
+    match words[0] {
+        MAGIC => Ok(Cow::Borrowed(words)),
+        magic if magic.swap_bytes() == MAGIC => Ok(Cow::Owned(
+            words.iter().map(|word| word.swap_bytes()).collect(),
+        )),
+        magic => Err(SpirvError::InvalidMagic(magic)),
+    }
+}
+
+/// An instruction of a SPIR-V module.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Instruction<'a> {
+    /// The opcode of the instruction.
+    pub opcode: u16,
+    /// The operands of the instruction.
+    pub operands: &'a [u32],
+    /// The offset of the instruction in the module, in words.
+    pub offset: usize,
+}
+
+impl Instruction<'_> {
+    /// Decodes the literal string which starts at the provided operand, and returns it along
+    /// with the number of operands that it spans.
+    pub fn string(&self, operand: usize) -> Result<(String, usize), SpirvError> {
+        // Strings are stored in little-endian order within words, regardless of the byte
+        // order of the module.
+        let mut bytes = Vec::new();
+        for (index, word) in self.operands.iter().skip(operand).enumerate() {
+            let chunk = word.to_le_bytes();
+            if let Some(end) = chunk.iter().position(|&byte| byte == 0) {
+                bytes.extend_from_slice(&chunk[..end]);
+                return String::from_utf8(bytes)
+                    .map(|string| (string, index + 1))
+                    .map_err(|_| SpirvError::InvalidString(self.offset));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        Err(SpirvError::InvalidString(self.offset))
+    }
+}
+
+/// Returns an iterator over the instructions of a SPIR-V module in the native byte order,
+/// whose header has already been checked.
+pub(crate) fn instructions(
+    words: &[u32],
+) -> impl Iterator<Item = Result<Instruction<'_>, SpirvError>> {
+    let mut offset = HEADER_LEN;
+
+    std::iter::from_fn(move || {
+        let &first = words.get(offset)?;
+        let len = (first >> 16) as usize;
+
+        if len == 0 || offset + len > words.len() {
+            let err = SpirvError::TruncatedInstruction(offset);
+            offset = words.len();
+            return Some(Err(err));
+        }
+
+        let instruction = Instruction {
+            opcode: first as u16,
+            operands: &words[offset + 1..offset + len],
+            offset,
+        };
+        offset += len;
+        Some(Ok(instruction))
+    })
+}
+
+/// The execution model of an entry point, which determines the stage it may be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ExecutionModel {
+    Vertex,
+    TessellationControl,
+    TessellationEvaluation,
+    Geometry,
+    Fragment,
+    GLCompute,
+    Kernel,
+    TaskNV,
+    MeshNV,
+    RayGeneration,
+    Intersection,
+    AnyHit,
+    ClosestHit,
+    Miss,
+    Callable,
+    Task,
+    Mesh,
+}
+
+impl ExecutionModel {
+    /// Returns the execution model matching the provided SPIR-V value, if it is known.
+    pub(crate) fn from_raw(value: u32) -> Option<Self> {
+        Some(match value {
+            0 => Self::Vertex,
+            1 => Self::TessellationControl,
+            2 => Self::TessellationEvaluation,
+            3 => Self::Geometry,
+            4 => Self::Fragment,
+            5 => Self::GLCompute,
+            6 => Self::Kernel,
+            5267 => Self::TaskNV,
+            5268 => Self::MeshNV,
+            5313 => Self::RayGeneration,
+            5314 => Self::Intersection,
+            5315 => Self::AnyHit,
+            5316 => Self::ClosestHit,
+            5317 => Self::Miss,
+            5318 => Self::Callable,
+            5364 => Self::Task,
+            5365 => Self::Mesh,
+            _ => return None,
+        })
+    }
+
+    /// Returns the pipeline stage that entry points with this execution model may be used
+    /// for, if it is supported by this crate.
+    pub fn stage(self) -> Option<ShaderStage> {
+        match self {
+            Self::Vertex => Some(ShaderStage::Vertex),
+            Self::TessellationControl => Some(ShaderStage::TessellationControl),
+            Self::TessellationEvaluation => Some(ShaderStage::TessellationEvaluation),
+            Self::Geometry => Some(ShaderStage::Geometry),
+            Self::Fragment => Some(ShaderStage::Fragment),
+            Self::GLCompute => Some(ShaderStage::Compute),
+            _ => None,
+        }
+    }
+}
+
+/// An entry point declared by a SPIR-V module.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntryPoint {
+    /// The name of the entry point.
+    pub name: String,
+    /// The execution model of the entry point.
+    pub execution_model: ExecutionModel,
+}
+
+impl EntryPoint {
+    /// Returns the pipeline stage that this entry point may be used for, if it is supported
+    /// by this crate.
+    #[inline(always)]
+    pub fn stage(&self) -> Option<ShaderStage> {
+        self.execution_model.stage()
+    }
+}
+
+/// The information extracted from a SPIR-V module by a lightweight parser.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpirvInfo {
+    /// The major and minor SPIR-V version of the module.
+    version: (u8, u8),
+    /// The upper bound of the ids used by the module.
+    bound: u32,
+    /// The entry points declared by the module.
+    entry_points: Vec<EntryPoint>,
+}
+
+impl SpirvInfo {
+    /// Parses a SPIR-V module, in either byte order.
+    ///
+    /// Entry points whose execution model is unknown to this crate are not listed.
+    pub fn parse(words: &[u32]) -> Result<Self, SpirvError> {
+        let words = native_words(words)?;
+        let version = words[1];
+
+        let mut entry_points = Vec::new();
+        for instruction in instructions(&words) {
+            let instruction = instruction?;
+            if instruction.opcode != OP_ENTRY_POINT {
+                continue;
+            }
+
+            let &[model, _function, ..] = instruction.operands else {
+                return Err(SpirvError::TruncatedInstruction(instruction.offset));
+            };
+            let (name, _) = instruction.string(2)?;
+
+            if let Some(execution_model) = ExecutionModel::from_raw(model) {
+                entry_points.push(EntryPoint {
+                    name,
+                    execution_model,
+                });
+            }
+        }
+
+        Ok(Self {
+            version: ((version >> 16) as u8, (version >> 8) as u8),
+            bound: words[3],
+            entry_points,
+        })
+    }
+
+    /// Returns the major and minor SPIR-V version of the module.
+    #[inline(always)]
+    pub fn version(&self) -> (u8, u8) {
+        self.version
+    }
+
+    /// Returns the upper bound of the ids used by the module.
+    #[inline(always)]
+    pub fn bound(&self) -> u32 {
+        self.bound
+    }
+
+    /// Returns the entry points declared by the module.
+    #[inline(always)]
+    pub fn entry_points(&self) -> &[EntryPoint] {
+        &self.entry_points
+    }
+
+    /// Returns the entry point with the provided name and stage, if the module declares it.
+    pub fn entry_point(&self, name: &str, stage: ShaderStage) -> Option<&EntryPoint> {
+        self.entry_points
+            .iter()
+            .find(|entry_point| entry_point.name == name && entry_point.stage() == Some(stage))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Encodes an instruction from its opcode and operands.
+    pub(crate) fn op(opcode: u16, operands: &[u32]) -> Vec<u32> {
+        let mut words = vec![((operands.len() as u32 + 1) << 16) | opcode as u32];
+        words.extend_from_slice(operands);
+        words
+    }
+
+    /// Encodes a nul-terminated literal string.
+    pub(crate) fn string(string: &str) -> Vec<u32> {
+        let mut bytes = string.as_bytes().to_vec();
+        bytes.resize(string.len() / 4 * 4 + 4, 0);
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect()
+    }
+
+    /// Assembles a SPIR-V 1.3 module in the native byte order from its instructions.
+    pub(crate) fn module(bound: u32, instructions: &[Vec<u32>]) -> Vec<u32> {
+        let mut words = vec![MAGIC, 0x0001_0300, 0, bound, 0];
+        for instruction in instructions {
+            words.extend_from_slice(instruction);
+        }
+        words
+    }
+
+    /// Encodes an `OpEntryPoint` instruction without interface variables.
+    pub(crate) fn entry_point(model: u32, function: u32, name: &str) -> Vec<u32> {
+        let mut operands = vec![model, function];
+        operands.extend(string(name));
+        op(OP_ENTRY_POINT, &operands)
+    }
+
+    /// Returns a module with a vertex and a fragment entry point, and one with an unknown
+    /// execution model.
+    fn shaders() -> Vec<u32> {
+        module(
+            4,
+            &[
+                op(17, &[1]), // OpCapability Shader
+                entry_point(0, 1, "main"),
+                entry_point(4, 2, "fragment_main"),
+                entry_point(1000, 3, "unknown"),
+            ],
+        )
+    }
+
+    fn bytes(words: &[u32], to_bytes: fn(u32) -> [u8; 4]) -> Vec<u8> {
+        words.iter().flat_map(|&word| to_bytes(word)).collect()
+    }
+
+    #[test]
+    fn words_in_either_byte_order() {
+        let words = shaders();
+
+        let native = bytes(&words, u32::to_ne_bytes);
+        assert_eq!(&*spirv_words(&native).unwrap(), &words[..]);
+        let swapped = bytes(&words, |word| word.swap_bytes().to_ne_bytes());
+        assert_eq!(&*spirv_words(&swapped).unwrap(), &words[..]);
+    }
+
+    #[test]
+    fn misaligned_words_are_copied() {
+        let words = shaders();
+
+        // Offset the code by one byte so that it cannot be borrowed as words.
+        let mut storage = vec![0u8];
+        storage.extend(bytes(&words, u32::to_ne_bytes));
+        let parsed = spirv_words(&storage[1..]).unwrap();
+        assert!(matches!(parsed, Cow::Owned(_)));
+        assert_eq!(&*parsed, &words[..]);
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let words = shaders();
+        let native = bytes(&words, u32::to_ne_bytes);
+
+        assert_eq!(
+            spirv_words(&native[..native.len() - 1]),
+            Err(SpirvError::InvalidLength)
+        );
+        assert_eq!(spirv_words(&native[..16]), Err(SpirvError::TooShort));
+        let mut invalid = native.clone();
+        invalid[..4].copy_from_slice(&0xdead_beef_u32.to_le_bytes());
+        assert_eq!(
+            spirv_words(&invalid),
+            Err(SpirvError::InvalidMagic(0xdead_beef))
+        );
+    }
+
+    #[test]
+    fn parse_entry_points() {
+        let info = SpirvInfo::parse(&shaders()).unwrap();
+
+        assert_eq!(info.version(), (1, 3));
+        assert_eq!(info.bound(), 4);
+        assert_eq!(
+            info.entry_points(),
+            &[
+                EntryPoint {
+                    name: "main".into(),
+                    execution_model: ExecutionModel::Vertex,
+                },
+                EntryPoint {
+                    name: "fragment_main".into(),
+                    execution_model: ExecutionModel::Fragment,
+                },
+            ]
+        );
+        assert!(info.entry_point("main", ShaderStage::Vertex).is_some());
+        assert!(info.entry_point("main", ShaderStage::Fragment).is_none());
+        assert!(info
+            .entry_point("fragment_main", ShaderStage::Fragment)
+            .is_some());
+
+        // Modules in the other byte order are swapped before being parsed.
+        let swapped: Vec<u32> = shaders().iter().map(|word| word.swap_bytes()).collect();
+        assert_eq!(SpirvInfo::parse(&swapped), Ok(info));
+    }
+
+    #[test]
+    fn parse_invalid_modules() {
+        assert_eq!(
+            SpirvInfo::parse(&[MAGIC, 0, 0, 0]),
+            Err(SpirvError::TooShort)
+        );
+        assert_eq!(
+            SpirvInfo::parse(&[0xdead_beef, 0, 0, 0, 0]),
+            Err(SpirvError::InvalidMagic(0xdead_beef))
+        );
+
+        // The second instruction claims more words than remain in the module.
+        let mut words = module(2, &[op(17, &[1])]);
+        words.push((4 << 16) | OP_ENTRY_POINT as u32);
+        words.push(0);
+        assert_eq!(
+            SpirvInfo::parse(&words),
+            Err(SpirvError::TruncatedInstruction(7))
+        );
+        // Instructions of zero words would never advance.
+        let words = module(2, &[vec![0]]);
+        assert_eq!(
+            SpirvInfo::parse(&words),
+            Err(SpirvError::TruncatedInstruction(5))
+        );
+        // An entry point whose name is not nul-terminated.
+        let words = module(
+            2,
+            &[op(OP_ENTRY_POINT, &[0, 1, u32::from_le_bytes(*b"main")])],
+        );
+        assert_eq!(SpirvInfo::parse(&words), Err(SpirvError::InvalidString(5)));
+        // An entry point without a function.
+        let words = module(2, &[op(OP_ENTRY_POINT, &[0])]);
+        assert_eq!(
+            SpirvInfo::parse(&words),
+            Err(SpirvError::TruncatedInstruction(5))
+        );
+    }
+}
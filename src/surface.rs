@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use ash::vk;
 
-use crate::{Instance, Result};
+use crate::{DisplaySurfaceDesc, Error, Instance, InstanceExtensions, Result};
 
 /// Represents a surface that can be rendered to.
 pub struct Surface {
@@ -11,6 +11,11 @@ pub struct Surface {
 
     /// The handle to the surface.
     handle: vk::SurfaceKHR,
+
+    /// The window that this surface was created from, if it is owned by the surface.
+    ///
+    /// This field is only used to keep the window alive until the surface is destroyed.
+    _window: Option<Box<dyn Send + Sync>>,
 }
 
 impl Surface {
@@ -20,33 +25,36 @@ impl Surface {
     ///
     /// The provided handle must be valid and belong to the provided instance.
     pub unsafe fn from_handle(instance: Arc<Instance>, handle: vk::SurfaceKHR) -> Arc<Self> {
-        Arc::new(Self { instance, handle })
+        Arc::new(Self {
+            instance,
+            handle,
+            _window: None,
+        })
     }
 
     /// Creates a new [`Surface`] from the provided xlib display and window.
     ///
-    /// # Panics
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the provided instance
+    /// does not have the `VK_KHR_xlib_surface` extension enabled.
     ///
-    /// This function panics if the provided instance does not have the `VK_KHR_xlib_surface`
-    pub fn from_xlib_window(
+    /// # Safety
+    ///
+    /// `dpy` must be a valid Xlib `Display` connection and `window` must be a valid Xlib window
+    /// on that display. Both must remain valid for as long as the returned surface is alive.
+    pub unsafe fn from_xlib_window(
         instance: Arc<Instance>,
         dpy: *mut vk::Display,
         window: vk::Window,
     ) -> Result<Arc<Self>> {
-        let create_fn = unsafe {
-            (instance.library().fns().get_instance_proc_addr)(
-                instance.handle(),
-                b"vkCreateXlibSurfaceKHR\0".as_ptr() as *const i8,
-            )
-        };
-
-        assert!(
-            create_fn.is_some(),
-            "the VK_KHR_xlib_surface extension is not enabled"
-        );
-
         let create_fn =
-            unsafe { std::mem::transmute::<_, vk::PFN_vkCreateXlibSurfaceKHR>(create_fn) };
+            instance
+                .fns()
+                .create_xlib_surface
+                .ok_or(Error::MissingInstanceExtension(
+                    InstanceExtensions::XLIB_SURFACE,
+                ))?;
 
         let create_info = vk::XlibSurfaceCreateInfoKHR {
             dpy,
@@ -77,29 +85,27 @@ impl Surface {
 
     /// Creates a new [`Surface`] from the provided xcb connection and window.
     ///
-    /// # Panics
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the provided instance
+    /// does not have the `VK_KHR_xcb_surface` extension enabled.
+    ///
+    /// # Safety
     ///
-    /// This function panics if the provided instance does not have the `VK_KHR_xcb_surface`
-    /// extension enabled.
-    pub fn from_xcb_window(
+    /// `connection` must be a valid XCB connection and `window` must be a valid XCB window on that
+    /// connection. Both must remain valid for as long as the returned surface is alive.
+    pub unsafe fn from_xcb_window(
         instance: Arc<Instance>,
         connection: *mut vk::xcb_connection_t,
         window: vk::xcb_window_t,
     ) -> Result<Arc<Self>> {
-        let create_fn = unsafe {
-            (instance.library().fns().get_instance_proc_addr)(
-                instance.handle(),
-                b"vkCreateXcbSurfaceKHR\0".as_ptr() as *const i8,
-            )
-        };
-
-        assert!(
-            create_fn.is_some(),
-            "the VK_KHR_xcb_surface extension is not enabled"
-        );
-
         let create_fn =
-            unsafe { std::mem::transmute::<_, vk::PFN_vkCreateXcbSurfaceKHR>(create_fn) };
+            instance
+                .fns()
+                .create_xcb_surface
+                .ok_or(Error::MissingInstanceExtension(
+                    InstanceExtensions::XCB_SURFACE,
+                ))?;
 
         let create_info = vk::XcbSurfaceCreateInfoKHR {
             connection,
@@ -107,7 +113,7 @@ impl Surface {
             flags: vk::XcbSurfaceCreateFlagsKHR::empty(),
 
             p_next: std::ptr::null(),
-            s_type: vk::StructureType::XLIB_SURFACE_CREATE_INFO_KHR,
+            s_type: vk::StructureType::XCB_SURFACE_CREATE_INFO_KHR,
         };
 
         let mut handle = vk::SurfaceKHR::null();
@@ -130,29 +136,27 @@ impl Surface {
 
     /// Creates a new [`Surface`] from the provided wayland display and surface.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// This function panics if the provided instance does not have the `VK_KHR_wayland_surface`
-    /// extension enabled.
-    pub fn from_wayland_surface(
+    /// This function fails with [`Error::MissingInstanceExtension`] if the provided instance
+    /// does not have the `VK_KHR_wayland_surface` extension enabled.
+    ///
+    /// # Safety
+    ///
+    /// `display` and `surface` must be valid Wayland objects, and must remain valid for as long as
+    /// the returned surface is alive.
+    pub unsafe fn from_wayland_surface(
         instance: Arc<Instance>,
         display: *mut vk::wl_display,
         surface: *mut vk::wl_surface,
     ) -> Result<Arc<Self>> {
-        let create_fn = unsafe {
-            (instance.library().fns().get_instance_proc_addr)(
-                instance.handle(),
-                b"vkCreateWaylandSurfaceKHR\0".as_ptr() as *const i8,
-            )
-        };
-
-        assert!(
-            create_fn.is_some(),
-            "the VK_KHR_wayland_surface extension is not enabled"
-        );
-
         let create_fn =
-            unsafe { std::mem::transmute::<_, vk::PFN_vkCreateWaylandSurfaceKHR>(create_fn) };
+            instance
+                .fns()
+                .create_wayland_surface
+                .ok_or(Error::MissingInstanceExtension(
+                    InstanceExtensions::WAYLAND_SURFACE,
+                ))?;
 
         let create_info = vk::WaylandSurfaceCreateInfoKHR {
             display,
@@ -183,29 +187,27 @@ impl Surface {
 
     /// Creates a new [`Surface`] from the provided Win32 instance and window.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// This function panics if the provided instance does not have the `VK_KHR_win32_surface`
-    /// extension enabled.
-    pub fn from_win32_window(
+    /// This function fails with [`Error::MissingInstanceExtension`] if the provided instance
+    /// does not have the `VK_KHR_win32_surface` extension enabled.
+    ///
+    /// # Safety
+    ///
+    /// `hinstance` and `hwnd` must be valid Win32 handles, and must remain valid for as long as
+    /// the returned surface is alive.
+    pub unsafe fn from_win32_window(
         instance: Arc<Instance>,
         hinstance: vk::HINSTANCE,
         hwnd: vk::HWND,
     ) -> Result<Arc<Self>> {
-        let create_fn = unsafe {
-            (instance.library().fns().get_instance_proc_addr)(
-                instance.handle(),
-                b"vkCreateWin32SurfaceKHR\0".as_ptr() as *const i8,
-            )
-        };
-
-        assert!(
-            create_fn.is_some(),
-            "the VK_KHR_win32_surface extension is not enabled"
-        );
-
         let create_fn =
-            unsafe { std::mem::transmute::<_, vk::PFN_vkCreateWin32SurfaceKHR>(create_fn) };
+            instance
+                .fns()
+                .create_win32_surface
+                .ok_or(Error::MissingInstanceExtension(
+                    InstanceExtensions::WIN32_SURFACE,
+                ))?;
 
         let create_info = vk::Win32SurfaceCreateInfoKHR {
             hinstance,
@@ -234,9 +236,227 @@ impl Surface {
         Ok(unsafe { Self::from_handle(instance, handle) })
     }
 
+    /// Creates a new [`Surface`] from the provided Android native window.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the provided instance does
+    /// not have the `VK_KHR_android_surface` extension enabled.
+    ///
+    /// # Safety
+    ///
+    /// `window` must be a valid `ANativeWindow` and must remain valid for as long as the returned
+    /// surface is alive.
+    #[doc(alias = "vkCreateAndroidSurfaceKHR")]
+    pub unsafe fn from_android_window(
+        instance: Arc<Instance>,
+        window: *mut vk::ANativeWindow,
+    ) -> Result<Arc<Self>> {
+        let create_fn =
+            instance
+                .fns()
+                .create_android_surface
+                .ok_or(Error::MissingInstanceExtension(
+                    InstanceExtensions::ANDROID_SURFACE,
+                ))?;
+
+        let create_info = vk::AndroidSurfaceCreateInfoKHR {
+            window,
+            flags: vk::AndroidSurfaceCreateFlagsKHR::empty(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::ANDROID_SURFACE_CREATE_INFO_KHR,
+        };
+
+        let mut handle = vk::SurfaceKHR::null();
+
+        let ret = unsafe {
+            create_fn(
+                instance.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(unsafe { Self::from_handle(instance, handle) })
+    }
+
+    /// Creates a new [`Surface`] from the provided `CAMetalLayer`.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the provided instance does
+    /// not have the `VK_EXT_metal_surface` extension enabled.
+    ///
+    /// # Safety
+    ///
+    /// `layer` must be a valid `CAMetalLayer` and must remain valid for as long as the returned
+    /// surface is alive.
+    #[doc(alias = "vkCreateMetalSurfaceEXT")]
+    pub unsafe fn from_metal_layer(
+        instance: Arc<Instance>,
+        layer: *const vk::CAMetalLayer,
+    ) -> Result<Arc<Self>> {
+        let create_fn =
+            instance
+                .fns()
+                .create_metal_surface
+                .ok_or(Error::MissingInstanceExtension(
+                    InstanceExtensions::METAL_SURFACE,
+                ))?;
+
+        let create_info = vk::MetalSurfaceCreateInfoEXT {
+            p_layer: layer,
+            flags: vk::MetalSurfaceCreateFlagsEXT::empty(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::METAL_SURFACE_CREATE_INFO_EXT,
+        };
+
+        let mut handle = vk::SurfaceKHR::null();
+
+        let ret = unsafe {
+            create_fn(
+                instance.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(unsafe { Self::from_handle(instance, handle) })
+    }
+
+    /// Creates a new headless [`Surface`].
+    ///
+    /// Headless surfaces are not backed by any window system. They are mostly useful to run
+    /// the presentation path of an application in environments where no display server is
+    /// available (such as continuous integration runners).
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the provided instance
+    /// does not have the `VK_EXT_headless_surface` extension enabled.
+    #[doc(alias = "vkCreateHeadlessSurfaceEXT")]
+    pub fn headless(instance: Arc<Instance>) -> Result<Arc<Self>> {
+        let create_fn =
+            instance
+                .fns()
+                .create_headless_surface
+                .ok_or(Error::MissingInstanceExtension(
+                    InstanceExtensions::HEADLESS_SURFACE,
+                ))?;
+
+        let create_info = vk::HeadlessSurfaceCreateInfoEXT {
+            flags: vk::HeadlessSurfaceCreateFlagsEXT::empty(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::HEADLESS_SURFACE_CREATE_INFO_EXT,
+        };
+
+        let mut handle = vk::SurfaceKHR::null();
+
+        let ret = unsafe {
+            create_fn(
+                instance.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(unsafe { Self::from_handle(instance, handle) })
+    }
+
+    /// Creates a new [`Surface`] that presents directly to a display plane.
+    ///
+    /// The surface is created on the instance that owns the display mode.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingInstanceExtension`] if the instance does not
+    /// have the `VK_KHR_display` extension enabled.
+    #[doc(alias = "vkCreateDisplayPlaneSurfaceKHR")]
+    pub fn from_display_plane(desc: DisplaySurfaceDesc) -> Result<Arc<Self>> {
+        let instance = desc.mode.display().physical_device().instance().clone();
+
+        let create_fn = instance
+            .fns()
+            .create_display_plane_surface
+            .ok_or(Error::MissingInstanceExtension(InstanceExtensions::DISPLAY))?;
+
+        let create_info = vk::DisplaySurfaceCreateInfoKHR {
+            display_mode: desc.mode.handle(),
+            plane_index: desc.plane_index,
+            plane_stack_index: desc.plane_stack_index,
+            transform: vk::SurfaceTransformFlagsKHR::from_raw(desc.transform as u32),
+            global_alpha: desc.global_alpha,
+            alpha_mode: vk::DisplayPlaneAlphaFlagsKHR::from_raw(desc.alpha_mode as u32),
+            image_extent: vk::Extent2D {
+                width: desc.image_extent[0],
+                height: desc.image_extent[1],
+            },
+            flags: vk::DisplaySurfaceCreateFlagsKHR::empty(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::DISPLAY_SURFACE_CREATE_INFO_KHR,
+        };
+
+        let mut handle = vk::SurfaceKHR::null();
+
+        let ret = unsafe {
+            create_fn(
+                instance.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(unsafe { Self::from_handle(instance, handle) })
+    }
+
     /// Creates a new [`Surface`] from the provided raw window handle.
+    ///
+    /// On macOS, an `NSView` that is not already backed by a `CAMetalLayer` gets a new one
+    /// assigned to it. On iOS, the `UIView` must already be backed by a `CAMetalLayer`.
+    ///
+    /// Direct-to-display (DRM) handles do not identify a physical device, and therefore cannot
+    /// be turned into a surface directly. Use [`PhysicalDevice::drm_display`] and
+    /// [`Surface::from_display_plane`] instead.
+    ///
+    /// [`PhysicalDevice::drm_display`]: crate::PhysicalDevice::drm_display
+    ///
+    /// # Safety
+    ///
+    /// The provided handles must be valid, and must remain valid for as long as the returned
+    /// surface is alive. Prefer [`Surface::from_window`], which guarantees this.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::UnsupportedWindowHandle`] if the provided handles have
+    /// no Vulkan surface counterpart on the current platform, and with
+    /// [`Error::NotMainThread`] if they belong to an AppKit window and the function is not
+    /// called on the main thread.
     #[cfg(feature = "raw-window-handle")]
-    pub fn from_raw_window_handle(
+    pub unsafe fn from_raw_window_handle(
         instance: Arc<Instance>,
         rwh: raw_window_handle::RawWindowHandle,
         rdh: raw_window_handle::RawDisplayHandle,
@@ -271,26 +491,60 @@ impl Surface {
                 display.display.as_ptr(),
                 handle.surface.as_ptr(),
             ),
-            _ => panic!("unsupported raw window handle"),
+            (Rdh::Android(_), Rwh::AndroidNdk(handle)) => {
+                Self::from_android_window(instance, handle.a_native_window.as_ptr())
+            }
+            #[cfg(target_os = "macos")]
+            (Rdh::AppKit(_), Rwh::AppKit(handle)) => {
+                // Views may only be accessed from the main thread.
+                if !crate::metal::is_main_thread() {
+                    return Err(Error::NotMainThread);
+                }
+                let layer = crate::metal::layer_from_ns_view(handle.ns_view.as_ptr());
+                Self::from_metal_layer(instance, layer)
+            }
+            #[cfg(target_os = "ios")]
+            (Rdh::UiKit(_), Rwh::UiKit(handle)) => {
+                let layer = crate::metal::layer_from_ui_view(handle.ui_view.as_ptr())
+                    .ok_or(Error::UnsupportedWindowHandle)?;
+                Self::from_metal_layer(instance, layer)
+            }
+            _ => Err(Error::UnsupportedWindowHandle),
         }
     }
 
     /// Creates a new [`Surface`] from the provided window.
     ///
-    /// # Panics
+    /// The surface takes ownership of `window` and keeps it alive until the surface itself is
+    /// dropped, ensuring that the underlying window handles outlive the surface. Shared windows
+    /// can be passed as an `Arc<W>`.
+    ///
+    /// # Errors
     ///
-    /// This function panics if the raw window handle or raw display handle of the window
-    /// cannot be retrieved.
+    /// This function fails with [`Error::WindowHandleUnavailable`] if the window or display
+    /// handle of the window cannot currently be retrieved, and with
+    /// [`Error::UnsupportedWindowHandle`] if they have no Vulkan surface counterpart on the
+    /// current platform. On macOS, it fails with [`Error::NotMainThread`] if it is not called on
+    /// the main thread.
     #[cfg(feature = "raw-window-handle")]
-    pub fn from_window<W>(instance: Arc<Instance>, window: &W) -> Result<Arc<Self>>
+    pub fn from_window<W>(instance: Arc<Instance>, window: W) -> Result<Arc<Self>>
     where
-        W: raw_window_handle::HasDisplayHandle + raw_window_handle::HasWindowHandle + ?Sized,
+        W: raw_window_handle::HasDisplayHandle
+            + raw_window_handle::HasWindowHandle
+            + Send
+            + Sync
+            + 'static,
     {
-        Self::from_raw_window_handle(
-            instance,
-            window.window_handle().unwrap().as_raw(),
-            window.display_handle().unwrap().as_raw(),
-        )
+        let rwh = window.window_handle().map_err(Error::from)?.as_raw();
+        let rdh = window.display_handle().map_err(Error::from)?.as_raw();
+
+        // SAFETY: the handles were retrieved from `window`, which is kept alive by the surface.
+        let mut this = unsafe { Self::from_raw_window_handle(instance, rwh, rdh)? };
+
+        // The surface was just created, so nobody else can be holding a reference to it.
+        Arc::get_mut(&mut this).unwrap()._window = Some(Box::new(window));
+
+        Ok(this)
     }
 
     /// Returns the instance that owns this surface.
@@ -308,12 +562,248 @@ impl Surface {
 
 impl Drop for Surface {
     fn drop(&mut self) {
+        // A surface can only exist if `VK_KHR_surface` was enabled on its instance, meaning that
+        // the function is always available here.
+        if let Some(destroy_fn) = self.instance.fns().destroy_surface {
+            unsafe { destroy_fn(self.instance.handle(), self.handle, std::ptr::null()) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+    use crate::{
+        ColorSpace, Display, DisplayMode, DisplayPlaneAlpha, Format, PhysicalDevice, PresentModes,
+        SurfaceTransform, SurfaceTransforms,
+    };
+    use ash::vk::Handle;
+
+    /// Creates a surface with each constructor, returning the results in the same order as
+    /// [`CREATE_FNS`].
+    fn create_all(instance: &Arc<Instance>) -> Vec<Result<Arc<Surface>>> {
+        let physical_device = unsafe {
+            PhysicalDevice::from_handle(instance.clone(), vk::PhysicalDevice::from_raw(1))
+        };
+        let display = unsafe { Display::from_handle(physical_device, vk::DisplayKHR::from_raw(2)) };
+        let mode = unsafe { DisplayMode::from_handle(display, vk::DisplayModeKHR::from_raw(3)) };
+
         unsafe {
-            (self.instance.fns().destroy_surface)(
-                self.instance.handle(),
-                self.handle,
-                std::ptr::null(),
+            vec![
+                Surface::from_xlib_window(instance.clone(), std::ptr::null_mut(), 0),
+                Surface::from_xcb_window(instance.clone(), std::ptr::null_mut(), 0),
+                Surface::from_wayland_surface(
+                    instance.clone(),
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                ),
+                Surface::from_win32_window(instance.clone(), std::ptr::null(), std::ptr::null()),
+                Surface::from_android_window(instance.clone(), std::ptr::null_mut()),
+                Surface::from_metal_layer(instance.clone(), std::ptr::null()),
+                Surface::headless(instance.clone()),
+                Surface::from_display_plane(DisplaySurfaceDesc {
+                    mode: &mode,
+                    plane_index: 0,
+                    plane_stack_index: 0,
+                    transform: SurfaceTransform::Identity,
+                    global_alpha: 1.0,
+                    alpha_mode: DisplayPlaneAlpha::Opaque,
+                    image_extent: [640, 480],
+                }),
+            ]
+        }
+    }
+
+    /// The creation function used by each constructor, along with the extension providing it.
+    const CREATE_FNS: [(&str, InstanceExtensions); 8] = [
+        ("vkCreateXlibSurfaceKHR", InstanceExtensions::XLIB_SURFACE),
+        ("vkCreateXcbSurfaceKHR", InstanceExtensions::XCB_SURFACE),
+        (
+            "vkCreateWaylandSurfaceKHR",
+            InstanceExtensions::WAYLAND_SURFACE,
+        ),
+        ("vkCreateWin32SurfaceKHR", InstanceExtensions::WIN32_SURFACE),
+        (
+            "vkCreateAndroidSurfaceKHR",
+            InstanceExtensions::ANDROID_SURFACE,
+        ),
+        ("vkCreateMetalSurfaceEXT", InstanceExtensions::METAL_SURFACE),
+        (
+            "vkCreateHeadlessSurfaceEXT",
+            InstanceExtensions::HEADLESS_SURFACE,
+        ),
+        (
+            "vkCreateDisplayPlaneSurfaceKHR",
+            InstanceExtensions::DISPLAY,
+        ),
+    ];
+
+    #[test]
+    fn constructors_pass_the_right_structure_type() {
+        let instance = mock::instance(&[]);
+
+        for surface in create_all(&instance) {
+            assert!(surface.is_ok());
+        }
+
+        let received = mock::received_s_types();
+        assert_eq!(received.len(), CREATE_FNS.len());
+        for (received, expected) in received {
+            assert_eq!(received.as_raw(), expected.as_raw());
+        }
+    }
+
+    #[test]
+    fn constructors_report_missing_extensions() {
+        for (name, extension) in CREATE_FNS {
+            let instance = mock::instance(&[name]);
+
+            let results = create_all(&instance);
+            assert_eq!(mock::received_s_types().len(), CREATE_FNS.len() - 1);
+
+            let errors = results
+                .iter()
+                .filter_map(|r| r.as_ref().err())
+                .collect::<Vec<_>>();
+            assert_eq!(errors, [&Error::MissingInstanceExtension(extension)]);
+        }
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[test]
+    fn raw_window_handles_use_the_matching_constructor() {
+        use raw_window_handle::*;
+        use std::num::{NonZeroIsize, NonZeroU32};
+
+        let ptr = std::ptr::NonNull::dangling();
+
+        let cases = [
+            (
+                RawWindowHandle::Win32(Win32WindowHandle::new(NonZeroIsize::MIN)),
+                RawDisplayHandle::Windows(WindowsDisplayHandle::new()),
+                vk::StructureType::WIN32_SURFACE_CREATE_INFO_KHR,
+            ),
+            (
+                RawWindowHandle::Xlib(XlibWindowHandle::new(1)),
+                RawDisplayHandle::Xlib(XlibDisplayHandle::new(None, 0)),
+                vk::StructureType::XLIB_SURFACE_CREATE_INFO_KHR,
+            ),
+            (
+                RawWindowHandle::Xcb(XcbWindowHandle::new(NonZeroU32::MIN)),
+                RawDisplayHandle::Xcb(XcbDisplayHandle::new(None, 0)),
+                vk::StructureType::XCB_SURFACE_CREATE_INFO_KHR,
+            ),
+            (
+                RawWindowHandle::Wayland(WaylandWindowHandle::new(ptr)),
+                RawDisplayHandle::Wayland(WaylandDisplayHandle::new(ptr)),
+                vk::StructureType::WAYLAND_SURFACE_CREATE_INFO_KHR,
+            ),
+            (
+                RawWindowHandle::AndroidNdk(AndroidNdkWindowHandle::new(ptr)),
+                RawDisplayHandle::Android(AndroidDisplayHandle::new()),
+                vk::StructureType::ANDROID_SURFACE_CREATE_INFO_KHR,
+            ),
+        ];
+
+        for (rwh, rdh, s_type) in cases {
+            let instance = mock::instance(&[]);
+            let surface = unsafe { Surface::from_raw_window_handle(instance, rwh, rdh) };
+            assert!(surface.is_ok(), "{rwh:?}");
+
+            let received = mock::received_s_types();
+            assert_eq!(received.len(), 1, "{rwh:?}");
+            assert_eq!(received[0].0.as_raw(), s_type.as_raw(), "{rwh:?}");
+        }
+    }
+
+    #[cfg(feature = "raw-window-handle")]
+    #[test]
+    fn unsupported_raw_window_handles() {
+        use raw_window_handle::*;
+        use std::num::NonZeroU32;
+
+        let ptr = std::ptr::NonNull::dangling();
+
+        let mut cases = vec![
+            // Window and display handles from different platforms.
+            (
+                RawWindowHandle::Xcb(XcbWindowHandle::new(NonZeroU32::MIN)),
+                RawDisplayHandle::Xlib(XlibDisplayHandle::new(None, 0)),
+            ),
+            (
+                RawWindowHandle::Wayland(WaylandWindowHandle::new(ptr)),
+                RawDisplayHandle::Windows(WindowsDisplayHandle::new()),
+            ),
+            // Platforms without a Vulkan surface counterpart.
+            (
+                RawWindowHandle::Drm(DrmWindowHandle::new(0)),
+                RawDisplayHandle::Drm(DrmDisplayHandle::new(0)),
+            ),
+            (
+                RawWindowHandle::Gbm(GbmWindowHandle::new(ptr)),
+                RawDisplayHandle::Gbm(GbmDisplayHandle::new(ptr)),
+            ),
+            (
+                RawWindowHandle::Web(WebWindowHandle::new(1)),
+                RawDisplayHandle::Web(WebDisplayHandle::new()),
+            ),
+            (
+                RawWindowHandle::Haiku(HaikuWindowHandle::new(ptr)),
+                RawDisplayHandle::Haiku(HaikuDisplayHandle::new()),
+            ),
+            (
+                RawWindowHandle::Orbital(OrbitalWindowHandle::new(ptr)),
+                RawDisplayHandle::Orbital(OrbitalDisplayHandle::new()),
+            ),
+        ];
+
+        // Metal layers can only be retrieved on Apple platforms.
+        #[cfg(not(target_os = "macos"))]
+        cases.push((
+            RawWindowHandle::AppKit(AppKitWindowHandle::new(ptr)),
+            RawDisplayHandle::AppKit(AppKitDisplayHandle::new()),
+        ));
+        #[cfg(not(target_os = "ios"))]
+        cases.push((
+            RawWindowHandle::UiKit(UiKitWindowHandle::new(ptr)),
+            RawDisplayHandle::UiKit(UiKitDisplayHandle::new()),
+        ));
+
+        for (rwh, rdh) in cases {
+            let instance = mock::instance(&[]);
+            let surface = unsafe { Surface::from_raw_window_handle(instance, rwh, rdh) };
+            assert_eq!(
+                surface.err(),
+                Some(Error::UnsupportedWindowHandle),
+                "{rwh:?}"
             );
+            assert!(mock::received_s_types().is_empty());
         }
     }
+
+    #[test]
+    fn headless_surface_skips_unknown_values() {
+        let instance = mock::instance(&[]);
+        let surface = Surface::headless(instance.clone()).unwrap();
+        let physical_device = unsafe {
+            PhysicalDevice::from_handle(instance.clone(), vk::PhysicalDevice::from_raw(1))
+        };
+
+        // The mock reports an unknown present mode, format, color space and transform.
+        let modes = physical_device.surface_present_modes(&surface).unwrap();
+        assert_eq!(modes, PresentModes::FIFO | PresentModes::MAILBOX);
+
+        let formats: Vec<_> = physical_device
+            .surface_supported_formats(&surface)
+            .unwrap()
+            .collect();
+        assert_eq!(formats, [(Format::Bgra8Srgb, ColorSpace::Srgb)]);
+
+        let caps = physical_device.surface_capabilities(&surface).unwrap();
+        assert_eq!(caps.current_transform, None);
+        assert_eq!(caps.supported_transforms, SurfaceTransforms::IDENTITY);
+        assert_eq!(caps.current_extent, Some([640, 480]));
+        assert_eq!(caps.max_image_count, None);
+    }
 }
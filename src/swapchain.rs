@@ -1,10 +1,14 @@
-use std::mem::ManuallyDrop;
 use std::sync::Arc;
+use std::time::Duration;
 
 use ash::vk;
 use bitflags::bitflags;
 
-use crate::{ColorSpace, Device, Format, ImageUsages, Instance, Result, SharingMode, Surface};
+use crate::{
+    ColorSpace, Device, DeviceExtensions, Error, Fence, Format, Image, ImageDesc, ImageFlags,
+    ImageLayout, ImageTiling, ImageType, ImageUsages, Instance, Result, SampleCount, Semaphore,
+    SharingMode, Surface,
+};
 
 bitflags! {
     /// Flags specifying a collection of [`PresentMode`]s.
@@ -61,8 +65,8 @@ pub struct SurfaceCaps {
     pub max_image_array_layers: u32,
     /// A bitmask of [`SurfaceTransform`]s that are supported by the presentation engine.
     pub supported_transforms: SurfaceTransforms,
-    /// The current transform of the surface.
-    pub current_transform: SurfaceTransform,
+    /// The current transform of the surface, or `None` if this crate does not know about it.
+    pub current_transform: Option<SurfaceTransform>,
     /// A bitmask of [`CompositeAlpha`]s that are supported by the presentation engine.
     pub supported_composite_alpha: CompositeAlphas,
     /// A bitmask of [`ImageUsage`]s that are supported by the presentation engine.
@@ -205,26 +209,110 @@ pub struct Swapchain {
 
     /// The Vulkan handle for the swapchain.
     handle: vk::SwapchainKHR,
+    /// The images of the swapchain.
+    images: Box<[Image]>,
 }
 
 impl Swapchain {
     /// Creates a new [`Swapchain`] instance.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingDeviceExtension`] if the device does not have
+    /// the `VK_KHR_swapchain` extension enabled.
+    #[doc(alias = "vkCreateSwapchainKHR")]
     pub fn new(device: Arc<Device>, surface: Arc<Surface>, desc: SwapchainDesc) -> Result<Self> {
         create_swapchain(&desc, device, surface, vk::SwapchainKHR::null())
     }
 
     /// Re-creates this swapchain with the provided description.
+    ///
+    /// The old swapchain is retired (and destroyed) regardless of whether the creation of the
+    /// new one succeeds.
+    #[doc(alias = "vkCreateSwapchainKHR")]
     pub fn recreate(self, desc: SwapchainDesc) -> Result<Self> {
-        let this = ManuallyDrop::new(self);
-
         create_swapchain(
             &desc,
-            this.device.clone(),
-            this.surface.clone(),
-            this.handle,
+            self.device.clone(),
+            self.surface.clone(),
+            self.handle,
         )
     }
 
+    /// Acquires the next image of this swapchain which is available for rendering.
+    ///
+    /// The provided semaphore and fence are signaled once the image can actually be used.
+    /// Returns `None` if no image became available before the timeout expired. Otherwise,
+    /// returns the index of the image, along with whether the swapchain no longer matches the
+    /// surface exactly, in which case it should be re-created.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`Error::MissingDeviceExtension`] if the device does not have
+    /// the `VK_KHR_swapchain` extension enabled.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if neither a semaphore nor a fence is provided.
+    ///
+    /// # Safety
+    ///
+    /// The semaphore, if provided, must be unsignaled and have no pending signal or wait
+    /// operation. The fence, if provided, must be unsignaled and not be associated with any
+    /// other queue command that has not completed yet.
+    #[doc(alias = "vkAcquireNextImageKHR")]
+    pub unsafe fn acquire_next_image(
+        &mut self,
+        timeout: Duration,
+        semaphore: Option<&mut Semaphore>,
+        fence: Option<&mut Fence>,
+    ) -> Result<Option<(u32, bool)>> {
+        assert!(
+            semaphore.is_some() || fence.is_some(),
+            "a semaphore or a fence must be provided"
+        );
+
+        let acquire_fn = self
+            .device
+            .fns()
+            .acquire_next_image
+            .ok_or(Error::MissingDeviceExtension(DeviceExtensions::SWAPCHAIN))?;
+
+        let mut image_index = 0;
+
+        let ret = acquire_fn(
+            self.device.handle(),
+            self.handle,
+            crate::sync::timeout_to_nanos(timeout),
+            semaphore.map_or(vk::Semaphore::null(), |s| s.handle()),
+            fence.map_or(vk::Fence::null(), |f| f.handle()),
+            &mut image_index,
+        );
+
+        match ret {
+            vk::Result::SUCCESS => Ok(Some((image_index, false))),
+            vk::Result::SUBOPTIMAL_KHR => Ok(Some((image_index, true))),
+            vk::Result::TIMEOUT | vk::Result::NOT_READY => Ok(None),
+            _ => Err(ret.into()),
+        }
+    }
+
+    /// Returns the images of this swapchain.
+    ///
+    /// The images are owned by the swapchain and cannot outlive it. They are retrieved when the
+    /// swapchain is created.
+    #[doc(alias = "vkGetSwapchainImagesKHR")]
+    #[inline(always)]
+    pub fn images(&self) -> &[Image] {
+        &self.images
+    }
+
+    /// Returns the device that owns this swapchain.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
     /// Returns the surface that this swapchain presents images to.
     #[inline(always)]
     pub fn surface(&self) -> &Arc<Surface> {
@@ -244,6 +332,16 @@ impl Swapchain {
     }
 }
 
+impl Drop for Swapchain {
+    fn drop(&mut self) {
+        // A swapchain can only exist if `VK_KHR_swapchain` was enabled on its device, meaning
+        // that the function is always available here.
+        if let Some(destroy_fn) = self.device.fns().destroy_swapchain {
+            unsafe { destroy_fn(self.device.handle(), self.handle, std::ptr::null()) };
+        }
+    }
+}
+
 /// Creates a new swapchain from the provided description.
 fn create_swapchain(
     desc: &SwapchainDesc,
@@ -251,6 +349,15 @@ fn create_swapchain(
     surface: Arc<Surface>,
     old_swapchain: vk::SwapchainKHR,
 ) -> Result<Swapchain> {
+    let create_fn = device
+        .fns()
+        .create_swapchain
+        .ok_or(Error::MissingDeviceExtension(DeviceExtensions::SWAPCHAIN))?;
+    let get_images_fn = device
+        .fns()
+        .get_swapchain_images
+        .ok_or(Error::MissingDeviceExtension(DeviceExtensions::SWAPCHAIN))?;
+
     let mut handle = vk::SwapchainKHR::null();
 
     let mut create_info = vk::SwapchainCreateInfoKHR {
@@ -288,22 +395,49 @@ fn create_swapchain(
         }
     };
 
-    let ret = unsafe {
-        (device.fns().create_swapchain)(
-            device.handle(),
-            &create_info,
-            std::ptr::null(),
-            &mut handle,
-        )
-    };
+    let ret = unsafe { create_fn(device.handle(), &create_info, std::ptr::null(), &mut handle) };
 
     if ret != vk::Result::SUCCESS {
         return Err(ret.into());
     }
 
-    Ok(Swapchain {
+    // Wrap the swapchain right away so that it gets destroyed if retrieving its images fails.
+    let mut swapchain = Swapchain {
         handle,
         device,
         surface,
-    })
+        images: Box::new([]),
+    };
+
+    let mut handles = Vec::new();
+    let ret = unsafe {
+        crate::utility::read_into_vector(&mut handles, |count, data| {
+            get_images_fn(swapchain.device.handle(), handle, count, data)
+        })
+    };
+
+    if ret != vk::Result::SUCCESS {
+        return Err(ret.into());
+    }
+
+    let image_desc = ImageDesc {
+        flags: ImageFlags::empty(),
+        image_type: ImageType::D2,
+        format: desc.format,
+        extent: [desc.extent[0], desc.extent[1], 1],
+        mip_levels: 1,
+        array_layers: desc.array_layers,
+        samples: SampleCount::X1,
+        tiling: ImageTiling::Optimal,
+        usage: desc.usage,
+        sharing_mode: desc.sharing_mode.clone(),
+        initial_layout: ImageLayout::Undefined,
+    };
+
+    swapchain.images = handles
+        .into_iter()
+        .map(|image| unsafe { Image::from_swapchain(swapchain.device.clone(), image, &image_desc) })
+        .collect();
+
+    Ok(swapchain)
 }
@@ -1,3 +1,12 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ash::vk;
+use bitflags::bitflags;
+use smallvec::SmallVec;
+
+use crate::{Buffer, Device, Image, ImageLayout, ImageSubresourceRange, Result};
+
 /// A mode for sharing a resource between multiple queues.
 #[derive(Debug, Clone)]
 pub enum SharingMode<I> {
@@ -6,3 +15,405 @@ pub enum SharingMode<I> {
     /// The resource is shared between multiple queue families.
     Concurrent(I),
 }
+
+impl<'a> SharingMode<&'a [u32]> {
+    /// Returns the raw Vulkan sharing mode, along with the queue families sharing the resource.
+    pub(crate) fn to_raw(&self) -> (vk::SharingMode, &'a [u32]) {
+        match *self {
+            Self::Exclusive => (vk::SharingMode::EXCLUSIVE, &[]),
+            Self::Concurrent(families) => (vk::SharingMode::CONCURRENT, families),
+        }
+    }
+}
+
+bitflags! {
+    /// A set of pipeline stages.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct PipelineStages: u32 {
+        const TOP_OF_PIPE = vk::PipelineStageFlags::TOP_OF_PIPE.as_raw();
+        const DRAW_INDIRECT = vk::PipelineStageFlags::DRAW_INDIRECT.as_raw();
+        const VERTEX_INPUT = vk::PipelineStageFlags::VERTEX_INPUT.as_raw();
+        const VERTEX_SHADER = vk::PipelineStageFlags::VERTEX_SHADER.as_raw();
+        const TESSELLATION_CONTROL_SHADER = vk::PipelineStageFlags::TESSELLATION_CONTROL_SHADER.as_raw();
+        const TESSELLATION_EVALUATION_SHADER = vk::PipelineStageFlags::TESSELLATION_EVALUATION_SHADER.as_raw();
+        const GEOMETRY_SHADER = vk::PipelineStageFlags::GEOMETRY_SHADER.as_raw();
+        const FRAGMENT_SHADER = vk::PipelineStageFlags::FRAGMENT_SHADER.as_raw();
+        const EARLY_FRAGMENT_TESTS = vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS.as_raw();
+        const LATE_FRAGMENT_TESTS = vk::PipelineStageFlags::LATE_FRAGMENT_TESTS.as_raw();
+        const COLOR_ATTACHMENT_OUTPUT = vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT.as_raw();
+        const COMPUTE_SHADER = vk::PipelineStageFlags::COMPUTE_SHADER.as_raw();
+        const TRANSFER = vk::PipelineStageFlags::TRANSFER.as_raw();
+        const BOTTOM_OF_PIPE = vk::PipelineStageFlags::BOTTOM_OF_PIPE.as_raw();
+        const HOST = vk::PipelineStageFlags::HOST.as_raw();
+        const ALL_GRAPHICS = vk::PipelineStageFlags::ALL_GRAPHICS.as_raw();
+        const ALL_COMMANDS = vk::PipelineStageFlags::ALL_COMMANDS.as_raw();
+    }
+}
+
+bitflags! {
+    /// A set of memory access types.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Accesses: u32 {
+        const INDIRECT_COMMAND_READ = vk::AccessFlags::INDIRECT_COMMAND_READ.as_raw();
+        const INDEX_READ = vk::AccessFlags::INDEX_READ.as_raw();
+        const VERTEX_ATTRIBUTE_READ = vk::AccessFlags::VERTEX_ATTRIBUTE_READ.as_raw();
+        const UNIFORM_READ = vk::AccessFlags::UNIFORM_READ.as_raw();
+        const INPUT_ATTACHMENT_READ = vk::AccessFlags::INPUT_ATTACHMENT_READ.as_raw();
+        const SHADER_READ = vk::AccessFlags::SHADER_READ.as_raw();
+        const SHADER_WRITE = vk::AccessFlags::SHADER_WRITE.as_raw();
+        const COLOR_ATTACHMENT_READ = vk::AccessFlags::COLOR_ATTACHMENT_READ.as_raw();
+        const COLOR_ATTACHMENT_WRITE = vk::AccessFlags::COLOR_ATTACHMENT_WRITE.as_raw();
+        const DEPTH_STENCIL_ATTACHMENT_READ = vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ.as_raw();
+        const DEPTH_STENCIL_ATTACHMENT_WRITE = vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE.as_raw();
+        const TRANSFER_READ = vk::AccessFlags::TRANSFER_READ.as_raw();
+        const TRANSFER_WRITE = vk::AccessFlags::TRANSFER_WRITE.as_raw();
+        const HOST_READ = vk::AccessFlags::HOST_READ.as_raw();
+        const HOST_WRITE = vk::AccessFlags::HOST_WRITE.as_raw();
+        const MEMORY_READ = vk::AccessFlags::MEMORY_READ.as_raw();
+        const MEMORY_WRITE = vk::AccessFlags::MEMORY_WRITE.as_raw();
+    }
+}
+
+/// A global memory barrier, which applies to all the resources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkMemoryBarrier")]
+pub struct MemoryBarrier {
+    /// The accesses that must be made available.
+    pub src_access: Accesses,
+    /// The accesses that the memory must be made visible to.
+    pub dst_access: Accesses,
+}
+
+/// A memory barrier which applies to a range of a [`Buffer`].
+#[derive(Clone, Copy)]
+#[doc(alias = "VkBufferMemoryBarrier")]
+pub struct BufferBarrier<'a> {
+    /// The buffer affected by the barrier.
+    pub buffer: &'a Buffer,
+    /// The accesses that must be made available.
+    pub src_access: Accesses,
+    /// The accesses that the memory must be made visible to.
+    pub dst_access: Accesses,
+    /// The source and destination queue families, if the ownership of the buffer is
+    /// transferred.
+    pub queue_family_transfer: Option<[u32; 2]>,
+    /// The offset of the range affected by the barrier.
+    pub offset: u64,
+    /// The size of the range affected by the barrier, or `vk::WHOLE_SIZE`.
+    pub size: u64,
+}
+
+/// A memory barrier which applies to a range of an [`Image`], and which may transition its
+/// layout.
+#[derive(Clone, Copy)]
+#[doc(alias = "VkImageMemoryBarrier")]
+pub struct ImageBarrier<'a> {
+    /// The image affected by the barrier.
+    pub image: &'a Image,
+    /// The accesses that must be made available.
+    pub src_access: Accesses,
+    /// The accesses that the memory must be made visible to.
+    pub dst_access: Accesses,
+    /// The layout of the image before the barrier.
+    pub old_layout: ImageLayout,
+    /// The layout of the image after the barrier.
+    pub new_layout: ImageLayout,
+    /// The source and destination queue families, if the ownership of the image is
+    /// transferred.
+    pub queue_family_transfer: Option<[u32; 2]>,
+    /// The part of the image affected by the barrier.
+    pub subresource_range: ImageSubresourceRange,
+}
+
+impl MemoryBarrier {
+    /// Converts this barrier into its raw Vulkan representation.
+    pub(crate) fn to_raw(self) -> vk::MemoryBarrier {
+        vk::MemoryBarrier {
+            src_access_mask: vk::AccessFlags::from_raw(self.src_access.bits()),
+            dst_access_mask: vk::AccessFlags::from_raw(self.dst_access.bits()),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::MEMORY_BARRIER,
+        }
+    }
+}
+
+impl BufferBarrier<'_> {
+    /// Converts this barrier into its raw Vulkan representation.
+    pub(crate) fn to_raw(self) -> vk::BufferMemoryBarrier {
+        let [src_queue_family_index, dst_queue_family_index] = self
+            .queue_family_transfer
+            .unwrap_or([vk::QUEUE_FAMILY_IGNORED; 2]);
+
+        vk::BufferMemoryBarrier {
+            src_access_mask: vk::AccessFlags::from_raw(self.src_access.bits()),
+            dst_access_mask: vk::AccessFlags::from_raw(self.dst_access.bits()),
+            src_queue_family_index,
+            dst_queue_family_index,
+            buffer: self.buffer.handle(),
+            offset: self.offset,
+            size: self.size,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::BUFFER_MEMORY_BARRIER,
+        }
+    }
+}
+
+impl ImageBarrier<'_> {
+    /// Converts this barrier into its raw Vulkan representation.
+    pub(crate) fn to_raw(self) -> vk::ImageMemoryBarrier {
+        let [src_queue_family_index, dst_queue_family_index] = self
+            .queue_family_transfer
+            .unwrap_or([vk::QUEUE_FAMILY_IGNORED; 2]);
+
+        vk::ImageMemoryBarrier {
+            src_access_mask: vk::AccessFlags::from_raw(self.src_access.bits()),
+            dst_access_mask: vk::AccessFlags::from_raw(self.dst_access.bits()),
+            old_layout: vk::ImageLayout::from_raw(self.old_layout as i32),
+            new_layout: vk::ImageLayout::from_raw(self.new_layout as i32),
+            src_queue_family_index,
+            dst_queue_family_index,
+            image: self.image.handle(),
+            subresource_range: self.subresource_range.to_raw(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::IMAGE_MEMORY_BARRIER,
+        }
+    }
+}
+
+/// Converts a timeout into nanoseconds, saturating to an infinite timeout.
+pub(crate) fn timeout_to_nanos(timeout: Duration) -> u64 {
+    timeout.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+/// A synchronization primitive used to wait on the host for queue operations to complete.
+pub struct Fence {
+    /// The device that owns this fence.
+    device: Arc<Device>,
+
+    /// The handle to the fence.
+    handle: vk::Fence,
+}
+
+impl Fence {
+    /// Creates a new [`Fence`], optionally in the signaled state.
+    #[doc(alias = "vkCreateFence")]
+    pub fn new(device: Arc<Device>, signaled: bool) -> Result<Self> {
+        let create_info = vk::FenceCreateInfo {
+            flags: if signaled {
+                vk::FenceCreateFlags::SIGNALED
+            } else {
+                vk::FenceCreateFlags::empty()
+            },
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::FENCE_CREATE_INFO,
+        };
+
+        let mut handle = vk::Fence::null();
+
+        let ret = unsafe {
+            (device.fns().create_fence)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self { device, handle })
+    }
+
+    /// Returns whether this fence is signaled.
+    #[doc(alias = "vkGetFenceStatus")]
+    pub fn status(&self) -> Result<bool> {
+        let ret =
+            unsafe { (self.device.fns().get_fence_status)(self.device.handle(), self.handle) };
+
+        match ret {
+            vk::Result::SUCCESS => Ok(true),
+            vk::Result::NOT_READY => Ok(false),
+            _ => Err(ret.into()),
+        }
+    }
+
+    /// Resets this fence to the unsignaled state.
+    ///
+    /// # Safety
+    ///
+    /// The fence must not be associated with any queue command that has not completed yet,
+    /// such as a submission that has not signaled it yet.
+    #[doc(alias = "vkResetFences")]
+    pub unsafe fn reset(&mut self) -> Result<()> {
+        let ret = (self.device.fns().reset_fences)(self.device.handle(), 1, &self.handle);
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+
+    /// Waits for this fence to become signaled.
+    ///
+    /// Returns `false` if the timeout expired before the fence was signaled. A timeout of
+    /// [`Duration::MAX`] waits indefinitely.
+    #[doc(alias = "vkWaitForFences")]
+    pub fn wait(&self, timeout: Duration) -> Result<bool> {
+        wait_for_fences(&self.device, &[self.handle], true, timeout)
+    }
+
+    /// Waits for all the provided fences to become signaled.
+    ///
+    /// Returns `false` if the timeout expired before all the fences were signaled.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the fences do not all belong to the same device.
+    #[doc(alias = "vkWaitForFences")]
+    pub fn wait_all(fences: &[&Fence], timeout: Duration) -> Result<bool> {
+        Self::wait_many(fences, true, timeout)
+    }
+
+    /// Waits for any of the provided fences to become signaled.
+    ///
+    /// Returns `false` if the timeout expired before any of the fences was signaled.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the fences do not all belong to the same device.
+    #[doc(alias = "vkWaitForFences")]
+    pub fn wait_any(fences: &[&Fence], timeout: Duration) -> Result<bool> {
+        Self::wait_many(fences, false, timeout)
+    }
+
+    /// Waits for all or any of the provided fences to become signaled.
+    fn wait_many(fences: &[&Fence], wait_all: bool, timeout: Duration) -> Result<bool> {
+        let Some(first) = fences.first() else {
+            return Ok(true);
+        };
+
+        assert!(
+            fences
+                .iter()
+                .all(|fence| Arc::ptr_eq(&fence.device, &first.device)),
+            "the fences do not belong to the same device"
+        );
+
+        let handles: SmallVec<[_; 4]> = fences.iter().map(|fence| fence.handle).collect();
+        wait_for_fences(&first.device, &handles, wait_all, timeout)
+    }
+
+    /// Returns the device that owns this fence.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this fence.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::Fence {
+        self.handle
+    }
+}
+
+impl Drop for Fence {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_fence)(self.device.handle(), self.handle, std::ptr::null());
+        }
+    }
+}
+
+/// Calls `vkWaitForFences`, mapping a timeout to `false`.
+fn wait_for_fences(
+    device: &Device,
+    fences: &[vk::Fence],
+    wait_all: bool,
+    timeout: Duration,
+) -> Result<bool> {
+    let ret = unsafe {
+        (device.fns().wait_for_fences)(
+            device.handle(),
+            fences.len() as u32,
+            fences.as_ptr(),
+            wait_all as vk::Bool32,
+            timeout_to_nanos(timeout),
+        )
+    };
+
+    match ret {
+        vk::Result::SUCCESS => Ok(true),
+        vk::Result::TIMEOUT => Ok(false),
+        _ => Err(ret.into()),
+    }
+}
+
+/// A binary semaphore, used to order queue operations.
+pub struct Semaphore {
+    /// The device that owns this semaphore.
+    device: Arc<Device>,
+
+    /// The handle to the semaphore.
+    handle: vk::Semaphore,
+}
+
+impl Semaphore {
+    /// Creates a new [`Semaphore`], in the unsignaled state.
+    #[doc(alias = "vkCreateSemaphore")]
+    pub fn new(device: Arc<Device>) -> Result<Self> {
+        let create_info = vk::SemaphoreCreateInfo {
+            flags: vk::SemaphoreCreateFlags::empty(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::SEMAPHORE_CREATE_INFO,
+        };
+
+        let mut handle = vk::Semaphore::null();
+
+        let ret = unsafe {
+            (device.fns().create_semaphore)(
+                device.handle(),
+                &create_info,
+                std::ptr::null(),
+                &mut handle,
+            )
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self { device, handle })
+    }
+
+    /// Returns the device that owns this semaphore.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this semaphore.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::Semaphore {
+        self.handle
+    }
+}
+
+impl Drop for Semaphore {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_semaphore)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
@@ -0,0 +1,515 @@
+use ash::vk;
+use bitflags::bitflags;
+use smallvec::SmallVec;
+
+use crate::{
+    Accesses, Buffer, BufferBarrier, CommandBuffer, DeviceFeatures, Image, ImageBarrier,
+    ImageLayout, ImageSubresourceRange, MemoryBarrier, PipelineStages, Recording,
+};
+
+bitflags! {
+    /// A set of pipeline stages, as introduced by `VK_KHR_synchronization2`.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[doc(alias = "VkPipelineStageFlags2")]
+    pub struct PipelineStages2: u64 {
+        const TOP_OF_PIPE = vk::PipelineStageFlags2KHR::TOP_OF_PIPE.as_raw();
+        const DRAW_INDIRECT = vk::PipelineStageFlags2KHR::DRAW_INDIRECT.as_raw();
+        const VERTEX_INPUT = vk::PipelineStageFlags2KHR::VERTEX_INPUT.as_raw();
+        const VERTEX_SHADER = vk::PipelineStageFlags2KHR::VERTEX_SHADER.as_raw();
+        const TESSELLATION_CONTROL_SHADER = vk::PipelineStageFlags2KHR::TESSELLATION_CONTROL_SHADER.as_raw();
+        const TESSELLATION_EVALUATION_SHADER = vk::PipelineStageFlags2KHR::TESSELLATION_EVALUATION_SHADER.as_raw();
+        const GEOMETRY_SHADER = vk::PipelineStageFlags2KHR::GEOMETRY_SHADER.as_raw();
+        const FRAGMENT_SHADER = vk::PipelineStageFlags2KHR::FRAGMENT_SHADER.as_raw();
+        const EARLY_FRAGMENT_TESTS = vk::PipelineStageFlags2KHR::EARLY_FRAGMENT_TESTS.as_raw();
+        const LATE_FRAGMENT_TESTS = vk::PipelineStageFlags2KHR::LATE_FRAGMENT_TESTS.as_raw();
+        const COLOR_ATTACHMENT_OUTPUT = vk::PipelineStageFlags2KHR::COLOR_ATTACHMENT_OUTPUT.as_raw();
+        const COMPUTE_SHADER = vk::PipelineStageFlags2KHR::COMPUTE_SHADER.as_raw();
+        const ALL_TRANSFER = vk::PipelineStageFlags2KHR::ALL_TRANSFER.as_raw();
+        const BOTTOM_OF_PIPE = vk::PipelineStageFlags2KHR::BOTTOM_OF_PIPE.as_raw();
+        const HOST = vk::PipelineStageFlags2KHR::HOST.as_raw();
+        const ALL_GRAPHICS = vk::PipelineStageFlags2KHR::ALL_GRAPHICS.as_raw();
+        const ALL_COMMANDS = vk::PipelineStageFlags2KHR::ALL_COMMANDS.as_raw();
+        const COPY = vk::PipelineStageFlags2KHR::COPY.as_raw();
+        const RESOLVE = vk::PipelineStageFlags2KHR::RESOLVE.as_raw();
+        const BLIT = vk::PipelineStageFlags2KHR::BLIT.as_raw();
+        const CLEAR = vk::PipelineStageFlags2KHR::CLEAR.as_raw();
+        const INDEX_INPUT = vk::PipelineStageFlags2KHR::INDEX_INPUT.as_raw();
+        const VERTEX_ATTRIBUTE_INPUT = vk::PipelineStageFlags2KHR::VERTEX_ATTRIBUTE_INPUT.as_raw();
+        const PRE_RASTERIZATION_SHADERS = vk::PipelineStageFlags2KHR::PRE_RASTERIZATION_SHADERS.as_raw();
+    }
+}
+
+impl PipelineStages2 {
+    /// The stages which are shared with [`PipelineStages`], with the same bits.
+    const LEGACY: Self = Self::from_bits_truncate(u32::MAX as u64);
+
+    /// Lowers this set of stages to the legacy stages which include them.
+    ///
+    /// An empty set is lowered to `empty`, which must be either `TOP_OF_PIPE` or
+    /// `BOTTOM_OF_PIPE` depending on the side of the barrier.
+    fn to_legacy(self, empty: PipelineStages) -> PipelineStages {
+        if self.is_empty() {
+            return empty;
+        }
+
+        let mut result =
+            PipelineStages::from_bits_truncate(self.intersection(Self::LEGACY).bits() as u32);
+
+        if self.intersects(Self::COPY | Self::RESOLVE | Self::BLIT | Self::CLEAR) {
+            result |= PipelineStages::TRANSFER;
+        }
+        if self.intersects(Self::INDEX_INPUT | Self::VERTEX_ATTRIBUTE_INPUT) {
+            result |= PipelineStages::VERTEX_INPUT;
+        }
+        if self.contains(Self::PRE_RASTERIZATION_SHADERS) {
+            result |= PipelineStages::VERTEX_SHADER
+                | PipelineStages::TESSELLATION_CONTROL_SHADER
+                | PipelineStages::TESSELLATION_EVALUATION_SHADER
+                | PipelineStages::GEOMETRY_SHADER;
+        }
+
+        result
+    }
+}
+
+bitflags! {
+    /// A set of memory access types, as introduced by `VK_KHR_synchronization2`.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[doc(alias = "VkAccessFlags2")]
+    pub struct Accesses2: u64 {
+        const INDIRECT_COMMAND_READ = vk::AccessFlags2KHR::INDIRECT_COMMAND_READ.as_raw();
+        const INDEX_READ = vk::AccessFlags2KHR::INDEX_READ.as_raw();
+        const VERTEX_ATTRIBUTE_READ = vk::AccessFlags2KHR::VERTEX_ATTRIBUTE_READ.as_raw();
+        const UNIFORM_READ = vk::AccessFlags2KHR::UNIFORM_READ.as_raw();
+        const INPUT_ATTACHMENT_READ = vk::AccessFlags2KHR::INPUT_ATTACHMENT_READ.as_raw();
+        const SHADER_READ = vk::AccessFlags2KHR::SHADER_READ.as_raw();
+        const SHADER_WRITE = vk::AccessFlags2KHR::SHADER_WRITE.as_raw();
+        const COLOR_ATTACHMENT_READ = vk::AccessFlags2KHR::COLOR_ATTACHMENT_READ.as_raw();
+        const COLOR_ATTACHMENT_WRITE = vk::AccessFlags2KHR::COLOR_ATTACHMENT_WRITE.as_raw();
+        const DEPTH_STENCIL_ATTACHMENT_READ = vk::AccessFlags2KHR::DEPTH_STENCIL_ATTACHMENT_READ.as_raw();
+        const DEPTH_STENCIL_ATTACHMENT_WRITE = vk::AccessFlags2KHR::DEPTH_STENCIL_ATTACHMENT_WRITE.as_raw();
+        const TRANSFER_READ = vk::AccessFlags2KHR::TRANSFER_READ.as_raw();
+        const TRANSFER_WRITE = vk::AccessFlags2KHR::TRANSFER_WRITE.as_raw();
+        const HOST_READ = vk::AccessFlags2KHR::HOST_READ.as_raw();
+        const HOST_WRITE = vk::AccessFlags2KHR::HOST_WRITE.as_raw();
+        const MEMORY_READ = vk::AccessFlags2KHR::MEMORY_READ.as_raw();
+        const MEMORY_WRITE = vk::AccessFlags2KHR::MEMORY_WRITE.as_raw();
+        const SHADER_SAMPLED_READ = vk::AccessFlags2KHR::SHADER_SAMPLED_READ.as_raw();
+        const SHADER_STORAGE_READ = vk::AccessFlags2KHR::SHADER_STORAGE_READ.as_raw();
+        const SHADER_STORAGE_WRITE = vk::AccessFlags2KHR::SHADER_STORAGE_WRITE.as_raw();
+    }
+}
+
+impl Accesses2 {
+    /// The accesses which are shared with [`Accesses`], with the same bits.
+    const LEGACY: Self = Self::from_bits_truncate(u32::MAX as u64);
+
+    /// Lowers this set of accesses to the legacy accesses which include them.
+    fn to_legacy(self) -> Accesses {
+        let mut result =
+            Accesses::from_bits_truncate(self.intersection(Self::LEGACY).bits() as u32);
+
+        if self.intersects(Self::SHADER_SAMPLED_READ | Self::SHADER_STORAGE_READ) {
+            result |= Accesses::SHADER_READ;
+        }
+        if self.contains(Self::SHADER_STORAGE_WRITE) {
+            result |= Accesses::SHADER_WRITE;
+        }
+
+        result
+    }
+}
+
+/// A global memory barrier, which applies to all the resources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[doc(alias = "VkMemoryBarrier2")]
+pub struct MemoryBarrier2 {
+    /// The stages that must complete before the barrier.
+    pub src_stages: PipelineStages2,
+    /// The accesses that must be made available.
+    pub src_access: Accesses2,
+    /// The stages that must wait for the barrier.
+    pub dst_stages: PipelineStages2,
+    /// The accesses that the memory must be made visible to.
+    pub dst_access: Accesses2,
+}
+
+impl MemoryBarrier2 {
+    /// Converts this barrier into its raw Vulkan representation.
+    fn to_raw(self) -> vk::MemoryBarrier2KHR {
+        vk::MemoryBarrier2KHR {
+            src_stage_mask: vk::PipelineStageFlags2KHR::from_raw(self.src_stages.bits()),
+            src_access_mask: vk::AccessFlags2KHR::from_raw(self.src_access.bits()),
+            dst_stage_mask: vk::PipelineStageFlags2KHR::from_raw(self.dst_stages.bits()),
+            dst_access_mask: vk::AccessFlags2KHR::from_raw(self.dst_access.bits()),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::MEMORY_BARRIER_2_KHR,
+        }
+    }
+
+    /// Lowers this barrier to a legacy barrier.
+    fn to_legacy(self) -> MemoryBarrier {
+        MemoryBarrier {
+            src_access: self.src_access.to_legacy(),
+            dst_access: self.dst_access.to_legacy(),
+        }
+    }
+}
+
+/// A memory barrier which applies to a range of a [`Buffer`].
+#[derive(Clone, Copy)]
+#[doc(alias = "VkBufferMemoryBarrier2")]
+pub struct BufferBarrier2<'a> {
+    /// The buffer affected by the barrier.
+    pub buffer: &'a Buffer,
+    /// The stages that must complete before the barrier.
+    pub src_stages: PipelineStages2,
+    /// The accesses that must be made available.
+    pub src_access: Accesses2,
+    /// The stages that must wait for the barrier.
+    pub dst_stages: PipelineStages2,
+    /// The accesses that the memory must be made visible to.
+    pub dst_access: Accesses2,
+    /// The source and destination queue families, if the ownership of the buffer is
+    /// transferred.
+    pub queue_family_transfer: Option<[u32; 2]>,
+    /// The offset of the range affected by the barrier.
+    pub offset: u64,
+    /// The size of the range affected by the barrier, or `vk::WHOLE_SIZE`.
+    pub size: u64,
+}
+
+impl<'a> BufferBarrier2<'a> {
+    /// Converts this barrier into its raw Vulkan representation.
+    fn to_raw(self) -> vk::BufferMemoryBarrier2KHR {
+        let [src_queue_family_index, dst_queue_family_index] = self
+            .queue_family_transfer
+            .unwrap_or([vk::QUEUE_FAMILY_IGNORED; 2]);
+
+        vk::BufferMemoryBarrier2KHR {
+            src_stage_mask: vk::PipelineStageFlags2KHR::from_raw(self.src_stages.bits()),
+            src_access_mask: vk::AccessFlags2KHR::from_raw(self.src_access.bits()),
+            dst_stage_mask: vk::PipelineStageFlags2KHR::from_raw(self.dst_stages.bits()),
+            dst_access_mask: vk::AccessFlags2KHR::from_raw(self.dst_access.bits()),
+            src_queue_family_index,
+            dst_queue_family_index,
+            buffer: self.buffer.handle(),
+            offset: self.offset,
+            size: self.size,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::BUFFER_MEMORY_BARRIER_2_KHR,
+        }
+    }
+
+    /// Lowers this barrier to a legacy barrier.
+    fn to_legacy(self) -> BufferBarrier<'a> {
+        BufferBarrier {
+            buffer: self.buffer,
+            src_access: self.src_access.to_legacy(),
+            dst_access: self.dst_access.to_legacy(),
+            queue_family_transfer: self.queue_family_transfer,
+            offset: self.offset,
+            size: self.size,
+        }
+    }
+}
+
+/// A memory barrier which applies to a range of an [`Image`], and which may transition its
+/// layout.
+#[derive(Clone, Copy)]
+#[doc(alias = "VkImageMemoryBarrier2")]
+pub struct ImageBarrier2<'a> {
+    /// The image affected by the barrier.
+    pub image: &'a Image,
+    /// The stages that must complete before the barrier.
+    pub src_stages: PipelineStages2,
+    /// The accesses that must be made available.
+    pub src_access: Accesses2,
+    /// The stages that must wait for the barrier.
+    pub dst_stages: PipelineStages2,
+    /// The accesses that the memory must be made visible to.
+    pub dst_access: Accesses2,
+    /// The layout of the image before the barrier.
+    pub old_layout: ImageLayout,
+    /// The layout of the image after the barrier.
+    pub new_layout: ImageLayout,
+    /// The source and destination queue families, if the ownership of the image is
+    /// transferred.
+    pub queue_family_transfer: Option<[u32; 2]>,
+    /// The part of the image affected by the barrier.
+    pub subresource_range: ImageSubresourceRange,
+}
+
+impl<'a> ImageBarrier2<'a> {
+    /// Returns a barrier transitioning the whole image between two layouts.
+    pub fn transition(
+        image: &'a Image,
+        (src_stages, src_access, old_layout): (PipelineStages2, Accesses2, ImageLayout),
+        (dst_stages, dst_access, new_layout): (PipelineStages2, Accesses2, ImageLayout),
+    ) -> Self {
+        Self {
+            image,
+            src_stages,
+            src_access,
+            dst_stages,
+            dst_access,
+            old_layout,
+            new_layout,
+            queue_family_transfer: None,
+            subresource_range: ImageSubresourceRange::whole(image.format().aspects()),
+        }
+    }
+
+    /// Returns a barrier preparing an image with undefined contents to be written by transfer
+    /// commands.
+    pub fn undefined_to_transfer_dst(image: &'a Image) -> Self {
+        Self::transition(
+            image,
+            (
+                PipelineStages2::empty(),
+                Accesses2::empty(),
+                ImageLayout::Undefined,
+            ),
+            (
+                PipelineStages2::ALL_TRANSFER,
+                Accesses2::TRANSFER_WRITE,
+                ImageLayout::TransferDstOptimal,
+            ),
+        )
+    }
+
+    /// Returns a barrier preparing an image with undefined contents, such as a newly acquired
+    /// swapchain image, to be rendered to.
+    ///
+    /// The barrier waits on the color attachment output stage, which is also the stage
+    /// waiting on the semaphore of the acquisition.
+    pub fn undefined_to_color_attachment(image: &'a Image) -> Self {
+        Self::transition(
+            image,
+            (
+                PipelineStages2::COLOR_ATTACHMENT_OUTPUT,
+                Accesses2::empty(),
+                ImageLayout::Undefined,
+            ),
+            (
+                PipelineStages2::COLOR_ATTACHMENT_OUTPUT,
+                Accesses2::COLOR_ATTACHMENT_READ | Accesses2::COLOR_ATTACHMENT_WRITE,
+                ImageLayout::ColorAttachmentOptimal,
+            ),
+        )
+    }
+
+    /// Returns a barrier preparing a depth/stencil image with undefined contents to be
+    /// rendered to.
+    pub fn undefined_to_depth_stencil_attachment(image: &'a Image) -> Self {
+        let stages = PipelineStages2::EARLY_FRAGMENT_TESTS | PipelineStages2::LATE_FRAGMENT_TESTS;
+
+        Self::transition(
+            image,
+            (stages, Accesses2::empty(), ImageLayout::Undefined),
+            (
+                stages,
+                Accesses2::DEPTH_STENCIL_ATTACHMENT_READ
+                    | Accesses2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                ImageLayout::DepthStencilAttachmentOptimal,
+            ),
+        )
+    }
+
+    /// Returns a barrier making an image written by transfer commands available to fragment
+    /// and compute shaders.
+    pub fn transfer_dst_to_shader_read(image: &'a Image) -> Self {
+        Self::transition(
+            image,
+            (
+                PipelineStages2::ALL_TRANSFER,
+                Accesses2::TRANSFER_WRITE,
+                ImageLayout::TransferDstOptimal,
+            ),
+            (
+                PipelineStages2::FRAGMENT_SHADER | PipelineStages2::COMPUTE_SHADER,
+                Accesses2::SHADER_SAMPLED_READ,
+                ImageLayout::ShaderReadOnlyOptimal,
+            ),
+        )
+    }
+
+    /// Returns a barrier making an image rendered to available to fragment and compute
+    /// shaders.
+    pub fn color_attachment_to_shader_read(image: &'a Image) -> Self {
+        Self::transition(
+            image,
+            (
+                PipelineStages2::COLOR_ATTACHMENT_OUTPUT,
+                Accesses2::COLOR_ATTACHMENT_WRITE,
+                ImageLayout::ColorAttachmentOptimal,
+            ),
+            (
+                PipelineStages2::FRAGMENT_SHADER | PipelineStages2::COMPUTE_SHADER,
+                Accesses2::SHADER_SAMPLED_READ,
+                ImageLayout::ShaderReadOnlyOptimal,
+            ),
+        )
+    }
+
+    /// Returns a barrier preparing a swapchain image that was rendered to for presentation.
+    ///
+    /// Presentation is synchronized through semaphores, so the barrier does not make the
+    /// image visible to any later stage.
+    pub fn color_attachment_to_present(image: &'a Image) -> Self {
+        Self::transition(
+            image,
+            (
+                PipelineStages2::COLOR_ATTACHMENT_OUTPUT,
+                Accesses2::COLOR_ATTACHMENT_WRITE,
+                ImageLayout::ColorAttachmentOptimal,
+            ),
+            (
+                PipelineStages2::empty(),
+                Accesses2::empty(),
+                ImageLayout::PresentSrc,
+            ),
+        )
+    }
+
+    /// Converts this barrier into its raw Vulkan representation.
+    fn to_raw(self) -> vk::ImageMemoryBarrier2KHR {
+        let [src_queue_family_index, dst_queue_family_index] = self
+            .queue_family_transfer
+            .unwrap_or([vk::QUEUE_FAMILY_IGNORED; 2]);
+
+        vk::ImageMemoryBarrier2KHR {
+            src_stage_mask: vk::PipelineStageFlags2KHR::from_raw(self.src_stages.bits()),
+            src_access_mask: vk::AccessFlags2KHR::from_raw(self.src_access.bits()),
+            dst_stage_mask: vk::PipelineStageFlags2KHR::from_raw(self.dst_stages.bits()),
+            dst_access_mask: vk::AccessFlags2KHR::from_raw(self.dst_access.bits()),
+            old_layout: vk::ImageLayout::from_raw(self.old_layout as i32),
+            new_layout: vk::ImageLayout::from_raw(self.new_layout as i32),
+            src_queue_family_index,
+            dst_queue_family_index,
+            image: self.image.handle(),
+            subresource_range: self.subresource_range.to_raw(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::IMAGE_MEMORY_BARRIER_2_KHR,
+        }
+    }
+
+    /// Lowers this barrier to a legacy barrier.
+    fn to_legacy(self) -> ImageBarrier<'a> {
+        ImageBarrier {
+            image: self.image,
+            src_access: self.src_access.to_legacy(),
+            dst_access: self.dst_access.to_legacy(),
+            old_layout: self.old_layout,
+            new_layout: self.new_layout,
+            queue_family_transfer: self.queue_family_transfer,
+            subresource_range: self.subresource_range,
+        }
+    }
+}
+
+impl<L> CommandBuffer<Recording<'_>, L> {
+    /// Inserts a dependency between the commands recorded before and after this one, with
+    /// stages specified per barrier.
+    ///
+    /// If the [`DeviceFeatures::SYNCHRONIZATION_2`] feature is not enabled, the barriers are
+    /// lowered to a single legacy barrier whose stages are the union of the stages of all the
+    /// barriers.
+    ///
+    /// # Safety
+    ///
+    /// The buffers and images referenced by the barriers must outlive the execution of the
+    /// command buffer, and the ranges and layouts of the barriers must match their state when the
+    /// command executes.
+    #[doc(alias = "vkCmdPipelineBarrier2")]
+    pub unsafe fn pipeline_barrier2(
+        &mut self,
+        memory_barriers: &[MemoryBarrier2],
+        buffer_barriers: &[BufferBarrier2],
+        image_barriers: &[ImageBarrier2],
+    ) {
+        let barrier2 = self.device().fns().cmd_pipeline_barrier2.filter(|_| {
+            self.device()
+                .features()
+                .contains(DeviceFeatures::SYNCHRONIZATION_2)
+        });
+
+        let Some(barrier2) = barrier2 else {
+            return self.legacy_pipeline_barrier(memory_barriers, buffer_barriers, image_barriers);
+        };
+
+        let memory_barriers: SmallVec<[_; 2]> = memory_barriers
+            .iter()
+            .map(|barrier| barrier.to_raw())
+            .collect();
+        let buffer_barriers: SmallVec<[_; 4]> = buffer_barriers
+            .iter()
+            .map(|barrier| barrier.to_raw())
+            .collect();
+        let image_barriers: SmallVec<[_; 4]> = image_barriers
+            .iter()
+            .map(|barrier| barrier.to_raw())
+            .collect();
+
+        let dependency_info = vk::DependencyInfoKHR {
+            dependency_flags: vk::DependencyFlags::empty(),
+            memory_barrier_count: memory_barriers.len() as u32,
+            p_memory_barriers: memory_barriers.as_ptr(),
+            buffer_memory_barrier_count: buffer_barriers.len() as u32,
+            p_buffer_memory_barriers: buffer_barriers.as_ptr(),
+            image_memory_barrier_count: image_barriers.len() as u32,
+            p_image_memory_barriers: image_barriers.as_ptr(),
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::DEPENDENCY_INFO_KHR,
+        };
+
+        barrier2(self.handle(), &dependency_info);
+    }
+
+    /// Records the provided barriers through the legacy `vkCmdPipelineBarrier` function.
+    ///
+    /// # Safety
+    ///
+    /// See [`CommandBuffer::pipeline_barrier2`].
+    unsafe fn legacy_pipeline_barrier(
+        &mut self,
+        memory_barriers: &[MemoryBarrier2],
+        buffer_barriers: &[BufferBarrier2],
+        image_barriers: &[ImageBarrier2],
+    ) {
+        let stages = memory_barriers
+            .iter()
+            .map(|b| (b.src_stages, b.dst_stages))
+            .chain(buffer_barriers.iter().map(|b| (b.src_stages, b.dst_stages)))
+            .chain(image_barriers.iter().map(|b| (b.src_stages, b.dst_stages)))
+            .fold(
+                (PipelineStages2::empty(), PipelineStages2::empty()),
+                |(src, dst), (b_src, b_dst)| (src | b_src, dst | b_dst),
+            );
+
+        let memory_barriers: SmallVec<[_; 2]> = memory_barriers
+            .iter()
+            .map(|barrier| barrier.to_legacy())
+            .collect();
+        let buffer_barriers: SmallVec<[_; 4]> = buffer_barriers
+            .iter()
+            .map(|barrier| barrier.to_legacy())
+            .collect();
+        let image_barriers: SmallVec<[_; 4]> = image_barriers
+            .iter()
+            .map(|barrier| barrier.to_legacy())
+            .collect();
+
+        self.pipeline_barrier(
+            stages.0.to_legacy(PipelineStages::TOP_OF_PIPE),
+            stages.1.to_legacy(PipelineStages::BOTTOM_OF_PIPE),
+            &memory_barriers,
+            &buffer_barriers,
+            &image_barriers,
+        );
+    }
+}
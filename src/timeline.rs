@@ -0,0 +1,288 @@
+use std::ffi::c_void;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ash::vk;
+use smallvec::SmallVec;
+
+use crate::{Device, DeviceFeatures, Error, Result};
+
+/// A timeline semaphore, whose state is a monotonically increasing 64-bit counter.
+///
+/// Queue submissions and the host can wait for the counter to reach a value, and signal it
+/// to a greater value.
+pub struct TimelineSemaphore {
+    /// The device that owns this semaphore.
+    device: Arc<Device>,
+
+    /// The handle to the semaphore.
+    handle: vk::Semaphore,
+}
+
+impl TimelineSemaphore {
+    /// Creates a new [`TimelineSemaphore`] with the provided initial value.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::MissingDeviceFeature`] if the
+    /// [`DeviceFeatures::TIMELINE_SEMAPHORE`] feature is not enabled on the device.
+    #[doc(alias = "vkCreateSemaphore")]
+    pub fn new(device: Arc<Device>, initial_value: u64) -> Result<Self> {
+        let fns = device.fns();
+        if !device
+            .features()
+            .contains(DeviceFeatures::TIMELINE_SEMAPHORE)
+            || fns.get_semaphore_counter_value.is_none()
+            || fns.wait_semaphores.is_none()
+            || fns.signal_semaphore.is_none()
+        {
+            return Err(Error::MissingDeviceFeature(
+                DeviceFeatures::TIMELINE_SEMAPHORE,
+            ));
+        }
+
+        let type_info = vk::SemaphoreTypeCreateInfo {
+            semaphore_type: vk::SemaphoreType::TIMELINE,
+            initial_value,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::SEMAPHORE_TYPE_CREATE_INFO,
+        };
+
+        let create_info = vk::SemaphoreCreateInfo {
+            flags: vk::SemaphoreCreateFlags::empty(),
+
+            p_next: &type_info as *const _ as *const c_void,
+            s_type: vk::StructureType::SEMAPHORE_CREATE_INFO,
+        };
+
+        let mut handle = vk::Semaphore::null();
+
+        let ret = unsafe {
+            (fns.create_semaphore)(device.handle(), &create_info, std::ptr::null(), &mut handle)
+        };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(Self { device, handle })
+    }
+
+    /// Returns the current value of the counter of this semaphore.
+    #[doc(alias = "vkGetSemaphoreCounterValue")]
+    pub fn value(&self) -> Result<u64> {
+        // The functions are known to be available since the semaphore was created.
+        let get_value = self.device.fns().get_semaphore_counter_value.unwrap();
+
+        let mut value = 0;
+        let ret = unsafe { get_value(self.device.handle(), self.handle, &mut value) };
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(value)
+    }
+
+    /// Signals this semaphore from the host, setting its counter to `value`.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be greater than the current value of the counter, and lower than the
+    /// values of the pending signal operations of the semaphore.
+    #[doc(alias = "vkSignalSemaphore")]
+    pub unsafe fn signal(&self, value: u64) -> Result<()> {
+        let signal = self.device.fns().signal_semaphore.unwrap();
+
+        let signal_info = vk::SemaphoreSignalInfo {
+            semaphore: self.handle,
+            value,
+
+            p_next: std::ptr::null(),
+            s_type: vk::StructureType::SEMAPHORE_SIGNAL_INFO,
+        };
+
+        let ret = signal(self.device.handle(), &signal_info);
+
+        if ret != vk::Result::SUCCESS {
+            return Err(ret.into());
+        }
+
+        Ok(())
+    }
+
+    /// Waits for the counter of this semaphore to reach `value`.
+    ///
+    /// Returns `false` if the timeout expired before the value was reached. A timeout of
+    /// [`Duration::MAX`] waits indefinitely.
+    #[doc(alias = "vkWaitSemaphores")]
+    pub fn wait(&self, value: u64, timeout: Duration) -> Result<bool> {
+        wait_semaphores(&self.device, &[self.handle], &[value], true, timeout)
+    }
+
+    /// Waits for the counters of all the provided semaphores to reach their associated value.
+    ///
+    /// Returns `false` if the timeout expired before all the values were reached.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the semaphores do not all belong to the same device.
+    #[doc(alias = "vkWaitSemaphores")]
+    pub fn wait_all(waits: &[(&TimelineSemaphore, u64)], timeout: Duration) -> Result<bool> {
+        Self::wait_many(waits, true, timeout)
+    }
+
+    /// Waits for the counter of any of the provided semaphores to reach its associated value.
+    ///
+    /// Returns `false` if the timeout expired before any of the values was reached.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the semaphores do not all belong to the same device.
+    #[doc(alias = "vkWaitSemaphores")]
+    pub fn wait_any(waits: &[(&TimelineSemaphore, u64)], timeout: Duration) -> Result<bool> {
+        Self::wait_many(waits, false, timeout)
+    }
+
+    /// Waits for all or any of the provided semaphores to reach their associated value.
+    fn wait_many(
+        waits: &[(&TimelineSemaphore, u64)],
+        wait_all: bool,
+        timeout: Duration,
+    ) -> Result<bool> {
+        let Some((first, _)) = waits.first() else {
+            return Ok(true);
+        };
+
+        assert!(
+            waits
+                .iter()
+                .all(|(semaphore, _)| Arc::ptr_eq(&semaphore.device, &first.device)),
+            "the semaphores do not belong to the same device"
+        );
+
+        let (handles, values): (SmallVec<[_; 4]>, SmallVec<[_; 4]>) = waits
+            .iter()
+            .map(|(semaphore, value)| (semaphore.handle, *value))
+            .unzip();
+
+        wait_semaphores(&first.device, &handles, &values, wait_all, timeout)
+    }
+
+    /// Returns a [`GpuFuture`] which resolves once the counter of this semaphore reaches
+    /// `value`.
+    pub fn future(self: &Arc<Self>, value: u64) -> GpuFuture {
+        GpuFuture {
+            semaphore: self.clone(),
+            value,
+        }
+    }
+
+    /// Returns the device that owns this semaphore.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the Vulkan handle for this semaphore.
+    #[inline(always)]
+    pub fn handle(&self) -> vk::Semaphore {
+        self.handle
+    }
+}
+
+impl Drop for TimelineSemaphore {
+    fn drop(&mut self) {
+        unsafe {
+            (self.device.fns().destroy_semaphore)(
+                self.device.handle(),
+                self.handle,
+                std::ptr::null(),
+            );
+        }
+    }
+}
+
+/// Calls `vkWaitSemaphores`, mapping a timeout to `false`.
+///
+/// The device must have created the semaphores, which means that the function is available.
+pub(crate) fn wait_semaphores(
+    device: &Device,
+    semaphores: &[vk::Semaphore],
+    values: &[u64],
+    wait_all: bool,
+    timeout: Duration,
+) -> Result<bool> {
+    let wait = device.fns().wait_semaphores.unwrap();
+
+    let wait_info = vk::SemaphoreWaitInfo {
+        flags: if wait_all {
+            vk::SemaphoreWaitFlags::empty()
+        } else {
+            vk::SemaphoreWaitFlags::ANY
+        },
+        semaphore_count: semaphores.len() as u32,
+        p_semaphores: semaphores.as_ptr(),
+        p_values: values.as_ptr(),
+
+        p_next: std::ptr::null(),
+        s_type: vk::StructureType::SEMAPHORE_WAIT_INFO,
+    };
+
+    let ret = unsafe {
+        wait(
+            device.handle(),
+            &wait_info,
+            crate::sync::timeout_to_nanos(timeout),
+        )
+    };
+
+    match ret {
+        vk::Result::SUCCESS => Ok(true),
+        vk::Result::TIMEOUT => Ok(false),
+        _ => Err(ret.into()),
+    }
+}
+
+/// A point on the timeline of a [`TimelineSemaphore`], typically signaled by a queue
+/// submission.
+///
+/// The future resolves once the counter of the semaphore reaches its value. Resources used by
+/// a submission can be kept alive alongside the future of that submission, and released once
+/// it has resolved.
+#[derive(Clone)]
+pub struct GpuFuture {
+    /// The semaphore that the future waits on.
+    semaphore: Arc<TimelineSemaphore>,
+    /// The value that the counter of the semaphore must reach.
+    value: u64,
+}
+
+impl GpuFuture {
+    /// Returns whether this future has resolved, without blocking.
+    #[doc(alias = "vkGetSemaphoreCounterValue")]
+    pub fn is_ready(&self) -> Result<bool> {
+        Ok(self.semaphore.value()? >= self.value)
+    }
+
+    /// Waits for this future to resolve.
+    ///
+    /// Returns `false` if the timeout expired before the future resolved.
+    #[doc(alias = "vkWaitSemaphores")]
+    pub fn wait(&self, timeout: Duration) -> Result<bool> {
+        self.semaphore.wait(self.value, timeout)
+    }
+
+    /// Returns the semaphore that this future waits on.
+    #[inline(always)]
+    pub fn semaphore(&self) -> &Arc<TimelineSemaphore> {
+        &self.semaphore
+    }
+
+    /// Returns the value that the counter of the semaphore must reach.
+    #[inline(always)]
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
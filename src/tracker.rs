@@ -0,0 +1,1586 @@
+use std::collections::{BTreeMap, HashMap};
+
+use ash::vk;
+use smallvec::SmallVec;
+
+use crate::{
+    Accesses2, Buffer, BufferBarrier2, BufferCopy, BufferImageCopy, ClearColor, CommandBuffer,
+    Filter, Image, ImageAspects, ImageBarrier2, ImageBlit, ImageCopy, ImageLayout,
+    ImageSubresourceLayers, ImageSubresourceRange, PipelineStages2, Primary, Recording,
+};
+
+/// The accesses which modify memory.
+const WRITE_ACCESSES: Accesses2 = Accesses2::SHADER_WRITE
+    .union(Accesses2::COLOR_ATTACHMENT_WRITE)
+    .union(Accesses2::DEPTH_STENCIL_ATTACHMENT_WRITE)
+    .union(Accesses2::TRANSFER_WRITE)
+    .union(Accesses2::HOST_WRITE)
+    .union(Accesses2::MEMORY_WRITE)
+    .union(Accesses2::SHADER_STORAGE_WRITE);
+
+/// The way a command uses a range of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImageUse {
+    /// The stages that access the image.
+    pub stages: PipelineStages2,
+    /// The accesses performed on the image.
+    pub access: Accesses2,
+    /// The layout that the image must be in.
+    pub layout: ImageLayout,
+}
+
+impl ImageUse {
+    /// The image is the source of a transfer command.
+    pub const TRANSFER_SRC: Self = Self {
+        stages: PipelineStages2::ALL_TRANSFER,
+        access: Accesses2::TRANSFER_READ,
+        layout: ImageLayout::TransferSrcOptimal,
+    };
+
+    /// The image is the destination of a transfer command.
+    pub const TRANSFER_DST: Self = Self {
+        stages: PipelineStages2::ALL_TRANSFER,
+        access: Accesses2::TRANSFER_WRITE,
+        layout: ImageLayout::TransferDstOptimal,
+    };
+
+    /// The image is sampled by fragment or compute shaders.
+    pub const SAMPLED: Self = Self {
+        stages: PipelineStages2::FRAGMENT_SHADER.union(PipelineStages2::COMPUTE_SHADER),
+        access: Accesses2::SHADER_SAMPLED_READ,
+        layout: ImageLayout::ShaderReadOnlyOptimal,
+    };
+
+    /// The image is a color attachment.
+    pub const COLOR_ATTACHMENT: Self = Self {
+        stages: PipelineStages2::COLOR_ATTACHMENT_OUTPUT,
+        access: Accesses2::COLOR_ATTACHMENT_READ.union(Accesses2::COLOR_ATTACHMENT_WRITE),
+        layout: ImageLayout::ColorAttachmentOptimal,
+    };
+
+    /// The image is a depth/stencil attachment.
+    pub const DEPTH_STENCIL_ATTACHMENT: Self = Self {
+        stages: PipelineStages2::EARLY_FRAGMENT_TESTS.union(PipelineStages2::LATE_FRAGMENT_TESTS),
+        access: Accesses2::DEPTH_STENCIL_ATTACHMENT_READ
+            .union(Accesses2::DEPTH_STENCIL_ATTACHMENT_WRITE),
+        layout: ImageLayout::DepthStencilAttachmentOptimal,
+    };
+
+    /// The image is about to be presented.
+    ///
+    /// Presentation is synchronized through semaphores, so no stage of the queue accesses the
+    /// image.
+    pub const PRESENT: Self = Self {
+        stages: PipelineStages2::empty(),
+        access: Accesses2::empty(),
+        layout: ImageLayout::PresentSrc,
+    };
+}
+
+/// The way a command uses a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BufferUse {
+    /// The stages that access the buffer.
+    pub stages: PipelineStages2,
+    /// The accesses performed on the buffer.
+    pub access: Accesses2,
+}
+
+impl BufferUse {
+    /// The buffer is the source of a transfer command.
+    pub const TRANSFER_SRC: Self = Self {
+        stages: PipelineStages2::ALL_TRANSFER,
+        access: Accesses2::TRANSFER_READ,
+    };
+
+    /// The buffer is the destination of a transfer command.
+    pub const TRANSFER_DST: Self = Self {
+        stages: PipelineStages2::ALL_TRANSFER,
+        access: Accesses2::TRANSFER_WRITE,
+    };
+
+    /// The buffer is bound as a vertex buffer.
+    pub const VERTEX: Self = Self {
+        stages: PipelineStages2::VERTEX_ATTRIBUTE_INPUT,
+        access: Accesses2::VERTEX_ATTRIBUTE_READ,
+    };
+
+    /// The buffer is bound as an index buffer.
+    pub const INDEX: Self = Self {
+        stages: PipelineStages2::INDEX_INPUT,
+        access: Accesses2::INDEX_READ,
+    };
+
+    /// The buffer holds the parameters of indirect draws or dispatches.
+    pub const INDIRECT: Self = Self {
+        stages: PipelineStages2::DRAW_INDIRECT,
+        access: Accesses2::INDIRECT_COMMAND_READ,
+    };
+
+    /// The buffer is read by shaders as a uniform buffer.
+    pub const UNIFORM: Self = Self {
+        stages: PipelineStages2::ALL_COMMANDS,
+        access: Accesses2::UNIFORM_READ,
+    };
+}
+
+/// The initial state of an image registered with a [`StateTracker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TrackedImageDesc {
+    /// The aspects of the format of the image.
+    pub aspects: ImageAspects,
+    /// The number of mipmap levels of the image.
+    pub mip_levels: u32,
+    /// The number of array layers of the image.
+    pub array_layers: u32,
+    /// The current layout of all the subresources of the image.
+    pub layout: ImageLayout,
+    /// The queue family which currently owns the image, if any.
+    ///
+    /// An image with no owner is acquired by the first queue family that uses it.
+    pub owner: Option<u32>,
+    /// Whether the image was created with the concurrent sharing mode, in which case it never
+    /// needs to be transferred between queue families.
+    pub concurrent: bool,
+}
+
+impl TrackedImageDesc {
+    /// Returns the description of an image with undefined contents and no owner, as created
+    /// with [`Image::new`].
+    pub fn new(image: &Image) -> Self {
+        Self {
+            aspects: image.format().aspects(),
+            mip_levels: image.mip_levels(),
+            array_layers: image.array_layers(),
+            layout: ImageLayout::Undefined,
+            owner: None,
+            concurrent: false,
+        }
+    }
+}
+
+/// The initial state of a buffer registered with a [`StateTracker`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TrackedBufferDesc {
+    /// The queue family which currently owns the buffer, if any.
+    ///
+    /// A buffer with no owner is acquired by the first queue family that uses it.
+    pub owner: Option<u32>,
+    /// Whether the buffer was created with the concurrent sharing mode, in which case it never
+    /// needs to be transferred between queue families.
+    pub concurrent: bool,
+}
+
+/// A buffer or a single subresource of an image, as tracked by a [`StateTracker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TrackedResource {
+    /// A whole buffer.
+    Buffer(vk::Buffer),
+    /// A single mipmap level and array layer of an image.
+    Image {
+        image: vk::Image,
+        mip_level: u32,
+        array_layer: u32,
+    },
+}
+
+/// A synchronization error detected by a [`StateTracker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hazard {
+    /// An exclusive resource owned by a queue family was used by another queue family
+    /// without being released by the first one.
+    ///
+    /// The contents of the resource are undefined for the second queue family.
+    MissingQueueTransfer {
+        resource: TrackedResource,
+        src_queue_family: u32,
+        dst_queue_family: u32,
+    },
+    /// A subresource of an image was used in two different layouts by the same command.
+    ConflictingLayouts {
+        resource: TrackedResource,
+        layouts: [ImageLayout; 2],
+    },
+}
+
+/// A dependency on a whole buffer, emitted by a [`StateTracker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BufferTransition {
+    /// The buffer affected by the barrier.
+    pub buffer: vk::Buffer,
+    /// The stages that must complete before the barrier.
+    pub src_stages: PipelineStages2,
+    /// The accesses that must be made available.
+    pub src_access: Accesses2,
+    /// The stages that must wait for the barrier.
+    pub dst_stages: PipelineStages2,
+    /// The accesses that the memory must be made visible to.
+    pub dst_access: Accesses2,
+    /// The source and destination queue families, if the ownership of the buffer is
+    /// transferred.
+    pub queue_family_transfer: Option<[u32; 2]>,
+}
+
+/// A dependency on a range of an image, emitted by a [`StateTracker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImageTransition {
+    /// The image affected by the barrier.
+    pub image: vk::Image,
+    /// The stages that must complete before the barrier.
+    pub src_stages: PipelineStages2,
+    /// The accesses that must be made available.
+    pub src_access: Accesses2,
+    /// The stages that must wait for the barrier.
+    pub dst_stages: PipelineStages2,
+    /// The accesses that the memory must be made visible to.
+    pub dst_access: Accesses2,
+    /// The layout of the range before the barrier.
+    pub old_layout: ImageLayout,
+    /// The layout of the range after the barrier.
+    pub new_layout: ImageLayout,
+    /// The source and destination queue families, if the ownership of the range is
+    /// transferred.
+    pub queue_family_transfer: Option<[u32; 2]>,
+    /// The part of the image affected by the barrier.
+    pub subresource_range: ImageSubresourceRange,
+}
+
+/// A set of barriers which must be recorded with a single pipeline barrier command.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BarrierBatch {
+    /// The barriers on buffers.
+    pub buffers: Vec<BufferTransition>,
+    /// The barriers on images.
+    pub images: Vec<ImageTransition>,
+}
+
+impl BarrierBatch {
+    /// Returns whether this batch contains no barrier.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty() && self.images.is_empty()
+    }
+}
+
+/// The queue families allowed to use a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ownership {
+    /// The resource may be used by any queue family.
+    Concurrent,
+    /// The resource is acquired by the first queue family that uses it.
+    Unowned,
+    /// The resource is owned by a queue family.
+    Owned(u32),
+    /// The resource has been released by a queue family, and must be acquired by another.
+    ///
+    /// The acquisition must repeat the layout transition of the release, from `old_layout`.
+    Released {
+        src: u32,
+        dst: u32,
+        old_layout: ImageLayout,
+    },
+}
+
+impl Ownership {
+    /// Returns the initial ownership of a resource.
+    fn new(owner: Option<u32>, concurrent: bool) -> Self {
+        match (owner, concurrent) {
+            (_, true) => Self::Concurrent,
+            (Some(family), false) => Self::Owned(family),
+            (None, false) => Self::Unowned,
+        }
+    }
+}
+
+/// The synchronization state of a buffer or of an image subresource.
+#[derive(Debug, Clone, Copy)]
+struct State {
+    /// The current layout, which is always undefined for buffers.
+    layout: ImageLayout,
+    /// The queue families allowed to use the resource.
+    ownership: Ownership,
+    /// The stages and accesses of the last write, if it may still be in flight.
+    ///
+    /// Layout transitions count as writes with no access.
+    write: Option<(PipelineStages2, Accesses2)>,
+    /// The stages which read the resource since the last write.
+    reads: PipelineStages2,
+    /// The stages and accesses that the last write has been made visible to.
+    visible: (PipelineStages2, Accesses2),
+}
+
+impl State {
+    /// Returns the state of a resource which has not been used yet.
+    fn new(layout: ImageLayout, ownership: Ownership) -> Self {
+        Self {
+            layout,
+            ownership,
+            write: None,
+            reads: PipelineStages2::empty(),
+            visible: (PipelineStages2::empty(), Accesses2::empty()),
+        }
+    }
+}
+
+/// A dependency on a single tracked resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Dependency {
+    src_stages: PipelineStages2,
+    src_access: Accesses2,
+    dst_stages: PipelineStages2,
+    dst_access: Accesses2,
+    old_layout: ImageLayout,
+    new_layout: ImageLayout,
+    queue_family_transfer: Option<[u32; 2]>,
+}
+
+/// The combined uses of a resource by the command being recorded.
+#[derive(Debug, Clone, Copy)]
+struct PendingUse {
+    queue_family: u32,
+    stages: PipelineStages2,
+    access: Accesses2,
+    layout: ImageLayout,
+}
+
+/// A tracked image.
+struct TrackedImage {
+    /// The aspects of the format of the image.
+    aspects: ImageAspects,
+    /// The number of array layers of the image.
+    array_layers: u32,
+    /// The state of each subresource, indexed by `mip_level * array_layers + array_layer`.
+    states: Box<[State]>,
+}
+
+impl TrackedImage {
+    /// Returns the number of mipmap levels of the image.
+    fn mip_levels(&self) -> u32 {
+        self.states.len() as u32 / self.array_layers
+    }
+
+    /// Calls `f` for each subresource of the image within `range`.
+    fn for_each_in(&self, range: &ImageSubresourceRange, mut f: impl FnMut(u32, u32)) {
+        let level_count = match range.level_count {
+            ImageSubresourceRange::REMAINING => self.mip_levels() - range.base_mip_level,
+            count => count,
+        };
+        let layer_count = match range.layer_count {
+            ImageSubresourceRange::REMAINING => self.array_layers - range.base_array_layer,
+            count => count,
+        };
+
+        for mip_level in range.base_mip_level..range.base_mip_level + level_count {
+            for array_layer in range.base_array_layer..range.base_array_layer + layer_count {
+                f(mip_level, array_layer);
+            }
+        }
+    }
+
+    /// Returns the state of a subresource of the image.
+    fn state_mut(&mut self, mip_level: u32, array_layer: u32) -> &mut State {
+        &mut self.states[(mip_level * self.array_layers + array_layer) as usize]
+    }
+}
+
+/// Tracks the layout, the pending accesses and the queue family ownership of buffers and
+/// image subresources, and computes the barriers required between their uses.
+///
+/// Commands declare how they use resources with [`StateTracker::use_image`] and
+/// [`StateTracker::use_buffer`] before being recorded. The uses of a command are combined, and
+/// [`StateTracker::flush`] returns the minimal set of barriers that must precede it. Adjacent
+/// subresources requiring the same barrier are merged into a single range.
+///
+/// The tracker only deals with Vulkan handles and can be driven without a device, which makes
+/// it possible to check the barriers produced for a recorded stream of uses. The
+/// [`TrackedCommandBuffer`] type drives it while recording a command buffer.
+///
+/// A tracker must see the uses in the order in which they execute on the device, which
+/// generally means recording command buffers in their submission order. Handles are reused
+/// once destroyed, so destroyed resources must be removed with [`StateTracker::forget_image`]
+/// or [`StateTracker::forget_buffer`].
+#[derive(Default)]
+pub struct StateTracker {
+    /// The tracked images.
+    images: HashMap<vk::Image, TrackedImage>,
+    /// The state of the tracked buffers.
+    buffers: HashMap<vk::Buffer, State>,
+    /// The uses declared since the last flush.
+    pending: BTreeMap<TrackedResource, PendingUse>,
+    /// The batches computed since the last flush.
+    batches: Vec<BarrierBatch>,
+    /// The hazards detected so far.
+    hazards: Vec<Hazard>,
+}
+
+impl StateTracker {
+    /// Creates a new, empty [`StateTracker`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts tracking an image, replacing its previous state if it was already tracked.
+    pub fn register_image(&mut self, image: vk::Image, desc: TrackedImageDesc) {
+        let state = State::new(desc.layout, Ownership::new(desc.owner, desc.concurrent));
+        let count = desc.mip_levels * desc.array_layers;
+
+        self.images.insert(
+            image,
+            TrackedImage {
+                aspects: desc.aspects,
+                array_layers: desc.array_layers,
+                states: vec![state; count as usize].into_boxed_slice(),
+            },
+        );
+    }
+
+    /// Starts tracking a buffer, replacing its previous state if it was already tracked.
+    ///
+    /// Buffers which are used without having been registered are tracked with the default
+    /// [`TrackedBufferDesc`].
+    pub fn register_buffer(&mut self, buffer: vk::Buffer, desc: TrackedBufferDesc) {
+        let state = State::new(
+            ImageLayout::Undefined,
+            Ownership::new(desc.owner, desc.concurrent),
+        );
+        self.buffers.insert(buffer, state);
+    }
+
+    /// Returns whether an image is tracked.
+    #[inline]
+    pub fn is_image_registered(&self, image: vk::Image) -> bool {
+        self.images.contains_key(&image)
+    }
+
+    /// Stops tracking an image, typically because it has been destroyed.
+    pub fn forget_image(&mut self, image: vk::Image) {
+        self.images.remove(&image);
+    }
+
+    /// Stops tracking a buffer, typically because it has been destroyed.
+    pub fn forget_buffer(&mut self, buffer: vk::Buffer) {
+        self.buffers.remove(&buffer);
+    }
+
+    /// Declares that the next command uses a range of an image on the provided queue family.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the image is not tracked, or if the range is out of its
+    /// bounds.
+    pub fn use_image(
+        &mut self,
+        queue_family: u32,
+        image: vk::Image,
+        range: &ImageSubresourceRange,
+        usage: ImageUse,
+    ) {
+        let tracked = self.images.get(&image).expect("the image is not tracked");
+
+        let pending = &mut self.pending;
+        let hazards = &mut self.hazards;
+        tracked.for_each_in(range, |mip_level, array_layer| {
+            let resource = TrackedResource::Image {
+                image,
+                mip_level,
+                array_layer,
+            };
+            add_pending(
+                pending,
+                hazards,
+                resource,
+                PendingUse {
+                    queue_family,
+                    stages: usage.stages,
+                    access: usage.access,
+                    layout: usage.layout,
+                },
+            );
+        });
+    }
+
+    /// Declares that the next command uses a buffer on the provided queue family.
+    pub fn use_buffer(&mut self, queue_family: u32, buffer: vk::Buffer, usage: BufferUse) {
+        self.buffers
+            .entry(buffer)
+            .or_insert_with(|| State::new(ImageLayout::Undefined, Ownership::Unowned));
+
+        add_pending(
+            &mut self.pending,
+            &mut self.hazards,
+            TrackedResource::Buffer(buffer),
+            PendingUse {
+                queue_family,
+                stages: usage.stages,
+                access: usage.access,
+                layout: ImageLayout::Undefined,
+            },
+        );
+    }
+
+    /// Releases the ownership of a range of an image from a queue family to another, and
+    /// transitions it to `new_layout`.
+    ///
+    /// The uses declared so far are resolved first. Once the release barrier has executed, the
+    /// range must only be used by `dst_queue_family`, which acquires it automatically.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the image is not tracked, or if the range is out of its
+    /// bounds.
+    pub fn release_image(
+        &mut self,
+        src_queue_family: u32,
+        dst_queue_family: u32,
+        image: vk::Image,
+        range: &ImageSubresourceRange,
+        new_layout: ImageLayout,
+    ) {
+        self.resolve_pending();
+
+        let tracked = self
+            .images
+            .get_mut(&image)
+            .expect("the image is not tracked");
+        let mut dependencies = Vec::new();
+
+        let mut subresources = Vec::new();
+        tracked.for_each_in(range, |mip_level, array_layer| {
+            subresources.push((mip_level, array_layer));
+        });
+
+        for (mip_level, array_layer) in subresources {
+            let resource = TrackedResource::Image {
+                image,
+                mip_level,
+                array_layer,
+            };
+            let state = tracked.state_mut(mip_level, array_layer);
+            if let Some(dependency) = release(
+                state,
+                &mut self.hazards,
+                resource,
+                src_queue_family,
+                dst_queue_family,
+                new_layout,
+            ) {
+                dependencies.push((mip_level, array_layer, dependency));
+            }
+        }
+
+        let mut batch = BarrierBatch::default();
+        merge_image_dependencies(&mut batch, image, tracked.aspects, dependencies);
+        self.push_batch(batch);
+    }
+
+    /// Releases the ownership of a buffer from a queue family to another.
+    ///
+    /// The uses declared so far are resolved first. Once the release barrier has executed, the
+    /// buffer must only be used by `dst_queue_family`, which acquires it automatically.
+    pub fn release_buffer(
+        &mut self,
+        src_queue_family: u32,
+        dst_queue_family: u32,
+        buffer: vk::Buffer,
+    ) {
+        self.resolve_pending();
+
+        let state = self
+            .buffers
+            .entry(buffer)
+            .or_insert_with(|| State::new(ImageLayout::Undefined, Ownership::Unowned));
+
+        let dependency = release(
+            state,
+            &mut self.hazards,
+            TrackedResource::Buffer(buffer),
+            src_queue_family,
+            dst_queue_family,
+            ImageLayout::Undefined,
+        );
+
+        let mut batch = BarrierBatch::default();
+        if let Some(dependency) = dependency {
+            batch.buffers.push(buffer_transition(buffer, &dependency));
+        }
+        self.push_batch(batch);
+    }
+
+    /// Resolves the uses declared since the last call, and returns the barriers that must be
+    /// recorded before the command using them.
+    ///
+    /// Each batch must be recorded with its own pipeline barrier command, in order.
+    pub fn flush(&mut self) -> Vec<BarrierBatch> {
+        self.resolve_pending();
+        std::mem::take(&mut self.batches)
+    }
+
+    /// Returns the hazards detected so far.
+    #[inline]
+    pub fn hazards(&self) -> &[Hazard] {
+        &self.hazards
+    }
+
+    /// Returns the hazards detected so far, and clears them.
+    #[inline]
+    pub fn take_hazards(&mut self) -> Vec<Hazard> {
+        std::mem::take(&mut self.hazards)
+    }
+
+    /// Computes the barriers required by the pending uses, and updates the state of the
+    /// resources accordingly.
+    fn resolve_pending(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        // Queue family acquisitions must complete before the layout transitions of the same
+        // subresources, so they are recorded in a separate batch.
+        let mut acquires = BarrierBatch::default();
+        let mut barriers = BarrierBatch::default();
+        let mut image_acquires = Vec::new();
+        let mut image_barriers = Vec::new();
+
+        // Pending uses are sorted by resource, so the subresources of an image are contiguous.
+        let pending = std::mem::take(&mut self.pending);
+        let mut pending = pending.into_iter().peekable();
+
+        while let Some((resource, usage)) = pending.next() {
+            match resource {
+                TrackedResource::Buffer(buffer) => {
+                    let state = self.buffers.get_mut(&buffer).unwrap();
+                    let (acquire, barrier) = access(state, &mut self.hazards, resource, usage);
+
+                    if let Some(acquire) = acquire {
+                        acquires.buffers.push(buffer_transition(buffer, &acquire));
+                    }
+                    if let Some(barrier) = barrier {
+                        barriers.buffers.push(buffer_transition(buffer, &barrier));
+                    }
+                }
+                TrackedResource::Image {
+                    image,
+                    mip_level,
+                    array_layer,
+                } => {
+                    let tracked = self.images.get_mut(&image).unwrap();
+                    let state = tracked.state_mut(mip_level, array_layer);
+                    let (acquire, barrier) = access(state, &mut self.hazards, resource, usage);
+
+                    if let Some(acquire) = acquire {
+                        image_acquires.push((mip_level, array_layer, acquire));
+                    }
+                    if let Some(barrier) = barrier {
+                        image_barriers.push((mip_level, array_layer, barrier));
+                    }
+
+                    let image_ends = !matches!(
+                        pending.peek(),
+                        Some((TrackedResource::Image { image: next, .. }, _)) if *next == image
+                    );
+
+                    if image_ends {
+                        let aspects = tracked.aspects;
+                        let acquires_of_image = std::mem::take(&mut image_acquires);
+                        let barriers_of_image = std::mem::take(&mut image_barriers);
+                        merge_image_dependencies(&mut acquires, image, aspects, acquires_of_image);
+                        merge_image_dependencies(&mut barriers, image, aspects, barriers_of_image);
+                    }
+                }
+            }
+        }
+
+        self.push_batch(acquires);
+        self.push_batch(barriers);
+    }
+
+    /// Appends a batch to the batches to be returned by the next flush, unless it is empty.
+    fn push_batch(&mut self, batch: BarrierBatch) {
+        if !batch.is_empty() {
+            self.batches.push(batch);
+        }
+    }
+}
+
+/// Combines a use of a resource with the other uses of the same command.
+fn add_pending(
+    pending: &mut BTreeMap<TrackedResource, PendingUse>,
+    hazards: &mut Vec<Hazard>,
+    resource: TrackedResource,
+    usage: PendingUse,
+) {
+    let Some(existing) = pending.get_mut(&resource) else {
+        pending.insert(resource, usage);
+        return;
+    };
+
+    if existing.layout != usage.layout {
+        hazards.push(Hazard::ConflictingLayouts {
+            resource,
+            layouts: [existing.layout, usage.layout],
+        });
+    }
+
+    existing.queue_family = usage.queue_family;
+    existing.stages |= usage.stages;
+    existing.access |= usage.access;
+}
+
+/// Checks that a queue family may use a resource, and returns the dependency acquiring it if
+/// it was released to that queue family.
+fn acquire(
+    state: &mut State,
+    hazards: &mut Vec<Hazard>,
+    resource: TrackedResource,
+    usage: &PendingUse,
+) -> Option<Dependency> {
+    let queue_family = usage.queue_family;
+
+    match state.ownership {
+        Ownership::Concurrent => None,
+        Ownership::Unowned => {
+            state.ownership = Ownership::Owned(queue_family);
+            None
+        }
+        Ownership::Owned(owner) if owner == queue_family => None,
+        Ownership::Released {
+            src,
+            dst,
+            old_layout,
+        } if dst == queue_family => {
+            state.ownership = Ownership::Owned(queue_family);
+
+            // When the layout does not change, the acquisition is the only barrier needed.
+            // Otherwise, the layout transition is chained after it.
+            let keeps_layout = state.layout == usage.layout;
+            let dependency = Dependency {
+                src_stages: PipelineStages2::empty(),
+                src_access: Accesses2::empty(),
+                dst_stages: usage.stages,
+                dst_access: if keeps_layout {
+                    usage.access
+                } else {
+                    Accesses2::empty()
+                },
+                old_layout,
+                new_layout: state.layout,
+                queue_family_transfer: Some([src, dst]),
+            };
+
+            state.write = if keeps_layout {
+                None
+            } else {
+                Some((usage.stages, Accesses2::empty()))
+            };
+            state.reads = PipelineStages2::empty();
+            state.visible = (PipelineStages2::empty(), Accesses2::empty());
+
+            Some(dependency)
+        }
+        Ownership::Owned(src) | Ownership::Released { src, .. } => {
+            hazards.push(Hazard::MissingQueueTransfer {
+                resource,
+                src_queue_family: src,
+                dst_queue_family: queue_family,
+            });
+            state.ownership = Ownership::Owned(queue_family);
+            None
+        }
+    }
+}
+
+/// Applies the combined uses of a command to the state of a resource, and returns the
+/// dependencies required before the command: an optional queue family acquisition, and an
+/// optional barrier.
+fn access(
+    state: &mut State,
+    hazards: &mut Vec<Hazard>,
+    resource: TrackedResource,
+    usage: PendingUse,
+) -> (Option<Dependency>, Option<Dependency>) {
+    let acquire = acquire(state, hazards, resource, &usage);
+
+    let changes_layout = state.layout != usage.layout;
+    let writes = usage.access.intersects(WRITE_ACCESSES);
+
+    let barrier = if changes_layout || writes {
+        // The previous accesses must complete before the resource is modified, and the
+        // previous write must be made available.
+        let (write_stages, write_access) = state
+            .write
+            .unwrap_or((PipelineStages2::empty(), Accesses2::empty()));
+        let needed = changes_layout || state.write.is_some() || !state.reads.is_empty();
+
+        let dependency = needed.then_some(Dependency {
+            src_stages: write_stages | state.reads,
+            src_access: write_access,
+            dst_stages: usage.stages,
+            dst_access: usage.access,
+            old_layout: state.layout,
+            new_layout: usage.layout,
+            queue_family_transfer: None,
+        });
+
+        state.layout = usage.layout;
+        state.reads = PipelineStages2::empty();
+        if writes {
+            state.write = Some((usage.stages, usage.access));
+            state.visible = (PipelineStages2::empty(), Accesses2::empty());
+        } else {
+            // The layout transition itself is a write, visible to the stages of this use.
+            state.write = Some((usage.stages, Accesses2::empty()));
+            state.reads = usage.stages;
+            state.visible = (usage.stages, usage.access);
+        }
+
+        dependency
+    } else {
+        let dependency = state.write.and_then(|(write_stages, write_access)| {
+            let (visible_stages, visible_access) = state.visible;
+            let covered =
+                visible_stages.contains(usage.stages) && visible_access.contains(usage.access);
+
+            (!covered).then_some(Dependency {
+                src_stages: write_stages,
+                src_access: write_access,
+                dst_stages: usage.stages,
+                dst_access: usage.access,
+                old_layout: state.layout,
+                new_layout: state.layout,
+                queue_family_transfer: None,
+            })
+        });
+
+        if dependency.is_some() {
+            state.visible.0 |= usage.stages;
+            state.visible.1 |= usage.access;
+        }
+        state.reads |= usage.stages;
+
+        dependency
+    };
+
+    (acquire, barrier)
+}
+
+/// Releases a resource from a queue family to another, and returns the release barrier.
+fn release(
+    state: &mut State,
+    hazards: &mut Vec<Hazard>,
+    resource: TrackedResource,
+    src_queue_family: u32,
+    dst_queue_family: u32,
+    new_layout: ImageLayout,
+) -> Option<Dependency> {
+    match state.ownership {
+        Ownership::Concurrent => return None,
+        Ownership::Unowned => (),
+        Ownership::Owned(owner) if owner == src_queue_family => (),
+        Ownership::Owned(owner) | Ownership::Released { src: owner, .. } => {
+            hazards.push(Hazard::MissingQueueTransfer {
+                resource,
+                src_queue_family: owner,
+                dst_queue_family: src_queue_family,
+            });
+        }
+    }
+
+    if src_queue_family == dst_queue_family {
+        state.ownership = Ownership::Owned(src_queue_family);
+        return None;
+    }
+
+    let (write_stages, write_access) = state
+        .write
+        .unwrap_or((PipelineStages2::empty(), Accesses2::empty()));
+
+    let dependency = Dependency {
+        src_stages: write_stages | state.reads,
+        src_access: write_access,
+        dst_stages: PipelineStages2::empty(),
+        dst_access: Accesses2::empty(),
+        old_layout: state.layout,
+        new_layout,
+        queue_family_transfer: Some([src_queue_family, dst_queue_family]),
+    };
+
+    *state = State::new(
+        new_layout,
+        Ownership::Released {
+            src: src_queue_family,
+            dst: dst_queue_family,
+            old_layout: dependency.old_layout,
+        },
+    );
+
+    Some(dependency)
+}
+
+/// Returns the barrier applying a dependency to a whole buffer.
+fn buffer_transition(buffer: vk::Buffer, dependency: &Dependency) -> BufferTransition {
+    BufferTransition {
+        buffer,
+        src_stages: dependency.src_stages,
+        src_access: dependency.src_access,
+        dst_stages: dependency.dst_stages,
+        dst_access: dependency.dst_access,
+        queue_family_transfer: dependency.queue_family_transfer,
+    }
+}
+
+/// Merges the dependencies of the subresources of an image into as few ranges as possible,
+/// and adds the resulting barriers to a batch.
+///
+/// `dependencies` must be sorted by mipmap level, then by array layer.
+fn merge_image_dependencies(
+    batch: &mut BarrierBatch,
+    image: vk::Image,
+    aspects: ImageAspects,
+    dependencies: Vec<(u32, u32, Dependency)>,
+) {
+    // Runs of consecutive array layers within a mipmap level, as
+    // `(mip_level, level_count, base_array_layer, layer_count, dependency)`.
+    let mut runs: Vec<(u32, u32, u32, u32, Dependency)> = Vec::new();
+
+    for (mip_level, array_layer, dependency) in dependencies {
+        match runs.last_mut() {
+            Some((level, _, base, count, dep))
+                if *level == mip_level && *base + *count == array_layer && *dep == dependency =>
+            {
+                *count += 1;
+            }
+            _ => runs.push((mip_level, 1, array_layer, 1, dependency)),
+        }
+    }
+
+    // Runs covering the same layers of consecutive mipmap levels are merged.
+    let mut merged: Vec<(u32, u32, u32, u32, Dependency)> = Vec::new();
+
+    for (mip_level, _, base, count, dependency) in runs {
+        let previous = merged.iter_mut().rev().find(|(level, levels, b, c, dep)| {
+            *level + *levels == mip_level && *b == base && *c == count && *dep == dependency
+        });
+
+        match previous {
+            Some((_, levels, ..)) => *levels += 1,
+            None => merged.push((mip_level, 1, base, count, dependency)),
+        }
+    }
+
+    batch.images.extend(merged.into_iter().map(
+        |(base_mip_level, level_count, base_array_layer, layer_count, dependency)| {
+            ImageTransition {
+                image,
+                src_stages: dependency.src_stages,
+                src_access: dependency.src_access,
+                dst_stages: dependency.dst_stages,
+                dst_access: dependency.dst_access,
+                old_layout: dependency.old_layout,
+                new_layout: dependency.new_layout,
+                queue_family_transfer: dependency.queue_family_transfer,
+                subresource_range: ImageSubresourceRange {
+                    aspects,
+                    base_mip_level,
+                    level_count,
+                    base_array_layer,
+                    layer_count,
+                },
+            }
+        },
+    ));
+}
+
+/// Returns the range covering a set of array layers of a single mipmap level.
+fn layers_to_range(layers: &ImageSubresourceLayers) -> ImageSubresourceRange {
+    ImageSubresourceRange {
+        aspects: layers.aspects,
+        base_mip_level: layers.mip_level,
+        level_count: 1,
+        base_array_layer: layers.base_array_layer,
+        layer_count: layers.layer_count,
+    }
+}
+
+/// A command buffer being recorded, whose barriers are inserted by a [`StateTracker`].
+///
+/// Resources are declared with [`TrackedCommandBuffer::use_image`] and
+/// [`TrackedCommandBuffer::use_buffer`], after which [`TrackedCommandBuffer::commands`]
+/// records the barriers they need and gives access to the underlying command buffer. The
+/// transfer commands are also provided directly, along with the uses they imply.
+///
+/// Images are registered with the tracker as having undefined contents the first time they
+/// are used, unless they were registered beforehand. Pending barriers are recorded when the
+/// recorder is dropped.
+///
+/// # Panics
+///
+/// Recording barriers panics if the tracker has uses pending for resources that were not
+/// declared through this recorder.
+pub struct TrackedCommandBuffer<'r, 'p, 'a, L = Primary> {
+    /// The command buffer being recorded.
+    command_buffer: &'r mut CommandBuffer<Recording<'p>, L>,
+    /// The tracker computing the barriers.
+    tracker: &'r mut StateTracker,
+    /// The images used through this recorder.
+    images: HashMap<vk::Image, &'a Image>,
+    /// The buffers used through this recorder.
+    buffers: HashMap<vk::Buffer, &'a Buffer>,
+}
+
+impl<'r, 'p, 'a, L> TrackedCommandBuffer<'r, 'p, 'a, L> {
+    /// Creates a new [`TrackedCommandBuffer`] recording into the provided command buffer.
+    ///
+    /// # Safety
+    ///
+    /// The images and buffers used through the recorder must outlive the execution of the
+    /// command buffer, and the regions passed to its commands must be in bounds of their
+    /// resources.
+    pub unsafe fn new(
+        command_buffer: &'r mut CommandBuffer<Recording<'p>, L>,
+        tracker: &'r mut StateTracker,
+    ) -> Self {
+        Self {
+            command_buffer,
+            tracker,
+            images: HashMap::new(),
+            buffers: HashMap::new(),
+        }
+    }
+
+    /// Declares that the next command uses a range of an image.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the range is out of the bounds of the image.
+    pub fn use_image(&mut self, image: &'a Image, range: &ImageSubresourceRange, usage: ImageUse) {
+        self.register_image(image);
+        let queue_family = self.command_buffer.queue_family();
+        self.tracker
+            .use_image(queue_family, image.handle(), range, usage);
+    }
+
+    /// Declares that the next command uses a buffer.
+    pub fn use_buffer(&mut self, buffer: &'a Buffer, usage: BufferUse) {
+        self.buffers.insert(buffer.handle(), buffer);
+        let queue_family = self.command_buffer.queue_family();
+        self.tracker
+            .use_buffer(queue_family, buffer.handle(), usage);
+    }
+
+    /// Releases a range of an image to another queue family, transitioning it to
+    /// `new_layout`.
+    ///
+    /// The command buffer which acquires the range on the other queue family must be recorded
+    /// with the same tracker, and submitted so that it waits for this one.
+    pub fn release_image(
+        &mut self,
+        image: &'a Image,
+        range: &ImageSubresourceRange,
+        dst_queue_family: u32,
+        new_layout: ImageLayout,
+    ) {
+        self.register_image(image);
+        let queue_family = self.command_buffer.queue_family();
+        self.tracker.release_image(
+            queue_family,
+            dst_queue_family,
+            image.handle(),
+            range,
+            new_layout,
+        );
+        self.flush();
+    }
+
+    /// Releases a buffer to another queue family.
+    ///
+    /// The command buffer which acquires the buffer on the other queue family must be
+    /// recorded with the same tracker, and submitted so that it waits for this one.
+    pub fn release_buffer(&mut self, buffer: &'a Buffer, dst_queue_family: u32) {
+        self.buffers.insert(buffer.handle(), buffer);
+        let queue_family = self.command_buffer.queue_family();
+        self.tracker
+            .release_buffer(queue_family, dst_queue_family, buffer.handle());
+        self.flush();
+    }
+
+    /// Records the barriers required by the uses declared so far, and returns the command
+    /// buffer to record the command using them.
+    #[inline]
+    pub fn commands(&mut self) -> &mut CommandBuffer<Recording<'p>, L> {
+        self.flush();
+        self.command_buffer
+    }
+
+    /// Returns the tracker computing the barriers.
+    #[inline(always)]
+    pub fn tracker(&self) -> &StateTracker {
+        self.tracker
+    }
+
+    /// Copies regions of a buffer into another buffer.
+    #[doc(alias = "vkCmdCopyBuffer")]
+    pub fn copy_buffer(&mut self, src: &'a Buffer, dst: &'a Buffer, regions: &[BufferCopy]) {
+        self.use_buffer(src, BufferUse::TRANSFER_SRC);
+        self.use_buffer(dst, BufferUse::TRANSFER_DST);
+        unsafe { self.commands().copy_buffer(src, dst, regions) };
+    }
+
+    /// Copies regions of a buffer into an image.
+    #[doc(alias = "vkCmdCopyBufferToImage")]
+    pub fn copy_buffer_to_image(
+        &mut self,
+        src: &'a Buffer,
+        dst: &'a Image,
+        regions: &[BufferImageCopy],
+    ) {
+        self.use_buffer(src, BufferUse::TRANSFER_SRC);
+        for region in regions {
+            self.use_image(
+                dst,
+                &layers_to_range(&region.image_subresource),
+                ImageUse::TRANSFER_DST,
+            );
+        }
+        unsafe {
+            self.commands()
+                .copy_buffer_to_image(src, dst, ImageLayout::TransferDstOptimal, regions)
+        };
+    }
+
+    /// Copies regions of an image into a buffer.
+    #[doc(alias = "vkCmdCopyImageToBuffer")]
+    pub fn copy_image_to_buffer(
+        &mut self,
+        src: &'a Image,
+        dst: &'a Buffer,
+        regions: &[BufferImageCopy],
+    ) {
+        for region in regions {
+            self.use_image(
+                src,
+                &layers_to_range(&region.image_subresource),
+                ImageUse::TRANSFER_SRC,
+            );
+        }
+        self.use_buffer(dst, BufferUse::TRANSFER_DST);
+        unsafe {
+            self.commands()
+                .copy_image_to_buffer(src, ImageLayout::TransferSrcOptimal, dst, regions)
+        };
+    }
+
+    /// Copies regions of an image into another image.
+    #[doc(alias = "vkCmdCopyImage")]
+    pub fn copy_image(&mut self, src: &'a Image, dst: &'a Image, regions: &[ImageCopy]) {
+        for region in regions {
+            self.use_image(
+                src,
+                &layers_to_range(&region.src_subresource),
+                ImageUse::TRANSFER_SRC,
+            );
+            self.use_image(
+                dst,
+                &layers_to_range(&region.dst_subresource),
+                ImageUse::TRANSFER_DST,
+            );
+        }
+        unsafe {
+            self.commands().copy_image(
+                src,
+                ImageLayout::TransferSrcOptimal,
+                dst,
+                ImageLayout::TransferDstOptimal,
+                regions,
+            )
+        };
+    }
+
+    /// Copies regions of an image into another image, scaling and converting the texels as
+    /// needed.
+    ///
+    /// The source and destination regions may belong to different mipmap levels of the same
+    /// image, which is how mipmap chains are typically generated.
+    #[doc(alias = "vkCmdBlitImage")]
+    pub fn blit_image(
+        &mut self,
+        src: &'a Image,
+        dst: &'a Image,
+        regions: &[ImageBlit],
+        filter: Filter,
+    ) {
+        for region in regions {
+            self.use_image(
+                src,
+                &layers_to_range(&region.src_subresource),
+                ImageUse::TRANSFER_SRC,
+            );
+            self.use_image(
+                dst,
+                &layers_to_range(&region.dst_subresource),
+                ImageUse::TRANSFER_DST,
+            );
+        }
+        unsafe {
+            self.commands().blit_image(
+                src,
+                ImageLayout::TransferSrcOptimal,
+                dst,
+                ImageLayout::TransferDstOptimal,
+                regions,
+                filter,
+            )
+        };
+    }
+
+    /// Clears ranges of a color image.
+    #[doc(alias = "vkCmdClearColorImage")]
+    pub fn clear_color_image(
+        &mut self,
+        image: &'a Image,
+        color: ClearColor,
+        ranges: &[ImageSubresourceRange],
+    ) {
+        for range in ranges {
+            self.use_image(image, range, ImageUse::TRANSFER_DST);
+        }
+        unsafe {
+            self.commands()
+                .clear_color_image(image, ImageLayout::TransferDstOptimal, color, ranges)
+        };
+    }
+
+    /// Fills a range of a buffer with a repeated 32-bit value.
+    #[doc(alias = "vkCmdFillBuffer")]
+    pub fn fill_buffer(&mut self, buffer: &'a Buffer, offset: u64, size: u64, data: u32) {
+        self.use_buffer(buffer, BufferUse::TRANSFER_DST);
+        unsafe { self.commands().fill_buffer(buffer, offset, size, data) };
+    }
+
+    /// Records the barriers required by the uses declared so far.
+    pub fn flush(&mut self) {
+        for batch in self.tracker.flush() {
+            let buffer_barriers: SmallVec<[_; 4]> = batch
+                .buffers
+                .iter()
+                .map(|transition| BufferBarrier2 {
+                    buffer: self.buffers[&transition.buffer],
+                    src_stages: transition.src_stages,
+                    src_access: transition.src_access,
+                    dst_stages: transition.dst_stages,
+                    dst_access: transition.dst_access,
+                    queue_family_transfer: transition.queue_family_transfer,
+                    offset: 0,
+                    size: vk::WHOLE_SIZE,
+                })
+                .collect();
+
+            let image_barriers: SmallVec<[_; 4]> = batch
+                .images
+                .iter()
+                .map(|transition| ImageBarrier2 {
+                    image: self.images[&transition.image],
+                    src_stages: transition.src_stages,
+                    src_access: transition.src_access,
+                    dst_stages: transition.dst_stages,
+                    dst_access: transition.dst_access,
+                    old_layout: transition.old_layout,
+                    new_layout: transition.new_layout,
+                    queue_family_transfer: transition.queue_family_transfer,
+                    subresource_range: transition.subresource_range,
+                })
+                .collect();
+
+            unsafe {
+                self.command_buffer
+                    .pipeline_barrier2(&[], &buffer_barriers, &image_barriers)
+            };
+        }
+    }
+
+    /// Remembers an image used through this recorder, and registers it with the tracker if
+    /// needed.
+    fn register_image(&mut self, image: &'a Image) {
+        self.images.insert(image.handle(), image);
+        if !self.tracker.is_image_registered(image.handle()) {
+            self.tracker
+                .register_image(image.handle(), TrackedImageDesc::new(image));
+        }
+    }
+}
+
+impl<L> Drop for TrackedCommandBuffer<'_, '_, '_, L> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ash::vk::Handle;
+
+    const IMAGE: u64 = 0x10;
+    const BUFFER: u64 = 0x20;
+
+    /// Returns a tracker with an image of `mip_levels` levels and `array_layers` layers, in
+    /// the undefined layout and owned by no queue family.
+    fn tracker_with_image(mip_levels: u32, array_layers: u32) -> StateTracker {
+        let mut tracker = StateTracker::new();
+        tracker.register_image(
+            vk::Image::from_raw(IMAGE),
+            TrackedImageDesc {
+                aspects: ImageAspects::COLOR,
+                mip_levels,
+                array_layers,
+                layout: ImageLayout::Undefined,
+                owner: None,
+                concurrent: false,
+            },
+        );
+        tracker
+    }
+
+    /// Returns the range covering the provided levels and layers.
+    fn range(mip_levels: [u32; 2], array_layers: [u32; 2]) -> ImageSubresourceRange {
+        ImageSubresourceRange {
+            aspects: ImageAspects::COLOR,
+            base_mip_level: mip_levels[0],
+            level_count: mip_levels[1],
+            base_array_layer: array_layers[0],
+            layer_count: array_layers[1],
+        }
+    }
+
+    /// Returns the transition of an image range from a transfer destination to a sampled
+    /// image.
+    fn transfer_to_sampled(subresource_range: ImageSubresourceRange) -> ImageTransition {
+        ImageTransition {
+            image: vk::Image::from_raw(IMAGE),
+            src_stages: ImageUse::TRANSFER_DST.stages,
+            src_access: ImageUse::TRANSFER_DST.access,
+            dst_stages: ImageUse::SAMPLED.stages,
+            dst_access: ImageUse::SAMPLED.access,
+            old_layout: ImageLayout::TransferDstOptimal,
+            new_layout: ImageLayout::ShaderReadOnlyOptimal,
+            queue_family_transfer: None,
+            subresource_range,
+        }
+    }
+
+    #[test]
+    fn barriers_between_writes_and_reads() {
+        let buffer = vk::Buffer::from_raw(BUFFER);
+        let mut tracker = StateTracker::new();
+
+        // Nothing precedes the first write.
+        tracker.use_buffer(0, buffer, BufferUse::TRANSFER_DST);
+        assert_eq!(tracker.flush(), []);
+
+        // The write is made visible to the first read only.
+        tracker.use_buffer(0, buffer, BufferUse::TRANSFER_SRC);
+        let expected = BufferTransition {
+            buffer,
+            src_stages: PipelineStages2::ALL_TRANSFER,
+            src_access: Accesses2::TRANSFER_WRITE,
+            dst_stages: PipelineStages2::ALL_TRANSFER,
+            dst_access: Accesses2::TRANSFER_READ,
+            queue_family_transfer: None,
+        };
+        assert_eq!(tracker.flush()[0].buffers, [expected]);
+
+        tracker.use_buffer(0, buffer, BufferUse::TRANSFER_SRC);
+        assert_eq!(tracker.flush(), []);
+
+        // The next write waits for the reads and for the previous write.
+        tracker.use_buffer(0, buffer, BufferUse::TRANSFER_DST);
+        let expected = BufferTransition {
+            dst_access: Accesses2::TRANSFER_WRITE,
+            ..expected
+        };
+        assert_eq!(tracker.flush()[0].buffers, [expected]);
+        assert_eq!(tracker.hazards(), []);
+    }
+
+    #[test]
+    fn layouts_are_tracked_per_subresource() {
+        let image = vk::Image::from_raw(IMAGE);
+        let mut tracker = tracker_with_image(2, 1);
+
+        tracker.use_image(0, image, &range([0, 1], [0, 1]), ImageUse::TRANSFER_DST);
+        let batches = tracker.flush();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].images.len(), 1);
+        assert_eq!(batches[0].images[0].old_layout, ImageLayout::Undefined);
+        assert_eq!(
+            batches[0].images[0].new_layout,
+            ImageLayout::TransferDstOptimal
+        );
+
+        // Each level is transitioned from its own layout.
+        let whole = ImageSubresourceRange::whole(ImageAspects::COLOR);
+        tracker.use_image(0, image, &whole, ImageUse::SAMPLED);
+        let batches = tracker.flush();
+        let from_undefined = ImageTransition {
+            src_stages: PipelineStages2::empty(),
+            src_access: Accesses2::empty(),
+            old_layout: ImageLayout::Undefined,
+            ..transfer_to_sampled(range([1, 1], [0, 1]))
+        };
+        assert_eq!(
+            batches,
+            [BarrierBatch {
+                buffers: Vec::new(),
+                images: vec![transfer_to_sampled(range([0, 1], [0, 1])), from_undefined],
+            }]
+        );
+
+        // Reading again in the same layout requires no barrier.
+        tracker.use_image(0, image, &whole, ImageUse::SAMPLED);
+        assert_eq!(tracker.flush(), []);
+    }
+
+    #[test]
+    fn adjacent_subresources_are_merged() {
+        let image = vk::Image::from_raw(IMAGE);
+        let mut tracker = tracker_with_image(3, 4);
+
+        let used = range([0, 3], [1, 2]);
+        tracker.use_image(0, image, &used, ImageUse::TRANSFER_DST);
+        tracker.flush();
+
+        // The levels share the same dependency and layers, so a single range covers them.
+        let whole = ImageSubresourceRange::whole(ImageAspects::COLOR);
+        tracker.use_image(0, image, &used, ImageUse::SAMPLED);
+        assert_eq!(tracker.flush()[0].images, [transfer_to_sampled(used)]);
+
+        // The layers on both sides of the used ones are still undefined, and cannot be merged
+        // with each other.
+        tracker.use_image(0, image, &whole, ImageUse::SAMPLED);
+        let ranges: Vec<_> = tracker.flush()[0]
+            .images
+            .iter()
+            .map(|transition| transition.subresource_range)
+            .collect();
+        assert_eq!(ranges, [range([0, 3], [0, 1]), range([0, 3], [3, 1])]);
+    }
+
+    #[test]
+    fn conflicting_layouts_are_reported() {
+        let image = vk::Image::from_raw(IMAGE);
+        let mut tracker = tracker_with_image(1, 2);
+
+        tracker.use_image(0, image, &range([0, 1], [0, 2]), ImageUse::TRANSFER_SRC);
+        tracker.use_image(0, image, &range([0, 1], [1, 1]), ImageUse::TRANSFER_DST);
+        tracker.flush();
+
+        assert_eq!(
+            tracker.take_hazards(),
+            [Hazard::ConflictingLayouts {
+                resource: TrackedResource::Image {
+                    image,
+                    mip_level: 0,
+                    array_layer: 1,
+                },
+                layouts: [
+                    ImageLayout::TransferSrcOptimal,
+                    ImageLayout::TransferDstOptimal
+                ],
+            }]
+        );
+        assert_eq!(tracker.hazards(), []);
+    }
+
+    #[test]
+    fn release_image_transitions_on_both_queues() {
+        let image = vk::Image::from_raw(IMAGE);
+        let whole = ImageSubresourceRange::whole(ImageAspects::COLOR);
+        let mut tracker = tracker_with_image(1, 1);
+
+        tracker.use_image(0, image, &whole, ImageUse::TRANSFER_DST);
+        tracker.flush();
+
+        // The release and the acquisition perform the same layout transition.
+        tracker.release_image(0, 1, image, &whole, ImageLayout::ShaderReadOnlyOptimal);
+        let expected = ImageTransition {
+            dst_stages: PipelineStages2::empty(),
+            dst_access: Accesses2::empty(),
+            queue_family_transfer: Some([0, 1]),
+            ..transfer_to_sampled(range([0, 1], [0, 1]))
+        };
+        assert_eq!(tracker.flush()[0].images, [expected]);
+
+        tracker.use_image(1, image, &whole, ImageUse::SAMPLED);
+        let expected = ImageTransition {
+            src_stages: PipelineStages2::empty(),
+            src_access: Accesses2::empty(),
+            dst_stages: ImageUse::SAMPLED.stages,
+            dst_access: ImageUse::SAMPLED.access,
+            ..expected
+        };
+        assert_eq!(tracker.flush()[0].images, [expected]);
+
+        // The layout is already the one of the use, so no further barrier is needed.
+        tracker.use_image(1, image, &whole, ImageUse::SAMPLED);
+        assert_eq!(tracker.flush(), []);
+        assert_eq!(tracker.hazards(), []);
+    }
+
+    #[test]
+    fn queue_family_transfers() {
+        let buffer = vk::Buffer::from_raw(BUFFER);
+        let mut tracker = StateTracker::new();
+
+        // The first queue family to use the buffer acquires it.
+        tracker.use_buffer(0, buffer, BufferUse::TRANSFER_DST);
+        tracker.flush();
+
+        tracker.release_buffer(0, 1, buffer);
+        let release = tracker.flush();
+        assert_eq!(release.len(), 1);
+        assert_eq!(release[0].buffers[0].queue_family_transfer, Some([0, 1]));
+        assert_eq!(
+            release[0].buffers[0].src_stages,
+            PipelineStages2::ALL_TRANSFER
+        );
+
+        tracker.use_buffer(1, buffer, BufferUse::TRANSFER_SRC);
+        let acquire = tracker.flush();
+        assert_eq!(acquire.len(), 1);
+        assert_eq!(acquire[0].buffers[0].queue_family_transfer, Some([0, 1]));
+        assert_eq!(acquire[0].buffers[0].dst_access, Accesses2::TRANSFER_READ);
+        assert_eq!(tracker.hazards(), []);
+
+        // Going back to the first queue family without a release is a hazard.
+        tracker.use_buffer(0, buffer, BufferUse::TRANSFER_SRC);
+        tracker.flush();
+        assert_eq!(
+            tracker.hazards(),
+            [Hazard::MissingQueueTransfer {
+                resource: TrackedResource::Buffer(buffer),
+                src_queue_family: 1,
+                dst_queue_family: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn concurrent_resources_need_no_transfer() {
+        let buffer = vk::Buffer::from_raw(BUFFER);
+        let mut tracker = StateTracker::new();
+        tracker.register_buffer(
+            buffer,
+            TrackedBufferDesc {
+                owner: Some(0),
+                concurrent: true,
+            },
+        );
+
+        tracker.use_buffer(0, buffer, BufferUse::TRANSFER_DST);
+        tracker.flush();
+        tracker.use_buffer(1, buffer, BufferUse::TRANSFER_SRC);
+        let batches = tracker.flush();
+
+        assert_eq!(batches[0].buffers[0].queue_family_transfer, None);
+        assert_eq!(tracker.hazards(), []);
+    }
+}
@@ -0,0 +1,125 @@
+//! Vulkan definitions which are missing from the version of [`ash`] used by this crate.
+//!
+//! They are written by hand from the Vulkan registry, and should be replaced by their [`ash`]
+//! counterparts once the crate depends on a version that exposes them.
+
+#![allow(non_camel_case_types)]
+
+use std::ffi::{c_void, CStr};
+
+use ash::vk;
+
+pub const API_VERSION_1_3: u32 = vk::make_api_version(0, 1, 3, 0);
+
+/// The name of the `VK_EXT_extended_dynamic_state3` extension.
+pub const EXT_EXTENDED_DYNAMIC_STATE_3_NAME: &CStr = c"VK_EXT_extended_dynamic_state3";
+
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_EXTENDED_DYNAMIC_STATE_3_FEATURES_EXT: vk::StructureType =
+    vk::StructureType::from_raw(1_000_455_000);
+
+pub const DYNAMIC_STATE_DEPTH_CLAMP_ENABLE_EXT: vk::DynamicState =
+    vk::DynamicState::from_raw(1_000_455_003);
+pub const DYNAMIC_STATE_POLYGON_MODE_EXT: vk::DynamicState =
+    vk::DynamicState::from_raw(1_000_455_004);
+pub const DYNAMIC_STATE_RASTERIZATION_SAMPLES_EXT: vk::DynamicState =
+    vk::DynamicState::from_raw(1_000_455_005);
+pub const DYNAMIC_STATE_ALPHA_TO_COVERAGE_ENABLE_EXT: vk::DynamicState =
+    vk::DynamicState::from_raw(1_000_455_007);
+pub const DYNAMIC_STATE_LOGIC_OP_ENABLE_EXT: vk::DynamicState =
+    vk::DynamicState::from_raw(1_000_455_009);
+pub const DYNAMIC_STATE_COLOR_BLEND_ENABLE_EXT: vk::DynamicState =
+    vk::DynamicState::from_raw(1_000_455_010);
+pub const DYNAMIC_STATE_COLOR_BLEND_EQUATION_EXT: vk::DynamicState =
+    vk::DynamicState::from_raw(1_000_455_011);
+pub const DYNAMIC_STATE_COLOR_WRITE_MASK_EXT: vk::DynamicState =
+    vk::DynamicState::from_raw(1_000_455_012);
+
+/// `VkPhysicalDeviceExtendedDynamicState3FeaturesEXT`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PhysicalDeviceExtendedDynamicState3FeaturesEXT {
+    pub s_type: vk::StructureType,
+    pub p_next: *mut c_void,
+    pub extended_dynamic_state3_tessellation_domain_origin: vk::Bool32,
+    pub extended_dynamic_state3_depth_clamp_enable: vk::Bool32,
+    pub extended_dynamic_state3_polygon_mode: vk::Bool32,
+    pub extended_dynamic_state3_rasterization_samples: vk::Bool32,
+    pub extended_dynamic_state3_sample_mask: vk::Bool32,
+    pub extended_dynamic_state3_alpha_to_coverage_enable: vk::Bool32,
+    pub extended_dynamic_state3_alpha_to_one_enable: vk::Bool32,
+    pub extended_dynamic_state3_logic_op_enable: vk::Bool32,
+    pub extended_dynamic_state3_color_blend_enable: vk::Bool32,
+    pub extended_dynamic_state3_color_blend_equation: vk::Bool32,
+    pub extended_dynamic_state3_color_write_mask: vk::Bool32,
+    /// The features of the states that this crate does not expose, from
+    /// `extendedDynamicState3RasterizationStream` to
+    /// `extendedDynamicState3ShadingRateImageEnable`.
+    pub other_features: [vk::Bool32; 20],
+}
+
+impl Default for PhysicalDeviceExtendedDynamicState3FeaturesEXT {
+    fn default() -> Self {
+        Self {
+            extended_dynamic_state3_tessellation_domain_origin: vk::FALSE,
+            extended_dynamic_state3_depth_clamp_enable: vk::FALSE,
+            extended_dynamic_state3_polygon_mode: vk::FALSE,
+            extended_dynamic_state3_rasterization_samples: vk::FALSE,
+            extended_dynamic_state3_sample_mask: vk::FALSE,
+            extended_dynamic_state3_alpha_to_coverage_enable: vk::FALSE,
+            extended_dynamic_state3_alpha_to_one_enable: vk::FALSE,
+            extended_dynamic_state3_logic_op_enable: vk::FALSE,
+            extended_dynamic_state3_color_blend_enable: vk::FALSE,
+            extended_dynamic_state3_color_blend_equation: vk::FALSE,
+            extended_dynamic_state3_color_write_mask: vk::FALSE,
+            other_features: [vk::FALSE; 20],
+
+            p_next: std::ptr::null_mut(),
+            s_type: STRUCTURE_TYPE_PHYSICAL_DEVICE_EXTENDED_DYNAMIC_STATE_3_FEATURES_EXT,
+        }
+    }
+}
+
+/// `VkColorBlendEquationEXT`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ColorBlendEquationEXT {
+    pub src_color_blend_factor: vk::BlendFactor,
+    pub dst_color_blend_factor: vk::BlendFactor,
+    pub color_blend_op: vk::BlendOp,
+    pub src_alpha_blend_factor: vk::BlendFactor,
+    pub dst_alpha_blend_factor: vk::BlendFactor,
+    pub alpha_blend_op: vk::BlendOp,
+}
+
+pub type PFN_vkCmdSetDepthClampEnableEXT =
+    unsafe extern "system" fn(command_buffer: vk::CommandBuffer, depth_clamp_enable: vk::Bool32);
+pub type PFN_vkCmdSetPolygonModeEXT =
+    unsafe extern "system" fn(command_buffer: vk::CommandBuffer, polygon_mode: vk::PolygonMode);
+pub type PFN_vkCmdSetRasterizationSamplesEXT = unsafe extern "system" fn(
+    command_buffer: vk::CommandBuffer,
+    rasterization_samples: vk::SampleCountFlags,
+);
+pub type PFN_vkCmdSetAlphaToCoverageEnableEXT = unsafe extern "system" fn(
+    command_buffer: vk::CommandBuffer,
+    alpha_to_coverage_enable: vk::Bool32,
+);
+pub type PFN_vkCmdSetLogicOpEnableEXT =
+    unsafe extern "system" fn(command_buffer: vk::CommandBuffer, logic_op_enable: vk::Bool32);
+pub type PFN_vkCmdSetColorBlendEnableEXT = unsafe extern "system" fn(
+    command_buffer: vk::CommandBuffer,
+    first_attachment: u32,
+    attachment_count: u32,
+    p_color_blend_enables: *const vk::Bool32,
+);
+pub type PFN_vkCmdSetColorBlendEquationEXT = unsafe extern "system" fn(
+    command_buffer: vk::CommandBuffer,
+    first_attachment: u32,
+    attachment_count: u32,
+    p_color_blend_equations: *const ColorBlendEquationEXT,
+);
+pub type PFN_vkCmdSetColorWriteMaskEXT = unsafe extern "system" fn(
+    command_buffer: vk::CommandBuffer,
+    first_attachment: u32,
+    attachment_count: u32,
+    p_color_write_masks: *const vk::ColorComponentFlags,
+);
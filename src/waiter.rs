@@ -0,0 +1,385 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use crate::{Device, Error, Fence, GpuFuture, Result, TimelineSemaphore};
+
+/// How often the waiter thread polls fences, which cannot be waited on alongside semaphores.
+const FENCE_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The state of a single wait, shared between a [`WaitFuture`] and the waiter thread.
+#[derive(Default)]
+struct WaitState {
+    /// The result of the wait, once it has completed.
+    result: Option<Result<()>>,
+    /// The waker of the task polling the future, if it has been polled.
+    waker: Option<Waker>,
+}
+
+impl WaitState {
+    /// Completes the wait with the provided result, and wakes the task waiting for it.
+    fn complete(&mut self, result: Result<()>) {
+        self.result = Some(result);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// An object waited on by the waiter thread.
+enum WaitTarget {
+    /// The counter of a timeline semaphore must reach a value.
+    Timeline(GpuFuture),
+    /// A fence must be signaled.
+    Fence(Arc<Fence>),
+}
+
+/// A wait registered with the waiter thread.
+struct PendingWait {
+    /// The object waited on.
+    target: WaitTarget,
+    /// The state shared with the future.
+    state: Arc<Mutex<WaitState>>,
+}
+
+/// The state of a [`Waiter`], protected by a mutex.
+struct WaiterState {
+    /// The waits that have not completed yet.
+    pending: Vec<PendingWait>,
+    /// The last value signaled on the wake-up semaphore.
+    wake_value: u64,
+    /// Whether the [`Waiter`] has been dropped.
+    ///
+    /// The thread keeps running until the pending waits have completed.
+    shutdown: bool,
+    /// The error that stopped the thread, if any.
+    error: Option<Error>,
+}
+
+/// The part of a [`Waiter`] that is shared with its thread.
+struct WaiterShared {
+    /// The semaphore that the thread waits on alongside the pending waits, signaled to wake
+    /// it up when the set of pending waits changes.
+    wake: TimelineSemaphore,
+    /// The state of the waiter.
+    state: Mutex<WaiterState>,
+}
+
+impl WaiterShared {
+    /// Wakes up the waiter thread.
+    ///
+    /// The provided state must be the locked state of the waiter, which keeps the values
+    /// signaled on the wake-up semaphore increasing.
+    fn wake(&self, state: &mut WaiterState) -> Result<()> {
+        state.wake_value += 1;
+        // The wake-up semaphore is only signaled from the host, with increasing values.
+        unsafe { self.wake.signal(state.wake_value) }
+    }
+}
+
+/// A background thread turning fences and timeline semaphore values into [`Future`]s.
+///
+/// All the waits registered with a waiter are multiplexed into a single `vkWaitSemaphores`
+/// call, so that no thread of an async runtime is ever blocked on the device. Fences are
+/// polled periodically while any of them is pending.
+///
+/// This requires the [`DeviceFeatures::TIMELINE_SEMAPHORE`](crate::DeviceFeatures) feature.
+pub struct Waiter {
+    /// The state shared with the thread.
+    shared: Arc<WaiterShared>,
+}
+
+impl Waiter {
+    /// Creates a new [`Waiter`] and starts its thread.
+    ///
+    /// # Errors
+    ///
+    /// This function returns [`Error::MissingDeviceFeature`](crate::Error) if the
+    /// [`DeviceFeatures::TIMELINE_SEMAPHORE`](crate::DeviceFeatures) feature is not enabled on
+    /// the device.
+    pub fn new(device: Arc<Device>) -> Result<Self> {
+        let shared = Arc::new(WaiterShared {
+            wake: TimelineSemaphore::new(device, 0)?,
+            state: Mutex::new(WaiterState {
+                pending: Vec::new(),
+                wake_value: 0,
+                shutdown: false,
+                error: None,
+            }),
+        });
+
+        let thread_shared = shared.clone();
+        std::thread::Builder::new()
+            .name("warm-waiter".into())
+            .spawn(move || run(&thread_shared))
+            .expect("failed to spawn the waiter thread");
+
+        Ok(Self { shared })
+    }
+
+    /// Returns a [`Future`] which resolves once the provided [`GpuFuture`] has resolved.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the semaphore of the future belongs to another device.
+    pub fn timeline(&self, future: GpuFuture) -> WaitFuture {
+        assert!(
+            Arc::ptr_eq(future.semaphore().device(), self.device()),
+            "the semaphore belongs to another device"
+        );
+
+        match future.is_ready() {
+            Ok(false) => self.register(WaitTarget::Timeline(future)),
+            ready => WaitFuture::completed(ready.map(drop)),
+        }
+    }
+
+    /// Returns a [`Future`] which resolves once the provided fence is signaled.
+    ///
+    /// Fences cannot be waited on alongside timeline semaphores, so while any fence is pending
+    /// the waiter thread wakes up every millisecond to poll it instead of sleeping until a
+    /// semaphore is signaled. Waiting on a timeline semaphore value is cheaper when possible.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the fence belongs to another device.
+    pub fn fence(&self, fence: Arc<Fence>) -> WaitFuture {
+        assert!(
+            Arc::ptr_eq(fence.device(), self.device()),
+            "the fence belongs to another device"
+        );
+
+        match fence.status() {
+            Ok(false) => self.register(WaitTarget::Fence(fence)),
+            ready => WaitFuture::completed(ready.map(drop)),
+        }
+    }
+
+    /// Registers a wait with the thread.
+    fn register(&self, target: WaitTarget) -> WaitFuture {
+        let state = Arc::new(Mutex::new(WaitState::default()));
+
+        let mut waiter_state = self
+            .shared
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(err) = waiter_state.error {
+            return WaitFuture::completed(Err(err));
+        }
+        if let Err(err) = self.shared.wake(&mut waiter_state) {
+            return WaitFuture::completed(Err(err));
+        }
+        waiter_state.pending.push(PendingWait {
+            target,
+            state: state.clone(),
+        });
+
+        WaitFuture { state }
+    }
+
+    /// Returns the device that this waiter waits on.
+    #[inline(always)]
+    pub fn device(&self) -> &Arc<Device> {
+        self.shared.wake.device()
+    }
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        let mut state = self
+            .shared
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        state.shutdown = true;
+
+        // If the thread cannot be woken up, it still exits after its next pending wait
+        // completes.
+        let _ = self.shared.wake(&mut state);
+    }
+}
+
+/// The body of the waiter thread.
+fn run(shared: &WaiterShared) {
+    let device = shared.wake.device();
+
+    loop {
+        let mut handles = vec![shared.wake.handle()];
+        let mut values;
+        let poll_fences;
+
+        {
+            let state = shared.state.lock().unwrap_or_else(PoisonError::into_inner);
+            if state.shutdown && state.pending.is_empty() {
+                return;
+            }
+
+            values = vec![state.wake_value + 1];
+            poll_fences = state
+                .pending
+                .iter()
+                .any(|wait| matches!(wait.target, WaitTarget::Fence(_)));
+
+            for wait in &state.pending {
+                if let WaitTarget::Timeline(future) = &wait.target {
+                    handles.push(future.semaphore().handle());
+                    values.push(future.value());
+                }
+            }
+        }
+
+        let timeout = if poll_fences {
+            FENCE_POLL_INTERVAL
+        } else {
+            Duration::MAX
+        };
+
+        let wait_result =
+            crate::timeline::wait_semaphores(device, &handles, &values, false, timeout);
+
+        let mut state = shared.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Err(err) = wait_result {
+            // The device is most likely lost, so none of the waits can complete anymore.
+            for wait in state.pending.drain(..) {
+                wait.state
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .complete(Err(err));
+            }
+            state.error = Some(err);
+            return;
+        }
+
+        state.pending.retain(|wait| {
+            let ready = match &wait.target {
+                WaitTarget::Timeline(future) => future.is_ready(),
+                WaitTarget::Fence(fence) => fence.status(),
+            };
+
+            match ready {
+                Ok(false) => true,
+                ready => {
+                    wait.state
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .complete(ready.map(drop));
+                    false
+                }
+            }
+        });
+    }
+}
+
+/// A [`Future`] which resolves once a fence or a timeline semaphore value waited on by a
+/// [`Waiter`] is signaled.
+///
+/// Dropping the future does not cancel the wait, which keeps the waited object alive until
+/// it completes.
+pub struct WaitFuture {
+    /// The state shared with the waiter thread.
+    state: Arc<Mutex<WaitState>>,
+}
+
+impl WaitFuture {
+    /// Returns a future which has already completed with the provided result.
+    fn completed(result: Result<()>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(WaitState {
+                result: Some(result),
+                waker: None,
+            })),
+        }
+    }
+}
+
+impl Future for WaitFuture {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::Thread;
+
+    use crate::{DeviceFeatures, Fence, PipelineStages, SubmitDesc, TimelineSemaphore, Waiter};
+
+    /// Wakes up a thread blocked in [`block_on`].
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// Polls a future on the current thread until it resolves.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    // Without lavapipe, this returns early and passes without testing anything, so it only
+    // runs when requested with `cargo test -- --ignored` on a machine that has the driver.
+    #[test]
+    #[ignore = "requires the lavapipe driver"]
+    fn resolves_timelines_and_fences() {
+        let Some((device, mut queue)) = crate::lavapipe::device(DeviceFeatures::TIMELINE_SEMAPHORE)
+        else {
+            return;
+        };
+
+        let waiter = Waiter::new(device.clone()).unwrap();
+        let semaphore = Arc::new(TimelineSemaphore::new(device.clone(), 0).unwrap());
+        let mut fence = Fence::new(device, false).unwrap();
+
+        // The submission waits for a value signaled from the host, so both waits are still
+        // pending when they are registered.
+        let timeline = waiter.timeline(semaphore.future(2));
+        unsafe {
+            queue
+                .submit(
+                    &[SubmitDesc {
+                        wait_timelines: &[(&semaphore, 1, PipelineStages::ALL_COMMANDS)],
+                        signal_timelines: &[(&semaphore, 2)],
+                        ..Default::default()
+                    }],
+                    Some(&mut fence),
+                )
+                .unwrap();
+        }
+        let fence = Arc::new(fence);
+        let fence_signaled = waiter.fence(fence.clone());
+
+        unsafe { semaphore.signal(1).unwrap() };
+
+        block_on(timeline).unwrap();
+        block_on(fence_signaled).unwrap();
+        assert_eq!(semaphore.value().unwrap(), 2);
+        assert!(fence.status().unwrap());
+    }
+}